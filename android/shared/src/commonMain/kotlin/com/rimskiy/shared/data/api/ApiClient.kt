@@ -487,7 +487,7 @@ class ApiClient(
         if (!response.status.isSuccess()) {
             throw Exception(ErrorHandler.getErrorMessage(response))
         }
-        return response.body()
+        return response.body<NotificationPage>().items
     }
 
     suspend fun markNotificationRead(token: String, notificationId: String) {
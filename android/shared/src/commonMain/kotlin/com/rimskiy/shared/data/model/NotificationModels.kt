@@ -13,3 +13,12 @@ data class NotificationResponse(
     val created_at: String? = null
 )
 
+
+@Serializable
+data class NotificationPage(
+    val items: List<NotificationResponse> = emptyList(),
+    val total_count: Long = 0,
+    val limit: Int = 20,
+    val offset: Int = 0,
+    val has_more: Boolean = false
+)
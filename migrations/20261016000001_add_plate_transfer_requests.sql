@@ -0,0 +1,13 @@
+-- Заявки на передачу номера новому владельцу: номер переходит к заявителю
+-- только после согласия одного из текущих владельцев
+CREATE TABLE IF NOT EXISTS plate_transfer_requests (
+    id UUID PRIMARY KEY,
+    plate TEXT NOT NULL,
+    claimant_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'declined')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMPTZ,
+    responded_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_plate_transfer_requests_pending ON plate_transfer_requests(plate, claimant_id) WHERE status = 'pending';
@@ -0,0 +1,3 @@
+-- Ключи фото-доказательств блокировки в хранилище (оригинал и превью)
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS photo_key TEXT;
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS photo_thumb_key TEXT;
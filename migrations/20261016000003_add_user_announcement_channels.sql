@@ -0,0 +1,3 @@
+-- Каналы, по которым пользователь получает объявления администрации
+ALTER TABLE users ADD COLUMN IF NOT EXISTS announcement_push BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS announcement_telegram BOOLEAN NOT NULL DEFAULT true;
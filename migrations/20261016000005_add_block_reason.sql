@@ -0,0 +1,4 @@
+-- Причина блокировки и пояснение к ней
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS reason TEXT
+    CHECK (reason IS NULL OR reason IN ('temporary_parking', 'loading', 'emergency', 'other'));
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS reason_text TEXT;
@@ -0,0 +1,9 @@
+-- Пользователи с номером из основного автомобиля (user_plates - источник истины).
+-- users.plate используется только как запасной вариант, пока у пользователя нет записей в user_plates
+CREATE OR REPLACE VIEW users_with_primary_plate AS
+SELECT u.id, u.phone_encrypted, u.phone_hash, u.telegram,
+       COALESCE(up.plate, u.plate) AS plate,
+       u.name, u.show_contacts, u.owner_type, u.owner_info, u.departure_time,
+       u.push_token, u.announcement_push, u.announcement_telegram, u.created_at, u.updated_at
+FROM users u
+LEFT JOIN user_plates up ON up.user_id = u.id AND up.is_primary;
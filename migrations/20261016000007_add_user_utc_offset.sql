@@ -0,0 +1,12 @@
+-- Смещение часового пояса пользователя от UTC в минутах (для тихих часов)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS utc_offset_minutes INTEGER
+    CHECK (utc_offset_minutes IS NULL OR utc_offset_minutes BETWEEN -720 AND 840);
+
+CREATE OR REPLACE VIEW users_with_primary_plate AS
+SELECT u.id, u.phone_encrypted, u.phone_hash, u.telegram,
+       COALESCE(up.plate, u.plate) AS plate,
+       u.name, u.show_contacts, u.owner_type, u.owner_info, u.departure_time,
+       u.push_token, u.announcement_push, u.announcement_telegram, u.created_at, u.updated_at,
+       u.utc_offset_minutes
+FROM users u
+LEFT JOIN user_plates up ON up.user_id = u.id AND up.is_primary;
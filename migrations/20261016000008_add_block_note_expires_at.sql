@@ -0,0 +1,3 @@
+-- Заметка блокирующего и ожидаемое время окончания блокировки
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS note TEXT;
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
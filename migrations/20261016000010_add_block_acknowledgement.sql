@@ -0,0 +1,3 @@
+-- Подтверждение владельцем, что он увидел уведомление, и шаг эскалации неподтверждённой блокировки
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS acknowledged_at TIMESTAMPTZ;
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS escalation_step SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Тихие блокировки: сохраняются для блокирующего, владельцы не уведомляются
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS silent BOOLEAN NOT NULL DEFAULT false;
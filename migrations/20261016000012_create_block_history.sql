@@ -0,0 +1,13 @@
+-- Завершённые (удалённые) блокировки: для статистики продолжительности
+CREATE TABLE IF NOT EXISTS block_history (
+    id UUID PRIMARY KEY,
+    blocker_id UUID NOT NULL,
+    blocked_plate TEXT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_block_history_deleted_at ON block_history(deleted_at DESC);
+-- Лимит блокировок в час учитывает и снятые блокировки
+CREATE INDEX IF NOT EXISTS idx_block_history_blocker_created ON block_history(blocker_id, created_at DESC);
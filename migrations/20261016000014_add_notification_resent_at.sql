@@ -0,0 +1,2 @@
+-- Время последней повторной отправки уведомления (ограничение частоты повторов)
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS last_resent_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- Подтверждения получения и открытия push-уведомлений приложением
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS delivered_at TIMESTAMPTZ;
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS opened_at TIMESTAMPTZ;
+ALTER TABLE notification_outbox ADD COLUMN IF NOT EXISTS confirmed_at TIMESTAMPTZ;
+ALTER TABLE notification_outbox ADD COLUMN IF NOT EXISTS opened_at TIMESTAMPTZ;
@@ -0,0 +1,11 @@
+-- Аватар пользователя (фото пользователя или его автомобиля) в хранилище файлов
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_key TEXT;
+
+CREATE OR REPLACE VIEW users_with_primary_plate AS
+SELECT u.id, u.phone_encrypted, u.phone_hash, u.telegram,
+       COALESCE(up.plate, u.plate) AS plate,
+       u.name, u.show_contacts, u.owner_type, u.owner_info, u.departure_time,
+       u.push_token, u.announcement_push, u.announcement_telegram, u.created_at, u.updated_at,
+       u.utc_offset_minutes, u.avatar_key
+FROM users u
+LEFT JOIN user_plates up ON up.user_id = u.id AND up.is_primary;
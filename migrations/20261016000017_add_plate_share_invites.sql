@@ -0,0 +1,14 @@
+-- Приглашения стать совладельцем номера (номер добавляется приглашённому после согласия)
+CREATE TABLE IF NOT EXISTS plate_share_invites (
+    id UUID PRIMARY KEY,
+    plate_id UUID NOT NULL REFERENCES user_plates(id) ON DELETE CASCADE,
+    plate TEXT NOT NULL,
+    inviter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invitee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_plate_share_invites_pending ON plate_share_invites(plate_id, invitee_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_plate_share_invites_invitee ON plate_share_invites(invitee_id, created_at DESC) WHERE status = 'pending';
@@ -0,0 +1,13 @@
+-- Блокировка аккаунта администратором (suspended_until NULL - бессрочно)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_until TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspension_reason TEXT;
+
+CREATE OR REPLACE VIEW users_with_primary_plate AS
+SELECT u.id, u.phone_encrypted, u.phone_hash, u.telegram,
+       COALESCE(up.plate, u.plate) AS plate,
+       u.name, u.show_contacts, u.owner_type, u.owner_info, u.departure_time,
+       u.push_token, u.announcement_push, u.announcement_telegram, u.created_at, u.updated_at,
+       u.utc_offset_minutes, u.avatar_key, u.suspended_at, u.suspended_until, u.suspension_reason
+FROM users u
+LEFT JOIN user_plates up ON up.user_id = u.id AND up.is_primary;
@@ -0,0 +1,38 @@
+-- Буквы Ё на номерах не бывает: номера теперь нормализуются с заменой Ё на Е.
+-- Приводим уже сохранённые номера к тому же виду, чтобы они находились по новому ключу.
+-- Если после замены получаются дубликаты, оставляем запись без Ё (или самую раннюю)
+
+DELETE FROM user_plates
+WHERE id IN (
+    SELECT id FROM (
+        SELECT id, ROW_NUMBER() OVER (
+            PARTITION BY user_id, REPLACE(plate, 'Ё', 'Е')
+            ORDER BY (plate LIKE '%Ё%'), created_at, id
+        ) AS position
+        FROM user_plates
+    ) ranked
+    WHERE position > 1
+);
+UPDATE user_plates SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%';
+
+UPDATE users SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%';
+
+DELETE FROM blocks
+WHERE id IN (
+    SELECT id FROM (
+        SELECT id, ROW_NUMBER() OVER (
+            PARTITION BY UPPER(TRIM(REPLACE(blocker_plate, 'Ё', 'Е'))),
+                         UPPER(TRIM(REPLACE(blocked_plate, 'Ё', 'Е')))
+            ORDER BY (blocker_plate LIKE '%Ё%' OR blocked_plate LIKE '%Ё%'), created_at, id
+        ) AS position
+        FROM blocks
+    ) ranked
+    WHERE position > 1
+);
+UPDATE blocks
+SET blocked_plate = REPLACE(blocked_plate, 'Ё', 'Е'),
+    blocker_plate = REPLACE(blocker_plate, 'Ё', 'Е')
+WHERE blocked_plate LIKE '%Ё%' OR blocker_plate LIKE '%Ё%';
+
+UPDATE block_history SET blocked_plate = REPLACE(blocked_plate, 'Ё', 'Е') WHERE blocked_plate LIKE '%Ё%';
+UPDATE plate_share_invites SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%';
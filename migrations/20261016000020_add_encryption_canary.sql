@@ -0,0 +1,8 @@
+-- Контрольное значение, зашифрованное при первом запуске: если оно перестаёт
+-- расшифровываться, сервер не стартует (ENCRYPTION_KEY сменили без миграции данных)
+CREATE TABLE IF NOT EXISTS encryption_canary (
+    id SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    value_encrypted TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Когда владелец заблокированного автомобиля последний раз просил блокирующего перезвонить
+-- (ограничение частоты просьб по одной блокировке)
+ALTER TABLE blocks ADD COLUMN IF NOT EXISTS callback_requested_at TIMESTAMPTZ;
@@ -0,0 +1,10 @@
+-- Отозванные при выходе JWT (по claim jti). Строка нужна, пока токен можно
+-- использовать или обменять через /api/auth/refresh, затем удаляется фоновой очисткой
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
@@ -0,0 +1,4 @@
+-- Звонок администратору дома (последний шаг эскалации) тоже отправляется через очередь
+-- уведомлений. У такого сообщения нет получателя-пользователя: телефон администратора
+-- берётся из ESCALATION_ADMIN_PHONE в момент отправки
+ALTER TABLE notification_outbox ALTER COLUMN user_id DROP NOT NULL;
@@ -0,0 +1,346 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::{Json, Response},
+    routing::{get, post, Router},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::block::csv_attachment;
+use crate::api::AppState;
+use crate::auth::middleware::AuthState;
+use crate::error::{AppError, AppResult};
+use crate::models::admin::{
+    AdminCreateBlockRequest, AnnounceJob, AnnounceRequest, AnnounceResponse, OverviewStats,
+    SuspendUserRequest, UserSuspensionResponse,
+};
+use crate::models::block::{BlockReasonStat, CreateBlockResponse};
+use crate::models::user::ReencryptResponse;
+use crate::repository::{AuditLogRepository, BlockRepository, CreateAuditLogData, UserRepository};
+use crate::service::block_export::{stream_blocks_csv, BlockExportScope};
+
+/// Роутер административного API (требует авторизации и прав администратора)
+pub fn admin_router() -> Router<AppState> {
+    Router::new()
+        .route("/announce", post(announce))
+        .route("/announce/:job_id", get(get_announce_job))
+        .route("/blocks", post(create_block_on_behalf))
+        .route("/blocks/reasons-stats", get(block_reasons_stats))
+        .route("/blocks/export.csv", get(export_blocks_csv))
+        .route("/stats/overview", get(stats_overview))
+        .route("/users/:id/reencrypt", post(reencrypt_user))
+        .route("/users/:id/suspend", post(suspend_user))
+        .route("/users/:id/unsuspend", post(unsuspend_user))
+}
+
+/// Отправить объявление всем пользователям (фоновая задача, прогресс - по `job_id`)
+#[utoipa::path(
+    post,
+    path = "/api/admin/announce",
+    request_body = AnnounceRequest,
+    responses(
+        (status = 202, description = "Рассылка запущена", body = AnnounceResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn announce(
+    State(state): State<AppState>,
+    Json(payload): Json<AnnounceRequest>,
+) -> AppResult<(StatusCode, Json<AnnounceResponse>)> {
+    let response = state
+        .admin_service
+        .announce(
+            payload,
+            &state.user_repository,
+            &state.notification_repository,
+        )
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Прогресс рассылки объявления
+#[utoipa::path(
+    get,
+    path = "/api/admin/announce/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "ID задачи рассылки")
+    ),
+    responses(
+        (status = 200, description = "Прогресс рассылки", body = AnnounceJob),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+        (status = 404, description = "Задача не найдена (или запущена другим экземпляром сервера)"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn get_announce_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<AnnounceJob>> {
+    let job = state.admin_service.announce_job(job_id).await?;
+    Ok(Json(job))
+}
+
+/// Статистика блокировок по причинам
+#[utoipa::path(
+    get,
+    path = "/api/admin/blocks/reasons-stats",
+    responses(
+        (status = 200, description = "Количество блокировок по причинам", body = Vec<BlockReasonStat>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn block_reasons_stats(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<BlockReasonStat>>> {
+    let stats = state.block_repository.count_by_reason().await?;
+    Ok(Json(stats))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockExportQuery {
+    /// Только блокировки этого пользователя (созданные им и перекрывшие его номера)
+    pub user_id: Option<Uuid>,
+}
+
+/// Выгрузить блокировки в CSV (все или одного пользователя), включая снятые
+#[utoipa::path(
+    get,
+    path = "/api/admin/blocks/export.csv",
+    params(
+        ("user_id" = Option<Uuid>, Query, description = "Только блокировки этого пользователя")
+    ),
+    responses(
+        (status = 200, description = "CSV: id, blocker_id, blocker_plate, blocked_plate, status (active/acknowledged/expired/removed), created_at, expires_at, acknowledged_at, removed_at", content_type = "text/csv", body = String),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn export_blocks_csv(
+    State(state): State<AppState>,
+    Query(query): Query<BlockExportQuery>,
+) -> Response {
+    let stream = stream_blocks_csv(
+        state.block_repository.clone(),
+        BlockExportScope::Admin(query.user_id),
+    );
+    csv_attachment(stream, "blocks-admin")
+}
+
+/// Сводная статистика парковки: активные блокировки, уникальные номера и блокирующие,
+/// средняя продолжительность, распределение по часам суток и доставка уведомлений по каналам
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats/overview",
+    responses(
+        (status = 200, description = "Сводная статистика", body = OverviewStats),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn stats_overview(State(state): State<AppState>) -> AppResult<Json<OverviewStats>> {
+    let stats = state
+        .admin_service
+        .overview_stats(
+            &state.block_repository,
+            &state.notification_outbox_repository,
+        )
+        .await?;
+    Ok(Json(stats))
+}
+
+/// Перешифровать данные пользователя текущим ключом шифрования
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/reencrypt",
+    params(
+        ("id" = String, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, description = "Данные перешифрованы или уже зашифрованы текущим ключом", body = ReencryptResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+        (status = 404, description = "Пользователь не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn reencrypt_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<ReencryptResponse>> {
+    let response = state
+        .user_service
+        .reencrypt_user_data(user_id, &state.user_repository)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Заблокировать аккаунт пользователя (например, за злоупотребления) без удаления:
+/// пользователь получает 403 `ACCOUNT_SUSPENDED` с причиной, его номера не уведомляются
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/suspend",
+    params(
+        ("id" = String, Path, description = "ID пользователя")
+    ),
+    request_body = SuspendUserRequest,
+    responses(
+        (status = 200, description = "Аккаунт заблокирован", body = UserSuspensionResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+        (status = 404, description = "Пользователь не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn suspend_user(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SuspendUserRequest>,
+) -> AppResult<Json<UserSuspensionResponse>> {
+    let response = state
+        .admin_service
+        .suspend_user(
+            auth_state.user_id,
+            user_id,
+            payload,
+            &state.user_repository,
+            &state.audit_log_repository,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Снять блокировку аккаунта пользователя
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/unsuspend",
+    params(
+        ("id" = String, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, description = "Блокировка снята", body = UserSuspensionResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+        (status = 404, description = "Пользователь не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn unsuspend_user(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<UserSuspensionResponse>> {
+    let response = state
+        .admin_service
+        .unsuspend_user(
+            auth_state.user_id,
+            user_id,
+            &state.user_repository,
+            &state.audit_log_repository,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Создать блокировку от имени жильца (например, консьержем за жильца без приложения)
+#[utoipa::path(
+    post,
+    path = "/api/admin/blocks",
+    request_body = AdminCreateBlockRequest,
+    responses(
+        (status = 200, description = "Блокировка создана от имени жильца", body = CreateBlockResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуются права администратора"),
+        (status = 404, description = "Жилец не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "admin"
+)]
+pub async fn create_block_on_behalf(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(payload): Json<AdminCreateBlockRequest>,
+) -> AppResult<Json<CreateBlockResponse>> {
+    let admin_id = auth_state.user_id;
+    let resident_id = payload.blocker_user_id;
+
+    if state
+        .user_repository
+        .find_by_id(resident_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::NotFound("User not found".to_string()));
+    }
+
+    // Та же логика, что и у обычной блокировки, но блокирующий - жилец
+    let response = state
+        .block_service
+        .create_block(
+            resident_id,
+            payload.block,
+            &state.block_repository,
+            &state.notification_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.notification_outbox_repository,
+            &state.telephony_service,
+            &state.telegram_service,
+        )
+        .await?;
+
+    tracing::info!(
+        "Admin {} created block {} on behalf of user {}",
+        admin_id,
+        response.block.id,
+        resident_id
+    );
+
+    // Блокировка уже создана, поэтому ошибку записи в журнал только логируем
+    if let Err(e) = state
+        .audit_log_repository
+        .record(&CreateAuditLogData {
+            actor_id: admin_id,
+            action: "block_created_on_behalf".to_string(),
+            subject_user_id: Some(resident_id),
+            entity_id: Some(response.block.id),
+            details: Some(serde_json::json!({
+                "blocked_plate": response.block.blocked_plate,
+                "blocker_plate": response.block.blocker_plate,
+            })),
+        })
+        .await
+    {
+        tracing::error!(
+            "Failed to write audit log for block {}: {:?}",
+            response.block.id,
+            e
+        );
+    }
+
+    Ok(Json(response))
+}
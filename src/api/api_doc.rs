@@ -0,0 +1,46 @@
+use axum::{extract::Query, response::Json, routing::get, Router};
+use serde::Deserialize;
+use std::sync::OnceLock;
+use utoipa::OpenApi;
+
+use crate::api::AppState;
+use crate::error::{AppError, AppResult};
+use crate::openapi::{filter_spec_by_tag, ApiDoc};
+
+/// Путь, по которому отдаётся спецификация (его же использует Swagger UI)
+pub const OPENAPI_JSON_PATH: &str = "/api-doc/openapi.json";
+
+pub fn api_doc_router() -> Router<AppState> {
+    Router::new().route(OPENAPI_JSON_PATH, get(get_openapi_json))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OpenApiQuery {
+    /// Оставить только операции с этим тегом (например, `blocks`)
+    pub tag: Option<String>,
+}
+
+/// Полная спецификация строится один раз за время жизни процесса
+fn full_spec() -> &'static serde_json::Value {
+    static SPEC: OnceLock<serde_json::Value> = OnceLock::new();
+    SPEC.get_or_init(|| {
+        serde_json::to_value(ApiDoc::openapi()).expect("OpenAPI spec must serialize to JSON")
+    })
+}
+
+/// OpenAPI спецификация; с `?tag=` - только операции этого тега и нужные им схемы
+async fn get_openapi_json(Query(query): Query<OpenApiQuery>) -> AppResult<Json<serde_json::Value>> {
+    let spec = full_spec();
+    match query
+        .tag
+        .as_deref()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+    {
+        None => Ok(Json(spec.clone())),
+        Some(tag) => filter_spec_by_tag(spec, tag).map(Json).ok_or_else(|| {
+            AppError::NotFound(format!("В спецификации нет операций с тегом '{}'", tag))
+        }),
+    }
+}
@@ -1,32 +1,75 @@
 use crate::api::AppState;
+use crate::auth::signed_url::{
+    sign_path, signed_cache_control, verify_signed_path, APP_DOWNLOAD_PATH,
+};
+use crate::middleware::PublicBaseUrl;
+use crate::models::download::{SignedUrlQuery, SignedUrlResponse};
 use axum::{
     body::Bytes,
-    extract::State,
+    extract::{Extension, Query, State},
     http::{header, HeaderMap, HeaderValue, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Json},
     routing::get,
     Router,
 };
 use std::path::Path;
 use tokio::fs;
 
-/// Роутер для скачивания приложения
+/// Роутер для скачивания приложения (по подписанной ссылке, без JWT)
 pub fn app_download_router() -> Router<AppState> {
     Router::new().route("/download", get(download_app))
 }
 
-/// Endpoint для скачивания релиза приложения
+/// Роутер выдачи подписанной ссылки на APK (требует авторизации)
+pub fn app_download_url_router() -> Router<AppState> {
+    Router::new().route("/download-url", get(get_download_url))
+}
+
+/// Получить подписанную ссылку на APK, которой можно поделиться (например, через бота)
+#[utoipa::path(
+    get,
+    path = "/api/app/download-url",
+    responses(
+        (status = 200, description = "Подписанная ссылка на APK", body = SignedUrlResponse),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "app"
+)]
+pub async fn get_download_url(
+    State(state): State<AppState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
+) -> Json<SignedUrlResponse> {
+    let mut signed = sign_path(APP_DOWNLOAD_PATH, &state.config);
+    signed.url = public_url.absolute(&signed.url);
+    Json(signed)
+}
+
+/// Endpoint для скачивания релиза приложения по подписанной ссылке
 #[utoipa::path(
     get,
     path = "/api/app/download",
+    params(
+        ("expires" = i64, Query, description = "Срок действия ссылки (Unix time)"),
+        ("signature" = String, Query, description = "Подпись ссылки")
+    ),
     responses(
         (status = 200, description = "APK файл"),
+        (status = 403, description = "Подпись ссылки отсутствует, неверна или истекла"),
         (status = 404, description = "APK файл не найден"),
         (status = 500, description = "Ошибка сервера при чтении файла")
     ),
     tag = "app"
 )]
-pub async fn download_app(State(state): State<AppState>) -> Result<impl IntoResponse, StatusCode> {
+pub async fn download_app(
+    State(state): State<AppState>,
+    Query(signed): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if let Err(e) = verify_signed_path(APP_DOWNLOAD_PATH, &signed, &state.config) {
+        tracing::warn!("Отклонена ссылка на скачивание APK: {}", e);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
     // Определяем путь к APK файлу
     let apk_path = if let Some(custom_path) = &state.config.app_apk_path {
         Path::new(custom_path)
@@ -65,6 +108,14 @@ pub async fn download_app(State(state): State<AppState>) -> Result<impl IntoResp
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
     );
 
+    // Ответ можно кэшировать, пока действует ссылка: у новой ссылки другой URL
+    headers.insert(
+        header::CACHE_CONTROL,
+        signed_cache_control(&signed)
+            .parse()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+
     tracing::info!("APK файл успешно отправлен: {}", filename);
     Ok((StatusCode::OK, headers, Bytes::from(file_contents)))
 }
@@ -1,10 +1,12 @@
 use axum::{
-    extract::State,
+    extract::{Extension, State},
+    http::StatusCode,
     response::Json,
     routing::{post, Router},
 };
 
 use crate::api::AppState;
+use crate::auth::middleware::{AuthState, AuthToken};
 use crate::error::AppResult;
 use crate::models::auth::{
     AuthStartRequest, AuthStartResponse, AuthVerifyRequest, AuthVerifyResponse,
@@ -18,7 +20,12 @@ pub fn auth_router() -> Router<AppState> {
         .route("/refresh", post(refresh_token))
 }
 
-/// Начало авторизации - отправка SMS кода
+/// Роутер выхода (требует авторизации)
+pub fn auth_logout_router() -> Router<AppState> {
+    Router::new().route("/logout", post(logout))
+}
+
+/// Начало авторизации - отправка кода по SMS или через Telegram бота
 #[utoipa::path(
     post,
     path = "/api/auth/start",
@@ -33,7 +40,15 @@ pub async fn start_auth(
     State(state): State<AppState>,
     Json(payload): Json<AuthStartRequest>,
 ) -> AppResult<Json<AuthStartResponse>> {
-    let response = state.auth_service.start_auth(&payload.phone).await?;
+    let response = state
+        .auth_service
+        .start_auth(
+            &payload.phone,
+            payload.channel,
+            &state.telegram_bot_repository,
+            &state.telegram_service,
+        )
+        .await?;
     Ok(Json(response))
 }
 
@@ -46,6 +61,7 @@ pub async fn start_auth(
         (status = 200, description = "Авторизация успешна", body = AuthVerifyResponse),
         (status = 400, description = "Неверный код"),
         (status = 401, description = "Код неверен или истек"),
+        (status = 429, description = "Исчерпаны попытки ввода кода, нужно запросить новый"),
     ),
     tag = "auth"
 )]
@@ -53,16 +69,29 @@ pub async fn verify_auth(
     State(state): State<AppState>,
     Json(payload): Json<AuthVerifyRequest>,
 ) -> AppResult<Json<AuthVerifyResponse>> {
-    let response = state
+    let result = state
         .auth_service
         .verify_auth(
             &payload.phone,
             &payload.code,
+            payload.device_id.as_deref(),
             &state.user_repository,
             &state.user_plate_repository,
+            &state.user_session_repository,
         )
-        .await?;
-    Ok(Json(response))
+        .await;
+
+    // В аналитику уходит только факт и исход проверки кода, без телефона
+    state.analytics.track(
+        "auth_verify",
+        result.as_ref().ok().map(|response| response.user_id),
+        serde_json::json!({
+            "success": result.is_ok(),
+            "error_code": result.as_ref().err().map(|e| e.code()),
+        }),
+    );
+
+    Ok(Json(result?))
 }
 
 /// Обновление JWT токена
@@ -80,6 +109,42 @@ pub async fn refresh_token(
     State(state): State<AppState>,
     Json(payload): Json<RefreshTokenRequest>,
 ) -> AppResult<Json<RefreshTokenResponse>> {
-    let response = state.auth_service.refresh_token(&payload.token).await?;
+    let response = state
+        .auth_service
+        .refresh_token(
+            &payload.token,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await?;
     Ok(Json(response))
 }
+
+/// Выход: текущий токен отзывается, а его сессия завершается. Токен больше не принимается
+/// (в том числе в `/api/auth/refresh`)
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    responses(
+        (status = 204, description = "Токен отозван"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "auth"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthState>,
+    Extension(token): Extension<AuthToken>,
+) -> AppResult<StatusCode> {
+    state
+        .auth_service
+        .logout(
+            auth.user_id,
+            &token,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,27 +1,56 @@
 use axum::{
-    extract::{Extension, Path, Query, State},
-    response::Json,
+    extract::{DefaultBodyLimit, Extension, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::{delete, get, post, Router},
 };
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
+use crate::api::pagination::OptionalPagination;
 use crate::api::AppState;
 use crate::auth::middleware::AuthState;
-use crate::error::AppResult;
-use crate::models::block::{Block, BlockWithBlockerInfo, CheckBlockResponse, CreateBlockRequest};
+use crate::auth::signed_url::{block_photo_download_path, sign_path};
+use crate::error::{AppError, AppResult};
+use crate::middleware::PublicBaseUrl;
+use crate::models::block::{
+    Block, BlockNotifiabilityResponse, BlockPhotoQuery, BlockPhotoSize, BlockPreviewResponse,
+    CallbackRequestResponse, CheckBlockResponse, CreateBlockRequest, CreateBlockResponse,
+    UpdateBlockRequest,
+};
+use crate::models::download::SignedUrlResponse;
+use crate::service::block_export::{stream_blocks_csv, BlockExportScope, CsvStream};
+use crate::service::ValidationService;
+use crate::utils::image::MAX_IMAGE_SIZE;
 
 pub fn block_router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_block))
         .route("/", get(get_my_blocks))
+        .route("/export.csv", get(export_my_blocks_csv))
+        .route("/preview", post(preview_block))
         .route("/my", get(get_blocks_for_my_plate))
         .route("/check", get(check_block))
+        .route("/check/:plate", get(check_block_by_plate))
+        .route("/check/:plate/longpoll", get(check_block_longpoll))
         .route("/:id/warn-owner", post(warn_owner))
-        .route("/:id", delete(delete_block))
+        .route("/:id/ack", post(acknowledge_block))
+        .route("/:id/request-callback", post(request_callback))
+        .route("/:id/notifiability", get(get_block_notifiability))
+        .route(
+            "/:id/photo",
+            post(upload_block_photo)
+                .get(get_block_photo)
+                .layer(DefaultBodyLimit::max(MAX_IMAGE_SIZE)),
+        )
+        .route("/:id/photo-url", get(get_block_photo_url))
+        .route("/:id", delete(delete_block).patch(update_block))
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct GetBlocksQuery {
     pub my_plate: Option<String>,
 }
@@ -32,9 +61,10 @@ pub struct GetBlocksQuery {
     path = "/api/blocks",
     request_body = CreateBlockRequest,
     responses(
-        (status = 200, description = "Блокировка создана", body = Block),
+        (status = 200, description = "Блокировка создана; в заголовке Location - ссылка на неё (абсолютная при заданном PUBLIC_BASE_URL)", body = CreateBlockResponse),
         (status = 400, description = "Неверные данные"),
         (status = 401, description = "Не авторизован"),
+        (status = 409, description = "Повторная блокировка того же автомобиля: требуется acknowledge_repeat"),
     ),
     security(("bearer_token" = [])),
     tag = "blocks"
@@ -42,8 +72,9 @@ pub struct GetBlocksQuery {
 pub async fn create_block(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
     Json(payload): Json<CreateBlockRequest>,
-) -> AppResult<Json<Block>> {
+) -> AppResult<([(header::HeaderName, String); 1], Json<CreateBlockResponse>)> {
     let blocker_id = auth_state.user_id;
 
     tracing::info!(
@@ -52,7 +83,7 @@ pub async fn create_block(
         payload.blocked_plate
     );
 
-    let block = state
+    let response = state
         .block_service
         .create_block(
             blocker_id,
@@ -61,6 +92,7 @@ pub async fn create_block(
             &state.notification_repository,
             &state.user_repository,
             &state.user_plate_repository,
+            &state.notification_outbox_repository,
             &state.telephony_service,
             &state.telegram_service,
         )
@@ -70,8 +102,43 @@ pub async fn create_block(
             e
         })?;
 
-    tracing::info!("API: Block created successfully: {}", block.id);
-    Ok(Json(block))
+    tracing::info!("API: Block created successfully: {}", response.block.id);
+    let location = public_url.absolute(&format!("/api/blocks/{}", response.block.id));
+    Ok(([(header::LOCATION, location)], Json(response)))
+}
+
+/// Предпросмотр уведомлений о блокировке (ничего не создаёт и не отправляет)
+#[utoipa::path(
+    post,
+    path = "/api/blocks/preview",
+    request_body = CreateBlockRequest,
+    responses(
+        (status = 200, description = "Тексты уведомлений и доступность владельцев", body = BlockPreviewResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn preview_block(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(payload): Json<CreateBlockRequest>,
+) -> AppResult<Json<BlockPreviewResponse>> {
+    let response = state
+        .block_service
+        .preview_block(
+            auth_state.user_id,
+            payload,
+            &state.block_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.telephony_service,
+            &state.telegram_service,
+        )
+        .await?;
+
+    Ok(Json(response))
 }
 
 /// Получить список тех, кто перекрыл мои автомобили
@@ -79,10 +146,12 @@ pub async fn create_block(
     get,
     path = "/api/blocks/my",
     params(
-        ("my_plate" = Option<String>, Query, description = "Фильтр по номеру автомобиля (опционально)")
+        ("my_plate" = Option<String>, Query, description = "Фильтр по номеру автомобиля (опционально)"),
+        ("limit" = Option<i64>, Query, description = "Размер страницы (по умолчанию 50, максимум 100)"),
+        ("offset" = Option<i64>, Query, description = "Смещение (по умолчанию 0)")
     ),
     responses(
-        (status = 200, description = "Список блокировок", body = Vec<BlockWithBlockerInfo>),
+        (status = 200, description = "Массив блокировок; при `limit`/`offset` - страница", body = BlockWithBlockerInfoPage),
         (status = 401, description = "Не авторизован"),
     ),
     security(("bearer_token" = [])),
@@ -92,29 +161,36 @@ pub async fn get_blocks_for_my_plate(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
     Query(params): Query<GetBlocksQuery>,
-) -> AppResult<Json<Vec<BlockWithBlockerInfo>>> {
+    pagination: OptionalPagination,
+) -> AppResult<Response> {
     let user_id = auth_state.user_id;
 
-    let blocks = state
+    let (blocks, total) = state
         .block_service
         .get_blocks_for_my_plate(
             user_id,
             params.my_plate,
+            pagination.limit(),
+            pagination.offset(),
             &state.block_repository,
             &state.user_repository,
             &state.user_plate_repository,
         )
         .await?;
 
-    Ok(Json(blocks))
+    Ok(pagination.into_response(blocks, total))
 }
 
 /// Получить список автомобилей, которые перекрыл текущий пользователь
 #[utoipa::path(
     get,
     path = "/api/blocks",
+    params(
+        ("limit" = Option<i64>, Query, description = "Размер страницы (по умолчанию 50, максимум 100)"),
+        ("offset" = Option<i64>, Query, description = "Смещение (по умолчанию 0)")
+    ),
     responses(
-        (status = 200, description = "Список созданных блокировок", body = Vec<Block>),
+        (status = 200, description = "Массив созданных блокировок; при `limit`/`offset` - страница", body = BlockPage),
         (status = 401, description = "Не авторизован"),
     ),
     security(("bearer_token" = [])),
@@ -123,19 +199,61 @@ pub async fn get_blocks_for_my_plate(
 pub async fn get_my_blocks(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
-) -> AppResult<Json<Vec<Block>>> {
+    pagination: OptionalPagination,
+) -> AppResult<Response> {
     let blocker_id = auth_state.user_id;
 
-    let blocks = state
+    let (blocks, total) = state
         .block_service
         .get_my_blocks(
             blocker_id,
+            pagination.limit(),
+            pagination.offset(),
             &state.block_repository,
             &state.user_plate_repository,
         )
         .await?;
 
-    Ok(Json(blocks))
+    Ok(pagination.into_response(blocks, total))
+}
+
+/// CSV-ответ для скачивания: тело отдаётся потоком, имя файла с текущей датой
+pub(crate) fn csv_attachment(stream: CsvStream, name: &str) -> Response {
+    let filename = format!("{}-{}.csv", name, chrono::Utc::now().format("%Y%m%d"));
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        axum::body::Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// Выгрузить свои блокировки в CSV: созданные пользователем и перекрывшие его номера,
+/// включая снятые. Контакты и идентификаторы других пользователей не выгружаются
+#[utoipa::path(
+    get,
+    path = "/api/blocks/export.csv",
+    responses(
+        (status = 200, description = "CSV: id, role (blocker/blocked), blocker_plate, blocked_plate, status (active/acknowledged/expired/removed), created_at, expires_at, acknowledged_at, removed_at", content_type = "text/csv", body = String),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn export_my_blocks_csv(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Response {
+    let stream = stream_blocks_csv(
+        state.block_repository.clone(),
+        BlockExportScope::User(auth_state.user_id),
+    );
+    csv_attachment(stream, "blocks")
 }
 
 /// Удалить блокировку
@@ -148,6 +266,7 @@ pub async fn get_my_blocks(
     responses(
         (status = 200, description = "Блокировка удалена"),
         (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на удаление блокировки"),
         (status = 404, description = "Блокировка не найдена"),
     ),
     security(("bearer_token" = [])),
@@ -169,6 +288,7 @@ pub async fn delete_block(
             &state.notification_repository,
             &state.user_repository,
             &state.user_plate_repository,
+            &state.notification_outbox_repository,
         )
         .await?;
 
@@ -177,12 +297,162 @@ pub async fn delete_block(
     ))
 }
 
+/// Изменить блокировку: время выезда (или продолжительность) и заметку
+#[utoipa::path(
+    patch,
+    path = "/api/blocks/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки")
+    ),
+    request_body = UpdateBlockRequest,
+    responses(
+        (status = 200, description = "Блокировка обновлена", body = Block),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на изменение блокировки"),
+        (status = 404, description = "Блокировка не найдена"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn update_block(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+    Json(payload): Json<UpdateBlockRequest>,
+) -> AppResult<Json<Block>> {
+    let block = state
+        .block_service
+        .update_block(
+            block_id,
+            auth_state.user_id,
+            payload,
+            &state.block_repository,
+            &state.notification_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.notification_outbox_repository,
+        )
+        .await?;
+
+    Ok(Json(block))
+}
+
+/// Подтвердить, что владелец увидел уведомление о блокировке
+#[utoipa::path(
+    post,
+    path = "/api/blocks/{id}/ack",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки")
+    ),
+    responses(
+        (status = 200, description = "Блокировка подтверждена", body = Block),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Заблокирован не ваш автомобиль"),
+        (status = 404, description = "Блокировка не найдена"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn acknowledge_block(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+) -> AppResult<Json<Block>> {
+    let block = state
+        .block_service
+        .acknowledge_block(
+            block_id,
+            auth_state.user_id,
+            &state.block_repository,
+            &state.user_plate_repository,
+        )
+        .await?;
+
+    Ok(Json(block))
+}
+
+/// Попросить блокирующего перезвонить (владелец заблокированного авто)
+#[utoipa::path(
+    post,
+    path = "/api/blocks/{id}/request-callback",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки")
+    ),
+    responses(
+        (status = 200, description = "Просьба отправлена блокирующему", body = CallbackRequestResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Заблокирован не ваш автомобиль"),
+        (status = 404, description = "Блокировка не найдена"),
+        (status = 429, description = "Просьба по этой блокировке уже отправлялась недавно"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn request_callback(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+) -> AppResult<Json<CallbackRequestResponse>> {
+    let response = state
+        .block_service
+        .request_callback(
+            block_id,
+            auth_state.user_id,
+            &state.block_repository,
+            &state.notification_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.notification_outbox_repository,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Проверить, можно ли связаться с владельцами заблокированного авто
+#[utoipa::path(
+    get,
+    path = "/api/blocks/{id}/notifiability",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки")
+    ),
+    responses(
+        (status = 200, description = "Доступность владельцев", body = BlockNotifiabilityResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на просмотр блокировки"),
+        (status = 404, description = "Блокировка не найдена"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn get_block_notifiability(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+) -> AppResult<Json<BlockNotifiabilityResponse>> {
+    let response = state
+        .block_service
+        .get_block_notifiability(
+            block_id,
+            auth_state.user_id,
+            &state.block_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.push_token_repository,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
 #[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct CheckBlockQuery {
     pub plate: String,
 }
 
-/// Проверить, заблокирована ли машина
+/// Проверить, заблокирована ли машина (`is_own_plate` - номер принадлежит текущему пользователю)
 #[utoipa::path(
     get,
     path = "/api/blocks/check",
@@ -198,20 +468,166 @@ pub struct CheckBlockQuery {
 )]
 pub async fn check_block(
     State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
     Query(params): Query<CheckBlockQuery>,
 ) -> AppResult<Json<CheckBlockResponse>> {
     let response = state
         .block_service
-        .check_block(
+        .check_block_for_user(
+            auth_state.user_id,
             &params.plate,
             &state.block_repository,
             &state.user_repository,
+            &state.user_plate_repository,
         )
         .await?;
 
     Ok(Json(response))
 }
 
+/// ETag состояния блокировки номера: хэш содержимого ответа без `input_plate`
+/// (тот же номер, введённый иначе, - то же состояние).
+/// Меняется при появлении/удалении блокировки и при изменении данных о ней
+fn check_block_etag(response: &CheckBlockResponse) -> AppResult<String> {
+    let mut state = serde_json::to_value(response)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize check response: {}", e)))?;
+    if let Some(fields) = state.as_object_mut() {
+        fields.remove("input_plate");
+    }
+    let body = state.to_string();
+    let digest = format!("{:x}", Sha256::digest(&body));
+    Ok(format!("\"{}\"", &digest[..32]))
+}
+
+/// Проверить, заблокирована ли машина (для киосков: поддерживает ETag / If-None-Match)
+#[utoipa::path(
+    get,
+    path = "/api/blocks/check/{plate}",
+    params(
+        ("plate" = String, Path, description = "Номер автомобиля"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag из предыдущего ответа")
+    ),
+    responses(
+        (status = 200, description = "Статус блокировки (с заголовком ETag)", body = CheckBlockResponse),
+        (status = 304, description = "Статус не изменился с момента выдачи ETag"),
+        (status = 400, description = "Неверный номер"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn check_block_by_plate(
+    State(state): State<AppState>,
+    Path(plate): Path<String>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let response = state
+        .block_service
+        .check_block(&plate, &state.block_repository, &state.user_repository)
+        .await?;
+
+    let etag = check_block_etag(&response)?;
+    let not_modified = etag_matches(&headers, &etag);
+    Ok(check_block_response(response, etag, not_modified))
+}
+
+/// Совпадает ли ETag с одним из значений заголовка If-None-Match
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+        })
+}
+
+/// Ответ проверки номера с заголовком ETag: 304 без тела, если состояние не изменилось
+fn check_block_response(
+    response: CheckBlockResponse,
+    etag: String,
+    not_modified: bool,
+) -> Response {
+    let cache_headers = [
+        (header::ETAG, etag),
+        (header::CACHE_CONTROL, "no-cache".to_string()),
+    ];
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+    (cache_headers, Json(response)).into_response()
+}
+
+/// Максимальное время ожидания long-poll проверки номера, секунд
+const LONGPOLL_MAX_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct LongPollQuery {
+    /// Сколько секунд ждать изменения (по умолчанию 30, не больше 60)
+    pub timeout: Option<u64>,
+}
+
+/// Long-poll проверка номера для устройств без WebSocket/SSE (шлагбаумы, контроллеры).
+/// Если состояние уже отличается от `If-None-Match`, отвечает сразу; иначе ждёт создания
+/// или снятия блокировки этого номера до `timeout` секунд и при отсутствии изменений возвращает 304
+#[utoipa::path(
+    get,
+    path = "/api/blocks/check/{plate}/longpoll",
+    params(
+        ("plate" = String, Path, description = "Номер автомобиля"),
+        ("timeout" = Option<u64>, Query, description = "Сколько секунд ждать изменения (по умолчанию 30, максимум 60)"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag из предыдущего ответа")
+    ),
+    responses(
+        (status = 200, description = "Статус блокировки изменился (с заголовком ETag)", body = CheckBlockResponse),
+        (status = 304, description = "За время ожидания статус не изменился"),
+        (status = 400, description = "Неверный номер"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn check_block_longpoll(
+    State(state): State<AppState>,
+    Path(plate): Path<String>,
+    Query(query): Query<LongPollQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let normalized_plate = ValidationService::validate_plate(&plate)?;
+    let timeout = query
+        .timeout
+        .unwrap_or(30)
+        .clamp(1, LONGPOLL_MAX_TIMEOUT_SECS);
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(timeout);
+
+    // Подписываемся до первой проверки, чтобы не пропустить изменение между ними
+    let mut events = state.block_service.events().subscribe();
+    loop {
+        let response = state
+            .block_service
+            .check_block(&plate, &state.block_repository, &state.user_repository)
+            .await?;
+        let etag = check_block_etag(&response)?;
+        if !etag_matches(&headers, &etag) {
+            return Ok(check_block_response(response, etag, false));
+        }
+
+        // Ждём события по этому номеру; после него перепроверяем состояние
+        loop {
+            match tokio::time::timeout_at(deadline, events.recv()).await {
+                Err(_) | Ok(Err(RecvError::Closed)) => {
+                    return Ok(check_block_response(response, etag, true));
+                }
+                Ok(Ok(changed_plate)) if changed_plate != normalized_plate => continue,
+                Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => break,
+            }
+        }
+    }
+}
+
 /// Предупредить владельца заблокированного автомобиля (звонок)
 #[utoipa::path(
     post,
@@ -222,6 +638,7 @@ pub async fn check_block(
     responses(
         (status = 200, description = "Владелец предупрежден"),
         (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на предупреждение по этой блокировке"),
         (status = 404, description = "Блокировка не найдена"),
     ),
     security(("bearer_token" = [])),
@@ -248,6 +665,7 @@ pub async fn warn_owner(
             &state.block_repository,
             &state.user_repository,
             &state.user_plate_repository,
+            &state.notification_outbox_repository,
             &state.telephony_service,
         )
         .await
@@ -261,3 +679,151 @@ pub async fn warn_owner(
         serde_json::json!({ "message": "Owner warned successfully" }),
     ))
 }
+
+/// Загрузить фото-доказательство блокировки (multipart, поле "image", JPEG или PNG)
+#[utoipa::path(
+    post,
+    path = "/api/blocks/{id}/photo",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки")
+    ),
+    responses(
+        (status = 200, description = "Фото загружено"),
+        (status = 400, description = "Неверное изображение"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на загрузку фото"),
+        (status = 404, description = "Блокировка не найдена"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn upload_block_photo(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<serde_json::Value>> {
+    let mut image_data: Option<Vec<u8>> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("Failed to read multipart field: {}", e)))?
+    {
+        if field.name() == Some("image") {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::Validation(format!("Failed to read image data: {}", e)))?;
+            image_data = Some(data.to_vec());
+            break;
+        }
+    }
+
+    let image_data =
+        image_data.ok_or_else(|| AppError::Validation("Image field is required".to_string()))?;
+
+    state
+        .block_service
+        .upload_block_photo(
+            block_id,
+            auth_state.user_id,
+            &image_data,
+            &state.block_repository,
+            &state.user_plate_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Photo uploaded successfully" }),
+    ))
+}
+
+/// Получить фото-доказательство блокировки (доступно блокирующему и владельцам заблокированного авто)
+#[utoipa::path(
+    get,
+    path = "/api/blocks/{id}/photo",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки"),
+        ("size" = Option<String>, Query, description = "Размер: thumb (превью) или full (оригинал, по умолчанию)")
+    ),
+    responses(
+        (status = 200, description = "Изображение (image/jpeg или image/png)"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на просмотр фото"),
+        (status = 404, description = "Блокировка или фото не найдены"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn get_block_photo(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(block_id): Path<Uuid>,
+    Query(params): Query<BlockPhotoQuery>,
+) -> AppResult<impl IntoResponse> {
+    let (data, content_type) = state
+        .block_service
+        .get_block_photo(
+            block_id,
+            auth_state.user_id,
+            params.size,
+            &state.block_repository,
+            &state.user_plate_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    Ok(([(header::CONTENT_TYPE, content_type)], data))
+}
+
+/// Получить подписанную ссылку на фото блокировки: по ней фото скачивается без JWT
+/// (например, для кэширования или показа в другом приложении)
+#[utoipa::path(
+    get,
+    path = "/api/blocks/{id}/photo-url",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки"),
+        ("size" = Option<String>, Query, description = "Размер: thumb (превью) или full (оригинал, по умолчанию)")
+    ),
+    responses(
+        (status = 200, description = "Подписанная ссылка на фото", body = SignedUrlResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав на просмотр фото"),
+        (status = 404, description = "Блокировка или фото не найдены"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "blocks"
+)]
+pub async fn get_block_photo_url(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
+    Path(block_id): Path<Uuid>,
+    Query(params): Query<BlockPhotoQuery>,
+) -> AppResult<Json<SignedUrlResponse>> {
+    let block = state
+        .block_service
+        .find_block_for_photo(
+            block_id,
+            auth_state.user_id,
+            &state.block_repository,
+            &state.user_plate_repository,
+        )
+        .await?;
+    let has_photo = match params.size {
+        BlockPhotoSize::Thumb => block.photo_thumb_key.is_some(),
+        BlockPhotoSize::Full => block.photo_key.is_some(),
+    };
+    if !has_photo {
+        return Err(AppError::NotFound("Block photo not found".to_string()));
+    }
+
+    let mut signed = sign_path(
+        &block_photo_download_path(block_id, params.size),
+        &state.config,
+    );
+    signed.url = public_url.absolute(&signed.url);
+    Ok(Json(signed))
+}
@@ -0,0 +1,11 @@
+use crate::error::AppError;
+
+/// Ответ для неизвестных маршрутов (404) в общем формате ошибок
+pub async fn route_not_found() -> AppError {
+    AppError::NotFound("Route not found".to_string())
+}
+
+/// Ответ для существующего маршрута с неподдерживаемым методом (405) в общем формате ошибок
+pub async fn method_not_allowed() -> AppError {
+    AppError::MethodNotAllowed("Method not allowed".to_string())
+}
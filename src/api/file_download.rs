@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{get, Router},
+};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::auth::signed_url::{
+    avatar_download_path, block_photo_download_path, signed_cache_control, verify_signed_path,
+};
+use crate::error::{AppError, AppResult};
+use crate::models::block::BlockPhotoSize;
+use crate::models::download::SignedUrlQuery;
+use crate::repository::BlockRepository;
+use crate::service::BlockService;
+
+/// Скачивание фото блокировок и аватаров по подписанным ссылкам (без JWT).
+/// Права проверяются при выдаче ссылки, здесь - только подпись и срок
+pub fn file_download_router() -> Router<AppState> {
+    Router::new()
+        .route("/blocks/:id/photo/:size", get(download_block_photo))
+        .route("/avatars/:id", get(download_avatar))
+}
+
+/// Скачать фото блокировки по подписанной ссылке
+#[utoipa::path(
+    get,
+    path = "/api/files/blocks/{id}/photo/{size}",
+    params(
+        ("id" = Uuid, Path, description = "ID блокировки"),
+        ("size" = String, Path, description = "Размер: thumb или full"),
+        ("expires" = i64, Query, description = "Срок действия ссылки (Unix time)"),
+        ("signature" = String, Query, description = "Подпись ссылки")
+    ),
+    responses(
+        (status = 200, description = "Изображение (image/jpeg или image/png)"),
+        (status = 401, description = "Ссылка без подписи"),
+        (status = 403, description = "Подпись неверна или истекла"),
+        (status = 404, description = "Блокировка или фото не найдены"),
+    ),
+    tag = "files"
+)]
+pub async fn download_block_photo(
+    State(state): State<AppState>,
+    Path((block_id, size)): Path<(Uuid, BlockPhotoSize)>,
+    Query(signed): Query<SignedUrlQuery>,
+) -> AppResult<impl IntoResponse> {
+    verify_signed_path(
+        &block_photo_download_path(block_id, size),
+        &signed,
+        &state.config,
+    )?;
+
+    let block = state
+        .block_repository
+        .find_by_id(block_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+    let (data, content_type) =
+        BlockService::read_block_photo(block, size, &state.blob_store).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CACHE_CONTROL, signed_cache_control(&signed)),
+        ],
+        data,
+    ))
+}
+
+/// Скачать аватар пользователя по подписанной ссылке
+#[utoipa::path(
+    get,
+    path = "/api/files/avatars/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID пользователя"),
+        ("expires" = i64, Query, description = "Срок действия ссылки (Unix time)"),
+        ("signature" = String, Query, description = "Подпись ссылки")
+    ),
+    responses(
+        (status = 200, description = "Изображение (image/jpeg)"),
+        (status = 401, description = "Ссылка без подписи"),
+        (status = 403, description = "Подпись неверна или истекла"),
+        (status = 404, description = "Аватар не загружен"),
+    ),
+    tag = "files"
+)]
+pub async fn download_avatar(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Query(signed): Query<SignedUrlQuery>,
+) -> AppResult<impl IntoResponse> {
+    verify_signed_path(&avatar_download_path(user_id), &signed, &state.config)?;
+
+    let data = state
+        .user_service
+        .read_avatar(user_id, &state.user_repository, &state.blob_store)
+        .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg".to_string()),
+            (header::CACHE_CONTROL, signed_cache_control(&signed)),
+        ],
+        data,
+    ))
+}
@@ -0,0 +1,64 @@
+use crate::api::AppState;
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Готовность сервера принимать трафик (становится true после применения миграций)
+#[derive(Clone, Default)]
+pub struct ReadinessState {
+    ready: Arc<AtomicBool>,
+}
+
+impl ReadinessState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::Release);
+    }
+}
+
+/// Счётчики деградации с момента запуска экземпляра
+#[derive(Serialize)]
+pub struct HealthMetrics {
+    /// Операции с кодами подтверждения, выполненные в памяти из-за сбоев Redis
+    /// (null, если REDIS_FALLBACK_INMEMORY не включён)
+    pub code_store_fallback_operations: Option<u64>,
+    /// Ошибки расшифровки персональных данных
+    pub decrypt_failures: u64,
+}
+
+/// `/health` - liveness (доступен сразу после старта),
+/// `/health/ready` - readiness (503, пока не применены миграции),
+/// `/health/metrics` - счётчики деградации
+pub fn health_router() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/health/ready", get(readiness_check))
+        .route("/health/metrics", get(health_metrics))
+}
+
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+async fn readiness_check(State(state): State<AppState>) -> (StatusCode, &'static str) {
+    if state.readiness.is_ready() {
+        (StatusCode::OK, "READY")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "NOT READY")
+    }
+}
+
+async fn health_metrics(State(state): State<AppState>) -> Json<HealthMetrics> {
+    Json(HealthMetrics {
+        code_store_fallback_operations: state.sms_service.code_store_fallback_operations(),
+        decrypt_failures: state.encryption.decrypt_failures(),
+    })
+}
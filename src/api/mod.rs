@@ -1,29 +1,49 @@
+pub mod admin;
+pub mod api_doc;
 pub mod app_download;
 pub mod auth;
 pub mod block;
+pub mod fallback;
+pub mod file_download;
+pub mod health;
 pub mod notification;
 pub mod ocr;
+pub mod pagination;
+pub mod security;
 pub mod server_info;
 pub mod user;
 pub mod user_plate;
+pub mod validation;
 
+pub use admin::*;
+pub use api_doc::*;
 pub use app_download::*;
 pub use auth::*;
 pub use block::*;
+pub use fallback::*;
+pub use file_download::*;
+pub use health::*;
 pub use notification::*;
 pub use ocr::*;
+pub use pagination::*;
+pub use security::*;
 pub use server_info::*;
 pub use user::*;
 pub use user_plate::*;
+pub use validation::*;
 
 use crate::auth::sms::SmsService;
 use crate::config::Config;
 use crate::repository::{
-    PostgresBlockRepository, PostgresNotificationRepository, PostgresUserPlateRepository,
-    PostgresUserRepository,
+    FsBlobStore, PostgresAuditLogRepository, PostgresBlockRepository,
+    PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+    PostgresPlateShareInviteRepository, PostgresPlateTransferRequestRepository,
+    PostgresPushTokenRepository, PostgresRevokedTokenRepository, PostgresTelegramBotRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, PostgresUserSessionRepository,
 };
 use crate::service::{
-    AuthService, BlockService, PushService, TelegramService, TelephonyService, UserService,
+    AdminService, AnalyticsService, AuthService, BlockService, PushService, TelegramService,
+    TelephonyService, UserService,
 };
 use crate::utils::encryption::Encryption;
 
@@ -35,11 +55,23 @@ pub struct AppState {
     pub telephony_service: TelephonyService,
     pub telegram_service: TelegramService,
     pub push_service: PushService,
+    pub analytics: AnalyticsService,
     pub auth_service: AuthService,
     pub user_service: UserService,
     pub block_service: BlockService,
+    pub admin_service: AdminService,
     pub user_repository: PostgresUserRepository,
     pub block_repository: PostgresBlockRepository,
     pub user_plate_repository: PostgresUserPlateRepository,
+    pub plate_transfer_request_repository: PostgresPlateTransferRequestRepository,
+    pub plate_share_invite_repository: PostgresPlateShareInviteRepository,
     pub notification_repository: PostgresNotificationRepository,
+    pub notification_outbox_repository: PostgresNotificationOutboxRepository,
+    pub push_token_repository: PostgresPushTokenRepository,
+    pub telegram_bot_repository: PostgresTelegramBotRepository,
+    pub audit_log_repository: PostgresAuditLogRepository,
+    pub revoked_token_repository: PostgresRevokedTokenRepository,
+    pub user_session_repository: PostgresUserSessionRepository,
+    pub blob_store: FsBlobStore,
+    pub readiness: ReadinessState,
 }
@@ -1,56 +1,142 @@
 use axum::{
     extract::{Extension, Path, Query, State},
     response::Json,
-    routing::{get, patch, Router},
+    routing::{get, patch, post, Router},
 };
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::api::pagination::{Page, Pagination};
 use crate::api::AppState;
 use crate::auth::middleware::AuthState;
-use crate::error::AppResult;
-use crate::models::notification::NotificationResponse;
-use crate::repository::NotificationRepository;
+use crate::error::{AppError, AppResult};
+use crate::models::notification::{
+    NotificationPage, NotificationResponse, ResendNotificationResponse,
+};
+use crate::models::outbox::OutboxChannel;
+use crate::repository::{NotificationOutboxRepository, NotificationRepository, UserRepository};
+use crate::service::notification_outbox::resend_messages;
+
+/// Минимальный интервал между повторными отправками одного уведомления
+const NOTIFICATION_RESEND_INTERVAL_SECS: i64 = 60;
+
+/// Размер страницы уведомлений по умолчанию (меньше общего: список листают в приложении)
+const NOTIFICATIONS_DEFAULT_LIMIT: i64 = 20;
 
 pub fn notification_router() -> Router<AppState> {
     Router::new()
-        .route("/", get(get_notifications))
+        .route(
+            "/",
+            get(get_notifications).delete(delete_read_notifications),
+        )
+        .route("/:id", get(get_notification).delete(delete_notification))
         .route("/:id/read", patch(mark_notification_read))
+        .route("/:id/resend", post(resend_notification))
+        .route("/:id/delivered", post(report_delivered))
+        .route("/:id/opened", post(report_opened))
         .route("/read-all", patch(mark_all_read))
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct GetNotificationsQuery {
     pub unread_only: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 async fn get_notifications(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
     Query(params): Query<GetNotificationsQuery>,
-) -> AppResult<Json<Vec<NotificationResponse>>> {
+) -> AppResult<Json<NotificationPage>> {
     let user_id = auth_state.user_id;
     let unread_only = params.unread_only.unwrap_or(false);
+    let pagination = Pagination::new(
+        Some(params.limit.unwrap_or(NOTIFICATIONS_DEFAULT_LIMIT)),
+        params.offset,
+    )?;
 
-    let notifications = state
+    let (notifications, total) = state
         .notification_repository
-        .find_by_user_id(user_id, unread_only)
+        .find_by_user_id(user_id, unread_only, pagination.limit, pagination.offset)
         .await?;
 
     let responses: Vec<NotificationResponse> = notifications
         .into_iter()
-        .map(|n| NotificationResponse {
-            id: n.id,
-            r#type: n.r#type,
-            title: n.title,
-            message: n.message,
-            data: n.data,
-            read: n.read,
-            created_at: n.created_at,
-        })
+        .map(NotificationResponse::from)
         .collect();
+    let page = Page::new(responses, total, pagination);
+
+    Ok(Json(NotificationPage {
+        items: page.items,
+        total_count: page.total,
+        limit: page.limit,
+        offset: page.offset,
+        has_more: page.has_more,
+    }))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct GetNotificationQuery {
+    /// Отметить уведомление прочитанным при открытии (по умолчанию true)
+    pub mark_read: Option<bool>,
+}
+
+async fn get_notification(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(notification_id): Path<Uuid>,
+    Query(params): Query<GetNotificationQuery>,
+) -> AppResult<Json<NotificationResponse>> {
+    let user_id = auth_state.user_id;
+
+    // Чужое уведомление неотличимо от несуществующего
+    let mut notification = state
+        .notification_repository
+        .find_by_id(notification_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+
+    if params.mark_read.unwrap_or(true) && !notification.read {
+        state
+            .notification_repository
+            .mark_as_read(notification_id, user_id)
+            .await?;
+        notification.read = true;
+    }
+
+    Ok(Json(NotificationResponse::from(notification)))
+}
+
+async fn delete_notification(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(notification_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    // Запрос ограничен user_id: чужое уведомление неотличимо от несуществующего
+    state
+        .notification_repository
+        .delete(notification_id, auth_state.user_id)
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Notification deleted" }),
+    ))
+}
+
+/// Удаляет все прочитанные уведомления пользователя (непрочитанные остаются)
+async fn delete_read_notifications(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<serde_json::Value>> {
+    let deleted = state
+        .notification_repository
+        .delete_read(auth_state.user_id)
+        .await?;
 
-    Ok(Json(responses))
+    Ok(Json(serde_json::json!({ "deleted": deleted })))
 }
 
 async fn mark_notification_read(
@@ -70,6 +156,139 @@ async fn mark_notification_read(
     ))
 }
 
+/// Повторно отправляет уведомление получателю (push, Telegram) через очередь доставки.
+/// Доступно получателю и администраторам
+async fn resend_notification(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(notification_id): Path<Uuid>,
+) -> AppResult<Json<ResendNotificationResponse>> {
+    let user_id = auth_state.user_id;
+    let is_admin = state.config.admin_user_ids.contains(&user_id);
+
+    // Чужое уведомление неотличимо от несуществующего
+    let notification = state
+        .notification_repository
+        .find_any_by_id(notification_id)
+        .await?
+        .filter(|n| is_admin || n.user_id == user_id)
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+    let recipient = state
+        .user_repository
+        .find_by_id(notification.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let messages = resend_messages(&notification, &recipient);
+    if messages.is_empty() {
+        return Err(AppError::Validation(
+            "Recipient has neither a push token nor Telegram".to_string(),
+        ));
+    }
+
+    let interval = chrono::Duration::seconds(NOTIFICATION_RESEND_INTERVAL_SECS);
+    if !state
+        .notification_repository
+        .mark_resent(notification_id, interval)
+        .await?
+    {
+        let retry_after_secs = notification
+            .last_resent_at
+            .map(|at| (at + interval - chrono::Utc::now()).num_seconds())
+            .unwrap_or(NOTIFICATION_RESEND_INTERVAL_SECS)
+            .max(1) as u64;
+        return Err(AppError::RateLimited {
+            message: "Notification was resent recently".to_string(),
+            retry_after_secs,
+        });
+    }
+
+    state
+        .notification_outbox_repository
+        .enqueue(&messages)
+        .await?;
+
+    let channels: Vec<String> = messages
+        .iter()
+        .map(|m| m.channel.as_str().to_string())
+        .collect();
+    tracing::info!(
+        "User {} resent notification {} via {:?}",
+        user_id,
+        notification_id,
+        channels
+    );
+
+    Ok(Json(ResendNotificationResponse {
+        notification_id,
+        channels,
+    }))
+}
+
+/// Приложение подтверждает, что push-уведомление получено
+async fn report_delivered(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(notification_id): Path<Uuid>,
+) -> AppResult<Json<NotificationResponse>> {
+    record_receipt(&state, auth_state.user_id, notification_id, false)
+        .await
+        .map(Json)
+}
+
+/// Приложение сообщает, что пользователь открыл push-уведомление (оно становится прочитанным)
+async fn report_opened(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(notification_id): Path<Uuid>,
+) -> AppResult<Json<NotificationResponse>> {
+    record_receipt(&state, auth_state.user_id, notification_id, true)
+        .await
+        .map(Json)
+}
+
+/// Записывает подтверждение в уведомление и в очередь доставки. Каждое событие
+/// учитывается один раз: повторные подтверждения не меняют статистику
+async fn record_receipt(
+    state: &AppState,
+    user_id: Uuid,
+    notification_id: Uuid,
+    opened: bool,
+) -> AppResult<NotificationResponse> {
+    // Чужое уведомление неотличимо от несуществующего
+    let (notification, first_report) = state
+        .notification_repository
+        .record_receipt(notification_id, user_id, opened)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+
+    if first_report {
+        let block_id = notification
+            .data
+            .as_ref()
+            .and_then(|data| data.get("block_id"))
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.parse::<Uuid>().ok());
+        if let Some(block_id) = block_id {
+            state
+                .notification_outbox_repository
+                .record_receipt(user_id, block_id, OutboxChannel::Push, opened)
+                .await?;
+        }
+        state.analytics.track(
+            if opened {
+                "notification_opened"
+            } else {
+                "notification_delivered"
+            },
+            Some(user_id),
+            serde_json::json!({ "type": notification.r#type, "channel": "push" }),
+        );
+    }
+
+    Ok(NotificationResponse::from(notification))
+}
+
 async fn mark_all_read(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
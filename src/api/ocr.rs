@@ -2,6 +2,7 @@ use crate::api::AppState;
 use crate::auth::middleware::AuthState;
 use crate::error::{AppError, AppResult};
 use crate::utils::ocr::recognize_plate_from_image;
+use crate::utils::{normalize_plate, validate_plate};
 use axum::{
     extract::{Extension, Multipart, State},
     response::Json,
@@ -18,7 +19,7 @@ pub fn ocr_router() -> Router<AppState> {
 
 // Открытый эндпоинт (без авторизации)
 async fn recognize_plate(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> AppResult<Json<serde_json::Value>> {
     let mut image_data: Option<Vec<u8>> = None;
@@ -41,21 +42,14 @@ async fn recognize_plate(
     let image_data =
         image_data.ok_or_else(|| AppError::Validation("Image field is required".to_string()))?;
 
-    match recognize_plate_from_image(&image_data).await {
-        Ok(plate) => Ok(Json(json!({
-            "success": true,
-            "plate": plate,
-        }))),
-        Err(e) => Ok(Json(json!({
-            "success": false,
-            "error": e.to_string(),
-        }))),
-    }
+    Ok(Json(plate_recognition_response(
+        recognize_plate_from_image(state.config.ocr_api_url.as_deref(), &image_data).await,
+    )))
 }
 
 // Защищенный эндпоинт (с авторизацией)
 async fn recognize_plate_auth(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Extension(_auth_state): Extension<AuthState>,
     mut multipart: Multipart,
 ) -> AppResult<Json<serde_json::Value>> {
@@ -79,14 +73,35 @@ async fn recognize_plate_auth(
     let image_data =
         image_data.ok_or_else(|| AppError::Validation("Image field is required".to_string()))?;
 
-    match recognize_plate_from_image(&image_data).await {
-        Ok(plate) => Ok(Json(json!({
-            "success": true,
-            "plate": plate,
-        }))),
-        Err(e) => Ok(Json(json!({
+    Ok(Json(plate_recognition_response(
+        recognize_plate_from_image(state.config.ocr_api_url.as_deref(), &image_data).await,
+    )))
+}
+
+/// Ответ распознавания. Если OCR вернул строку, не похожую на номер, отдаём её как есть
+/// с `valid: false`, чтобы клиент попросил пользователя подтвердить или исправить номер
+pub fn plate_recognition_response(result: AppResult<String>) -> serde_json::Value {
+    match result {
+        Ok(raw) => {
+            let normalized = normalize_plate(&raw);
+            if validate_plate(&normalized) {
+                json!({
+                    "success": true,
+                    "valid": true,
+                    "plate": normalized,
+                })
+            } else {
+                tracing::info!("OCR returned an invalid plate: {}", raw);
+                json!({
+                    "success": true,
+                    "valid": false,
+                    "plate": raw,
+                })
+            }
+        }
+        Err(e) => json!({
             "success": false,
             "error": e.to_string(),
-        }))),
+        }),
     }
 }
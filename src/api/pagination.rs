@@ -0,0 +1,149 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::models::block::{ActiveBlockInfo, Block, BlockWithBlockerInfo};
+
+/// Размер страницы по умолчанию
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Максимальный размер страницы (большие значения урезаются до него)
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct PaginationQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Параметры постраничной выдачи (`?limit=&offset=`).
+/// `limit` по умолчанию `DEFAULT_PAGE_LIMIT` и ограничен диапазоном 1..=`MAX_PAGE_LIMIT`,
+/// `offset` по умолчанию 0 и не может быть отрицательным
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_PAGE_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+impl Pagination {
+    pub fn new(limit: Option<i64>, offset: Option<i64>) -> Result<Self, AppError> {
+        let offset = offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(AppError::Validation(
+                "offset не может быть отрицательным".to_string(),
+            ));
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+
+        Ok(Self { limit, offset })
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PaginationQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| AppError::Validation(format!("Неверные параметры пагинации: {}", e)))?;
+
+        Self::new(query.limit, query.offset)
+    }
+}
+
+/// Пагинация, если клиент её запросил: None, когда нет ни `limit`, ни `offset`.
+/// Такие запросы получают список массивом целиком, как до появления пагинации,
+/// потому что старые версии приложений разбирают ответ как массив
+#[derive(Debug, Clone, Copy)]
+pub struct OptionalPagination(pub Option<Pagination>);
+
+impl OptionalPagination {
+    /// `limit` для SQL: None - без ограничения
+    pub fn limit(&self) -> Option<i64> {
+        self.0.map(|pagination| pagination.limit)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.0.map(|pagination| pagination.offset).unwrap_or(0)
+    }
+
+    /// Массив, если пагинация не запрошена, иначе страница `Page`
+    pub fn into_response<T: Serialize>(self, items: Vec<T>, total: i64) -> Response {
+        match self.0 {
+            Some(pagination) => Json(Page::new(items, total, pagination)).into_response(),
+            None => Json(items).into_response(),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for OptionalPagination {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PaginationQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| AppError::Validation(format!("Неверные параметры пагинации: {}", e)))?;
+
+        if query.limit.is_none() && query.offset.is_none() {
+            return Ok(Self(None));
+        }
+        Pagination::new(query.limit, query.offset).map(|pagination| Self(Some(pagination)))
+    }
+}
+
+/// Страница результатов
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[aliases(
+    BlockPage = Page<Block>,
+    BlockWithBlockerInfoPage = Page<BlockWithBlockerInfo>,
+    ActiveBlockInfoPage = Page<ActiveBlockInfo>
+)]
+pub struct Page<T> {
+    /// Элементы текущей страницы
+    pub items: Vec<T>,
+    /// Общее количество элементов
+    #[schema(example = 42)]
+    pub total: i64,
+    /// Размер страницы
+    #[schema(example = 50)]
+    pub limit: i64,
+    /// Смещение от начала
+    #[schema(example = 0)]
+    pub offset: i64,
+    /// Есть ли элементы после этой страницы
+    #[schema(example = false)]
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, pagination: Pagination) -> Self {
+        let has_more = pagination.offset + (items.len() as i64) < total;
+        Self {
+            items,
+            total,
+            limit: pagination.limit,
+            offset: pagination.offset,
+            has_more,
+        }
+    }
+}
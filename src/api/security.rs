@@ -0,0 +1,48 @@
+use axum::{
+    extract::State,
+    response::Json,
+    routing::{get, Router},
+};
+
+use crate::api::pagination::{Page, Pagination};
+use crate::api::AppState;
+use crate::error::AppResult;
+use crate::models::block::ActiveBlockInfo;
+
+/// Роутер API охраны (требует авторизации и роли охраны или администратора)
+pub fn security_router() -> Router<AppState> {
+    Router::new().route("/active-blocks", get(get_active_blocks))
+}
+
+/// Действующие блокировки на парковке (новые сначала)
+#[utoipa::path(
+    get,
+    path = "/api/security/active-blocks",
+    params(
+        ("limit" = Option<i64>, Query, description = "Размер страницы (по умолчанию 50, максимум 100)"),
+        ("offset" = Option<i64>, Query, description = "Смещение (по умолчанию 0)")
+    ),
+    responses(
+        (status = 200, description = "Страница действующих блокировок", body = ActiveBlockInfoPage),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Требуется роль охраны или администратора"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "security"
+)]
+pub async fn get_active_blocks(
+    State(state): State<AppState>,
+    pagination: Pagination,
+) -> AppResult<Json<Page<ActiveBlockInfo>>> {
+    let (blocks, total) = state
+        .block_service
+        .get_active_blocks(
+            pagination.limit,
+            pagination.offset,
+            &state.block_repository,
+            &state.user_repository,
+        )
+        .await?;
+
+    Ok(Json(Page::new(blocks, total, pagination)))
+}
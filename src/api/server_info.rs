@@ -1,24 +1,40 @@
 use crate::api::AppState;
+use crate::auth::signed_url::{sign_path, APP_DOWNLOAD_PATH};
+use crate::middleware::PublicBaseUrl;
 use crate::utils::network::get_server_url;
-use axum::{extract::State, response::Json, routing::get, Router};
+use axum::{
+    extract::{Extension, State},
+    http::header,
+    response::{IntoResponse, Json},
+    routing::get,
+    Router,
+};
 use serde_json::json;
 
 pub fn server_info_router() -> Router<AppState> {
     Router::new().route("/server-info", get(get_server_info))
 }
 
-async fn get_server_info(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let server_url = get_server_url(state.config.server_port);
+async fn get_server_info(
+    State(state): State<AppState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
+) -> impl IntoResponse {
+    // За прокси локальный адрес бесполезен клиенту - отдаём внешний, если он известен
+    let server_url = public_url
+        .0
+        .unwrap_or_else(|| get_server_url(state.config.server_port));
 
-    // Получаем URL для скачивания APK
-    let app_download_url = state
-        .config
-        .app_download_url
-        .clone()
-        .unwrap_or_else(|| format!("{}/api/app/download", server_url));
+    // Получаем URL для скачивания APK (свой APK - по подписанной ссылке,
+    // которая истекает через SIGNED_URL_EXPIRATION_MINUTES: ответ не кэшируется)
+    let app_download_url = state.config.app_download_url.clone().unwrap_or_else(|| {
+        format!(
+            "{}{}",
+            server_url,
+            sign_path(APP_DOWNLOAD_PATH, &state.config).url
+        )
+    });
 
-    // Получаем username бота из токена (если есть)
-    let telegram_bot_username = std::env::var("TELEGRAM_BOT_USERNAME").ok();
+    let telegram_bot_username = state.telegram_service.bot_username();
 
     // Автоматически определяем версию приложения на основе версии сервера
     // Если release_client_version не указан, используем server_version
@@ -28,7 +44,7 @@ async fn get_server_info(State(state): State<AppState>) -> Json<serde_json::Valu
         .clone()
         .or_else(|| Some(env!("CARGO_PKG_VERSION").to_string()));
 
-    Json(json!({
+    let info = Json(json!({
         "server_url": server_url,
         "port": state.config.server_port,
         "server_version": env!("CARGO_PKG_VERSION"),
@@ -36,5 +52,7 @@ async fn get_server_info(State(state): State<AppState>) -> Json<serde_json::Valu
         "release_client_version": auto_release_version,
         "app_download_url": app_download_url,
         "telegram_bot_username": telegram_bot_username,
-    }))
+    }));
+
+    ([(header::CACHE_CONTROL, "no-store")], info)
 }
@@ -1,26 +1,62 @@
 use axum::{
-    extract::{Extension, Query, State},
-    response::Json,
-    routing::{get, post, put, Router},
+    extract::{DefaultBodyLimit, Extension, Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json},
+    routing::{delete, get, post, put, Router},
 };
 use serde::Deserialize;
 use serde::Serialize;
+use uuid::Uuid;
 
 use crate::api::AppState;
-use crate::auth::middleware::AuthState;
-use crate::error::AppResult;
-use crate::models::user::{PublicUserInfo, UpdateUserRequest, UserResponse};
+use crate::auth::middleware::{AuthState, AuthToken};
+use crate::auth::signed_url::{avatar_download_path, sign_path};
+use crate::error::{AppError, AppResult};
+use crate::middleware::PublicBaseUrl;
+use crate::models::download::SignedUrlResponse;
+use crate::models::push_token::PUSH_PLATFORMS;
+use crate::models::session::SessionResponse;
+use crate::models::user::{
+    AccountDeletionResponse, PublicUserInfo, ReencryptResponse, TelegramReachabilityResponse,
+    UpdateUserRequest, UserResponse, UsersByPlatesRequest,
+};
 use crate::repository::user_repository::UserRepository;
+use crate::repository::PushTokenRepository;
+use crate::utils::image::MAX_IMAGE_SIZE;
+use crate::utils::projection::project_fields;
+use std::collections::BTreeMap;
 
 pub fn user_router() -> Router<AppState> {
     Router::new()
         .route("/me", get(get_profile))
         .route("/me", put(update_profile))
+        .route("/me", delete(delete_account))
+        .route("/me/reencrypt", post(reencrypt_profile))
+        .route("/me/sessions", get(list_sessions))
+        .route("/me/sessions/:id", delete(revoke_session))
+        .route(
+            "/me/avatar",
+            post(upload_avatar)
+                .get(get_my_avatar)
+                .layer(DefaultBodyLimit::max(MAX_IMAGE_SIZE)),
+        )
+        .route("/:id/avatar", get(get_user_avatar))
+        .route("/:id/avatar-url", get(get_user_avatar_url))
+        .route("/telegram/reachable", get(telegram_reachable))
         .route("/push-token", post(register_push_token))
         .route("/by-plate", get(get_user_by_plate))
+        .route("/by-plates", post(get_users_by_plates))
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct GetProfileQuery {
+    /// Список полей через запятую (например, `name,plate`). По умолчанию - все поля
+    pub fields: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct GetUserByPlateQuery {
     pub plate: String,
 }
@@ -29,8 +65,12 @@ pub struct GetUserByPlateQuery {
 #[utoipa::path(
     get,
     path = "/api/users/me",
+    params(
+        ("fields" = Option<String>, Query, description = "Вернуть только указанные поля (через запятую), id возвращается всегда")
+    ),
     responses(
         (status = 200, description = "Профиль пользователя", body = UserResponse),
+        (status = 400, description = "Неизвестное поле в fields"),
         (status = 401, description = "Не авторизован"),
     ),
     security(("bearer_token" = [])),
@@ -39,7 +79,8 @@ pub struct GetUserByPlateQuery {
 pub async fn get_profile(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
-) -> AppResult<Json<UserResponse>> {
+    Query(params): Query<GetProfileQuery>,
+) -> AppResult<Json<serde_json::Value>> {
     let user_id = auth_state.user_id;
 
     let response = state
@@ -50,7 +91,14 @@ pub async fn get_profile(
             &state.user_plate_repository,
         )
         .await?;
-    Ok(Json(response))
+
+    let body = match params.fields.as_deref() {
+        Some(fields) => project_fields(&response, fields)?,
+        None => serde_json::to_value(&response).map_err(|e| {
+            crate::error::AppError::Internal(format!("Failed to serialize profile: {}", e))
+        })?,
+    };
+    Ok(Json(body))
 }
 
 /// Обновить профиль текущего пользователя
@@ -114,23 +162,66 @@ pub async fn update_profile(
 )]
 pub async fn get_user_by_plate(
     State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
     Query(params): Query<GetUserByPlateQuery>,
 ) -> AppResult<Json<Option<PublicUserInfo>>> {
     let user_info = state
         .user_service
         .get_user_by_plate(
+            auth_state.user_id,
             &params.plate,
             &state.user_repository,
             &state.user_plate_repository,
+            &state.block_repository,
         )
         .await?;
 
     Ok(Json(user_info))
 }
 
+/// Получить публичную информацию о владельцах нескольких номеров
+/// (ключ - нормализованный номер, null - номер не зарегистрирован)
+#[utoipa::path(
+    post,
+    path = "/api/users/by-plates",
+    request_body = UsersByPlatesRequest,
+    responses(
+        (status = 200, description = "Номер -> информация о владельце или null", body = HashMap<String, PublicUserInfo>),
+        (status = 400, description = "Неверный номер или больше 50 номеров"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn get_users_by_plates(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(payload): Json<UsersByPlatesRequest>,
+) -> AppResult<Json<BTreeMap<String, Option<PublicUserInfo>>>> {
+    let users = state
+        .user_service
+        .get_users_by_plates(
+            auth_state.user_id,
+            &payload.plates,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.block_repository,
+        )
+        .await?;
+
+    Ok(Json(users))
+}
+
 #[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub struct PushTokenRequest {
     pub token: String,
+    /// Платформа устройства: "android" или "ios" (старые клиенты не передают)
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Версия приложения, например "1.4.2"
+    #[serde(default)]
+    pub app_version: Option<String>,
 }
 
 /// Зарегистрировать push token для текущего пользователя
@@ -159,7 +250,44 @@ pub async fn register_push_token(
         ));
     }
 
-    // Обновляем только токен
+    let platform = payload
+        .platform
+        .as_deref()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty());
+    if let Some(ref platform) = platform {
+        if !PUSH_PLATFORMS.contains(&platform.as_str()) {
+            return Err(crate::error::AppError::Validation(format!(
+                "Неизвестная платформа: {}. Допустимо: android, ios",
+                platform
+            )));
+        }
+    }
+
+    let app_version = payload
+        .app_version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(version) = app_version {
+        let is_valid = version.len() <= 32
+            && version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+        if !is_valid {
+            return Err(crate::error::AppError::Validation(
+                "Некорректная версия приложения".into(),
+            ));
+        }
+    }
+
+    // Повторная регистрация того же токена только обновляет last_seen
+    state
+        .push_token_repository
+        .upsert(user_id, token, platform.as_deref(), app_version)
+        .await?;
+
+    // Колонка users.push_token пока используется для отправки пушей, поэтому обновляем и её
     let update = crate::repository::user_repository::UpdateUserData {
         name: None,
         phone_encrypted: None,
@@ -171,8 +299,299 @@ pub async fn register_push_token(
         owner_info: None,
         departure_time: None,
         push_token: Some(token.to_string()),
+        announcement_push: None,
+        announcement_telegram: None,
+        utc_offset_minutes: None,
     };
 
     state.user_repository.update(user_id, &update).await?;
     Ok(Json(serde_json::json!({"message": "push token saved"})))
 }
+
+/// Удалить аккаунт и все данные пользователя: автомобили, блокировки, уведомления, сессии,
+/// регистрации в Telegram боте, аватар и фото. Отменить удаление нельзя
+#[utoipa::path(
+    delete,
+    path = "/api/users/me",
+    responses(
+        (status = 200, description = "Аккаунт удалён; сколько записей удалено", body = AccountDeletionResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Пользователь не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn delete_account(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<AccountDeletionResponse>> {
+    let response = state
+        .user_service
+        .delete_account(
+            auth_state.user_id,
+            &state.user_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Перешифровать свои данные текущим ключом шифрования (после ротации ключа)
+#[utoipa::path(
+    post,
+    path = "/api/users/me/reencrypt",
+    responses(
+        (status = 200, description = "Данные перешифрованы или уже зашифрованы текущим ключом", body = ReencryptResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Пользователь не найден"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn reencrypt_profile(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<ReencryptResponse>> {
+    let response = state
+        .user_service
+        .reencrypt_user_data(auth_state.user_id, &state.user_repository)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Активные сессии (устройства, где выполнен вход), от последней активной к первой
+#[utoipa::path(
+    get,
+    path = "/api/users/me/sessions",
+    responses(
+        (status = 200, description = "Активные сессии", body = Vec<SessionResponse>),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Extension(token): Extension<AuthToken>,
+) -> AppResult<Json<Vec<SessionResponse>>> {
+    let sessions = state
+        .auth_service
+        .list_sessions(
+            auth_state.user_id,
+            token.session_id,
+            &state.user_session_repository,
+        )
+        .await?;
+
+    Ok(Json(sessions))
+}
+
+/// Завершить сессию (например, на потерянном телефоне): её токены сразу перестают приниматься
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/sessions/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID сессии")
+    ),
+    responses(
+        (status = 204, description = "Сессия завершена"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Сессия не найдена"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(session_id): Path<Uuid>,
+) -> AppResult<StatusCode> {
+    state
+        .auth_service
+        .revoke_session(
+            auth_state.user_id,
+            session_id,
+            &state.user_session_repository,
+        )
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Загрузить аватар (фото пользователя или его автомобиля; multipart, поле "image", JPEG или PNG).
+/// Сохраняется уменьшенная копия
+#[utoipa::path(
+    post,
+    path = "/api/users/me/avatar",
+    responses(
+        (status = 200, description = "Аватар загружен, в ответе avatar_url"),
+        (status = 400, description = "Неверное изображение"),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn upload_avatar(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
+    mut multipart: Multipart,
+) -> AppResult<Json<serde_json::Value>> {
+    let mut image_data: Option<Vec<u8>> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("Failed to read multipart field: {}", e)))?
+    {
+        if field.name() == Some("image") {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::Validation(format!("Failed to read image data: {}", e)))?;
+            image_data = Some(data.to_vec());
+            break;
+        }
+    }
+
+    let image_data =
+        image_data.ok_or_else(|| AppError::Validation("Image field is required".to_string()))?;
+
+    let avatar_url = state
+        .user_service
+        .upload_avatar(
+            auth_state.user_id,
+            &image_data,
+            &state.user_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    let avatar_url = public_url.absolute(&avatar_url);
+    Ok(Json(serde_json::json!({ "avatar_url": avatar_url })))
+}
+
+/// Получить свой аватар
+#[utoipa::path(
+    get,
+    path = "/api/users/me/avatar",
+    responses(
+        (status = 200, description = "Изображение (image/jpeg)"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Аватар не загружен"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn get_my_avatar(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<impl IntoResponse> {
+    let data = state
+        .user_service
+        .get_avatar(
+            auth_state.user_id,
+            auth_state.user_id,
+            &state.user_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    Ok(([(header::CONTENT_TYPE, "image/jpeg")], data))
+}
+
+/// Получить аватар пользователя (доступен, только если он разрешил показывать контакты)
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/avatar",
+    params(
+        ("id" = Uuid, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, description = "Изображение (image/jpeg)"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Аватар не загружен или скрыт"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn get_user_avatar(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let data = state
+        .user_service
+        .get_avatar(
+            user_id,
+            auth_state.user_id,
+            &state.user_repository,
+            &state.blob_store,
+        )
+        .await?;
+
+    Ok(([(header::CONTENT_TYPE, "image/jpeg")], data))
+}
+
+/// Получить подписанную ссылку на аватар пользователя: по ней аватар скачивается без JWT
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/avatar-url",
+    params(
+        ("id" = Uuid, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, description = "Подписанная ссылка на аватар", body = SignedUrlResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Аватар не загружен или скрыт"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn get_user_avatar_url(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Extension(public_url): Extension<PublicBaseUrl>,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<SignedUrlResponse>> {
+    state
+        .user_service
+        .check_avatar_access(user_id, auth_state.user_id, &state.user_repository)
+        .await?;
+
+    let mut signed = sign_path(&avatar_download_path(user_id), &state.config);
+    signed.url = public_url.absolute(&signed.url);
+    Ok(Json(signed))
+}
+
+/// Проверить, может ли Telegram бот писать текущему пользователю
+/// (перед выбором Telegram способом уведомлений)
+#[utoipa::path(
+    get,
+    path = "/api/users/telegram/reachable",
+    responses(
+        (status = 200, description = "Доступность пользователя для бота", body = TelegramReachabilityResponse),
+        (status = 401, description = "Не авторизован"),
+    ),
+    security(("bearer_token" = [])),
+    tag = "users"
+)]
+pub async fn telegram_reachable(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<TelegramReachabilityResponse>> {
+    let response = state
+        .user_service
+        .telegram_reachability(
+            auth_state.user_id,
+            &state.user_repository,
+            &state.telegram_bot_repository,
+            &state.telegram_service,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
@@ -1,25 +1,41 @@
 use axum::{
-    extract::{Extension, Path, State},
-    response::Json,
+    extract::{Extension, Path, Query, State},
+    response::{Json, Response},
     routing::{delete, get, patch, post, Router},
 };
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::api::pagination::OptionalPagination;
 use crate::api::AppState;
 use crate::auth::middleware::AuthState;
 use crate::error::AppResult;
+use crate::models::block::PlateBlockCounts;
+use crate::models::user::PublicUserInfo;
 use crate::models::user_plate::{
-    CreateUserPlateRequest, UpdateUserPlateRequest, UserPlateResponse,
+    CheckPlateQuery, CheckPlateResponse, ClaimPlateRequest, ClaimPlateResponse,
+    CreateUserPlateRequest, GetUserPlatesQuery, PlateShareInviteResponse,
+    PlateTransferRequestResponse, SharePlateRequest, UpdateUserPlateRequest, UserPlateResponse,
 };
-use crate::repository::UserPlateRepository;
+use crate::repository::{BlockRepository, UserPlateRepository};
 use crate::service::validation_service::ValidationService;
-use chrono::NaiveTime;
+use crate::utils::normalize_plate;
 
 pub fn user_plate_router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_user_plate))
         .route("/", get(get_user_plates))
+        .route("/check", get(check_user_plate))
+        .route("/claim", post(claim_user_plate))
+        .route("/transfers", get(get_transfer_requests))
+        .route("/transfers/:id/approve", post(approve_transfer_request))
+        .route("/transfers/:id/decline", post(decline_transfer_request))
+        .route("/share", post(share_user_plate))
+        .route("/share/invites", get(get_share_invites))
+        .route("/share/invites/:id/accept", post(accept_share_invite))
+        .route("/share/invites/:id/decline", post(decline_share_invite))
         .route("/:id/primary", post(set_primary_plate))
+        .route("/:id/co-owners", get(get_co_owners))
         .route("/:id", patch(update_user_plate))
         .route("/:id", delete(delete_user_plate))
 }
@@ -47,14 +63,9 @@ async fn create_user_plate(
     let is_primary = payload.is_primary.unwrap_or(false);
     let departure_time = payload
         .departure_time
-        .as_ref()
-        .map(|t| NaiveTime::parse_from_str(t, "%H:%M"))
-        .transpose()
-        .map_err(|_| {
-            crate::error::AppError::Validation(
-                "Некорректное время выезда, используйте HH:MM".to_string(),
-            )
-        })?;
+        .as_deref()
+        .map(ValidationService::parse_departure_time)
+        .transpose()?;
 
     let user_plate = state
         .user_plate_repository
@@ -69,23 +80,242 @@ async fn create_user_plate(
     Ok(Json(user_plate.to_response()))
 }
 
+async fn claim_user_plate(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(payload): Json<ClaimPlateRequest>,
+) -> AppResult<Json<ClaimPlateResponse>> {
+    let user_id = auth_state.user_id;
+
+    tracing::info!(
+        "API: claim_user_plate called for user {} with plate {}",
+        user_id,
+        payload.plate
+    );
+
+    let response = state
+        .user_service
+        .claim_plate(
+            user_id,
+            payload,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.plate_transfer_request_repository,
+            &state.notification_repository,
+            &state.push_service,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to claim user plate: {:?}", e);
+            e
+        })?;
+
+    tracing::info!("User plate claim for user {}: {}", user_id, response.status);
+    Ok(Json(response))
+}
+
+async fn get_transfer_requests(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<Vec<PlateTransferRequestResponse>>> {
+    let requests = state
+        .user_service
+        .get_plate_transfer_requests(
+            auth_state.user_id,
+            &state.user_repository,
+            &state.plate_transfer_request_repository,
+        )
+        .await?;
+
+    Ok(Json(requests))
+}
+
+async fn approve_transfer_request(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(request_id): Path<Uuid>,
+) -> AppResult<Json<PlateTransferRequestResponse>> {
+    let request = state
+        .user_service
+        .approve_plate_transfer(
+            auth_state.user_id,
+            request_id,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.plate_transfer_request_repository,
+            &state.notification_repository,
+            &state.push_service,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to approve plate transfer: {:?}", e);
+            e
+        })?;
+
+    Ok(Json(request))
+}
+
+async fn decline_transfer_request(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(request_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    state
+        .user_service
+        .decline_plate_transfer(
+            auth_state.user_id,
+            request_id,
+            &state.user_repository,
+            &state.plate_transfer_request_repository,
+            &state.notification_repository,
+            &state.push_service,
+        )
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Transfer request declined" }),
+    ))
+}
+
+async fn share_user_plate(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(payload): Json<SharePlateRequest>,
+) -> AppResult<Json<PlateShareInviteResponse>> {
+    let user_id = auth_state.user_id;
+
+    tracing::info!(
+        "API: share_user_plate called for user {} and plate {}",
+        user_id,
+        payload.plate_id
+    );
+
+    let invite = state
+        .user_service
+        .share_plate(
+            user_id,
+            payload,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.plate_share_invite_repository,
+            &state.notification_repository,
+            &state.push_service,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to share user plate: {:?}", e);
+            e
+        })?;
+
+    Ok(Json(invite))
+}
+
+async fn get_share_invites(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+) -> AppResult<Json<Vec<PlateShareInviteResponse>>> {
+    let invites = state
+        .user_service
+        .get_plate_share_invites(
+            auth_state.user_id,
+            &state.user_repository,
+            &state.plate_share_invite_repository,
+        )
+        .await?;
+
+    Ok(Json(invites))
+}
+
+async fn accept_share_invite(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(invite_id): Path<Uuid>,
+) -> AppResult<Json<UserPlateResponse>> {
+    let user_plate = state
+        .user_service
+        .accept_plate_share_invite(
+            auth_state.user_id,
+            invite_id,
+            &state.user_plate_repository,
+            &state.plate_share_invite_repository,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to accept share invite: {:?}", e);
+            e
+        })?;
+
+    Ok(Json(user_plate))
+}
+
+async fn decline_share_invite(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(invite_id): Path<Uuid>,
+) -> AppResult<Json<serde_json::Value>> {
+    state
+        .user_service
+        .decline_plate_share_invite(
+            auth_state.user_id,
+            invite_id,
+            &state.plate_share_invite_repository,
+        )
+        .await?;
+
+    Ok(Json(serde_json::json!({ "message": "Invite declined" })))
+}
+
+/// Свои номера: по умолчанию весь список массивом; при `limit`/`offset` - страница,
+/// при `include_counts=true` - с количеством активных блокировок по каждому номеру
 async fn get_user_plates(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
-) -> AppResult<Json<Vec<UserPlateResponse>>> {
+    Query(query): Query<GetUserPlatesQuery>,
+    pagination: OptionalPagination,
+) -> AppResult<Response> {
     let user_id = auth_state.user_id;
 
-    let plates = state
+    let (plates, total) = state
         .user_plate_repository
-        .find_by_user_id(user_id)
+        .find_page_by_user_id(user_id, pagination.limit(), pagination.offset())
         .await
         .map_err(|e| {
             tracing::error!("Failed to get user plates: {:?}", e);
             e
         })?;
+    let responses: Vec<UserPlateResponse> = plates.iter().map(|p| p.to_response()).collect();
+
+    // Счётчики запрашиваются только для номеров текущей страницы
+    let responses = with_block_counts(&state, responses, query.include_counts).await?;
+    Ok(pagination.into_response(responses, total))
+}
+
+/// Заполняет количество активных блокировок по номерам, если оно запрошено
+async fn with_block_counts(
+    state: &AppState,
+    mut responses: Vec<UserPlateResponse>,
+    include_counts: bool,
+) -> AppResult<Vec<UserPlateResponse>> {
+    if !include_counts {
+        return Ok(responses);
+    }
+
+    let plates: Vec<String> = responses.iter().map(|p| p.plate.clone()).collect();
+    let counts: HashMap<String, PlateBlockCounts> = state
+        .block_repository
+        .counts_by_plates(&plates)
+        .await?
+        .into_iter()
+        .map(|counts| (counts.plate.clone(), counts))
+        .collect();
+
+    for response in &mut responses {
+        let plate_counts = counts.get(&normalize_plate(&response.plate));
+        response.received_block_count = Some(plate_counts.map_or(0, |c| c.received_block_count));
+        response.created_block_count = Some(plate_counts.map_or(0, |c| c.created_block_count));
+    }
 
-    let responses = plates.iter().map(|p| p.to_response()).collect();
-    Ok(Json(responses))
+    Ok(responses)
 }
 
 async fn set_primary_plate(
@@ -116,6 +346,42 @@ async fn set_primary_plate(
     ))
 }
 
+async fn check_user_plate(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Query(params): Query<CheckPlateQuery>,
+) -> AppResult<Json<CheckPlateResponse>> {
+    let response = state
+        .user_service
+        .check_plate(
+            auth_state.user_id,
+            &params.plate,
+            &state.user_plate_repository,
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+async fn get_co_owners(
+    State(state): State<AppState>,
+    Extension(auth_state): Extension<AuthState>,
+    Path(plate_id): Path<Uuid>,
+) -> AppResult<Json<Vec<PublicUserInfo>>> {
+    let user_id = auth_state.user_id;
+
+    let co_owners = state
+        .user_service
+        .get_co_owners(user_id, plate_id, &state.user_plate_repository)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get co-owners: {:?}", e);
+            e
+        })?;
+
+    Ok(Json(co_owners))
+}
+
 async fn delete_user_plate(
     State(state): State<AppState>,
     Extension(auth_state): Extension<AuthState>,
@@ -129,7 +395,7 @@ async fn delete_user_plate(
         plate_id
     );
 
-    state
+    let new_primary = state
         .user_plate_repository
         .delete(plate_id, user_id)
         .await
@@ -138,10 +404,19 @@ async fn delete_user_plate(
             e
         })?;
 
+    if let Some(ref primary) = new_primary {
+        tracing::info!(
+            "Deleted plate was primary, {} is now primary for user {}",
+            primary.plate,
+            user_id
+        );
+    }
+
     tracing::info!("User plate deleted successfully");
-    Ok(Json(
-        serde_json::json!({ "message": "User plate deleted successfully" }),
-    ))
+    Ok(Json(serde_json::json!({
+        "message": "User plate deleted successfully",
+        "new_primary": new_primary.map(|p| p.to_response()),
+    })))
 }
 
 async fn update_user_plate(
@@ -154,14 +429,9 @@ async fn update_user_plate(
 
     let time = payload
         .departure_time
-        .as_ref()
-        .map(|t| NaiveTime::parse_from_str(t, "%H:%M"))
-        .transpose()
-        .map_err(|_| {
-            crate::error::AppError::Validation(
-                "Некорректное время выезда, используйте HH:MM".to_string(),
-            )
-        })?;
+        .as_deref()
+        .map(ValidationService::parse_departure_time)
+        .transpose()?;
 
     let updated = state
         .user_plate_repository
@@ -0,0 +1,32 @@
+use crate::api::AppState;
+use crate::config::Config;
+use crate::middleware::{ip_rate_limit_middleware, IpRateLimiter};
+use crate::models::validation::{ValidateRequest, ValidateResponse};
+use crate::service::validation_service::ValidationService;
+use axum::{middleware::from_fn_with_state, response::Json, routing::post, Router};
+
+/// Роутер проверки полей форм (без авторизации, с ограничением частоты по IP)
+pub fn validation_router(config: &Config) -> Router<AppState> {
+    Router::new()
+        .route("/api/validate", post(validate_fields))
+        .route_layer(from_fn_with_state(
+            IpRateLimiter::per_minute(config.validate_rate_limit_per_minute),
+            ip_rate_limit_middleware,
+        ))
+}
+
+/// Проверить номер автомобиля, телефон и Telegram username без сохранения.
+/// Правила и нормализация те же, что при сохранении профиля и создании блокировок
+#[utoipa::path(
+    post,
+    path = "/api/validate",
+    request_body = ValidateRequest,
+    responses(
+        (status = 200, description = "Результат проверки каждого переданного поля", body = ValidateResponse),
+        (status = 429, description = "Слишком много запросов с этого IP"),
+    ),
+    tag = "app"
+)]
+pub async fn validate_fields(Json(payload): Json<ValidateRequest>) -> Json<ValidateResponse> {
+    Json(ValidationService::check_fields(&payload))
+}
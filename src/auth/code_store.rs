@@ -0,0 +1,431 @@
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, RwLock};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/// Префикс ключей кодов в Redis
+const REDIS_KEY_PREFIX: &str = "sms_code:";
+
+/// Сколько после ошибки основного хранилища коды обслуживаются памятью процесса,
+/// прежде чем основное хранилище пробуется снова
+pub const FALLBACK_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Увеличивает счётчик неверных попыток в записи кода, сохраняя срок жизни ключа.
+/// Возвращает новое значение или -1, если кода нет
+const REDIS_FAILED_ATTEMPT_SCRIPT: &str = r#"
+local value = redis.call('GET', KEYS[1])
+if not value then
+    return -1
+end
+local entry = cjson.decode(value)
+entry.failed_attempts = (entry.failed_attempts or 0) + 1
+redis.call('SET', KEYS[1], cjson.encode(entry), 'KEEPTTL')
+return entry.failed_attempts
+"#;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CodeEntry {
+    pub code: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub user_id: Option<Uuid>,
+    /// Сколько раз для этого кода ввели неверное значение
+    #[serde(default)]
+    pub failed_attempts: u32,
+}
+
+impl CodeEntry {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= chrono::Utc::now()
+    }
+}
+
+/// Хранилище кодов подтверждения по номеру телефона (DIP).
+/// Код хранится до `entry.expires_at`, после чего хранилище может удалить его само
+#[async_trait::async_trait]
+pub trait CodeStore: Send + Sync {
+    /// Сохраняет код, заменяя предыдущий код этого телефона
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()>;
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>>;
+    /// Удаляет код. Возвращает false, если кода уже не было (его забрал параллельный запрос)
+    async fn remove(&self, phone: &str) -> AppResult<bool>;
+    /// Атомарно увеличивает счётчик неверных попыток кода и возвращает новое значение.
+    /// None, если кода нет
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>>;
+    /// Удаляет истёкшие коды, возвращает их количество.
+    /// По умолчанию ничего не делает: хранилище удаляет коды само (TTL)
+    async fn prune_expired(&self) -> AppResult<usize> {
+        Ok(0)
+    }
+    /// Сколько операций выполнено в памяти процесса из-за сбоев основного хранилища (метрика).
+    /// None, если запасного хранилища нет
+    fn fallback_operations(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Хранилище в памяти процесса: подходит, пока сервер запущен в одном экземпляре
+#[derive(Clone, Default)]
+pub struct InMemoryCodeStore {
+    codes: Arc<RwLock<HashMap<String, CodeEntry>>>,
+}
+
+impl InMemoryCodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CodeStore for InMemoryCodeStore {
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()> {
+        self.codes.write().await.insert(phone.to_string(), entry);
+        Ok(())
+    }
+
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>> {
+        Ok(self.codes.read().await.get(phone).cloned())
+    }
+
+    async fn remove(&self, phone: &str) -> AppResult<bool> {
+        Ok(self.codes.write().await.remove(phone).is_some())
+    }
+
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>> {
+        Ok(self.codes.write().await.get_mut(phone).map(|entry| {
+            entry.failed_attempts += 1;
+            entry.failed_attempts
+        }))
+    }
+
+    async fn prune_expired(&self) -> AppResult<usize> {
+        let mut codes = self.codes.write().await;
+        let before = codes.len();
+        codes.retain(|_, entry| !entry.is_expired());
+        Ok(before - codes.len())
+    }
+}
+
+/// Хранилище в Redis: коды доступны всем экземплярам сервера (и Telegram боту).
+/// Redis сам удаляет ключ по истечении срока жизни кода
+#[derive(Clone)]
+pub struct RedisCodeStore {
+    connection: redis::aio::ConnectionManager,
+}
+
+impl RedisCodeStore {
+    pub async fn connect(redis_url: &str) -> AppResult<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| AppError::Internal(format!("Invalid REDIS_URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to connect to Redis: {}", e)))?;
+        Ok(Self { connection })
+    }
+
+    fn key(phone: &str) -> String {
+        format!("{}{}", REDIS_KEY_PREFIX, phone)
+    }
+}
+
+fn redis_error(e: redis::RedisError) -> AppError {
+    AppError::Internal(format!("Redis error: {}", e))
+}
+
+#[async_trait::async_trait]
+impl CodeStore for RedisCodeStore {
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()> {
+        let ttl_secs = (entry.expires_at - chrono::Utc::now()).num_seconds().max(1) as u64;
+        let value = serde_json::to_string(&entry)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize code: {}", e)))?;
+        let mut connection = self.connection.clone();
+        connection
+            .set_ex::<_, _, ()>(Self::key(phone), value, ttl_secs)
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection
+            .get(Self::key(phone))
+            .await
+            .map_err(redis_error)?;
+        value
+            .map(|value| {
+                serde_json::from_str(&value)
+                    .map_err(|e| AppError::Internal(format!("Invalid code entry in Redis: {}", e)))
+            })
+            .transpose()
+    }
+
+    async fn remove(&self, phone: &str) -> AppResult<bool> {
+        let mut connection = self.connection.clone();
+        let removed: i64 = connection
+            .del(Self::key(phone))
+            .await
+            .map_err(redis_error)?;
+        Ok(removed > 0)
+    }
+
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>> {
+        let mut connection = self.connection.clone();
+        let attempts: i64 = redis::cmd("EVAL")
+            .arg(REDIS_FAILED_ATTEMPT_SCRIPT)
+            .arg(1)
+            .arg(Self::key(phone))
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+        Ok(u32::try_from(attempts).ok())
+    }
+}
+
+/// Redis, к которому не удалось подключиться при запуске. Подключение повторяется в фоне,
+/// а пока его нет, операции сразу завершаются ошибкой: запросы не ждут подключения,
+/// и `FallbackCodeStore` обслуживает их из памяти
+#[derive(Clone)]
+pub struct ReconnectingRedisCodeStore {
+    redis_url: String,
+    store: Arc<OnceCell<RedisCodeStore>>,
+    /// Идёт ли сейчас попытка подключения (одновременно выполняется одна)
+    connecting: Arc<AtomicBool>,
+}
+
+impl ReconnectingRedisCodeStore {
+    pub fn new(redis_url: &str) -> Self {
+        Self {
+            redis_url: redis_url.to_string(),
+            store: Arc::default(),
+            connecting: Arc::default(),
+        }
+    }
+
+    /// Подключённое хранилище; без подключения - ошибка и попытка подключиться в фоне
+    fn store(&self) -> AppResult<&RedisCodeStore> {
+        if let Some(store) = self.store.get() {
+            return Ok(store);
+        }
+        if !self.connecting.swap(true, Ordering::AcqRel) {
+            let this = self.clone();
+            tokio::spawn(async move {
+                match RedisCodeStore::connect(&this.redis_url).await {
+                    Ok(store) => {
+                        let _ = this.store.set(store);
+                        tracing::info!("Connected to Redis, SMS codes are stored in Redis again");
+                    }
+                    Err(e) => tracing::warn!("Redis is still unavailable: {}", e),
+                }
+                this.connecting.store(false, Ordering::Release);
+            });
+        }
+        Err(AppError::Internal("Redis is not connected".to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl CodeStore for ReconnectingRedisCodeStore {
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()> {
+        self.store()?.insert(phone, entry).await
+    }
+
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>> {
+        self.store()?.get(phone).await
+    }
+
+    async fn remove(&self, phone: &str) -> AppResult<bool> {
+        self.store()?.remove(phone).await
+    }
+
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>> {
+        self.store()?.record_failed_attempt(phone).await
+    }
+}
+
+/// Хранилище с запасным вариантом в памяти процесса (REDIS_FALLBACK_INMEMORY): при ошибке
+/// основного хранилища операция выполняется в памяти, а основное не используется
+/// `FALLBACK_RETRY_INTERVAL` (по умолчанию). Гарантии снижаются: код, выданный одним экземпляром сервера
+/// во время сбоя, другой экземпляр не примет. Коды из памяти проверяются и после восстановления.
+/// Других данных в Redis нет: ограничения частоты запросов хранятся в памяти процесса,
+/// отзыв токенов и сессии - в PostgreSQL, поэтому запасной вариант нужен только кодам
+#[derive(Clone)]
+pub struct FallbackCodeStore {
+    primary: Arc<dyn CodeStore>,
+    fallback: InMemoryCodeStore,
+    retry_interval: Duration,
+    /// До какого момента основное хранилище не используется после ошибки
+    retry_at: Arc<Mutex<Option<Instant>>>,
+    /// Сколько операций выполнено в памяти из-за недоступности основного хранилища (метрика)
+    fallback_operations: Arc<AtomicU64>,
+}
+
+impl FallbackCodeStore {
+    pub fn new(primary: Box<dyn CodeStore>) -> Self {
+        Self::with_retry_interval(primary, FALLBACK_RETRY_INTERVAL)
+    }
+
+    pub fn with_retry_interval(primary: Box<dyn CodeStore>, retry_interval: Duration) -> Self {
+        Self {
+            primary: Arc::from(primary),
+            fallback: InMemoryCodeStore::new(),
+            retry_interval,
+            retry_at: Arc::default(),
+            fallback_operations: Arc::default(),
+        }
+    }
+
+    fn primary_available(&self) -> bool {
+        let mut retry_at = self.retry_at.lock().unwrap_or_else(|e| e.into_inner());
+        match *retry_at {
+            Some(at) if Instant::now() < at => false,
+            Some(_) => {
+                *retry_at = None;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Результат основного хранилища; при ошибке - None и переход на память
+    fn check<T>(&self, operation: &str, result: AppResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                *self.retry_at.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some(Instant::now() + self.retry_interval);
+                tracing::warn!(
+                    "Code store {} failed, falling back to in-memory storage for {:?}: {}",
+                    operation,
+                    self.retry_interval,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    async fn try_primary<T>(
+        &self,
+        operation: &str,
+        call: impl std::future::Future<Output = AppResult<T>>,
+    ) -> Option<T> {
+        if !self.primary_available() {
+            return None;
+        }
+        self.check(operation, call.await)
+    }
+
+    fn count_fallback(&self) {
+        let total = self.fallback_operations.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::warn!(
+            "SMS code operation served from memory (total in-memory fallbacks: {})",
+            total
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl CodeStore for FallbackCodeStore {
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()> {
+        match self
+            .try_primary("insert", self.primary.insert(phone, entry.clone()))
+            .await
+        {
+            Some(()) => {
+                // Код, выданный во время сбоя, заменён новым
+                self.fallback.remove(phone).await?;
+                Ok(())
+            }
+            None => {
+                self.count_fallback();
+                self.fallback.insert(phone, entry).await
+            }
+        }
+    }
+
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>> {
+        match self.try_primary("get", self.primary.get(phone)).await {
+            Some(Some(entry)) => Ok(Some(entry)),
+            Some(None) => self.fallback.get(phone).await,
+            None => {
+                self.count_fallback();
+                self.fallback.get(phone).await
+            }
+        }
+    }
+
+    async fn remove(&self, phone: &str) -> AppResult<bool> {
+        let removed = self.try_primary("remove", self.primary.remove(phone)).await;
+        if removed.is_none() {
+            self.count_fallback();
+        }
+        let removed_from_memory = self.fallback.remove(phone).await?;
+        Ok(removed.unwrap_or(false) || removed_from_memory)
+    }
+
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>> {
+        match self
+            .try_primary(
+                "record_failed_attempt",
+                self.primary.record_failed_attempt(phone),
+            )
+            .await
+        {
+            Some(Some(attempts)) => Ok(Some(attempts)),
+            Some(None) => self.fallback.record_failed_attempt(phone).await,
+            None => {
+                self.count_fallback();
+                self.fallback.record_failed_attempt(phone).await
+            }
+        }
+    }
+
+    async fn prune_expired(&self) -> AppResult<usize> {
+        let pruned = self
+            .try_primary("prune_expired", self.primary.prune_expired())
+            .await
+            .unwrap_or(0);
+        Ok(pruned + self.fallback.prune_expired().await?)
+    }
+
+    fn fallback_operations(&self) -> Option<u64> {
+        Some(self.fallback_operations.load(Ordering::Relaxed))
+    }
+}
+
+/// Хранилище кодов по конфигурации: Redis, если задан `REDIS_URL`, иначе память процесса.
+/// С `fallback_in_memory` сбои Redis не приводят к ошибкам авторизации; если Redis недоступен
+/// при запуске, к нему подключаются в фоне, а до тех пор коды хранятся в памяти
+pub async fn code_store_from_config(
+    redis_url: Option<&str>,
+    fallback_in_memory: bool,
+) -> AppResult<Box<dyn CodeStore>> {
+    match redis_url {
+        Some(redis_url) => {
+            let store = match RedisCodeStore::connect(redis_url).await {
+                Ok(store) => store,
+                Err(e) if fallback_in_memory => {
+                    tracing::warn!(
+                        "Redis is unavailable at startup, SMS codes are stored in memory until it reconnects: {}",
+                        e
+                    );
+                    let primary = ReconnectingRedisCodeStore::new(redis_url);
+                    return Ok(Box::new(FallbackCodeStore::new(Box::new(primary))));
+                }
+                Err(e) => return Err(e),
+            };
+            tracing::info!("SMS codes are stored in Redis");
+            if fallback_in_memory {
+                Ok(Box::new(FallbackCodeStore::new(Box::new(store))))
+            } else {
+                Ok(Box::new(store))
+            }
+        }
+        None => Ok(Box::new(InMemoryCodeStore::new())),
+    }
+}
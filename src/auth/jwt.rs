@@ -1,16 +1,30 @@
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use serde::{Deserialize, Serialize};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{
+    decode, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::config::Config;
+use crate::config::{Config, JwtAlgorithm};
 use crate::error::{AppError, AppResult};
 
+/// Сколько минут после истечения токен ещё можно обменять на новый (`/api/auth/refresh`)
+pub const REFRESH_GRACE_MINUTES: i64 = 30;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: Uuid, // user_id
     pub exp: i64,
     pub iat: i64,
+    /// Идентификатор токена для отзыва при выходе. У токенов, выданных до появления
+    /// отзыва, его нет - такие токены действуют до истечения
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<Uuid>,
+    /// Сессия устройства (`user_sessions`), в которой выдан токен. Сохраняется при обновлении
+    /// токена; после удаления сессии её токены не принимаются
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
 }
 
 impl Claims {
@@ -22,6 +36,8 @@ impl Claims {
             sub: user_id,
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            jti: Some(Uuid::new_v4()),
+            sid: None,
         }
     }
 
@@ -35,20 +51,88 @@ impl Claims {
 }
 
 pub fn create_token(user_id: Uuid, config: &Config) -> AppResult<String> {
-    let claims = Claims::new(user_id, config.jwt_expiration_minutes);
-    let key = EncodingKey::from_secret(config.jwt_secret.as_ref());
+    encode_claims(&Claims::new(user_id, config.jwt_expiration_minutes), config)
+}
+
+/// Создаёт токен, привязанный к сессии устройства
+pub fn create_session_token(user_id: Uuid, session_id: Uuid, config: &Config) -> AppResult<String> {
+    let claims = Claims {
+        sid: Some(session_id),
+        ..Claims::new(user_id, config.jwt_expiration_minutes)
+    };
+    encode_claims(&claims, config)
+}
 
-    encode(&Header::default(), &claims, &key)
-        .map_err(|e| AppError::Auth(format!("Failed to create token: {}", e)))
+fn encode_claims(claims: &Claims, config: &Config) -> AppResult<String> {
+    let header = Header::new(algorithm(config));
+    let token = match config.jwt_algorithm {
+        JwtAlgorithm::Hs256 => encode(
+            &header,
+            claims,
+            &EncodingKey::from_secret(config.jwt_secret.as_ref()),
+        ),
+        // Закрытый ключ разобран при загрузке конфигурации
+        JwtAlgorithm::Rs256 => match config.jwt_private_key.as_ref() {
+            Some(key) => encode(&header, claims, key),
+            None => Err(ErrorKind::InvalidRsaKey(
+                "JWT_PRIVATE_KEY_PATH is not configured".to_string(),
+            )
+            .into()),
+        },
+    };
+    token.map_err(|e| AppError::Auth(format!("Failed to create token: {}", e)))
+}
+
+fn algorithm(config: &Config) -> Algorithm {
+    match config.jwt_algorithm {
+        JwtAlgorithm::Hs256 => Algorithm::HS256,
+        JwtAlgorithm::Rs256 => Algorithm::RS256,
+    }
+}
+
+/// Параметры проверки токена для алгоритма из конфигурации (срок действия проверяется)
+pub fn token_validation(config: &Config) -> Validation {
+    Validation::new(algorithm(config))
 }
 
 pub fn verify_token(token: &str, config: &Config) -> AppResult<Claims> {
-    let key = DecodingKey::from_secret(config.jwt_secret.as_ref());
-    let mut validation = Validation::default();
+    let mut validation = token_validation(config);
     // Не позволяем использовать токен с истекшим сроком жизни
     validation.validate_exp = true;
 
-    decode::<Claims>(token, &key, &validation)
+    decode_with_accepted_secrets::<Claims>(token, config, &validation)
         .map(|data| data.claims)
         .map_err(|e| AppError::Auth(format!("Invalid token: {}", e)))
 }
+
+/// Декодирует токен, перебирая основной секрет и секреты из JWT_SECRETS_ACCEPT
+/// (ротация секрета без разлогина пользователей). Подписываются токены только основным секретом.
+/// При RS256 токен проверяется открытым ключом
+pub fn decode_with_accepted_secrets<T: DeserializeOwned>(
+    token: &str,
+    config: &Config,
+    validation: &Validation,
+) -> Result<TokenData<T>, jsonwebtoken::errors::Error> {
+    if config.jwt_algorithm == JwtAlgorithm::Rs256 {
+        let key = config
+            .jwt_public_key
+            .as_ref()
+            .ok_or(ErrorKind::InvalidRsaKey(
+                "JWT_PUBLIC_KEY_PATH is not configured".to_string(),
+            ))?;
+        return decode::<T>(token, key, validation);
+    }
+
+    let mut last_error = None;
+    for secret in std::iter::once(&config.jwt_secret).chain(&config.jwt_secrets_accept) {
+        let key = DecodingKey::from_secret(secret.as_ref());
+        match decode::<T>(token, &key, validation) {
+            Ok(data) => return Ok(data),
+            // Подпись не подошла - пробуем следующий секрет
+            Err(e) if matches!(e.kind(), ErrorKind::InvalidSignature) => last_error = Some(e),
+            // Подпись верна, но токен невалиден (например, истёк) - дальше искать нет смысла
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| ErrorKind::InvalidSignature.into()))
+}
@@ -4,12 +4,31 @@ use uuid::Uuid;
 use crate::api::AppState;
 use crate::auth::jwt::verify_token;
 use crate::error::AppError;
+use crate::repository::{RevokedTokenRepository, UserRepository, UserSessionRepository};
+
+/// Максимальная длина JWT. Наши токены намного короче; более длинные отклоняем
+/// до декодирования, чтобы не тратить ресурсы на заведомо мусорные значения
+pub const MAX_TOKEN_LENGTH: usize = 4096;
+
+/// Длина начала заголовка, которое попадает в логи
+const HEADER_LOG_PREVIEW_CHARS: usize = 20;
 
 #[derive(Clone, Debug)]
 pub struct AuthState {
     pub user_id: Uuid,
 }
 
+/// Токен текущего запроса (для выхода: отзывается именно он)
+#[derive(Clone, Debug)]
+pub struct AuthToken {
+    /// Claim `jti`; None у токенов, выданных до появления отзыва
+    pub jti: Option<Uuid>,
+    /// Claim `exp` (Unix time)
+    pub expires_at: i64,
+    /// Claim `sid`; None у токенов, выданных вне сессии
+    pub session_id: Option<Uuid>,
+}
+
 pub async fn auth_middleware(
     axum::extract::State(state): axum::extract::State<AppState>,
     mut request: Request,
@@ -18,20 +37,25 @@ pub async fn auth_middleware(
     let path = request.uri().path();
 
     // Извлекаем заголовок Authorization
-    let auth_header = request
-        .headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| {
-            tracing::warn!("[Middleware] Missing Authorization header for {}", path);
-            AppError::Auth("Missing Authorization header".to_string())
-        })?;
+    let raw_header = request.headers().get("Authorization").ok_or_else(|| {
+        tracing::warn!("[Middleware] Missing Authorization header for {}", path);
+        AppError::Auth("Missing Authorization header".to_string())
+    })?;
+    let auth_header = raw_header.to_str().map_err(|_| {
+        tracing::warn!(
+            "[Middleware] Authorization header contains non-ASCII bytes for {}: '{}'",
+            path,
+            header_preview(&String::from_utf8_lossy(raw_header.as_bytes()))
+        );
+        AppError::Auth("Invalid Authorization header encoding".to_string())
+    })?;
 
     // Проверяем формат Bearer token
     if !auth_header.starts_with("Bearer ") {
         tracing::warn!(
-            "[Middleware] Invalid Authorization header format (expected 'Bearer <token>') for {}",
-            path
+            "[Middleware] Invalid Authorization header format (expected 'Bearer <token>') for {}: '{}'",
+            path,
+            header_preview(auth_header)
         );
         return Err(AppError::Auth(
             "Invalid Authorization header format. Expected 'Bearer <token>'".to_string(),
@@ -39,7 +63,7 @@ pub async fn auth_middleware(
     }
 
     // Извлекаем токен
-    let token = auth_header[7..].trim(); // Обрезаем "Bearer " и возможные пробелы
+    let token = auth_header["Bearer ".len()..].trim(); // Обрезаем "Bearer " и возможные пробелы
 
     if token.is_empty() {
         tracing::warn!(
@@ -51,16 +75,72 @@ pub async fn auth_middleware(
         ));
     }
 
+    if token.len() > MAX_TOKEN_LENGTH {
+        tracing::warn!(
+            "[Middleware] Token too long ({} bytes, max {}) for {}",
+            token.len(),
+            MAX_TOKEN_LENGTH,
+            path
+        );
+        return Err(AppError::Auth("Token is too long".to_string()));
+    }
+
     // Верифицируем токен
     let claims = verify_token(token, &state.config).map_err(|e| {
         tracing::warn!("[Middleware] Token verification failed for {}: {}", path, e);
         e
     })?;
 
+    // Отозванный при выходе токен (один запрос по первичному ключу на каждый запрос к API)
+    if let Some(jti) = claims.jti {
+        if state.revoked_token_repository.is_revoked(jti).await? {
+            tracing::warn!(
+                "[Middleware] Revoked token of user {} used for {}",
+                claims.sub,
+                path
+            );
+            return Err(AppError::Auth("Token has been revoked".to_string()));
+        }
+    }
+
+    // Завершённая сессия: удалена пользователем с другого устройства или при выходе
+    if let Some(session_id) = claims.sid {
+        if !state
+            .user_session_repository
+            .touch(session_id, claims.sub)
+            .await?
+        {
+            tracing::warn!(
+                "[Middleware] Token of terminated session {} used by user {} for {}",
+                session_id,
+                claims.sub,
+                path
+            );
+            return Err(AppError::Auth("Session has been terminated".to_string()));
+        }
+    }
+
+    // Заблокированный администратором аккаунт не может пользоваться API даже с действующим токеном
+    if let Some(user) = state.user_repository.find_by_id(claims.sub).await? {
+        if user.is_suspended() {
+            tracing::warn!(
+                "[Middleware] Suspended user {} tried to access {}",
+                claims.sub,
+                path
+            );
+            return Err(AppError::AccountSuspended(user.suspension_message()));
+        }
+    }
+
     // Добавляем user_id в extensions для использования в handlers
     request.extensions_mut().insert(AuthState {
         user_id: claims.sub,
     });
+    request.extensions_mut().insert(AuthToken {
+        jti: claims.jti,
+        expires_at: claims.exp,
+        session_id: claims.sid,
+    });
 
     // Продолжаем обработку запроса
     let response = next.run(request).await;
@@ -77,6 +157,62 @@ pub async fn auth_middleware(
     Ok(response)
 }
 
+/// Пропускает только администраторов (ADMIN_USER_IDS).
+/// Должен выполняться после `auth_middleware`
+pub async fn admin_middleware(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let user_id = extract_user_id(&request)
+        .ok_or_else(|| AppError::Auth("Missing authentication".to_string()))?;
+
+    if !state.config.admin_user_ids.contains(&user_id) {
+        tracing::warn!(
+            "[Middleware] User {} tried to access admin endpoint {}",
+            user_id,
+            request.uri().path()
+        );
+        return Err(AppError::Forbidden("Admin access required".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Пропускает охрану (SECURITY_USER_IDS) и администраторов.
+/// Должен выполняться после `auth_middleware`
+pub async fn security_middleware(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let user_id = extract_user_id(&request)
+        .ok_or_else(|| AppError::Auth("Missing authentication".to_string()))?;
+
+    if !state.config.security_user_ids.contains(&user_id)
+        && !state.config.admin_user_ids.contains(&user_id)
+    {
+        tracing::warn!(
+            "[Middleware] User {} tried to access security endpoint {}",
+            user_id,
+            request.uri().path()
+        );
+        return Err(AppError::Forbidden("Security access required".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Начало заголовка для логов. Обрезается по символам, а не по байтам,
+/// чтобы не паниковать на многобайтовых символах
+fn header_preview(header: &str) -> String {
+    let mut preview: String = header.chars().take(HEADER_LOG_PREVIEW_CHARS).collect();
+    if header.chars().count() > HEADER_LOG_PREVIEW_CHARS {
+        preview.push('…');
+    }
+    preview
+}
+
 pub fn extract_user_id(request: &Request) -> Option<Uuid> {
     request
         .extensions()
@@ -1,7 +1,11 @@
+pub mod code_store;
 pub mod jwt;
 pub mod middleware;
+pub mod signed_url;
 pub mod sms;
 
+pub use code_store::*;
 pub use jwt::*;
 pub use middleware::*;
+pub use signed_url::*;
 pub use sms::*;
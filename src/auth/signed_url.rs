@@ -0,0 +1,88 @@
+use chrono::{Duration, SubsecRound, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::block::BlockPhotoSize;
+use crate::models::download::{SignedUrlQuery, SignedUrlResponse};
+
+/// Путь скачивания APK
+pub const APP_DOWNLOAD_PATH: &str = "/api/app/download";
+
+/// Путь скачивания фото блокировки по подписанной ссылке
+pub fn block_photo_download_path(block_id: Uuid, size: BlockPhotoSize) -> String {
+    format!("/api/files/blocks/{}/photo/{}", block_id, size.as_str())
+}
+
+/// Путь скачивания аватара по подписанной ссылке
+pub fn avatar_download_path(user_id: Uuid) -> String {
+    format!("/api/files/avatars/{}", user_id)
+}
+
+/// Подписывает путь на `SIGNED_URL_EXPIRATION_MINUTES` основным секретом JWT.
+/// Возвращает относительную ссылку с параметрами `expires` и `signature`
+pub fn sign_path(path: &str, config: &Config) -> SignedUrlResponse {
+    let expires_at =
+        (Utc::now() + Duration::minutes(config.signed_url_expiration_minutes)).trunc_subsecs(0);
+    let expires = expires_at.timestamp();
+    let signature = hex::encode(
+        signature_mac(&config.jwt_secret, path, expires)
+            .finalize()
+            .into_bytes(),
+    );
+
+    SignedUrlResponse {
+        url: format!("{}?expires={}&signature={}", path, expires, signature),
+        expires_at,
+    }
+}
+
+/// Проверяет подпись и срок действия ссылки на `path`.
+/// Подпись принимается от основного секрета и секретов из JWT_SECRETS_ACCEPT (ротация)
+pub fn verify_signed_path(path: &str, query: &SignedUrlQuery, config: &Config) -> AppResult<()> {
+    let (Some(expires), Some(signature)) = (query.expires, query.signature.as_deref()) else {
+        return Err(AppError::Auth("Missing download signature".to_string()));
+    };
+    let signature = hex::decode(signature)
+        .map_err(|_| AppError::Forbidden("Invalid download signature".to_string()))?;
+
+    let valid = std::iter::once(&config.jwt_secret)
+        .chain(&config.jwt_secrets_accept)
+        .any(|secret| {
+            signature_mac(secret, path, expires)
+                .verify_slice(&signature)
+                .is_ok()
+        });
+    if !valid {
+        return Err(AppError::Forbidden(
+            "Invalid download signature".to_string(),
+        ));
+    }
+
+    // Срок проверяем после подписи: иначе можно было бы подобрать expires без секрета
+    if expires <= Utc::now().timestamp() {
+        return Err(AppError::Forbidden("Download link has expired".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Значение Cache-Control для ответа по проверенной подписанной ссылке:
+/// кэшировать можно, пока ссылка действует
+pub fn signed_cache_control(query: &SignedUrlQuery) -> String {
+    let remaining = query.expires.unwrap_or_default() - Utc::now().timestamp();
+    format!("private, max-age={}", remaining.max(0))
+}
+
+fn signature_mac(secret: &str, path: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    // Префикс отделяет подписи ссылок от других HMAC на том же секрете
+    mac.update(b"download:");
+    mac.update(path.as_bytes());
+    mac.update(b":");
+    mac.update(expires.to_string().as_bytes());
+    mac
+}
@@ -1,53 +1,37 @@
-use crate::config::Config;
+use crate::auth::code_store::{CodeEntry, CodeStore};
+use crate::config::{Config, SmsCodeFormat};
+use crate::error::{AppError, AppResult};
+use rand::Rng;
 use reqwest::Client;
-use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use uuid::Uuid;
+use std::time::Duration;
 
-/// Простое хранилище кодов для MVP (в продакшене использовать Redis)
-pub type CodeStorage = Arc<RwLock<HashMap<String, CodeEntry>>>;
+/// Алфавит буквенно-цифровых кодов: без 0/O и 1/I, которые легко перепутать
+const ALPHANUMERIC_CODE_CHARSET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
 
-#[derive(Clone)]
-pub struct CodeEntry {
-    pub code: String,
-    pub expires_at: chrono::DateTime<chrono::Utc>,
-    pub user_id: Option<Uuid>,
-}
+/// Как часто из хранилища удаляются истёкшие коды
+pub const CODE_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct SmsService {
-    codes: CodeStorage,
+    codes: Arc<dyn CodeStore>,
     config: Config,
 }
 
 impl SmsService {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, codes: Box<dyn CodeStore>) -> Self {
         Self {
-            codes: Arc::new(RwLock::new(HashMap::new())),
+            codes: Arc::from(codes),
             config,
         }
     }
 
-    /// Генерирует и сохраняет код для телефона, отправляет SMS
-    pub async fn generate_code(&self, phone: &str) -> Result<String, String> {
-        // Генерируем код заданной длины
-        let max_value = 10_u32.pow(self.config.sms_code_length);
-        let code = format!(
-            "{:0width$}",
-            rand::random::<u32>() % max_value,
-            width = self.config.sms_code_length as usize
-        );
-
-        let entry = CodeEntry {
-            code: code.clone(),
-            expires_at: chrono::Utc::now()
-                + chrono::Duration::minutes(self.config.sms_code_expiration_minutes),
-            user_id: None,
-        };
-
-        let mut codes = self.codes.write().await;
-        codes.insert(phone.to_string(), entry);
+    /// Генерирует и сохраняет код заданной длины для телефона, отправляет SMS
+    pub async fn generate_code(&self, phone: &str, length: u32) -> Result<String, String> {
+        let code = self
+            .store_code(phone, length)
+            .await
+            .map_err(|e| format!("Failed to store code: {}", e))?;
 
         // Отправляем SMS
         match self.send_sms(phone, &code).await {
@@ -77,13 +61,52 @@ impl SmsService {
         Ok(code)
     }
 
+    /// Генерирует и сохраняет код заданной длины для телефона без отправки SMS
+    /// (код доставляется другим каналом, например Telegram ботом)
+    pub async fn store_code(&self, phone: &str, length: u32) -> AppResult<String> {
+        let code = self.new_code(length);
+
+        let entry = CodeEntry {
+            code: code.clone(),
+            expires_at: chrono::Utc::now()
+                + chrono::Duration::minutes(self.config.sms_code_expiration_minutes),
+            user_id: None,
+            failed_attempts: 0,
+        };
+        self.codes.insert(phone, entry).await?;
+
+        Ok(code)
+    }
+
+    /// Генерирует код заданной длины в настроенном формате
+    fn new_code(&self, length: u32) -> String {
+        match self.config.sms_code_format {
+            SmsCodeFormat::Numeric => {
+                let max_value = 10_u32.pow(length);
+                format!(
+                    "{:0width$}",
+                    rand::random::<u32>() % max_value,
+                    width = length as usize
+                )
+            }
+            SmsCodeFormat::Alphanumeric => {
+                let mut rng = rand::thread_rng();
+                (0..length)
+                    .map(|_| {
+                        let index = rng.gen_range(0..ALPHANUMERIC_CODE_CHARSET.len());
+                        ALPHANUMERIC_CODE_CHARSET[index] as char
+                    })
+                    .collect()
+            }
+        }
+    }
+
     /// Отправляет SMS с кодом подтверждения
     async fn send_sms(&self, phone: &str, code: &str) -> Result<(), String> {
         // Проверяем, есть ли настройки SMS провайдера
-        let sms_api_url = std::env::var("SMS_API_URL").ok();
-        let sms_api_key = std::env::var("SMS_API_KEY").ok();
-
-        if sms_api_url.is_none() || sms_api_key.is_none() {
+        let (Some(sms_api_url), Some(sms_api_key)) =
+            (&self.config.sms_api_url, &self.config.sms_api_key)
+        else {
             // Если нет настроек SMS провайдера
             // В dev режиме (return_sms_code_in_response=true) продолжаем без ошибки
             // В prod режиме возвращаем ошибку
@@ -96,18 +119,15 @@ impl SmsService {
             } else {
                 return Err("SMS provider not configured. Set SMS_API_URL and SMS_API_KEY environment variables".to_string());
             }
-        }
+        };
 
         let client = Client::new();
         let message = format!("Ваш код подтверждения: {}", code);
 
         // Пример использования SMS API (можно адаптировать под любой провайдер)
         let response = client
-            .post(sms_api_url.as_ref().unwrap())
-            .header(
-                "Authorization",
-                format!("Bearer {}", sms_api_key.as_ref().unwrap()),
-            )
+            .post(sms_api_url)
+            .header("Authorization", format!("Bearer {}", sms_api_key))
             .json(&serde_json::json!({
                 "phone": phone,
                 "message": message
@@ -124,22 +144,69 @@ impl SmsService {
         Ok(())
     }
 
-    /// Проверяет код
-    pub async fn verify_code(&self, phone: &str, code: &str) -> bool {
-        let codes = self.codes.read().await;
+    /// Проверяет код и, если он верен, удаляет его (код одноразовый).
+    /// Буквенно-цифровой код сравнивается без учёта регистра.
+    /// Истёкший код считается отсутствующим и удаляется.
+    /// После `SMS_CODE_MAX_ATTEMPTS` неверных попыток код блокируется до истечения срока:
+    /// проверка возвращает `AppError::CodeAttemptsExceeded`, даже если код введён верно
+    pub async fn consume_code(&self, phone: &str, code: &str) -> AppResult<bool> {
+        let Some(entry) = self.codes.get(phone).await? else {
+            return Ok(false);
+        };
+        if entry.is_expired() {
+            self.codes.remove(phone).await?;
+            return Ok(false);
+        }
+        if entry.failed_attempts >= self.config.sms_code_max_attempts {
+            return Err(Self::attempts_exceeded());
+        }
 
-        if let Some(entry) = codes.get(phone) {
-            if entry.code == code && entry.expires_at > chrono::Utc::now() {
-                return true;
+        let matches = match self.config.sms_code_format {
+            SmsCodeFormat::Numeric => entry.code == code,
+            SmsCodeFormat::Alphanumeric => entry.code.eq_ignore_ascii_case(code),
+        };
+        if !matches {
+            // Счётчик увеличивается атомарно: параллельные догадки не обходят лимит
+            let attempts = self.codes.record_failed_attempt(phone).await?;
+            if attempts.is_some_and(|attempts| attempts >= self.config.sms_code_max_attempts) {
+                tracing::warn!(
+                    "SMS code for {} locked after {:?} failed attempts",
+                    phone,
+                    attempts
+                );
+                return Err(Self::attempts_exceeded());
             }
+            return Ok(false);
         }
 
-        false
+        // Из параллельных запросов с одним кодом пройдёт только тот, кто удалил его первым
+        self.codes.remove(phone).await
+    }
+
+    fn attempts_exceeded() -> AppError {
+        AppError::CodeAttemptsExceeded("Too many invalid code attempts".to_string())
+    }
+
+    /// Запускает периодическое удаление истёкших кодов в фоне: иначе коды
+    /// брошенных попыток входа копятся в хранилище (Redis удаляет их сам)
+    pub fn start_cleanup(&self, interval: Duration) {
+        let codes = self.codes.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match codes.prune_expired().await {
+                    Ok(0) => {}
+                    Ok(removed) => tracing::debug!("Removed {} expired SMS codes", removed),
+                    Err(e) => tracing::error!("SMS code cleanup failed: {:?}", e),
+                }
+            }
+        });
     }
 
-    /// Удаляет использованный код
-    pub async fn remove_code(&self, phone: &str) {
-        let mut codes = self.codes.write().await;
-        codes.remove(phone);
+    /// Сколько операций с кодами выполнено в памяти из-за сбоев Redis
+    /// (None, если REDIS_FALLBACK_INMEMORY не включён)
+    pub fn code_store_fallback_operations(&self) -> Option<u64> {
+        self.codes.fallback_operations()
     }
 }
@@ -0,0 +1,38 @@
+//! Однократная сверка users.plate с основными номерами из user_plates.
+//! Запуск: `cargo run --bin reconcile_plates` (нужен DATABASE_URL)
+
+use anyhow::Context;
+use rimskiy_service::db::pool::create_pool;
+use rimskiy_service::repository::PostgresUserPlateRepository;
+use rimskiy_service::service::plate_reconciliation::reconcile_plates;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+
+    let default_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&default_filter)),
+        )
+        .init();
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL is required")?;
+    let pool = create_pool(&database_url)
+        .await
+        .context("Failed to create database pool")?;
+    let user_plate_repository = PostgresUserPlateRepository::new(Arc::new(pool));
+
+    let report = reconcile_plates(&user_plate_repository)
+        .await
+        .map_err(|e| anyhow::anyhow!("Plate reconciliation failed: {}", e))?;
+
+    println!("Plates created from users.plate: {}", report.plates_created);
+    println!("Primaries promoted: {}", report.primaries_promoted);
+    println!("users.plate synced: {}", report.users_synced);
+    println!("Total rows corrected: {}", report.total());
+
+    Ok(())
+}
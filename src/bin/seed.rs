@@ -0,0 +1,162 @@
+//! Тестовые данные для локальной разработки: пользователи с известными телефонами и номерами,
+//! блокировки между ними и уведомления. Повторный запуск ничего не дублирует.
+//! Запуск: `DEV_MODE=true cargo run --bin seed` (нужны те же переменные, что и серверу)
+
+use anyhow::Context;
+use rimskiy_service::auth::create_token;
+use rimskiy_service::config::Config;
+use rimskiy_service::db::init::ensure_database_and_tables;
+use rimskiy_service::db::pool::create_pool;
+use rimskiy_service::models::notification::NotificationType;
+use rimskiy_service::repository::{
+    BlockRepository, CreateNotificationData, CreateUserData, NotificationRepository,
+    PostgresBlockRepository, PostgresNotificationRepository, PostgresUserPlateRepository,
+    PostgresUserRepository, UpdateUserData, UserPlateRepository, UserRepository,
+};
+use rimskiy_service::service::AuthService;
+use rimskiy_service::utils::encryption::{aad, Encryption};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Тестовые пользователи: телефон, имя, номер автомобиля
+const SEED_USERS: [(&str, &str, &str); 3] = [
+    ("+79990000001", "Тестовый Алексей", "А001АА777"),
+    ("+79990000002", "Тестовая Мария", "В002ВВ777"),
+    ("+79990000003", "Тестовый Охранник", "Е003ЕЕ777"),
+];
+
+/// Блокировки между тестовыми пользователями: (кто перекрыл, кого перекрыл) - индексы в SEED_USERS
+const SEED_BLOCKS: [(usize, usize); 2] = [(0, 1), (2, 1)];
+
+struct SeededUser {
+    id: Uuid,
+    phone: &'static str,
+    plate: &'static str,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+
+    let default_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&default_filter)),
+        )
+        .init();
+
+    // Защита от заполнения боевой базы тестовыми данными
+    let dev_mode = std::env::var("DEV_MODE")
+        .map(|value| matches!(value.trim(), "true" | "1"))
+        .unwrap_or(false);
+    if !dev_mode {
+        anyhow::bail!("Refusing to seed: set DEV_MODE=true (never run against production)");
+    }
+
+    let config = Config::from_env()?;
+    let pool = create_pool(&config.database_url)
+        .await
+        .context("Failed to create database pool")?;
+    ensure_database_and_tables(&pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to initialize database: {}", e))?;
+    let encryption = Encryption::from_config(&config)?;
+
+    let pool = Arc::new(pool);
+    let user_repository = PostgresUserRepository::new(pool.clone());
+    let user_plate_repository = PostgresUserPlateRepository::new(pool.clone());
+    let block_repository = PostgresBlockRepository::new(pool.clone());
+    let notification_repository = PostgresNotificationRepository::new(pool.clone());
+
+    let mut users = Vec::new();
+    for (phone, name, plate) in SEED_USERS {
+        let user = match user_repository
+            .find_by_phone_hash(&AuthService::phone_hash(phone))
+            .await?
+        {
+            Some(user) => user,
+            None => {
+                let id = Uuid::new_v4();
+                let phone_encrypted = encryption.encrypt(phone, Some(&aad::user_phone(id)))?;
+                let user = user_repository
+                    .create(&CreateUserData {
+                        id,
+                        phone_encrypted,
+                        phone_hash: AuthService::phone_hash(phone),
+                        plate: String::new(),
+                    })
+                    .await?;
+                user_repository
+                    .update(
+                        user.id,
+                        &UpdateUserData {
+                            name: Some(name.to_string()),
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+                println!("Created user {} ({})", phone, user.id);
+                user
+            }
+        };
+
+        let plates = user_plate_repository.find_by_user_id(user.id).await?;
+        if !plates.iter().any(|p| p.plate == plate) {
+            user_plate_repository
+                .create(user.id, plate, plates.is_empty(), None)
+                .await?;
+            println!("Added plate {} to {}", plate, phone);
+        }
+
+        users.push(SeededUser {
+            id: user.id,
+            phone,
+            plate,
+        });
+    }
+
+    for (blocker, blocked) in SEED_BLOCKS {
+        let (blocker, blocked) = (&users[blocker], &users[blocked]);
+        if block_repository
+            .exists(blocker.plate, blocked.plate)
+            .await?
+        {
+            continue;
+        }
+        let block = block_repository
+            .create(
+                blocker.id,
+                blocker.plate,
+                blocked.plate,
+                Some("loading"),
+                None,
+                false,
+            )
+            .await?;
+        // Уведомление создаётся вместе с блокировкой, поэтому тоже не дублируется
+        notification_repository
+            .create(&CreateNotificationData {
+                user_id: blocked.id,
+                r#type: NotificationType::BlockCreated,
+                title: "Ваш автомобиль заблокирован".to_string(),
+                message: format!(
+                    "Автомобиль {} заблокирован автомобилем {}",
+                    blocked.plate, blocker.plate
+                ),
+                data: Some(serde_json::json!({ "block_id": block.id })),
+            })
+            .await?;
+        println!("Created block {} -> {}", blocker.plate, blocked.plate);
+    }
+
+    println!();
+    println!("Seeded users (вход по SMS-коду; при RETURN_SMS_CODE_IN_RESPONSE=true код возвращается в ответе):");
+    for user in &users {
+        let token = create_token(user.id, &config)?;
+        println!("{}  {}  {}", user.phone, user.plate, user.id);
+        println!("  Authorization: Bearer {}", token);
+    }
+
+    Ok(())
+}
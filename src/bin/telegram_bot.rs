@@ -1,10 +1,15 @@
 use anyhow::Context;
 use axum::{extract::State, http::StatusCode, response::Json, routing::post, Router};
-use rimskiy_service::auth::sms::SmsService;
-use rimskiy_service::config::Config;
+use rimskiy_service::auth::code_store::code_store_from_config;
+use rimskiy_service::auth::signed_url::{sign_path, APP_DOWNLOAD_PATH};
+use rimskiy_service::auth::sms::{SmsService, CODE_CLEANUP_INTERVAL};
+use rimskiy_service::config::{
+    optional_env, Config, EncryptionBackend, JwtAlgorithm, SmsCodeFormat, DEFAULT_TELEGRAM_API_URL,
+};
 use rimskiy_service::db::pool::create_pool;
 use rimskiy_service::repository::{
-    PostgresTelegramBotRepository, PostgresUserRepository, TelegramBotRepository, UserRepository,
+    PostgresTelegramBotRepository, PostgresUserRepository, TelegramBotRepository, TelegramBotUser,
+    UserRepository,
 };
 use rimskiy_service::service::validation_service::ValidationService;
 use serde::{Deserialize, Serialize};
@@ -33,16 +38,27 @@ enum Command {
 struct BotConfig {
     sms_code_expiration_minutes: i64,
     sms_code_length: u32,
+    redis_url: Option<String>,
+    redis_fallback_inmemory: bool,
+    sms_code_format: SmsCodeFormat,
     return_sms_code_in_response: bool,
     server_host: String,
     server_port: u16,
     app_apk_path: Option<String>,
+    /// Секрет сервера (JWT_SECRET) для подписи ссылки на APK при скачивании через API
+    jwt_secret: String,
+    sms_api_url: Option<String>,
+    sms_api_key: Option<String>,
+    /// Базовый адрес Telegram Bot API (TELEGRAM_API_URL)
+    telegram_api_url: reqwest::Url,
 }
 
 #[derive(Clone)]
 struct BotState {
     sms_service: Arc<SmsService>,
     config: Arc<BotConfig>,
+    /// Конфигурация в формате сервера (для подписи ссылки на APK)
+    service_config: Arc<Config>,
     http_client: reqwest::Client,
     api_base_url: String,
     apk_path: Option<String>,
@@ -51,12 +67,43 @@ struct BotState {
     user_repository: Arc<PostgresUserRepository>,
 }
 
+/// Через сколько дней без активности лишний чат номера считается устаревшим
+const STALE_REGISTRATION_DAYS: i64 = 180;
+
 fn phone_hash(phone: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(phone.as_bytes());
     format!("{:x}", hasher.finalize())
 }
 
+/// Убирает дубли после привязки номера: временную запись и запись с прежним номером
+/// этого чата, а также давно неактивные чаты номера (самый свежий сохраняется)
+async fn consolidate_registrations(state: &BotState, registration: &TelegramBotUser) {
+    let repository = &state.telegram_bot_repository;
+    match repository
+        .delete_other_for_chat_id(registration.chat_id, registration.id)
+        .await
+    {
+        Ok(0) => {}
+        Ok(deleted) => tracing::info!(
+            "Удалено {} лишних регистраций chat_id {}",
+            deleted,
+            registration.chat_id
+        ),
+        Err(e) => tracing::warn!("Не удалось удалить лишние регистрации чата: {}", e),
+    }
+
+    let inactive_since = chrono::Utc::now() - chrono::Duration::days(STALE_REGISTRATION_DAYS);
+    match repository
+        .delete_stale_for_phone_hash(&registration.phone_hash, inactive_since)
+        .await
+    {
+        Ok(0) => {}
+        Ok(deleted) => tracing::info!("Удалено {} устаревших чатов номера", deleted),
+        Err(e) => tracing::warn!("Не удалось удалить устаревшие чаты номера: {}", e),
+    }
+}
+
 fn load_bot_config() -> anyhow::Result<BotConfig> {
     let sms_code_expiration_minutes = std::env::var("SMS_CODE_EXPIRATION_MINUTES")
         .unwrap_or_else(|_| "10".to_string())
@@ -66,24 +113,44 @@ fn load_bot_config() -> anyhow::Result<BotConfig> {
         .unwrap_or_else(|_| "4".to_string())
         .parse()
         .context("SMS_CODE_LENGTH must be a valid number")?;
+    let sms_code_format = match std::env::var("SMS_CODE_FORMAT") {
+        Ok(value) => SmsCodeFormat::parse(&value)
+            .context("SMS_CODE_FORMAT must be numeric or alphanumeric")?,
+        Err(_) => SmsCodeFormat::Numeric,
+    };
     let return_sms_code_in_response = std::env::var("RETURN_SMS_CODE_IN_RESPONSE")
         .unwrap_or_else(|_| "true".to_string())
         .parse()
         .unwrap_or(true);
+    let redis_fallback_inmemory = std::env::var("REDIS_FALLBACK_INMEMORY")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .context("REDIS_FALLBACK_INMEMORY must be true or false")?;
     let server_host = std::env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
     let server_port = std::env::var("SERVER_PORT")
         .unwrap_or_else(|_| "8080".to_string())
         .parse()
         .context("SERVER_PORT must be a valid number")?;
     let app_apk_path = std::env::var("APP_APK_PATH").ok();
+    let telegram_api_url = optional_env("TELEGRAM_API_URL")
+        .unwrap_or_else(|| DEFAULT_TELEGRAM_API_URL.to_string())
+        .parse()
+        .context("TELEGRAM_API_URL must be a valid URL")?;
 
     Ok(BotConfig {
         sms_code_expiration_minutes,
         sms_code_length,
+        redis_url: optional_env("REDIS_URL"),
+        redis_fallback_inmemory,
+        sms_code_format,
         return_sms_code_in_response,
         server_host,
         server_port,
         app_apk_path,
+        jwt_secret: std::env::var("JWT_SECRET").unwrap_or_default(),
+        sms_api_url: optional_env("SMS_API_URL"),
+        sms_api_key: optional_env("SMS_API_KEY"),
+        telegram_api_url,
     })
 }
 
@@ -235,24 +302,75 @@ async fn main() -> anyhow::Result<()> {
 
     // Создаём SMS сервис (используем минимальную конфигурацию)
     let sms_config = Config {
-        database_url: String::new(), // Не используется ботом
-        jwt_secret: String::new(),   // Не используется ботом
-        jwt_expiration_minutes: 0,   // Не используется ботом
+        database_url: String::new(),           // Не используется ботом
+        jwt_secret: config.jwt_secret.clone(), // Подпись ссылки на APK
+        jwt_secrets_accept: Vec::new(),        // Не используется ботом
+        jwt_expiration_minutes: 0,             // Не используется ботом
+        jwt_algorithm: JwtAlgorithm::Hs256,    // Не используется ботом
+        jwt_private_key: None,                 // Не используется ботом
+        jwt_public_key: None,                  // Не используется ботом
         encryption_key: "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
             .to_string(), // Не используется ботом, но требуется для создания SmsService
+        encryption_key_version: 1,             // Не используется ботом
+        encryption_previous_keys: Vec::new(),  // Не используется ботом
+        encryption_backend: EncryptionBackend::AesGcm, // Не используется ботом
+        encryption_allow_key_mismatch: false,  // Не используется ботом
         server_host: config.server_host.clone(),
         server_port: config.server_port,
         migrations_path: String::new(), // Не используется ботом
         sms_code_expiration_minutes: config.sms_code_expiration_minutes,
         sms_code_length: config.sms_code_length,
+        telegram_code_length: config.sms_code_length, // Не используется ботом
+        sms_code_format: config.sms_code_format,
+        sms_code_max_attempts: 5, // Не используется ботом
+        redis_url: config.redis_url.clone(),
+        redis_fallback_inmemory: config.redis_fallback_inmemory,
         return_sms_code_in_response: config.return_sms_code_in_response,
         fcm_server_key: None,
         min_client_version: None,
         release_client_version: None,
+        min_client_version_routes: Vec::new(), // Не используется ботом
         app_download_url: None,
         app_apk_path: config.app_apk_path.clone(),
+        signed_url_expiration_minutes: 1, // Ссылку на APK бот использует сразу
+        block_rate_limit_per_hour: 0,     // Не используется ботом
+        validate_rate_limit_per_minute: 0, // Не используется ботом
+        announce_batch_size: 0,           // Не используется ботом
+        announce_max_concurrency: 0,      // Не используется ботом
+        repeat_block_free_count: 0,       // Не используется ботом
+        blob_storage_path: String::new(), // Не используется ботом
+        admin_user_ids: Vec::new(),       // Не используется ботом
+        security_user_ids: Vec::new(),    // Не используется ботом
+        mask_public_plates: false,        // Не используется ботом
+        plate_reconcile_interval_minutes: 0, // Не используется ботом
+        notification_retention_days: 0,   // Не используется ботом
+        block_retention_days: 0,          // Не используется ботом
+        analytics_enabled: false,         // Не используется ботом
+        analytics_endpoint: None,         // Не используется ботом
+        analytics_secret: None,           // Не используется ботом
+        owner_quiet_hours: None,          // Не используется ботом
+        trusted_proxies: Vec::new(),      // Не используется ботом
+        public_base_url: None,            // Не используется ботом
+        sms_api_url: config.sms_api_url.clone(),
+        sms_api_key: config.sms_api_key.clone(),
+        telephony_api_url: None, // Не используется ботом
+        telephony_api_key: None, // Не используется ботом
+        ocr_api_url: None,       // Не используется ботом
+        telegram_api_url: config
+            .telegram_api_url
+            .as_str()
+            .trim_end_matches('/')
+            .to_string(),
+        escalation_window_minutes: 0,          // Не используется ботом
+        escalation_admin_phone: None,          // Не используется ботом
+        message_templates: Default::default(), // Не используется ботом
     };
-    let sms_service = Arc::new(SmsService::new(sms_config));
+    // С общим Redis коды, выданные ботом, принимает и основной сервер
+    let code_store =
+        code_store_from_config(config.redis_url.as_deref(), config.redis_fallback_inmemory).await?;
+    let service_config = Arc::new(sms_config.clone());
+    let sms_service = Arc::new(SmsService::new(sms_config, code_store));
+    sms_service.start_cleanup(CODE_CLEANUP_INTERVAL);
 
     // Получаем базовый URL API сервера
     let api_base_url = std::env::var("API_BASE_URL")
@@ -274,11 +392,12 @@ async fn main() -> anyhow::Result<()> {
         None
     });
 
-    let bot = Bot::new(token.clone());
+    let bot = Bot::new(token.clone()).set_api_url(config.telegram_api_url.clone());
 
     let bot_state = Arc::new(BotState {
         sms_service,
         config,
+        service_config,
         http_client: reqwest::Client::new(),
         api_base_url,
         apk_path,
@@ -649,13 +768,14 @@ async fn handle_code_command(
         )
         .await
     {
-        Ok(_) => {
+        Ok(registration) => {
             tracing::info!(
                 "Сохранена связь {} -> chat_id {} -> user_id {} в БД",
                 normalized_phone,
                 msg.chat.id.0,
                 user.id
             );
+            consolidate_registrations(state, &registration).await;
         }
         Err(e) => {
             tracing::warn!("Не удалось сохранить связь в БД: {}", e);
@@ -680,6 +800,9 @@ async fn handle_code_command(
                 owner_info: None,
                 departure_time: None,
                 push_token: None,
+                announcement_push: None,
+                announcement_telegram: None,
+                utc_offset_minutes: None,
             };
             if let Err(e) = state.user_repository.update(user.id, &update_data).await {
                 tracing::warn!("Не удалось обновить telegram username в профиле: {}", e);
@@ -705,7 +828,11 @@ async fn handle_code_command(
         .await?;
 
     // Генерируем код (это автоматически отправляет SMS)
-    match state.sms_service.generate_code(&normalized_phone).await {
+    match state
+        .sms_service
+        .generate_code(&normalized_phone, state.config.sms_code_length)
+        .await
+    {
         Ok(code) => {
             // Проверяем, настроен ли SMS провайдер
             let sms_configured =
@@ -918,7 +1045,11 @@ async fn handle_apk_command(bot: &Bot, msg: &Message, state: &BotState) -> Respo
 
     if !apk_sent {
         // Если не удалось отправить файл напрямую, пробуем через API
-        let download_url = format!("{}/api/app/download", state.api_base_url);
+        let download_url = format!(
+            "{}{}",
+            state.api_base_url,
+            sign_path(APP_DOWNLOAD_PATH, &state.service_config).url
+        );
         let _ = bot.delete_message(msg.chat.id, processing_msg.id).await;
 
         match state.http_client.get(&download_url).send().await {
@@ -1336,7 +1467,7 @@ async fn send_code_handler(
                                             "auto_registered": true
                                         })))
                                     }
-                                    Err(_e) => {
+                                    Err(e) => {
                                         tracing::warn!(
                                             "Не удалось отправить код в чат {}: {}",
                                             updated_reg.chat_id,
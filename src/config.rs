@@ -1,40 +1,205 @@
 use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use jsonwebtoken::{DecodingKey, EncodingKey};
 use std::env;
+use uuid::Uuid;
+
+use crate::service::message_templates::MessageTemplates;
+use crate::utils::encryption::MAX_KEY_VERSION;
+use crate::utils::network::IpNetwork;
+use crate::utils::time::parse_time_window;
+
+/// Адрес Telegram Bot API по умолчанию (TELEGRAM_API_URL)
+pub const DEFAULT_TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Формат кода подтверждения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsCodeFormat {
+    /// Только цифры
+    Numeric,
+    /// Цифры и заглавные латинские буквы без неоднозначных символов
+    Alphanumeric,
+}
+
+impl SmsCodeFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "numeric" => Some(Self::Numeric),
+            "alphanumeric" => Some(Self::Alphanumeric),
+            _ => None,
+        }
+    }
+}
+
+/// Алгоритм подписи JWT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    /// HMAC с общим секретом JWT_SECRET
+    Hs256,
+    /// RSA: подпись закрытым ключом, проверка открытым (его можно раздать другим сервисам)
+    Rs256,
+}
+
+impl JwtAlgorithm {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "HS256" => Some(Self::Hs256),
+            "RS256" => Some(Self::Rs256),
+            _ => None,
+        }
+    }
+}
+
+/// Алгоритм шифрования персональных данных
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionBackend {
+    /// AES-256-GCM с ключами из ENCRYPTION_KEY / ENCRYPTION_PREVIOUS_KEYS
+    AesGcm,
+}
+
+impl EncryptionBackend {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "aes-gcm" | "aes256-gcm" => Some(Self::AesGcm),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
+    pub jwt_secrets_accept: Vec<String>,
     pub jwt_expiration_minutes: i64,
+    pub jwt_algorithm: JwtAlgorithm,
+    /// Закрытый RSA ключ (из JWT_PRIVATE_KEY_PATH) для подписи токенов при RS256.
+    /// Разбирается один раз при загрузке конфигурации
+    pub jwt_private_key: Option<EncodingKey>,
+    /// Открытый RSA ключ (из JWT_PUBLIC_KEY_PATH) для проверки токенов при RS256
+    pub jwt_public_key: Option<DecodingKey>,
     pub encryption_key: String,
+    pub encryption_key_version: u8,
+    pub encryption_previous_keys: Vec<(u8, String)>,
+    pub encryption_backend: EncryptionBackend,
+    pub encryption_allow_key_mismatch: bool,
     pub server_host: String,
     pub server_port: u16,
     pub migrations_path: String,
     pub sms_code_expiration_minutes: i64,
     pub sms_code_length: u32,
+    /// Длина кода, отправляемого через Telegram бота (по умолчанию как у SMS)
+    pub telegram_code_length: u32,
+    pub sms_code_format: SmsCodeFormat,
+    /// Сколько неверных попыток ввода кода допускается, прежде чем код блокируется
+    pub sms_code_max_attempts: u32,
+    /// Redis для кодов подтверждения (общий для всех экземпляров); без него - память процесса
+    pub redis_url: Option<String>,
+    /// При сбоях Redis хранить коды в памяти процесса вместо ошибки авторизации
+    pub redis_fallback_inmemory: bool,
     pub return_sms_code_in_response: bool,
     pub fcm_server_key: Option<String>,
     pub min_client_version: Option<String>,
     pub release_client_version: Option<String>,
+    /// Минимальные версии клиента для отдельных маршрутов: (префикс пути, версия)
+    pub min_client_version_routes: Vec<(String, String)>,
     pub app_download_url: Option<String>,
     pub app_apk_path: Option<String>,
+    /// Срок жизни подписанных ссылок на скачивание (APK, фото, аватары)
+    pub signed_url_expiration_minutes: i64,
+    pub block_rate_limit_per_hour: u32,
+    /// Сколько запросов к `POST /api/validate` допускается с одного IP в минуту (0 - без ограничения)
+    pub validate_rate_limit_per_minute: u32,
+    /// Размер пачки пользователей при рассылке объявления
+    pub announce_batch_size: i64,
+    /// Максимум одновременных запросов к провайдерам (FCM, Telegram) при рассылке объявлений
+    pub announce_max_concurrency: usize,
+    pub repeat_block_free_count: u32,
+    pub blob_storage_path: String,
+    pub admin_user_ids: Vec<Uuid>,
+    pub security_user_ids: Vec<Uuid>,
+    pub mask_public_plates: bool,
+    pub plate_reconcile_interval_minutes: u64,
+    pub notification_retention_days: u32,
+    pub block_retention_days: u32,
+    pub analytics_enabled: bool,
+    pub analytics_endpoint: Option<String>,
+    /// Секрет HMAC для обезличивания идентификаторов пользователей в аналитике
+    pub analytics_secret: Option<String>,
+    pub owner_quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    pub trusted_proxies: Vec<IpNetwork>,
+    /// Внешний адрес сервиса (схема и хост за прокси) для абсолютных ссылок, без `/` в конце
+    pub public_base_url: Option<String>,
+    /// Адреса и ключи внешних провайдеров: читаются один раз при запуске, чтобы направить
+    /// сервис на staging или self-hosted заглушки (None - провайдер не настроен)
+    pub sms_api_url: Option<String>,
+    pub sms_api_key: Option<String>,
+    pub telephony_api_url: Option<String>,
+    pub telephony_api_key: Option<String>,
+    pub ocr_api_url: Option<String>,
+    /// Базовый адрес Telegram Bot API без `/` в конце
+    pub telegram_api_url: String,
+    pub escalation_window_minutes: u64,
+    pub escalation_admin_phone: Option<String>,
+    pub message_templates: MessageTemplates,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self> {
         let database_url = env::var("DATABASE_URL").context("DATABASE_URL is required")?;
         let jwt_secret = env::var("JWT_SECRET").context("JWT_SECRET is required")?;
+        // Прежние секреты, токены с которыми ещё принимаются (через запятую, для ротации JWT_SECRET)
+        let jwt_secrets_accept = env::var("JWT_SECRETS_ACCEPT")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|secret| !secret.is_empty())
+            .map(str::to_string)
+            .collect();
         // Срок жизни JWT: по умолчанию 7 дней (10080 минут)
         let jwt_expiration_minutes = env::var("JWT_EXPIRATION_MINUTES")
             .unwrap_or_else(|_| "10080".to_string())
             .parse()
             .context("JWT_EXPIRATION_MINUTES must be a valid number")?;
 
+        let jwt_algorithm = match env::var("JWT_ALGORITHM") {
+            Ok(value) => {
+                JwtAlgorithm::parse(&value).context("JWT_ALGORITHM must be HS256 or RS256")?
+            }
+            Err(_) => JwtAlgorithm::Hs256,
+        };
+        let jwt_private_key = optional_env("JWT_PRIVATE_KEY_PATH")
+            .map(|path| parse_jwt_private_key(&read_key_file("JWT_PRIVATE_KEY_PATH", &path)?))
+            .transpose()?;
+        let jwt_public_key = optional_env("JWT_PUBLIC_KEY_PATH")
+            .map(|path| parse_jwt_public_key(&read_key_file("JWT_PUBLIC_KEY_PATH", &path)?))
+            .transpose()?;
         let encryption_key = env::var("ENCRYPTION_KEY")
             .context("ENCRYPTION_KEY is required (must be 64 hex characters)")?;
-        if encryption_key.len() != 64 || hex::decode(&encryption_key).is_err() {
-            anyhow::bail!("ENCRYPTION_KEY must be 64 hex characters");
-        }
+        // Версия текущего ключа (записывается первым байтом шифротекста)
+        let encryption_key_version = env::var("ENCRYPTION_KEY_VERSION")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .context("ENCRYPTION_KEY_VERSION must be a number from 0 to 127")?;
+        // Предыдущие ключи для расшифровки старых данных: "версия:ключ" через запятую
+        let encryption_previous_keys = env::var("ENCRYPTION_PREVIOUS_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (version, key) = entry.split_once(':').with_context(|| {
+                    "ENCRYPTION_PREVIOUS_KEYS entries must look like <version>:<hex key>"
+                })?;
+                let version = version.trim().parse::<u8>().with_context(|| {
+                    format!(
+                        "Invalid key version in ENCRYPTION_PREVIOUS_KEYS: {}",
+                        version
+                    )
+                })?;
+                Ok((version, key.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
         let server_port = env::var("SERVER_PORT")
@@ -47,10 +212,40 @@ impl Config {
             .unwrap_or_else(|_| "10".to_string())
             .parse()
             .context("SMS_CODE_EXPIRATION_MINUTES must be a valid number")?;
+        let encryption_backend = match env::var("ENCRYPTION_BACKEND") {
+            Ok(value) => {
+                EncryptionBackend::parse(&value).context("ENCRYPTION_BACKEND must be aes-gcm")?
+            }
+            Err(_) => EncryptionBackend::AesGcm,
+        };
+        // Разрешить запуск, если сохранённые данные не расшифровываются текущими ключами
+        let encryption_allow_key_mismatch = env::var("ENCRYPTION_ALLOW_KEY_MISMATCH")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
         let sms_code_length = env::var("SMS_CODE_LENGTH")
             .unwrap_or_else(|_| "4".to_string())
             .parse()
             .context("SMS_CODE_LENGTH must be a valid number")?;
+        let telegram_code_length = match optional_env("TELEGRAM_CODE_LENGTH") {
+            Some(value) => value
+                .parse()
+                .context("TELEGRAM_CODE_LENGTH must be a valid number")?,
+            None => sms_code_length,
+        };
+        let sms_code_format = match env::var("SMS_CODE_FORMAT") {
+            Ok(value) => SmsCodeFormat::parse(&value)
+                .context("SMS_CODE_FORMAT must be numeric or alphanumeric")?,
+            Err(_) => SmsCodeFormat::Numeric,
+        };
+        let redis_fallback_inmemory = env::var("REDIS_FALLBACK_INMEMORY")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .context("REDIS_FALLBACK_INMEMORY must be true or false")?;
+        let sms_code_max_attempts = env::var("SMS_CODE_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .context("SMS_CODE_MAX_ATTEMPTS must be a valid number")?;
         let return_sms_code_in_response = env::var("RETURN_SMS_CODE_IN_RESPONSE")
             .unwrap_or_else(|_| "true".to_string())
             .parse()
@@ -58,25 +253,466 @@ impl Config {
         let fcm_server_key = env::var("FCM_SERVER_KEY").ok();
         let min_client_version = env::var("MIN_CLIENT_VERSION").ok();
         let release_client_version = env::var("RELEASE_CLIENT_VERSION").ok();
+        // Маршруты, требующие более новой версии клиента: "/api/path=1.2.0,/api/other=1.3.0"
+        let min_client_version_routes = env::var("MIN_CLIENT_VERSION_ROUTES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (route, version) = entry.split_once('=').with_context(|| {
+                    "MIN_CLIENT_VERSION_ROUTES entries must look like <path>=<version>"
+                })?;
+                Ok((
+                    route.trim().trim_end_matches('/').to_string(),
+                    version.trim().to_string(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
         let app_download_url = env::var("APP_DOWNLOAD_URL").ok();
         let app_apk_path = env::var("APP_APK_PATH").ok();
+        let signed_url_expiration_minutes = env::var("SIGNED_URL_EXPIRATION_MINUTES")
+            .unwrap_or_else(|_| "15".to_string())
+            .parse()
+            .context("SIGNED_URL_EXPIRATION_MINUTES must be a valid number")?;
+        // Сколько блокировок пользователь может создать за час (0 - без ограничения)
+        let block_rate_limit_per_hour = env::var("BLOCK_RATE_LIMIT_PER_HOUR")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .context("BLOCK_RATE_LIMIT_PER_HOUR must be a valid number")?;
+        let validate_rate_limit_per_minute = env::var("VALIDATE_RATE_LIMIT_PER_MINUTE")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .context("VALIDATE_RATE_LIMIT_PER_MINUTE must be a valid number")?;
+        // Рассылка объявлений: по сколько пользователей за раз и сколько запросов к провайдерам одновременно
+        let announce_batch_size = env::var("ANNOUNCE_BATCH_SIZE")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()
+            .context("ANNOUNCE_BATCH_SIZE must be a valid number")?;
+        let announce_max_concurrency = env::var("ANNOUNCE_MAX_CONCURRENCY")
+            .unwrap_or_else(|_| "16".to_string())
+            .parse()
+            .context("ANNOUNCE_MAX_CONCURRENCY must be a valid number")?;
+        // Сколько раз можно перекрыть один и тот же автомобиль без явного подтверждения
+        // повтора (acknowledge_repeat; 0 - без ограничения)
+        let repeat_block_free_count = env::var("REPEAT_BLOCK_FREE_COUNT")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .context("REPEAT_BLOCK_FREE_COUNT must be a valid number")?;
+        // Каталог для хранения загруженных файлов (фото блокировок)
+        let blob_storage_path =
+            env::var("BLOB_STORAGE_PATH").unwrap_or_else(|_| "./storage".to_string());
+        // Пользователи с правами администратора (UUID через запятую)
+        let admin_user_ids = env::var("ADMIN_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<Uuid>()
+                    .with_context(|| format!("ADMIN_USER_IDS contains invalid UUID: {}", id))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Пользователи с ролью охраны (UUID через запятую): видят все действующие блокировки
+        let security_user_ids = env::var("SECURITY_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<Uuid>()
+                    .with_context(|| format!("SECURITY_USER_IDS contains invalid UUID: {}", id))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Скрывать ли часть номера в публичной информации для посторонних пользователей
+        let mask_public_plates = env::var("MASK_PUBLIC_PLATES")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        // Период фоновой сверки users.plate с user_plates (0 - отключена)
+        let plate_reconcile_interval_minutes = env::var("PLATE_RECONCILE_INTERVAL_MINUTES")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .context("PLATE_RECONCILE_INTERVAL_MINUTES must be a valid number")?;
+
+        // Срок хранения уведомлений и истории снятых блокировок в днях (0 - хранить бессрочно)
+        let notification_retention_days = env::var("NOTIFICATION_RETENTION_DAYS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("NOTIFICATION_RETENTION_DAYS must be a valid number")?;
+        let block_retention_days = env::var("BLOCK_RETENTION_DAYS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("BLOCK_RETENTION_DAYS must be a valid number")?;
 
-        Ok(Config {
+        // Обезличенная аналитика (агрегированные события без ПДн)
+        let analytics_enabled = env::var("ANALYTICS_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+        // Куда отправлять события аналитики (если не задан - события пишутся в лог)
+        let analytics_endpoint = env::var("ANALYTICS_ENDPOINT")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+        // Секрет обезличивания идентификаторов (обязателен при включённой аналитике)
+        let analytics_secret = env::var("ANALYTICS_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty());
+
+        // Тихие часы владельца по его местному времени: вместо звонка отправляется push (пусто - отключены)
+        let owner_quiet_hours = match env::var("OWNER_QUIET_HOURS") {
+            Ok(value) if !value.trim().is_empty() => Some(
+                parse_time_window(&value)
+                    .context("OWNER_QUIET_HOURS must be in format HH:MM-HH:MM")?,
+            ),
+            _ => None,
+        };
+
+        // Доверенные прокси (nginx, балансировщик), которым можно верить в X-Forwarded-For
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                IpNetwork::parse(value)
+                    .with_context(|| format!("TRUSTED_PROXIES contains invalid address: {}", value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Внешний адрес сервиса за прокси, например https://parking.example.com
+        let public_base_url = env::var("PUBLIC_BASE_URL")
+            .ok()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+
+        // Внешние провайдеры (SMS, телефония, OCR, Telegram)
+        let sms_api_url = optional_env("SMS_API_URL");
+        let sms_api_key = optional_env("SMS_API_KEY");
+        let telephony_api_url = optional_env("TELEPHONY_API_URL");
+        let telephony_api_key = optional_env("TELEPHONY_API_KEY");
+        let ocr_api_url = optional_env("OCR_API_URL");
+        let telegram_api_url = optional_env("TELEGRAM_API_URL")
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| DEFAULT_TELEGRAM_API_URL.to_string());
+
+        // Эскалация неподтверждённой блокировки: через сколько минут без подтверждения
+        // выполняется каждый следующий шаг (повторный push, звонок, контакт администратора; 0 - отключена)
+        let escalation_window_minutes = env::var("ESCALATION_WINDOW_MINUTES")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("ESCALATION_WINDOW_MINUTES must be a valid number")?;
+        // Телефон администратора дома, которому звоним на последнем шаге эскалации
+        let escalation_admin_phone = env::var("ESCALATION_ADMIN_PHONE")
+            .ok()
+            .map(|phone| phone.trim().to_string())
+            .filter(|phone| !phone.is_empty());
+        // Шаблоны сообщений о блокировке (MESSAGE_TEMPLATE_*)
+        let message_templates = MessageTemplates::from_env();
+
+        let config = Config {
             database_url,
             jwt_secret,
+            jwt_secrets_accept,
             jwt_expiration_minutes,
+            jwt_algorithm,
+            jwt_private_key,
+            jwt_public_key,
             encryption_key,
+            encryption_key_version,
+            encryption_previous_keys,
+            encryption_backend,
+            encryption_allow_key_mismatch,
             server_host,
             server_port,
             migrations_path,
             sms_code_expiration_minutes,
             sms_code_length,
+            telegram_code_length,
+            sms_code_format,
+            sms_code_max_attempts,
+            redis_url: optional_env("REDIS_URL"),
+            redis_fallback_inmemory,
             return_sms_code_in_response,
             fcm_server_key,
             min_client_version,
             release_client_version,
+            min_client_version_routes,
             app_download_url,
             app_apk_path,
-        })
+            signed_url_expiration_minutes,
+            block_rate_limit_per_hour,
+            validate_rate_limit_per_minute,
+            announce_batch_size,
+            announce_max_concurrency,
+            repeat_block_free_count,
+            blob_storage_path,
+            admin_user_ids,
+            security_user_ids,
+            mask_public_plates,
+            plate_reconcile_interval_minutes,
+            notification_retention_days,
+            block_retention_days,
+            analytics_enabled,
+            analytics_endpoint,
+            analytics_secret,
+            owner_quiet_hours,
+            trusted_proxies,
+            public_base_url,
+            sms_api_url,
+            sms_api_key,
+            telephony_api_url,
+            telephony_api_key,
+            ocr_api_url,
+            telegram_api_url,
+            escalation_window_minutes,
+            escalation_admin_phone,
+            message_templates,
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Проверяет корректность конфигурации целиком (включая зависимости между полями).
+    /// Вызывается один раз после загрузки, чтобы сервер не стартовал с заведомо неверными настройками
+    pub fn validate(&self) -> Result<()> {
+        if self.jwt_secret.len() < 32 {
+            anyhow::bail!("JWT_SECRET must be at least 32 characters");
+        }
+        if self
+            .jwt_secrets_accept
+            .iter()
+            .any(|secret| secret.len() < 32)
+        {
+            anyhow::bail!("JWT_SECRETS_ACCEPT secrets must be at least 32 characters");
+        }
+        if self.jwt_expiration_minutes <= 0 {
+            anyhow::bail!("JWT_EXPIRATION_MINUTES must be positive");
+        }
+        match self.jwt_algorithm {
+            JwtAlgorithm::Rs256 => {
+                // Сервер и выдаёт, и проверяет токены: нужны оба ключа
+                if self.jwt_private_key.is_none() {
+                    anyhow::bail!("JWT_PRIVATE_KEY_PATH is required for JWT_ALGORITHM=RS256");
+                }
+                if self.jwt_public_key.is_none() {
+                    anyhow::bail!("JWT_PUBLIC_KEY_PATH is required for JWT_ALGORITHM=RS256");
+                }
+            }
+            JwtAlgorithm::Hs256 => {
+                if self.jwt_private_key.is_some() || self.jwt_public_key.is_some() {
+                    tracing::warn!(
+                        "JWT_PRIVATE_KEY_PATH / JWT_PUBLIC_KEY_PATH are ignored with JWT_ALGORITHM=HS256"
+                    );
+                }
+            }
+        }
+        if !(1..=10_000).contains(&self.announce_batch_size) {
+            anyhow::bail!("ANNOUNCE_BATCH_SIZE must be between 1 and 10000");
+        }
+        if self.announce_max_concurrency == 0 {
+            anyhow::bail!("ANNOUNCE_MAX_CONCURRENCY must be positive");
+        }
+        if self.signed_url_expiration_minutes <= 0 {
+            anyhow::bail!("SIGNED_URL_EXPIRATION_MINUTES must be positive");
+        }
+
+        if !is_hex_key(&self.encryption_key) {
+            anyhow::bail!("ENCRYPTION_KEY must be 64 hex characters");
+        }
+        if self.encryption_key_version > MAX_KEY_VERSION {
+            anyhow::bail!(
+                "ENCRYPTION_KEY_VERSION must be from 0 to {}",
+                MAX_KEY_VERSION
+            );
+        }
+        let mut versions = std::collections::HashSet::from([self.encryption_key_version]);
+        for (version, key) in &self.encryption_previous_keys {
+            if !is_hex_key(key) {
+                anyhow::bail!("ENCRYPTION_PREVIOUS_KEYS keys must be 64 hex characters");
+            }
+            if *version > MAX_KEY_VERSION {
+                anyhow::bail!(
+                    "ENCRYPTION_PREVIOUS_KEYS key versions must be from 0 to {}",
+                    MAX_KEY_VERSION
+                );
+            }
+            if !versions.insert(*version) {
+                anyhow::bail!(
+                    "Encryption key version {} is used more than once (ENCRYPTION_KEY_VERSION / ENCRYPTION_PREVIOUS_KEYS)",
+                    version
+                );
+            }
+        }
+
+        if self.server_host.parse::<std::net::IpAddr>().is_err() {
+            anyhow::bail!(
+                "SERVER_HOST must be a valid IP address: {}",
+                self.server_host
+            );
+        }
+        if self.server_port == 0 {
+            anyhow::bail!("SERVER_PORT must not be 0");
+        }
+
+        if self.sms_code_expiration_minutes <= 0 {
+            anyhow::bail!("SMS_CODE_EXPIRATION_MINUTES must be positive");
+        }
+        if !(4..=8).contains(&self.sms_code_length) {
+            anyhow::bail!("SMS_CODE_LENGTH must be between 4 and 8");
+        }
+        if !(4..=8).contains(&self.telegram_code_length) {
+            anyhow::bail!("TELEGRAM_CODE_LENGTH must be between 4 and 8");
+        }
+        if self.sms_code_max_attempts == 0 {
+            anyhow::bail!("SMS_CODE_MAX_ATTEMPTS must be positive");
+        }
+        if let Some(redis_url) = &self.redis_url {
+            if !redis_url.starts_with("redis://") && !redis_url.starts_with("rediss://") {
+                anyhow::bail!("REDIS_URL must start with redis:// or rediss://");
+            }
+        }
+        if self.return_sms_code_in_response {
+            tracing::warn!(
+                "RETURN_SMS_CODE_IN_RESPONSE is enabled: SMS codes are returned in API responses, do not use in production"
+            );
+        }
+
+        for (name, version) in [
+            ("MIN_CLIENT_VERSION", &self.min_client_version),
+            ("RELEASE_CLIENT_VERSION", &self.release_client_version),
+        ] {
+            if let Some(version) = version {
+                if parse_version(version).is_none() {
+                    anyhow::bail!("{} must look like 1.2.3: {}", name, version);
+                }
+            }
+        }
+        for (route, version) in &self.min_client_version_routes {
+            if !route.starts_with('/') {
+                anyhow::bail!(
+                    "MIN_CLIENT_VERSION_ROUTES path must start with '/': {}",
+                    route
+                );
+            }
+            if parse_version(version).is_none() {
+                anyhow::bail!(
+                    "MIN_CLIENT_VERSION_ROUTES version for {} must look like 1.2.3: {}",
+                    route,
+                    version
+                );
+            }
+        }
+        if let (Some(min), Some(release)) = (
+            self.min_client_version.as_deref().and_then(parse_version),
+            self.release_client_version
+                .as_deref()
+                .and_then(parse_version),
+        ) {
+            if min > release {
+                anyhow::bail!("MIN_CLIENT_VERSION must not be greater than RELEASE_CLIENT_VERSION");
+            }
+        }
+
+        if self.blob_storage_path.trim().is_empty() {
+            anyhow::bail!("BLOB_STORAGE_PATH must not be empty");
+        }
+
+        let analytics_secret_len = self.analytics_secret.as_ref().map_or(0, String::len);
+        if self.analytics_enabled && analytics_secret_len < 32 {
+            anyhow::bail!(
+                "ANALYTICS_SECRET must be set and at least 32 characters when ANALYTICS_ENABLED=true"
+            );
+        }
+
+        if let Some(endpoint) = &self.analytics_endpoint {
+            if !is_http_url(endpoint) {
+                anyhow::bail!("ANALYTICS_ENDPOINT must be an http(s) URL");
+            }
+        }
+
+        for (name, url) in [
+            ("SMS_API_URL", self.sms_api_url.as_deref()),
+            ("TELEPHONY_API_URL", self.telephony_api_url.as_deref()),
+            ("OCR_API_URL", self.ocr_api_url.as_deref()),
+            ("TELEGRAM_API_URL", Some(self.telegram_api_url.as_str())),
+        ] {
+            if let Some(url) = url {
+                if !is_http_url(url) {
+                    anyhow::bail!("{} must be an http(s) URL", name);
+                }
+            }
+        }
+
+        if let Some(url) = &self.public_base_url {
+            let host = url
+                .strip_prefix("https://")
+                .or_else(|| url.strip_prefix("http://"))
+                .unwrap_or_default();
+            if host.is_empty() || host.contains(['?', '#']) {
+                anyhow::bail!("PUBLIC_BASE_URL must be an http(s) URL without query or fragment");
+            }
+        }
+
+        if let Some(phone) = &self.escalation_admin_phone {
+            if !crate::utils::validate_phone(phone) {
+                anyhow::bail!("ESCALATION_ADMIN_PHONE must be a valid phone number");
+            }
+        }
+
+        self.message_templates.validate()?;
+
+        Ok(())
+    }
+}
+
+/// Непустое значение переменной окружения (пробелы по краям отбрасываются)
+pub fn optional_env(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Разбирает закрытый RSA ключ в PEM для подписи токенов
+pub fn parse_jwt_private_key(pem: &str) -> Result<EncodingKey> {
+    EncodingKey::from_rsa_pem(pem.as_bytes())
+        .context("JWT_PRIVATE_KEY_PATH must contain an RSA private key in PEM")
+}
+
+/// Разбирает открытый RSA ключ в PEM для проверки токенов
+pub fn parse_jwt_public_key(pem: &str) -> Result<DecodingKey> {
+    DecodingKey::from_rsa_pem(pem.as_bytes())
+        .context("JWT_PUBLIC_KEY_PATH must contain an RSA public key in PEM")
+}
+
+/// Читает PEM ключ из файла, указанного в переменной `name`
+fn read_key_file(name: &str, path: &str) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}: {}", name, path))
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .is_some_and(|rest| !rest.is_empty())
+}
+
+fn is_hex_key(key: &str) -> bool {
+    key.len() == 64 && hex::decode(key).is_ok()
+}
+
+/// Разбирает версию вида "1.2.3" (недостающие части считаются нулями)
+pub(crate) fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
     }
+    let mut parts = parts;
+    parts.resize(3, 0);
+    Some(parts)
 }
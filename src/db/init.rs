@@ -125,6 +125,27 @@ pub async fn ensure_database_and_tables(pool: &PgPool) -> AppResult<()> {
                         END IF;
                         ALTER TABLE users ADD CONSTRAINT plate_format 
                             CHECK (plate IS NULL OR (LENGTH(TRIM(plate)) >= 8 AND LENGTH(plate) <= 15));
+
+            -- Смещение часового пояса пользователя от UTC в минутах (для тихих часов)
+            IF NOT EXISTS (SELECT 1 FROM information_schema.columns
+                          WHERE table_name = 'users' AND column_name = 'utc_offset_minutes') THEN
+                ALTER TABLE users ADD COLUMN utc_offset_minutes INTEGER
+                    CHECK (utc_offset_minutes IS NULL OR utc_offset_minutes BETWEEN -720 AND 840);
+            END IF;
+
+            -- Ключ аватара пользователя в хранилище файлов
+            IF NOT EXISTS (SELECT 1 FROM information_schema.columns
+                          WHERE table_name = 'users' AND column_name = 'avatar_key') THEN
+                ALTER TABLE users ADD COLUMN avatar_key TEXT;
+            END IF;
+
+            -- Блокировка аккаунта администратором (suspended_until NULL - бессрочно)
+            IF NOT EXISTS (SELECT 1 FROM information_schema.columns
+                          WHERE table_name = 'users' AND column_name = 'suspended_at') THEN
+                ALTER TABLE users ADD COLUMN suspended_at TIMESTAMPTZ;
+                ALTER TABLE users ADD COLUMN suspended_until TIMESTAMPTZ;
+                ALTER TABLE users ADD COLUMN suspension_reason TEXT;
+            END IF;
         END $$;
         "#
     )
@@ -200,6 +221,97 @@ pub async fn ensure_database_and_tables(pool: &PgPool) -> AppResult<()> {
     .execute(pool)
     .await?;
 
+    // Колонки для фото-доказательств блокировки (оригинал и превью в BlobStore)
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'photo_key'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN photo_key TEXT;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'photo_thumb_key'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN photo_thumb_key TEXT;
+            END IF;
+        END $$;
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Причина блокировки (temporary_parking, loading, emergency, other) и пояснение к ней
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'reason'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN reason TEXT
+                    CHECK (reason IS NULL OR reason IN ('temporary_parking', 'loading', 'emergency', 'other'));
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'reason_text'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN reason_text TEXT;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'note'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN note TEXT;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'expires_at'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN expires_at TIMESTAMPTZ;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'acknowledged_at'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN acknowledged_at TIMESTAMPTZ;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'escalation_step'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN escalation_step SMALLINT NOT NULL DEFAULT 0;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'silent'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN silent BOOLEAN NOT NULL DEFAULT false;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'blocks' AND column_name = 'callback_requested_at'
+            ) THEN
+                ALTER TABLE blocks ADD COLUMN callback_requested_at TIMESTAMPTZ;
+            END IF;
+        END $$;
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     // Индекс для поиска блокировок по номеру блокирующего
     sqlx::query(
         r#"
@@ -399,6 +511,13 @@ pub async fn ensure_database_and_tables(pool: &PgPool) -> AppResult<()> {
             ) THEN
                 ALTER TABLE users ADD COLUMN push_token TEXT;
             END IF;
+
+            -- Каналы, по которым пользователь получает объявления администрации
+            IF NOT EXISTS (SELECT 1 FROM information_schema.columns
+                          WHERE table_name = 'users' AND column_name = 'announcement_push') THEN
+                ALTER TABLE users ADD COLUMN announcement_push BOOLEAN NOT NULL DEFAULT true;
+                ALTER TABLE users ADD COLUMN announcement_telegram BOOLEAN NOT NULL DEFAULT true;
+            END IF;
         END $$;
         "#,
     )
@@ -531,6 +650,31 @@ pub async fn ensure_database_and_tables(pool: &PgPool) -> AppResult<()> {
         e
     });
 
+    // Заявки на передачу номера новому владельцу (номер переходит после согласия владельца)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS plate_transfer_requests (
+            id UUID PRIMARY KEY,
+            plate TEXT NOT NULL,
+            claimant_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'declined')),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            responded_at TIMESTAMPTZ,
+            responded_by UUID REFERENCES users(id) ON DELETE SET NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_plate_transfer_requests_pending ON plate_transfer_requests(plate, claimant_id) WHERE status = 'pending'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     // Индекс для blocks с нормализованным номером
     sqlx::query(
         r#"
@@ -540,6 +684,384 @@ pub async fn ensure_database_and_tables(pool: &PgPool) -> AppResult<()> {
     .execute(pool)
     .await?;
 
+    // Пользователи с номером из основного автомобиля (user_plates - источник истины).
+    // users.plate используется только как запасной вариант, пока у пользователя нет записей в user_plates
+    sqlx::query(
+        r#"
+        CREATE OR REPLACE VIEW users_with_primary_plate AS
+        SELECT u.id, u.phone_encrypted, u.phone_hash, u.telegram,
+               COALESCE(up.plate, u.plate) AS plate,
+               u.name, u.show_contacts, u.owner_type, u.owner_info, u.departure_time,
+               u.push_token, u.announcement_push, u.announcement_telegram, u.created_at, u.updated_at,
+               u.utc_offset_minutes, u.avatar_key,
+               u.suspended_at, u.suspended_until, u.suspension_reason
+        FROM users u
+        LEFT JOIN user_plates up ON up.user_id = u.id AND up.is_primary
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Push токены устройств (несколько устройств на пользователя)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS push_tokens (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            token TEXT NOT NULL UNIQUE,
+            platform TEXT CHECK (platform IS NULL OR platform IN ('android', 'ios')),
+            app_version TEXT,
+            last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_push_tokens_user_id ON push_tokens(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Журнал действий администраторов (например, блокировки от имени жильцов)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+            actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+            action TEXT NOT NULL,
+            subject_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+            entity_id UUID,
+            details JSONB,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_audit_log_actor_id ON audit_log(actor_id, created_at DESC)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Завершённые (удалённые) блокировки: для статистики продолжительности
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS block_history (
+            id UUID PRIMARY KEY,
+            blocker_id UUID NOT NULL,
+            blocked_plate TEXT NOT NULL,
+            reason TEXT,
+            created_at TIMESTAMPTZ NOT NULL,
+            deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_block_history_deleted_at ON block_history(deleted_at DESC)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_block_history_blocker_created ON block_history(blocker_id, created_at DESC)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Очередь исходящих уведомлений (push, Telegram, звонки) с повторными попытками
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notification_outbox (
+            id UUID PRIMARY KEY,
+            block_id UUID,
+            user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            channel TEXT NOT NULL CHECK (channel IN ('push', 'telegram', 'call')),
+            payload JSONB NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'dead')),
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_notification_outbox_pending ON notification_outbox(next_attempt_at) WHERE status = 'pending'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Время последней повторной отправки уведомления
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'notifications' AND column_name = 'last_resent_at'
+            ) THEN
+                ALTER TABLE notifications ADD COLUMN last_resent_at TIMESTAMPTZ;
+            END IF;
+        END $$;
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Подтверждения получения и открытия push-уведомлений приложением
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'notifications' AND column_name = 'delivered_at'
+            ) THEN
+                ALTER TABLE notifications ADD COLUMN delivered_at TIMESTAMPTZ;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'notifications' AND column_name = 'opened_at'
+            ) THEN
+                ALTER TABLE notifications ADD COLUMN opened_at TIMESTAMPTZ;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'notification_outbox' AND column_name = 'confirmed_at'
+            ) THEN
+                ALTER TABLE notification_outbox ADD COLUMN confirmed_at TIMESTAMPTZ;
+            END IF;
+
+            IF NOT EXISTS (
+                SELECT 1 FROM information_schema.columns
+                WHERE table_name = 'notification_outbox' AND column_name = 'opened_at'
+            ) THEN
+                ALTER TABLE notification_outbox ADD COLUMN opened_at TIMESTAMPTZ;
+            END IF;
+        END $$;
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Приглашения стать совладельцем номера (номер добавляется приглашённому после согласия)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS plate_share_invites (
+            id UUID PRIMARY KEY,
+            plate_id UUID NOT NULL REFERENCES user_plates(id) ON DELETE CASCADE,
+            plate TEXT NOT NULL,
+            inviter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            invitee_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined')),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            responded_at TIMESTAMPTZ
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_plate_share_invites_pending ON plate_share_invites(plate_id, invitee_id) WHERE status = 'pending'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_plate_share_invites_invitee ON plate_share_invites(invitee_id, created_at DESC) WHERE status = 'pending'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Буквы Ё на номерах не бывает: сохранённые номера приводятся к виду с Е, чтобы находиться
+    // по нормализованному ключу. При дубликатах после замены остаётся запись без Ё (или самая ранняя).
+    // Повторный запуск ничего не меняет: записей с Ё уже нет, а других дубликатов не допускают
+    // уникальные ограничения
+    sqlx::query(
+        r#"
+        DELETE FROM user_plates
+        WHERE id IN (
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY user_id, REPLACE(plate, 'Ё', 'Е')
+                    ORDER BY (plate LIKE '%Ё%'), created_at, id
+                ) AS position
+                FROM user_plates
+            ) ranked
+            WHERE position > 1
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_plates SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE users SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM blocks
+        WHERE id IN (
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY UPPER(TRIM(REPLACE(blocker_plate, 'Ё', 'Е'))),
+                                 UPPER(TRIM(REPLACE(blocked_plate, 'Ё', 'Е')))
+                    ORDER BY (blocker_plate LIKE '%Ё%' OR blocked_plate LIKE '%Ё%'), created_at, id
+                ) AS position
+                FROM blocks
+            ) ranked
+            WHERE position > 1
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE blocks
+        SET blocked_plate = REPLACE(blocked_plate, 'Ё', 'Е'),
+            blocker_plate = REPLACE(blocker_plate, 'Ё', 'Е')
+        WHERE blocked_plate LIKE '%Ё%' OR blocker_plate LIKE '%Ё%'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE block_history SET blocked_plate = REPLACE(blocked_plate, 'Ё', 'Е') WHERE blocked_plate LIKE '%Ё%'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE plate_share_invites SET plate = REPLACE(plate, 'Ё', 'Е') WHERE plate LIKE '%Ё%'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Контрольное значение для проверки ключа шифрования при запуске
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS encryption_canary (
+            id SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+            value_encrypted TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Отозванные при выходе токены (удаляются после истечения)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS revoked_tokens (
+            jti UUID PRIMARY KEY,
+            user_id UUID NOT NULL,
+            expires_at TIMESTAMPTZ NOT NULL,
+            revoked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens(expires_at)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Сессии устройств (claim sid в токенах)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_sessions (
+            id UUID PRIMARY KEY,
+            user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            device_id VARCHAR(128),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_user_sessions_user_id ON user_sessions(user_id)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_user_sessions_last_seen ON user_sessions(last_seen)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Сообщения очереди без получателя-пользователя (звонок администратору дома)
+    sqlx::query(
+        r#"
+        ALTER TABLE notification_outbox ALTER COLUMN user_id DROP NOT NULL
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     tracing::info!("Database schema ensured successfully");
     Ok(())
 }
@@ -3,6 +3,9 @@ use std::sync::Arc;
 
 pub type DbPool = Arc<PgPool>;
 
+/// Транзакция БД, которую можно передавать между репозиториями
+pub type DbTransaction = sqlx::Transaction<'static, sqlx::Postgres>;
+
 pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
     PgPool::connect(database_url).await
 }
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -19,9 +19,45 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(String),
+
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after_secs: u64,
+    },
+
+    /// Сервер ещё не готов принимать запросы (не применены миграции)
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    /// Запрос конфликтует с параллельным изменением тех же данных - можно повторить
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// Повторная блокировка того же автомобиля без подтверждения (`acknowledge_repeat`)
+    #[error("Repeat block not acknowledged: {0}")]
+    RepeatBlockNotAcknowledged(String),
+
+    /// Исчерпаны попытки ввода кода подтверждения: нужно запросить новый код
+    #[error("Code attempts exceeded: {0}")]
+    CodeAttemptsExceeded(String),
+
+    /// Аккаунт заблокирован администратором (сообщение содержит срок и причину)
+    #[error("Account suspended: {0}")]
+    AccountSuspended(String),
+
+    /// Клиент слишком старый для этого маршрута (MIN_CLIENT_VERSION_ROUTES)
+    #[error("Upgrade required: {0}")]
+    UpgradeRequired(String),
+
     #[error("Encryption error: {0}")]
     Encryption(String),
 
@@ -29,6 +65,29 @@ pub enum AppError {
     Internal(String),
 }
 
+impl AppError {
+    /// Стабильный машинный код ошибки для клиентов (не зависит от текста сообщения)
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "DATABASE",
+            AppError::Auth(_) => "UNAUTHORIZED",
+            AppError::Validation(_) => "VALIDATION",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::MethodNotAllowed(_) => "METHOD_NOT_ALLOWED",
+            AppError::RateLimited { .. } => "RATE_LIMITED",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::RepeatBlockNotAcknowledged(_) => "REPEAT_BLOCK_NOT_ACKNOWLEDGED",
+            AppError::CodeAttemptsExceeded(_) => "CODE_ATTEMPTS_EXCEEDED",
+            AppError::AccountSuspended(_) => "ACCOUNT_SUSPENDED",
+            AppError::UpgradeRequired(_) => "UPGRADE_REQUIRED",
+            AppError::Encryption(_) => "ENCRYPTION",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let error_details = self.to_string();
@@ -42,7 +101,18 @@ impl IntoResponse for AppError {
             }
             AppError::Auth(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
+            AppError::MethodNotAllowed(msg) => (StatusCode::METHOD_NOT_ALLOWED, msg.clone()),
+            AppError::RateLimited { message, .. } => {
+                (StatusCode::TOO_MANY_REQUESTS, message.clone())
+            }
+            AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::RepeatBlockNotAcknowledged(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::CodeAttemptsExceeded(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
+            AppError::AccountSuspended(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            AppError::UpgradeRequired(msg) => (StatusCode::UPGRADE_REQUIRED, msg.clone()),
             AppError::Encryption(msg) => {
                 tracing::error!("Encryption error: {}", msg);
                 (
@@ -60,10 +130,21 @@ impl IntoResponse for AppError {
         };
 
         let body = Json(json!({
+            "code": self.code(),
             "error": error_message,
             "details": error_details
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let AppError::RateLimited {
+            retry_after_secs, ..
+        } = &self
+        {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*retry_after_secs));
+        }
+
+        response
     }
 }
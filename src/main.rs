@@ -1,26 +1,42 @@
 use anyhow::{Context, Result};
-use axum::{middleware, routing::get, Router};
+use axum::{middleware, Router};
 use rimskiy_service::api::{
-    app_download_router, auth_router, block_router, notification_router, ocr_router,
-    server_info_router, user_plate_router, user_router, AppState,
+    admin_router, api_doc_router, app_download_router, app_download_url_router, auth_logout_router,
+    auth_router, block_router, file_download_router, health_router, method_not_allowed,
+    notification_router, ocr_router, route_not_found, security_router, server_info_router,
+    user_plate_router, user_router, validation_router, AppState, ReadinessState, OPENAPI_JSON_PATH,
 };
-use rimskiy_service::auth::sms::SmsService;
+use rimskiy_service::auth::code_store::code_store_from_config;
+use rimskiy_service::auth::sms::{SmsService, CODE_CLEANUP_INTERVAL};
 use rimskiy_service::config::Config;
 use rimskiy_service::db::{create_pool, init::ensure_database_and_tables};
 use rimskiy_service::error::AppError;
-use rimskiy_service::middleware::logging_middleware;
-use rimskiy_service::openapi::ApiDoc;
+use rimskiy_service::middleware::{
+    client_ip_middleware, client_version_middleware, logging_middleware, public_url_middleware,
+    readiness_middleware,
+};
 use rimskiy_service::repository::{
-    PostgresBlockRepository, PostgresNotificationRepository, PostgresUserPlateRepository,
-    PostgresUserRepository,
+    FsBlobStore, PostgresAuditLogRepository, PostgresBlockRepository,
+    PostgresEncryptionCanaryRepository, PostgresNotificationOutboxRepository,
+    PostgresNotificationRepository, PostgresPlateShareInviteRepository,
+    PostgresPlateTransferRequestRepository, PostgresPushTokenRepository,
+    PostgresRevokedTokenRepository, PostgresTelegramBotRepository, PostgresUserPlateRepository,
+    PostgresUserRepository, PostgresUserSessionRepository,
 };
+use rimskiy_service::service::block_escalation::spawn_block_escalation;
+use rimskiy_service::service::data_retention::{spawn_data_retention, RetentionPolicy};
+use rimskiy_service::service::encryption_canary::verify_encryption_key;
+use rimskiy_service::service::notification_outbox::{spawn_outbox_worker, OutboxDispatcher};
+use rimskiy_service::service::plate_reconciliation::spawn_plate_reconciliation;
+use rimskiy_service::service::revoked_tokens::spawn_revoked_token_cleanup;
+use rimskiy_service::service::user_sessions::{session_idle_timeout, spawn_session_cleanup};
 use rimskiy_service::service::{
-    AuthService, BlockService, PushService, TelegramService, TelephonyService, UserService,
+    AdminService, AnalyticsService, AuthService, BlockService, PushService, TelegramService,
+    TelephonyService, UserService,
 };
 use rimskiy_service::utils::encryption::Encryption;
 use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
-use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 #[tokio::main]
@@ -45,16 +61,15 @@ async fn main() -> Result<()> {
     let pool = create_pool(&config.database_url).await?;
     tracing::info!("Connected to database");
 
-    // Автоматически создаём БД и таблицы, если их нет
-    ensure_database_and_tables(&pool).await?;
-    tracing::info!("Database schema ensured");
-
     // Инициализируем шифрование
     let encryption =
-        Encryption::new(&config.encryption_key).map_err(|e| AppError::Encryption(e.to_string()))?;
+        Encryption::from_config(&config).map_err(|e| AppError::Encryption(e.to_string()))?;
 
-    // Инициализируем SMS сервис
-    let sms_service = SmsService::new(config.clone());
+    // Инициализируем SMS сервис (коды - в Redis, если он настроен)
+    let code_store =
+        code_store_from_config(config.redis_url.as_deref(), config.redis_fallback_inmemory).await?;
+    let sms_service = SmsService::new(config.clone(), code_store);
+    sms_service.start_cleanup(CODE_CLEANUP_INTERVAL);
 
     // Инициализируем сервис телефонии
     let telephony_service = TelephonyService::new(config.clone());
@@ -64,16 +79,29 @@ async fn main() -> Result<()> {
 
     // Создаём репозитории
     let db_pool = std::sync::Arc::new(pool);
+    let readiness = ReadinessState::new();
     let user_repository = PostgresUserRepository::new(db_pool.clone());
     let block_repository = PostgresBlockRepository::new(db_pool.clone());
     let user_plate_repository = PostgresUserPlateRepository::new(db_pool.clone());
+    let plate_transfer_request_repository =
+        PostgresPlateTransferRequestRepository::new(db_pool.clone());
+    let plate_share_invite_repository = PostgresPlateShareInviteRepository::new(db_pool.clone());
     let notification_repository = PostgresNotificationRepository::new(db_pool.clone());
+    let notification_outbox_repository = PostgresNotificationOutboxRepository::new(db_pool.clone());
+    let push_token_repository = PostgresPushTokenRepository::new(db_pool.clone());
+    let telegram_bot_repository = PostgresTelegramBotRepository::new(db_pool.clone());
+    let audit_log_repository = PostgresAuditLogRepository::new(db_pool.clone());
+    let revoked_token_repository = PostgresRevokedTokenRepository::new(db_pool.clone());
+    let user_session_repository = PostgresUserSessionRepository::new(db_pool.clone());
+    let blob_store = FsBlobStore::new(&config.blob_storage_path);
 
     // Создаём сервисы
     let auth_service = AuthService::new(sms_service.clone(), encryption.clone(), config.clone());
-    let user_service = UserService::new(encryption.clone());
+    let user_service = UserService::new(encryption.clone(), config.mask_public_plates);
     let push_service = PushService::new(config.fcm_server_key.clone());
-    let block_service = BlockService::new(encryption.clone(), push_service.clone());
+    let analytics = AnalyticsService::from_config(&config);
+    let block_service = BlockService::new(encryption.clone(), analytics.clone(), config.clone());
+    let admin_service = AdminService::new(push_service.clone(), telegram_service.clone(), &config);
 
     // Создаём состояние приложения
     let app_state = AppState {
@@ -84,24 +112,73 @@ async fn main() -> Result<()> {
         telegram_service,
         auth_service,
         push_service,
+        analytics,
         user_service,
         block_service,
+        admin_service,
         user_repository,
         block_repository,
         user_plate_repository,
+        plate_transfer_request_repository,
+        plate_share_invite_repository,
         notification_repository,
+        notification_outbox_repository,
+        push_token_repository,
+        telegram_bot_repository,
+        audit_log_repository,
+        revoked_token_repository,
+        user_session_repository,
+        blob_store,
+        readiness: readiness.clone(),
     };
 
-    // Создаём OpenAPI документацию
-    let openapi = ApiDoc::openapi();
+    // Проверка ключа шифрования по контрольному значению (после миграций)
+    let canary_encryption = app_state.encryption.clone();
+    let encryption_allow_key_mismatch = config.encryption_allow_key_mismatch;
+
+    // Сервисы для фоновой эскалации блокировок (запускается после миграций)
+    let escalation_block_service = app_state.block_service.clone();
+    let escalation_telephony_service = app_state.telephony_service.clone();
+
+    // Отправка уведомлений из очереди (запускается после миграций)
+    let outbox_dispatcher = OutboxDispatcher::new(
+        app_state.push_service.clone(),
+        app_state.telegram_service.clone(),
+        app_state.telephony_service.clone(),
+        app_state.encryption.clone(),
+        app_state.analytics.clone(),
+    );
 
     // Создаём роутер
     let app = Router::new()
-        .route("/health", get(health_check))
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-doc/openapi.json", openapi.clone()))
+        .merge(health_router())
+        // Спецификацию отдаёт api_doc_router (поддерживает фильтр ?tag=), Swagger UI только ссылается на неё
+        .merge(api_doc_router())
+        .merge(
+            SwaggerUi::new("/swagger-ui")
+                .config(utoipa_swagger_ui::Config::from(OPENAPI_JSON_PATH)),
+        )
         .merge(server_info_router())
-        .nest("/api/app", app_download_router())
-        .nest("/api/auth", auth_router())
+        .merge(validation_router(&app_state.config))
+        .nest(
+            "/api/app",
+            app_download_router().merge(app_download_url_router().layer(
+                axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::auth_middleware,
+                ),
+            )),
+        )
+        .nest("/api/files", file_download_router())
+        .nest(
+            "/api/auth",
+            auth_router().merge(
+                auth_logout_router().layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::auth_middleware,
+                )),
+            ),
+        )
         .nest("/api/ocr", ocr_router())
         .nest(
             "/api/users",
@@ -131,6 +208,42 @@ async fn main() -> Result<()> {
                 rimskiy_service::auth::middleware::auth_middleware,
             )),
         )
+        .nest(
+            "/api/admin",
+            admin_router()
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::admin_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::auth_middleware,
+                )),
+        )
+        .nest(
+            "/api/security",
+            security_router()
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::security_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    rimskiy_service::auth::middleware::auth_middleware,
+                )),
+        )
+        // До готовности (применения миграций и проверки ключа шифрования) отвечают только health-пробы
+        .layer(middleware::from_fn_with_state(
+            readiness.clone(),
+            readiness_middleware,
+        ))
+        // Единый формат ошибок и для неизвестных маршрутов / неподдерживаемых методов
+        .fallback(route_not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            client_version_middleware,
+        ))
         .layer(
             CorsLayer::permissive()
                 .allow_origin(tower_http::cors::Any)
@@ -138,6 +251,14 @@ async fn main() -> Result<()> {
                 .allow_headers(tower_http::cors::Any),
         )
         .layer(middleware::from_fn(logging_middleware))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            client_ip_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            public_url_middleware,
+        ))
         .with_state(app_state);
 
     // Запускаем сервер
@@ -175,11 +296,97 @@ async fn main() -> Result<()> {
     println!("[SERVER] ========================================");
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
 
-    Ok(())
-}
+    // Миграции применяются в фоне: /health отвечает сразу, а /health/ready - только после
+    // того, как схема БД готова и ключ шифрования проверен по контрольному значению
+    let migrations_pool = db_pool.clone();
+    let reconcile_interval_minutes = config.plate_reconcile_interval_minutes;
+    let session_idle_timeout = session_idle_timeout(&config);
+    let escalation_window_minutes = config.escalation_window_minutes;
+    let retention_policy = RetentionPolicy {
+        notification_days: config.notification_retention_days,
+        block_days: config.block_retention_days,
+    };
+    tokio::spawn(async move {
+        match ensure_database_and_tables(&migrations_pool).await {
+            Ok(()) => {
+                // Не обслуживаем запросы с ключом, которым не расшифровать сохранённые данные
+                if let Err(e) = verify_encryption_key(
+                    &canary_encryption,
+                    &PostgresEncryptionCanaryRepository::new(migrations_pool.clone()),
+                    encryption_allow_key_mismatch,
+                )
+                .await
+                {
+                    tracing::error!("Encryption key check failed: {}", e);
+                    std::process::exit(1);
+                }
+
+                readiness.set_ready(true);
+                tracing::info!("Database schema ensured, server is ready");
+
+                // Очередь уведомлений отправляется всегда: она заполняется при создании блокировок
+                spawn_outbox_worker(
+                    outbox_dispatcher,
+                    PostgresNotificationOutboxRepository::new(migrations_pool.clone()),
+                    PostgresUserRepository::new(migrations_pool.clone()),
+                    PostgresTelegramBotRepository::new(migrations_pool.clone()),
+                );
+
+                // Удаление записей об отозванных токенах после их истечения
+                spawn_revoked_token_cleanup(PostgresRevokedTokenRepository::new(
+                    migrations_pool.clone(),
+                ));
 
-async fn health_check() -> &'static str {
-    "OK"
+                // Удаление сессий, токены которых уже нельзя использовать или обновить
+                spawn_session_cleanup(
+                    PostgresUserSessionRepository::new(migrations_pool.clone()),
+                    session_idle_timeout,
+                );
+
+                // Фоновая сверка users.plate с user_plates (после миграций)
+                if reconcile_interval_minutes > 0 {
+                    spawn_plate_reconciliation(
+                        PostgresUserPlateRepository::new(migrations_pool.clone()),
+                        std::time::Duration::from_secs(reconcile_interval_minutes * 60),
+                    );
+                }
+
+                // Очистка уведомлений и истории блокировок старше сроков хранения
+                if retention_policy.is_enabled() {
+                    spawn_data_retention(
+                        retention_policy,
+                        PostgresNotificationRepository::new(migrations_pool.clone()),
+                        PostgresBlockRepository::new(migrations_pool.clone()),
+                    );
+                }
+
+                // Эскалация блокировок, которые владелец не подтвердил
+                if escalation_window_minutes > 0 {
+                    spawn_block_escalation(
+                        escalation_block_service,
+                        PostgresBlockRepository::new(migrations_pool.clone()),
+                        PostgresUserRepository::new(migrations_pool.clone()),
+                        PostgresUserPlateRepository::new(migrations_pool.clone()),
+                        PostgresNotificationOutboxRepository::new(migrations_pool),
+                        escalation_telephony_service,
+                        chrono::Duration::minutes(escalation_window_minutes as i64),
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to ensure database schema: {}", e);
+                std::process::exit(1);
+            }
+        }
+    });
+
+    // ConnectInfo нужен для определения IP клиента (см. client_ip_middleware)
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
 }
@@ -0,0 +1,36 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::api::AppState;
+use crate::utils::network::resolve_client_ip;
+
+/// Реальный IP клиента (с учётом TRUSTED_PROXIES), доступен обработчикам через `Extension<ClientIp>`
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// Middleware определения IP клиента: кладёт `ClientIp` в расширения запроса
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(peer) = peer {
+        let forwarded_for = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|h| h.to_str().ok());
+        let client_ip = resolve_client_ip(peer, forwarded_for, &state.config.trusted_proxies);
+        request.extensions_mut().insert(ClientIp(client_ip));
+    }
+
+    next.run(request).await
+}
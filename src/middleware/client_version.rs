@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::AppState;
+use crate::config::parse_version;
+use crate::error::AppError;
+
+/// Заголовок, в котором клиент передаёт свою версию (например, `1.4.2`)
+pub const CLIENT_VERSION_HEADER: &str = "x-client-version";
+
+/// Middleware минимальной версии клиента для отдельных маршрутов (MIN_CLIENT_VERSION_ROUTES).
+/// На маршрутах с заданным минимумом клиент без `X-Client-Version` или со старой версией
+/// получает 426; остальные маршруты доступны любым клиентам
+pub async fn client_version_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let routes = &state.config.min_client_version_routes;
+    if routes.is_empty() || request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+
+    // Если подходит несколько префиксов, действует самый длинный (наиболее конкретный)
+    let path = request.uri().path();
+    let required = routes
+        .iter()
+        .filter(|(route, _)| {
+            path == route
+                || path
+                    .strip_prefix(route.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|(route, _)| route.len())
+        .map(|(_, version)| version);
+
+    if let Some(required) = required {
+        let client_version = request
+            .headers()
+            .get(CLIENT_VERSION_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .and_then(parse_version);
+        let is_outdated = match (client_version, parse_version(required)) {
+            (Some(client), Some(required)) => client < required,
+            (None, _) => true,
+            (_, None) => false,
+        };
+        if is_outdated {
+            return AppError::UpgradeRequired(format!(
+                "Для этого действия обновите приложение до версии {} или новее",
+                required
+            ))
+            .into_response();
+        }
+    }
+
+    next.run(request).await
+}
@@ -1,6 +1,8 @@
 use axum::{extract::Request, middleware::Next, response::Response};
 use std::time::Instant;
 
+use crate::middleware::ClientIp;
+
 /// Middleware для логирования всех входящих API запросов
 pub async fn logging_middleware(request: Request, next: Next) -> Response {
     let method = request.method().clone();
@@ -8,13 +10,12 @@ pub async fn logging_middleware(request: Request, next: Next) -> Response {
     let path = uri.path();
     let query = uri.query().unwrap_or("");
 
-    // Получаем IP адрес клиента (если доступен)
+    // IP адрес клиента (определяется client_ip_middleware с учётом доверенных прокси)
     let client_ip = request
-        .headers()
-        .get("x-forwarded-for")
-        .or_else(|| request.headers().get("x-real-ip"))
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown");
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
 
     // Логируем входящий запрос с полной информацией
     tracing::info!(
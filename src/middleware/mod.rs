@@ -1,3 +1,13 @@
+pub mod client_ip;
+pub mod client_version;
 pub mod logging;
+pub mod public_url;
+pub mod rate_limit;
+pub mod readiness;
 
+pub use client_ip::{client_ip_middleware, ClientIp};
+pub use client_version::client_version_middleware;
 pub use logging::logging_middleware;
+pub use public_url::{public_url_middleware, PublicBaseUrl};
+pub use rate_limit::{ip_rate_limit_middleware, IpRateLimiter};
+pub use readiness::readiness_middleware;
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::net::SocketAddr;
+
+use crate::api::AppState;
+use crate::utils::network::resolve_public_base_url;
+
+/// Внешний адрес сервиса (PUBLIC_BASE_URL или заголовки доверенного прокси),
+/// доступен обработчикам через `Extension<PublicBaseUrl>`
+#[derive(Debug, Clone, Default)]
+pub struct PublicBaseUrl(pub Option<String>);
+
+impl PublicBaseUrl {
+    /// Абсолютная ссылка на путь сервиса; если внешний адрес неизвестен - сам путь
+    pub fn absolute(&self, path: &str) -> String {
+        match &self.0 {
+            Some(base) => format!("{}{}", base, path),
+            None => path.to_string(),
+        }
+    }
+}
+
+/// Middleware определения внешнего адреса: кладёт `PublicBaseUrl` в расширения запроса
+pub async fn public_url_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    let base_url = resolve_public_base_url(
+        state.config.public_base_url.as_deref(),
+        peer,
+        request.headers(),
+        &state.config.trusted_proxies,
+    );
+    request.extensions_mut().insert(PublicBaseUrl(base_url));
+
+    next.run(request).await
+}
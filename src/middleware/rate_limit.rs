@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::middleware::ClientIp;
+
+/// После скольких отслеживаемых адресов удаляются окна, которые уже закончились
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Ограничение числа запросов с одного IP за окно фиксированной длины.
+/// Счётчики хранятся в памяти процесса (у каждого экземпляра сервера свои)
+#[derive(Clone)]
+pub struct IpRateLimiter {
+    limit: u32,
+    window: Duration,
+    windows: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
+}
+
+impl IpRateLimiter {
+    /// `limit` запросов в минуту с одного IP; 0 - без ограничения
+    pub fn per_minute(limit: u32) -> Self {
+        Self {
+            limit,
+            window: Duration::from_secs(60),
+            windows: Arc::default(),
+        }
+    }
+
+    /// Учитывает запрос. Err - лимит исчерпан, в ошибке время до начала нового окна
+    pub fn check(&self, ip: IpAddr) -> Result<(), AppError> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= PRUNE_THRESHOLD {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+        }
+
+        let (started, count) = windows.entry(ip).or_insert((now, 0));
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            let retry_after = self.window.saturating_sub(now.duration_since(*started));
+            return Err(AppError::RateLimited {
+                message: "Слишком много запросов. Повторите позже".to_string(),
+                retry_after_secs: retry_after.as_secs().max(1),
+            });
+        }
+        *count += 1;
+
+        Ok(())
+    }
+}
+
+/// Middleware ограничения частоты запросов по IP клиента (`ClientIp`).
+/// Запросы без определённого IP делят один общий лимит
+pub async fn ip_rate_limit_middleware(
+    State(limiter): State<IpRateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| *ip)
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    match limiter.check(ip) {
+        Ok(()) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!("Rate limit exceeded for {} on {}", ip, request.uri().path());
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::ReadinessState;
+use crate::error::AppError;
+
+/// Маршруты, доступные до готовности сервера (liveness/readiness-пробы)
+const HEALTH_PATHS: [&str; 2] = ["/health", "/health/ready"];
+
+/// Middleware готовности: пока не применены миграции,
+/// все маршруты, кроме `/health` и `/health/ready`, отвечают 503
+pub async fn readiness_middleware(
+    State(readiness): State<ReadinessState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if readiness.is_ready() || HEALTH_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    AppError::ServiceUnavailable("Сервер запускается, повторите запрос позже".to_string())
+        .into_response()
+}
@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::block::CreateBlockRequest;
+
+/// Канал доставки объявления (помимо уведомления в приложении)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnounceChannel {
+    /// Push-уведомление через FCM (пользователям с push token)
+    Push,
+    /// Сообщение через Telegram бота (пользователям с указанным Telegram)
+    Telegram,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({
+    "title": "Парковка закрыта",
+    "message": "Завтра с 8:00 до 12:00 парковка закрыта на уборку",
+    "channels": ["push", "telegram"]
+}))]
+pub struct AnnounceRequest {
+    /// Заголовок объявления
+    #[schema(example = "Парковка закрыта")]
+    pub title: String,
+    /// Текст объявления
+    #[schema(example = "Завтра с 8:00 до 12:00 парковка закрыта на уборку")]
+    pub message: String,
+    /// Дополнительные каналы доставки. Уведомление в приложении создаётся всегда
+    #[serde(default)]
+    pub channels: Vec<AnnounceChannel>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AnnounceResponse {
+    /// Задача рассылки: прогресс - `GET /api/admin/announce/{job_id}`
+    #[schema(value_type = String, format = "uuid")]
+    pub job_id: Uuid,
+    /// Сколько пользователей получат объявление
+    #[schema(example = 120)]
+    pub total_users: u64,
+    /// Будет ли рассылка по push/telegram
+    #[schema(example = true)]
+    pub delivery_started: bool,
+}
+
+/// Состояние фоновой рассылки объявления
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnounceJobStatus {
+    Running,
+    Completed,
+    /// Рассылка прервана ошибкой (уже обработанные пользователи объявление получили)
+    Failed,
+}
+
+/// Прогресс рассылки объявления (обновляется после каждой пачки пользователей)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AnnounceJob {
+    #[schema(value_type = String, format = "uuid")]
+    pub job_id: Uuid,
+    pub status: AnnounceJobStatus,
+    /// Пользователей на момент запуска
+    #[schema(example = 120)]
+    pub total_users: u64,
+    /// Сколько пользователей уже обработано
+    #[schema(example = 60)]
+    pub processed_users: u64,
+    #[schema(example = 60)]
+    pub notifications_created: u64,
+    #[schema(example = 40)]
+    pub push_sent: u64,
+    #[schema(example = 15)]
+    pub telegram_sent: u64,
+    /// Неудачные отправки по push/telegram
+    #[schema(example = 1)]
+    pub delivery_failed: u64,
+    /// Причина остановки (для `failed`)
+    pub error: Option<String>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    #[schema(value_type = String, format = "date-time")]
+    pub started_at: DateTime<Utc>,
+    #[serde(with = "crate::utils::time::rfc3339_utc_option")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Блокировка от имени жильца (для консьержа/администратора)
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({
+    "blocker_user_id": "550e8400-e29b-41d4-a716-446655440000",
+    "blocked_plate": "А123БВ777",
+    "notify_owner": true,
+    "reason": "loading"
+}))]
+pub struct AdminCreateBlockRequest {
+    /// Жилец, от имени которого создаётся блокировка
+    #[schema(value_type = String, format = "uuid")]
+    pub blocker_user_id: Uuid,
+    /// Обычные поля блокировки
+    #[serde(flatten)]
+    pub block: CreateBlockRequest,
+}
+
+/// Сводная статистика парковки для панели администратора
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OverviewStats {
+    /// Активные блокировки
+    #[schema(example = 12)]
+    pub active_blocks: i64,
+    /// Уникальные заблокированные номера среди активных блокировок
+    #[schema(example = 10)]
+    pub unique_blocked_plates: i64,
+    /// Уникальные блокирующие среди активных блокировок
+    #[schema(example = 9)]
+    pub unique_blockers: i64,
+    /// Завершённые (удалённые) блокировки
+    #[schema(example = 340)]
+    pub completed_blocks: i64,
+    /// Средняя продолжительность завершённой блокировки в минутах (null, если завершённых нет)
+    #[schema(example = 47.5)]
+    pub average_block_duration_minutes: Option<f64>,
+    /// Количество блокировок (активных и завершённых) по часу создания: 24 значения, индекс - час
+    #[schema(example = json!([0, 0, 0, 0, 0, 0, 1, 5, 20, 12, 4, 3, 2, 2, 3, 4, 6, 10, 18, 9, 5, 2, 1, 0]))]
+    pub blocks_per_hour: Vec<i64>,
+    /// Смещение от UTC в минутах, по которому считаются часы суток
+    #[schema(example = 180)]
+    pub utc_offset_minutes: i32,
+    /// Доставка уведомлений по каналам за последние 30 дней
+    pub delivery: Vec<ChannelDeliveryStats>,
+    /// Когда посчитана статистика (ответ кэшируется ненадолго)
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    #[schema(value_type = String, format = "date-time")]
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Доставка уведомлений через один канал
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ChannelDeliveryStats {
+    /// Канал: push, telegram или call
+    #[schema(example = "push")]
+    pub channel: String,
+    /// Принято провайдером
+    #[schema(example = 120)]
+    pub sent: i64,
+    /// Не отправлено после всех повторных попыток
+    #[schema(example = 3)]
+    pub failed: i64,
+    /// Получение подтверждено приложением
+    #[schema(example = 96)]
+    pub confirmed: i64,
+    /// Открыто пользователем
+    #[schema(example = 71)]
+    pub opened: i64,
+    /// Доля подтверждённых среди принятых провайдером (null - канал без подтверждений
+    /// получения или ничего не отправлялось)
+    #[schema(example = 0.8)]
+    pub delivery_rate: Option<f64>,
+}
+
+/// Заблокировать аккаунт пользователя
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({
+    "reason": "Ложные блокировки",
+    "until": "2026-11-01T00:00:00Z"
+}))]
+pub struct SuspendUserRequest {
+    /// Причина (показывается пользователю)
+    #[schema(example = "Ложные блокировки")]
+    pub reason: Option<String>,
+    /// До какого момента действует блокировка (не указано - бессрочно)
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Состояние блокировки аккаунта
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct UserSuspensionResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub user_id: Uuid,
+    /// Аккаунт заблокирован
+    #[schema(example = true)]
+    pub suspended: bool,
+    /// До какого момента (null - бессрочно или не заблокирован)
+    #[serde(with = "crate::utils::time::rfc3339_utc_option")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+}
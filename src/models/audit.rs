@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Запись журнала действий администраторов
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    /// Администратор, выполнивший действие
+    pub actor_id: Option<Uuid>,
+    /// Действие, например "block_created_on_behalf"
+    pub action: String,
+    /// Пользователь, от имени которого или над которым выполнено действие
+    pub subject_user_id: Option<Uuid>,
+    /// Затронутая сущность (например, ID блокировки)
+    pub entity_id: Option<Uuid>,
+    pub details: Option<serde_json::Value>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+}
@@ -4,16 +4,32 @@ use serde_json::json;
 use utoipa::ToSchema;
 use validator::Validate;
 
+/// Канал доставки кода авторизации
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthCodeChannel {
+    /// SMS на номер телефона
+    #[default]
+    Sms,
+    /// Сообщение от Telegram бота (номер должен быть привязан к боту)
+    Telegram,
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
-#[schema(example = json!({"phone": "+79165180900"}))]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({"phone": "+79165180900", "channel": "sms"}))]
 pub struct AuthStartRequest {
     /// Номер телефона в формате +7XXXXXXXXXX
     #[validate(length(min = 10, max = 15))]
     #[schema(example = "+79165180900")]
     pub phone: String,
+    /// Куда отправить код (по умолчанию sms)
+    #[serde(default)]
+    pub channel: AuthCodeChannel,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct AuthStartResponse {
     /// SMS код для подтверждения
     #[schema(example = "1234")]
@@ -21,9 +37,12 @@ pub struct AuthStartResponse {
     /// Время жизни кода в секундах
     #[schema(example = 600)]
     pub expires_in: u64,
+    /// Куда отправлен код
+    pub channel: AuthCodeChannel,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "snake_case")]
 #[schema(example = json!({"phone": "+79165180900", "code": "1234"}))]
 pub struct AuthVerifyRequest {
     /// Номер телефона в формате +7XXXXXXXXXX
@@ -31,12 +50,18 @@ pub struct AuthVerifyRequest {
     #[schema(example = "+79165180900")]
     pub phone: String,
     /// SMS код подтверждения
-    #[validate(length(min = 4, max = 6))]
+    #[validate(length(min = 4, max = 8))]
     #[schema(example = "1234")]
     pub code: String,
+    /// Идентификатор устройства (до 128 символов): показывается в списке сессий
+    #[serde(default)]
+    #[validate(length(max = 128))]
+    #[schema(example = "iPhone 15 (A1B2C3)")]
+    pub device_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct AuthVerifyResponse {
     /// JWT токен для авторизации
     #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
@@ -47,11 +72,13 @@ pub struct AuthVerifyResponse {
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct PinAuthRequest {
     pub pin: String,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct PinAuthResponse {
     pub token: String,
     #[schema(value_type = String, format = "uuid")]
@@ -59,6 +86,7 @@ pub struct PinAuthResponse {
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 #[schema(example = json!({"token": "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9..."}))]
 pub struct RefreshTokenRequest {
     /// Текущий JWT токен
@@ -67,6 +95,7 @@ pub struct RefreshTokenRequest {
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct RefreshTokenResponse {
     /// Новый JWT токен
     #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
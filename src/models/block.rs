@@ -7,9 +7,10 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::utils::normalize_plate;
+use crate::utils::{format_plate, normalize_plate};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct Block {
     /// ID блокировки
     #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
@@ -24,15 +25,60 @@ pub struct Block {
     #[schema(example = "А123БВ777")]
     pub blocked_plate: String,
     /// Дата создания блокировки
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
+    /// Причина блокировки (см. `BlockReason`)
+    #[sqlx(default)]
+    #[schema(example = "loading")]
+    pub reason: Option<String>,
+    /// Пояснение к причине в свободной форме
+    #[sqlx(default)]
+    #[schema(example = "Разгружаю мебель")]
+    pub reason_text: Option<String>,
+    /// Заметка блокирующего для владельца
+    #[sqlx(default)]
+    #[schema(example = "Стою у второго подъезда")]
+    pub note: Option<String>,
+    /// Когда блокирующий планирует уехать
+    #[sqlx(default)]
+    #[serde(with = "crate::utils::time::rfc3339_utc_option")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Когда владелец подтвердил, что увидел уведомление о блокировке
+    #[sqlx(default)]
+    #[serde(with = "crate::utils::time::rfc3339_utc_option")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Тихая блокировка: владельцы не получали уведомлений
+    #[sqlx(default)]
+    #[schema(example = false)]
+    pub silent: bool,
+    /// Сколько шагов эскалации уже выполнено для неподтверждённой блокировки
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub escalation_step: i16,
+    /// Ключ оригинала фото-доказательства в хранилище
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub photo_key: Option<String>,
+    /// Ключ превью фото-доказательства в хранилище
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub photo_thumb_key: Option<String>,
+    /// Когда владелец последний раз просил блокирующего перезвонить
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub callback_requested_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "snake_case")]
 #[schema(example = json!({
     "blocked_plate": "А123БВ777",
     "notify_owner": true,
     "departure_time": "18:30",
-    "notification_method": "android_push"
+    "notification_method": "android_push",
+    "reason": "loading"
 }))]
 pub struct CreateBlockRequest {
     /// Номер автомобиля, который блокируется
@@ -55,19 +101,304 @@ pub struct CreateBlockRequest {
     #[serde(default)]
     #[schema(example = "android_push")]
     pub notification_method: Option<String>,
+    /// Причина блокировки: "temporary_parking", "loading", "emergency" или "other"
+    #[serde(default)]
+    #[schema(example = "loading")]
+    pub reason: Option<String>,
+    /// Пояснение к причине в свободной форме (до 200 символов)
+    #[serde(default)]
+    #[schema(example = "Разгружаю мебель")]
+    pub reason_text: Option<String>,
+    /// Тихая блокировка: только запись для себя, владельцы не уведомляются
+    /// (но видят блокировку, если проверят свой номер)
+    #[serde(default)]
+    #[schema(example = false)]
+    pub silent: bool,
+    /// Подтверждение повторной блокировки того же автомобиля
+    /// (требуется, если пользователь уже перекрывал его `REPEAT_BLOCK_FREE_COUNT` раз)
+    #[serde(default)]
+    #[schema(example = false)]
+    pub acknowledge_repeat: bool,
+}
+
+/// Максимальная длина пояснения к причине блокировки
+pub const BLOCK_REASON_TEXT_MAX_LEN: usize = 200;
+
+/// Максимальная длина заметки к блокировке
+pub const BLOCK_NOTE_MAX_LEN: usize = 200;
+
+/// Максимальная продолжительность блокировки, которую можно указать (сутки)
+pub const BLOCK_MAX_DURATION_MINUTES: i64 = 24 * 60;
+
+/// Как часто владелец может просить блокирующего перезвонить по одной блокировке
+pub const CALLBACK_REQUEST_INTERVAL_SECS: i64 = 5 * 60;
+
+/// Причина блокировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockReason {
+    /// Временная парковка
+    TemporaryParking,
+    /// Погрузка/разгрузка
+    Loading,
+    /// Экстренная ситуация
+    Emergency,
+    /// Другое (с пояснением в свободной форме)
+    Other,
+}
+
+impl BlockReason {
+    pub const ALL: [BlockReason; 4] = [
+        BlockReason::TemporaryParking,
+        BlockReason::Loading,
+        BlockReason::Emergency,
+        BlockReason::Other,
+    ];
+
+    /// Значение, хранимое в БД и передаваемое в API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockReason::TemporaryParking => "temporary_parking",
+            BlockReason::Loading => "loading",
+            BlockReason::Emergency => "emergency",
+            BlockReason::Other => "other",
+        }
+    }
+
+    /// Человекочитаемое название для уведомлений
+    pub fn label(&self) -> &'static str {
+        match self {
+            BlockReason::TemporaryParking => "временная парковка",
+            BlockReason::Loading => "погрузка/разгрузка",
+            BlockReason::Emergency => "экстренная ситуация",
+            BlockReason::Other => "другое",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|reason| reason.as_str() == value.trim().to_lowercase())
+    }
+}
+
+/// Описание причины для текста уведомления, например "погрузка/разгрузка, до 18:30"
+pub fn describe_block_reason(
+    reason: Option<BlockReason>,
+    reason_text: Option<&str>,
+    departure_time: Option<&str>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    match (reason, reason_text) {
+        // Для "другое" пояснение информативнее самой категории
+        (Some(BlockReason::Other), Some(text)) => parts.push(text.to_string()),
+        (Some(reason), Some(text)) => parts.push(format!("{}: {}", reason.label(), text)),
+        (Some(reason), None) => parts.push(reason.label().to_string()),
+        (None, Some(text)) => parts.push(text.to_string()),
+        (None, None) => {}
+    }
+    if let Some(time) = departure_time {
+        parts.push(format!("до {}", time));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// Количество блокировок по причине (для админской статистики)
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockReasonStat {
+    /// Причина блокировки (null - не указана)
+    #[schema(example = "loading")]
+    pub reason: Option<String>,
+    /// Количество блокировок
+    #[schema(example = 42)]
+    pub count: i64,
+}
+
+/// Количество активных блокировок номера: перекрывших его и созданных с него
+#[derive(Debug, FromRow)]
+pub struct PlateBlockCounts {
+    /// Нормализованный номер
+    pub plate: String,
+    /// Активные блокировки, перекрывающие этот номер
+    pub received_block_count: i64,
+    /// Активные блокировки, созданные с этого номера
+    pub created_block_count: i64,
+}
+
+/// Строка выгрузки блокировок в CSV: активная блокировка или снятая (из block_history)
+#[derive(Debug, FromRow)]
+pub struct BlockExportRow {
+    pub id: Uuid,
+    pub blocker_id: Uuid,
+    /// У снятых блокировок номер блокирующего не хранится
+    pub blocker_plate: Option<String>,
+    pub blocked_plate: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+impl BlockExportRow {
+    /// Состояние блокировки на момент выгрузки: removed, expired, acknowledged или active
+    pub fn status(&self, now: DateTime<Utc>) -> &'static str {
+        if self.removed_at.is_some() {
+            "removed"
+        } else if self.expires_at.is_some_and(|at| at <= now) {
+            "expired"
+        } else if self.acknowledged_at.is_some() {
+            "acknowledged"
+        } else {
+            "active"
+        }
+    }
+}
+
+/// Итог рассылки уведомлений о блокировке.
+/// Сами отправки выполняются асинхронно, здесь - намерения доставки на момент создания
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct NotifySummary {
+    /// Сколько владельцев у заблокированного номера (без самого блокирующего)
+    #[schema(example = 3)]
+    pub owners_total: usize,
+    /// Скольким владельцам будет отправлено уведомление хотя бы по одному каналу
+    #[schema(example = 2)]
+    pub owners_reachable: usize,
+    /// Задействованные каналы: "push", "telegram", "call"
+    #[schema(example = json!(["push", "call"]))]
+    pub channels_used: Vec<String>,
+}
+
+/// Можно ли связаться с владельцем заблокированного авто и почему нет
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OwnerNotifiability {
+    /// Доступен ли владелец хотя бы по одному каналу
+    #[schema(example = false)]
+    pub reachable: bool,
+    /// Доступные каналы: "push", "telegram", "call"
+    #[schema(example = json!([]))]
+    pub channels: Vec<String>,
+    /// Причины недоступности каналов: "no_push_token", "stale_push_token", "no_telegram",
+    /// "no_phone", "quiet_hours"
+    #[schema(example = json!(["stale_push_token", "no_telegram", "no_phone"]))]
+    pub issues: Vec<String>,
+}
+
+/// Доступность владельцев заблокированного авто: если никто не доступен,
+/// блокирующему стоит найти владельца самостоятельно
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockNotifiabilityResponse {
+    /// ID блокировки
+    #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub block_id: Uuid,
+    /// Номер заблокированного автомобиля
+    #[schema(example = "А123БВ777")]
+    pub blocked_plate: String,
+    /// Сколько владельцев зарегистрировано с этим номером (без самого блокирующего)
+    #[schema(example = 1)]
+    pub owners_total: usize,
+    /// Скольким из них можно отправить уведомление хотя бы по одному каналу
+    #[schema(example = 0)]
+    pub owners_reachable: usize,
+    pub owners: Vec<OwnerNotifiability>,
+}
+
+/// Ответ на создание блокировки: блокировка и итог рассылки уведомлений
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CreateBlockResponse {
+    #[serde(flatten)]
+    pub block: Block,
+    /// Номер заблокированного автомобиля для отображения (`blocked_plate` остаётся основным)
+    #[schema(example = "А 123 БВ 777")]
+    pub blocked_plate_formatted: String,
+    /// Номер блокирующего для отображения
+    #[schema(example = "А 777 ВС 178")]
+    pub blocker_plate_formatted: String,
+    pub notify_summary: NotifySummary,
+}
+
+impl CreateBlockResponse {
+    pub fn new(block: Block, notify_summary: NotifySummary) -> Self {
+        Self {
+            blocked_plate_formatted: format_plate(&block.blocked_plate),
+            blocker_plate_formatted: format_plate(&block.blocker_plate),
+            block,
+            notify_summary,
+        }
+    }
+}
+
+/// Результат просьбы владельца перезвонить блокирующему
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CallbackRequestResponse {
+    /// Каналы, которыми отправлена просьба: "in_app", "push", "telegram"
+    #[schema(example = json!(["in_app", "push"]))]
+    pub channels_used: Vec<String>,
+    /// Передан ли блокирующему телефон владельца (только если владелец разрешил
+    /// показывать контакты)
+    #[schema(example = true)]
+    pub phone_shared: bool,
+}
+
+/// Предпросмотр уведомлений, которые получат владельцы при создании блокировки.
+/// Ничего не создаётся и не отправляется
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockPreviewResponse {
+    /// Нормализованный номер заблокированного автомобиля
+    #[schema(example = "А123БВ777")]
+    pub blocked_plate: String,
+    /// Способ отправки уведомлений: "android_push" или "telegram"
+    #[schema(example = "android_push")]
+    pub notification_method: String,
+    /// Заголовок уведомления в приложении
+    #[schema(example = "Ваш автомобиль заблокирован")]
+    pub title: String,
+    /// Текст уведомления в приложении
+    #[schema(
+        example = "Автомобиль А123БВ777 заблокирован пользователем Иван (погрузка/разгрузка)"
+    )]
+    pub message: String,
+    /// Заголовок push-уведомления (для способа "android_push")
+    pub push_title: Option<String>,
+    /// Текст push-уведомления (для способа "android_push")
+    pub push_body: Option<String>,
+    /// Текст сообщения в Telegram (для способа "telegram")
+    pub telegram_message: Option<String>,
+    /// Текст звонка владельцу (если запрошен notify_owner)
+    pub call_message: Option<String>,
+    /// Кому и по каким каналам будут доставлены уведомления
+    pub notify_summary: NotifySummary,
+    /// Предупреждение о повторной блокировке того же автомобиля: для создания
+    /// потребуется `acknowledge_repeat: true`
+    pub repeat_warning: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct BlockResponse {
     #[schema(value_type = String, format = "uuid")]
     pub id: Uuid,
     #[schema(value_type = String, format = "uuid")]
     pub blocker_id: Uuid,
     pub blocked_plate: String,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct BlockWithBlockerInfo {
     /// ID блокировки
     #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
@@ -76,27 +407,146 @@ pub struct BlockWithBlockerInfo {
     #[schema(example = "А123БВ777")]
     pub blocked_plate: String,
     /// Дата создания
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
     /// Информация о блокирующем пользователе
     pub blocker: crate::models::user::PublicUserInfo,
     /// Тип владельца блокирующего
     #[schema(example = "renter")]
     pub blocker_owner_type: Option<String>,
-    /// Информация о собственнике блокирующего
-    pub blocker_owner_info: Option<serde_json::Value>,
+    /// Сведения о собственнике автомобиля блокирующего (если блокирующий - арендатор)
+    pub blocker_owner_info: Option<crate::models::user::RenterOwnerInfo>,
+    /// Сведения о блокирующем загрузить не удалось: в `blocker` только id и номер его авто
+    #[schema(example = false)]
+    pub enrichment_failed: bool,
+}
+
+/// Действующая блокировка для охраны: оба номера и контакты блокирующего
+/// (телефон - только если блокирующий разрешил показывать контакты)
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ActiveBlockInfo {
+    /// ID блокировки
+    #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: Uuid,
+    /// Номер автомобиля, который перекрыл выезд
+    #[schema(example = "В456ГД777")]
+    pub blocker_plate: String,
+    /// Номер заблокированного автомобиля
+    #[schema(example = "А123БВ777")]
+    pub blocked_plate: String,
+    /// Дата создания
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+    /// Когда блокирующий планирует уехать
+    #[serde(with = "crate::utils::time::rfc3339_utc_option")]
+    #[schema(value_type = Option<String>, format = "date-time")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Информация о блокирующем пользователе
+    pub blocker: crate::models::user::PublicUserInfo,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct CheckBlockResponse {
+    /// Номер в том виде, в котором его передал клиент
+    #[schema(example = "а 123 бв 777")]
+    pub input_plate: String,
+    /// Нормализованный номер, по которому выполнялась проверка
+    #[schema(example = "А123БВ777")]
+    pub normalized_plate: String,
     /// Заблокирована ли машина
     #[schema(example = true)]
     pub is_blocked: bool,
     /// Информация о блокировке (если заблокирована)
     pub block: Option<BlockWithBlockerInfo>,
+    /// Проверяемый номер принадлежит текущему пользователю
+    /// (только в `GET /api/blocks/check`; в ответе для киосков поля нет)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = false)]
+    pub is_own_plate: Option<bool>,
+}
+
+/// Размер фото-доказательства блокировки
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockPhotoSize {
+    /// Превью (большая сторона не более 320 пикселей)
+    Thumb,
+    /// Оригинал
+    #[default]
+    Full,
+}
+
+impl BlockPhotoSize {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlockPhotoSize::Thumb => "thumb",
+            BlockPhotoSize::Full => "full",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct BlockPhotoQuery {
+    /// Размер фото: "thumb" или "full" (по умолчанию)
+    #[serde(default)]
+    pub size: BlockPhotoSize,
+}
+
+/// Изменение активной блокировки: время выезда (или продолжительность) и заметка
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({
+    "departure_time": "19:15",
+    "note": "Задерживаюсь, буду через час",
+    "notify_owners": true
+}))]
+pub struct UpdateBlockRequest {
+    /// Новое время выезда (HH:MM, по местному времени блокирующего)
+    #[serde(default)]
+    #[schema(example = "19:15")]
+    pub departure_time: Option<String>,
+    /// Заметка для владельца (до 200 символов)
+    #[serde(default)]
+    #[schema(example = "Задерживаюсь, буду через час")]
+    pub note: Option<String>,
+    /// Через сколько минут блокирующий уедет (взаимоисключающе с departure_time)
+    #[serde(default)]
+    #[schema(example = 45)]
+    pub duration_minutes: Option<i64>,
+    /// Уведомить владельцев о новом времени выезда
+    #[serde(default)]
+    #[schema(example = true)]
+    pub notify_owners: bool,
+}
+
+impl UpdateBlockRequest {
+    pub fn normalize(&mut self) {
+        self.departure_time = self
+            .departure_time
+            .as_deref()
+            .map(str::trim)
+            .filter(|time| !time.is_empty())
+            .map(str::to_string);
+        self.note = self
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .map(str::to_string);
+    }
 }
 
 impl CreateBlockRequest {
     pub fn normalize(&mut self) {
         self.blocked_plate = normalize_plate(&self.blocked_plate);
+        self.reason_text = self
+            .reason_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string);
     }
 }
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Подписанная ссылка на скачивание: работает без JWT до `expires_at`
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SignedUrlResponse {
+    /// Ссылка с параметрами `expires` и `signature` (абсолютная при известном внешнем адресе)
+    #[schema(
+        example = "https://api.example.com/api/app/download?expires=1767225600&signature=3f9a..."
+    )]
+    pub url: String,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    #[schema(value_type = String, format = "date-time")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Параметры подписанной ссылки
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SignedUrlQuery {
+    /// Момент истечения ссылки (Unix time, секунды)
+    pub expires: Option<i64>,
+    /// HMAC-SHA256 пути и `expires` (hex)
+    pub signature: Option<String>,
+}
@@ -1,11 +1,30 @@
+//! Модели API и базы данных.
+//!
+//! Поля JSON во всех моделях - в `snake_case` (`#[serde(rename_all = "snake_case")]` на каждой
+//! структуре), одинаково для запросов и ответов.
+
+pub mod admin;
+pub mod audit;
 pub mod auth;
 pub mod block;
+pub mod download;
 pub mod notification;
+pub mod outbox;
+pub mod push_token;
+pub mod session;
 pub mod user;
 pub mod user_plate;
+pub mod validation;
 
+pub use admin::*;
+pub use audit::*;
 pub use auth::*;
 pub use block::*;
+pub use download::*;
 pub use notification::*;
+pub use outbox::*;
+pub use push_token::*;
+pub use session::*;
 pub use user::*;
 pub use user_plate::*;
+pub use validation::*;
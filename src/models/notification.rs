@@ -3,30 +3,102 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Тип уведомления. Набор значений совпадает с ограничением `notification_type_check`
+/// в таблице notifications: новый тип нужно добавить и туда
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum NotificationType {
+    /// Автомобиль пользователя заблокировали
+    BlockCreated,
+    /// Блокировку автомобиля пользователя сняли
+    BlockDeleted,
+    /// Предупреждение о звонке владельцу
+    WarningCall,
+    /// Прочие сообщения: изменения блокировки, просьбы перезвонить, объявления
+    System,
+}
+
+impl NotificationType {
+    /// Значение, хранимое в БД и передаваемое в API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationType::BlockCreated => "block_created",
+            NotificationType::BlockDeleted => "block_deleted",
+            NotificationType::WarningCall => "warning_call",
+            NotificationType::System => "system",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
 pub struct Notification {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub r#type: String, // 'block', 'unblock', etc.
+    pub r#type: NotificationType,
     pub title: String,
     pub message: String,
     pub data: Option<serde_json::Value>,
     pub read: bool,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
+    /// Время последней повторной отправки (выбирается только в find_any_by_id)
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub last_resent_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub struct NotificationResponse {
     pub id: Uuid,
-    pub r#type: String,
+    pub r#type: NotificationType,
     pub title: String,
     pub message: String,
     pub data: Option<serde_json::Value>,
     pub read: bool,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
 }
 
+impl From<Notification> for NotificationResponse {
+    fn from(n: Notification) -> Self {
+        Self {
+            id: n.id,
+            r#type: n.r#type,
+            title: n.title,
+            message: n.message,
+            data: n.data,
+            read: n.read,
+            created_at: n.created_at,
+        }
+    }
+}
+
+/// Страница уведомлений (`GET /api/notifications`). В отличие от общей `Page`,
+/// общее количество называется `total_count`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct NotificationPage {
+    pub items: Vec<NotificationResponse>,
+    pub total_count: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
+/// Результат повторной отправки уведомления
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ResendNotificationResponse {
+    pub notification_id: Uuid,
+    /// Каналы, через которые уведомление поставлено в очередь (push, telegram)
+    pub channels: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub struct MarkNotificationReadRequest {
     pub read: bool,
 }
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Канал доставки сообщения из очереди уведомлений
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboxChannel {
+    /// Push-уведомление через FCM (по текущему push token получателя)
+    Push,
+    /// Сообщение через Telegram (по текущему username получателя)
+    Telegram,
+    /// Звонок через API телефонии (по расшифрованному телефону получателя
+    /// или по телефону администратора дома)
+    Call,
+}
+
+impl OutboxChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutboxChannel::Push => "push",
+            OutboxChannel::Telegram => "telegram",
+            OutboxChannel::Call => "call",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "push" => Some(OutboxChannel::Push),
+            "telegram" => Some(OutboxChannel::Telegram),
+            "call" => Some(OutboxChannel::Call),
+            _ => None,
+        }
+    }
+}
+
+/// Состояние сообщения в очереди уведомлений
+pub mod outbox_status {
+    /// Ожидает отправки (в том числе повторной)
+    pub const PENDING: &str = "pending";
+    /// Доставлено провайдеру
+    pub const DELIVERED: &str = "delivered";
+    /// Все попытки исчерпаны
+    pub const DEAD: &str = "dead";
+}
+
+/// Сообщение в очереди уведомлений (transactional outbox).
+/// Получатель хранится как user_id: адрес (push token, username, телефон) берётся в момент отправки
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    pub block_id: Option<Uuid>,
+    /// None - звонок администратору дома (ESCALATION_ADMIN_PHONE)
+    pub user_id: Option<Uuid>,
+    /// "push", "telegram" или "call"
+    pub channel: String,
+    /// Содержимое: title/body/data для push, message для Telegram и звонка
+    pub payload: serde_json::Value,
+    pub status: String,
+    /// Сколько попыток отправки уже начато
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Push token устройства пользователя (одна запись на устройство)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
+pub struct PushToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub token: String,
+    /// Платформа устройства: "android" или "ios" (NULL для старых клиентов)
+    pub platform: Option<String>,
+    pub app_version: Option<String>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub last_seen: DateTime<Utc>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PushToken {
+    /// Токен считается устаревшим, если устройство давно не регистрировало его заново
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.last_seen > chrono::Duration::days(PUSH_TOKEN_STALE_DAYS)
+    }
+}
+
+/// Поддерживаемые платформы устройств
+pub const PUSH_PLATFORMS: [&str; 2] = ["android", "ios"];
+
+/// Через сколько дней без повторной регистрации push токен считается устаревшим
+pub const PUSH_TOKEN_STALE_DAYS: i64 = 60;
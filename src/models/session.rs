@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Сессия устройства: создаётся при входе по коду, живёт, пока её токены обновляются
+#[derive(Debug, Clone, FromRow)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Идентификатор устройства, переданный клиентом при входе
+    pub device_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SessionResponse {
+    /// ID сессии (для завершения через `DELETE /api/users/me/sessions/{id}`)
+    #[schema(value_type = String, format = "uuid")]
+    pub id: Uuid,
+    /// Идентификатор устройства, переданный при входе
+    pub device_id: Option<String>,
+    /// Когда выполнен вход
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: DateTime<Utc>,
+    /// Последний запрос с токеном этой сессии (с точностью до минуты)
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    #[schema(value_type = String, format = "date-time")]
+    pub last_seen: DateTime<Utc>,
+    /// Сессия, в которой выполнен текущий запрос
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn from_session(session: UserSession, current_session_id: Option<Uuid>) -> Self {
+        Self {
+            current: current_session_id == Some(session.id),
+            id: session.id,
+            device_id: session.device_id,
+            created_at: session.created_at,
+            last_seen: session.last_seen,
+        }
+    }
+}
+
+/// Максимальная длина идентификатора устройства
+pub const MAX_DEVICE_ID_LENGTH: usize = 128;
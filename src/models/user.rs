@@ -16,7 +16,45 @@ pub enum OwnerType {
     Renter,
 }
 
+/// Максимальная длина текстовых полей `RenterOwnerInfo`
+pub const OWNER_INFO_FIELD_MAX_LEN: usize = 100;
+
+/// Сведения о собственнике автомобиля, которые указывает арендатор (`owner_type = "renter"`).
+/// Для `owner_type = "owner"` не хранятся
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RenterOwnerInfo {
+    /// Имя собственника
+    #[schema(example = "Пётр Петров")]
+    pub owner_name: Option<String>,
+    /// Телефон собственника (в ответах другим пользователям - только если арендатор
+    /// разрешил показывать контакты)
+    #[schema(example = "+79161234567")]
+    pub owner_phone: Option<String>,
+    /// Агентство, через которое арендован автомобиль или место
+    #[schema(example = "Римский Паркинг")]
+    pub agency: Option<String>,
+}
+
+impl RenterOwnerInfo {
+    /// Допустимые поля объекта owner_info
+    pub const FIELDS: [&'static str; 3] = ["owner_name", "owner_phone", "agency"];
+
+    pub fn is_empty(&self) -> bool {
+        self.owner_name.is_none() && self.owner_phone.is_none() && self.agency.is_none()
+    }
+
+    /// Разбирает сохранённое значение. Старые записи в свободной форме: лишние поля
+    /// игнорируются, нераспознанное значение считается отсутствующим
+    pub fn from_stored(value: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value::<Self>(value.clone())
+            .ok()
+            .filter(|info| !info.is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
 pub struct User {
     pub id: Uuid,
     pub phone_encrypted: Option<String>,
@@ -31,13 +69,38 @@ pub struct User {
     pub owner_info: Option<serde_json::Value>,
     #[sqlx(default)]
     pub departure_time: Option<chrono::NaiveTime>,
+    /// Push token устройства - секрет, хранится только на сервере и никогда не сериализуется
     #[sqlx(default)]
+    #[serde(skip_serializing)]
     pub push_token: Option<String>,
+    /// Получать объявления администрации push уведомлением
+    pub announcement_push: bool,
+    /// Получать объявления администрации в Telegram
+    pub announcement_telegram: bool,
+    /// Смещение часового пояса от UTC в минутах (None - часовой пояс не указан)
+    #[sqlx(default)]
+    pub utc_offset_minutes: Option<i32>,
+    /// Ключ аватара (фото пользователя или его автомобиля) в хранилище файлов
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub avatar_key: Option<String>,
+    /// Когда аккаунт заблокирован администратором (None - не заблокирован)
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub suspended_at: Option<DateTime<Utc>>,
+    /// До какого момента действует блокировка (None - бессрочно)
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub suspended_until: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub suspension_reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "snake_case")]
 pub struct CreateUserRequest {
     #[validate(length(min = 1, max = 20, message = "Имя должно быть от 1 до 20 символов"))]
     pub name: Option<String>,
@@ -59,13 +122,15 @@ pub struct CreateUserRequest {
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 #[schema(example = json!({
     "name": "Иван Иванов",
     "telegram": "@ivan",
     "plate": "А123БВ777",
     "show_contacts": true,
     "owner_type": "renter",
-    "departure_time": "08:00"
+    "departure_time": "08:00",
+    "utc_offset_minutes": 180
 }))]
 pub struct UpdateUserRequest {
     /// Имя пользователя
@@ -86,16 +151,32 @@ pub struct UpdateUserRequest {
     /// Тип владельца: "owner" или "renter"
     #[schema(example = "renter")]
     pub owner_type: Option<String>,
-    /// Дополнительная информация о собственнике (для арендаторов)
+    /// Сведения о собственнике (только для арендаторов; поля см. `RenterOwnerInfo`,
+    /// пустой объект очищает сведения)
+    #[schema(value_type = Option<RenterOwnerInfo>)]
     pub owner_info: Option<serde_json::Value>,
     /// Время выезда в формате HH:MM
     #[schema(example = "08:00")]
     pub departure_time: Option<String>,
-    /// Push token устройства
+    /// Не поддерживается: push токен регистрируется через `POST /api/users/push-token`,
+    /// запрос с этим полем отклоняется с ошибкой 400
+    #[schema(deprecated)]
     pub push_token: Option<String>,
+    /// Получать объявления администрации push уведомлением
+    #[schema(example = true)]
+    pub announcement_push: Option<bool>,
+    /// Получать объявления администрации в Telegram
+    #[schema(example = true)]
+    pub announcement_telegram: Option<bool>,
+    /// Смещение часового пояса от UTC в минутах (от -720 до 840), например 180 для Москвы.
+    /// `null` очищает смещение (используется часовой пояс по умолчанию, UTC+3)
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    #[schema(value_type = Option<i32>, nullable, example = 180)]
+    pub utc_offset_minutes: Option<Option<i32>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct UserResponse {
     /// ID пользователя
     #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
@@ -118,18 +199,30 @@ pub struct UserResponse {
     /// Тип владельца
     #[schema(example = "renter")]
     pub owner_type: Option<String>,
-    /// Информация о собственнике
-    pub owner_info: Option<serde_json::Value>,
+    /// Сведения о собственнике (для арендаторов)
+    pub owner_info: Option<RenterOwnerInfo>,
     /// Время выезда
     #[schema(example = "08:00")]
     pub departure_time: Option<String>,
-    /// Push token устройства
-    pub push_token: Option<String>,
+    /// Получать объявления администрации push уведомлением
+    #[schema(example = true)]
+    pub announcement_push: bool,
+    /// Получать объявления администрации в Telegram
+    #[schema(example = true)]
+    pub announcement_telegram: bool,
+    /// Смещение часового пояса от UTC в минутах
+    #[schema(example = 180)]
+    pub utc_offset_minutes: Option<i32>,
+    /// Ссылка на аватар (если загружен)
+    #[schema(example = "/api/users/550e8400-e29b-41d4-a716-446655440000/avatar")]
+    pub avatar_url: Option<String>,
     /// Дата создания
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub struct PublicUserInfo {
     /// ID пользователя
     #[schema(value_type = String, format = "uuid", example = "550e8400-e29b-41d4-a716-446655440000")]
@@ -149,9 +242,155 @@ pub struct PublicUserInfo {
     /// Время выезда
     #[schema(example = "08:00")]
     pub departure_time: Option<String>,
+    /// Ссылка на аватар (только если загружен и show_contacts = true)
+    #[schema(example = "/api/users/550e8400-e29b-41d4-a716-446655440000/avatar")]
+    pub avatar_url: Option<String>,
+}
+
+impl PublicUserInfo {
+    /// Сведения, известные без загрузки пользователя: id и номер его автомобиля
+    pub fn minimal(id: Uuid, plate: String) -> Self {
+        Self {
+            id,
+            name: None,
+            plate,
+            phone: None,
+            telegram: None,
+            departure_time: None,
+            avatar_url: None,
+        }
+    }
+}
+
+/// Максимум номеров в одном запросе `POST /api/users/by-plates`
+pub const MAX_PLATES_PER_LOOKUP: usize = 50;
+
+/// Публичная информация о владельцах нескольких номеров
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({"plates": ["А123БВ777", "В456ГД199"]}))]
+pub struct UsersByPlatesRequest {
+    /// Номера автомобилей (не более 50)
+    pub plates: Vec<String>,
+}
+
+/// Может ли Telegram бот писать пользователю
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TelegramReachabilityResponse {
+    /// Пользователь запустил бота (/start и отправил контакт), уведомления в Telegram дойдут
+    #[schema(example = false)]
+    pub reachable: bool,
+    /// Username бота (без "@")
+    #[schema(example = "rimskiy_parking_bot")]
+    pub bot_username: Option<String>,
+    /// Ссылка для запуска бота (только если бот не может писать пользователю)
+    #[schema(example = "https://t.me/rimskiy_parking_bot")]
+    pub start_link: Option<String>,
+}
+
+/// Что удалено вместе с аккаунтом
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AccountDeletionResponse {
+    /// Автомобили пользователя
+    #[schema(example = 1)]
+    pub plates: i64,
+    /// Активные блокировки, созданные пользователем
+    #[schema(example = 2)]
+    pub blocks: i64,
+    /// Завершённые блокировки пользователя из истории
+    #[schema(example = 5)]
+    pub block_history: i64,
+    #[schema(example = 12)]
+    pub notifications: i64,
+    #[schema(example = 1)]
+    pub push_tokens: i64,
+    /// Сессии устройств (их токены больше не принимаются)
+    #[schema(example = 2)]
+    pub sessions: i64,
+    /// Регистрации в Telegram боте
+    #[schema(example = 1)]
+    pub telegram_registrations: i64,
+    /// Файлы: аватар, фото блокировок и их превью
+    #[schema(example = 1)]
+    pub files: usize,
+}
+
+/// Результат перешифровки данных пользователя текущим ключом
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ReencryptResponse {
+    /// Были ли данные перешифрованы (false - уже зашифрованы текущим ключом)
+    #[schema(example = true)]
+    pub reencrypted: bool,
+    /// Текущая версия ключа шифрования
+    #[schema(example = 2)]
+    pub key_version: u8,
 }
 
 impl User {
+    /// Push token для объявлений администрации, если пользователь их не отключил
+    pub fn announcement_push_token(&self) -> Option<&str> {
+        self.push_token
+            .as_deref()
+            .filter(|token| self.announcement_push && !token.is_empty())
+    }
+
+    /// Telegram username для объявлений администрации, если пользователь их не отключил
+    pub fn announcement_telegram_username(&self) -> Option<&str> {
+        self.telegram
+            .as_deref()
+            .filter(|username| self.announcement_telegram && !username.is_empty())
+    }
+
+    /// Аккаунт заблокирован администратором и блокировка ещё действует
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some() && self.suspended_until.is_none_or(|until| until > Utc::now())
+    }
+
+    /// Сообщение для заблокированного пользователя: срок и причина блокировки
+    pub fn suspension_message(&self) -> String {
+        let mut message = match self.suspended_until {
+            Some(until) => format!(
+                "Аккаунт заблокирован до {}",
+                until.format("%d.%m.%Y %H:%M UTC")
+            ),
+            None => "Аккаунт заблокирован".to_string(),
+        };
+        if let Some(reason) = self.suspension_reason.as_deref() {
+            message.push_str(&format!(". Причина: {}", reason));
+        }
+        message
+    }
+
+    /// Сведения о собственнике, если пользователь - арендатор
+    pub fn renter_owner_info(&self) -> Option<RenterOwnerInfo> {
+        if self.owner_type.as_deref() == Some("owner") {
+            return None;
+        }
+        self.owner_info
+            .as_ref()
+            .and_then(RenterOwnerInfo::from_stored)
+    }
+
+    /// Сведения о собственнике для других пользователей: телефон собственника -
+    /// только если пользователь разрешил показывать контакты
+    pub fn public_owner_info(&self) -> Option<RenterOwnerInfo> {
+        let mut info = self.renter_owner_info()?;
+        if !self.show_contacts {
+            info.owner_phone = None;
+        }
+        Some(info).filter(|info| !info.is_empty())
+    }
+
+    /// Ссылка на аватар пользователя (None - аватар не загружен)
+    pub fn avatar_url(&self) -> Option<String> {
+        self.avatar_key
+            .as_ref()
+            .map(|_| format!("/api/users/{}/avatar", self.id))
+    }
+
     pub fn to_response(&self, phone_decrypted: Option<String>) -> UserResponse {
         UserResponse {
             id: self.id,
@@ -161,9 +400,12 @@ impl User {
             plate: self.plate.clone().unwrap_or_default(),
             show_contacts: self.show_contacts,
             owner_type: self.owner_type.clone(),
-            owner_info: self.owner_info.clone(),
+            owner_info: self.renter_owner_info(),
             departure_time: self.departure_time.map(|t| t.format("%H:%M").to_string()),
-            push_token: self.push_token.clone(),
+            announcement_push: self.announcement_push,
+            announcement_telegram: self.announcement_telegram,
+            utc_offset_minutes: self.utc_offset_minutes,
+            avatar_url: self.avatar_url(),
             created_at: self.created_at,
         }
     }
@@ -184,6 +426,11 @@ impl User {
                 None
             },
             departure_time: self.departure_time.map(|t| t.format("%H:%M").to_string()),
+            avatar_url: if self.show_contacts {
+                self.avatar_url()
+            } else {
+                None
+            },
         }
     }
 }
@@ -8,6 +8,7 @@ use validator::Validate;
 use crate::utils::normalize_plate;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
 pub struct UserPlate {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -19,6 +20,7 @@ pub struct UserPlate {
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "snake_case")]
 pub struct CreateUserPlateRequest {
     #[validate(length(
         min = 8,
@@ -31,19 +33,87 @@ pub struct CreateUserPlateRequest {
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "snake_case")]
 pub struct UpdateUserPlateRequest {
     pub departure_time: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "snake_case")]
+pub struct ClaimPlateRequest {
+    #[validate(length(
+        min = 8,
+        max = 9,
+        message = "Номер автомобиля должен быть от 8 до 9 символов"
+    ))]
+    pub plate: String,
+}
+
+/// Результат заявки на номер
+pub mod claim_status {
+    /// Номер был свободен и добавлен заявителю сразу
+    pub const CLAIMED: &str = "claimed";
+    /// Номер принадлежит другим пользователям: ждём согласия одного из владельцев
+    pub const PENDING_APPROVAL: &str = "pending_approval";
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ClaimPlateResponse {
+    /// `claimed` или `pending_approval`
+    pub status: String,
+    /// Добавленный номер (только для `claimed`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plate: Option<UserPlateResponse>,
+    /// Заявка на передачу номера (только для `pending_approval`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_request: Option<PlateTransferRequestResponse>,
+}
+
+/// Параметры списка своих номеров (`limit`/`offset` разбирает `OptionalPagination`)
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct GetUserPlatesQuery {
+    /// Добавить к каждому номеру количество активных блокировок
+    #[serde(default)]
+    pub include_counts: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CheckPlateQuery {
+    pub plate: String,
+}
+
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CheckPlateResponse {
+    /// Номер уже добавлен текущим пользователем
+    pub exists_for_me: bool,
+    /// Номер добавлен другими пользователями (совместный автомобиль)
+    pub exists_for_others: bool,
+    /// Нормализованный номер
+    pub normalized: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub struct UserPlateResponse {
     pub id: String,
     pub user_id: String,
     pub plate: String,
     pub is_primary: bool,
     pub departure_time: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub updated_at: DateTime<Utc>,
+    /// Активные блокировки, перекрывающие номер (только при `include_counts=true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_block_count: Option<i64>,
+    /// Активные блокировки, созданные с номера (только при `include_counts=true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_block_count: Option<i64>,
 }
 
 impl UserPlate {
@@ -54,8 +124,10 @@ impl UserPlate {
             plate: self.plate.clone(),
             is_primary: self.is_primary,
             departure_time: self.departure_time.map(|t| t.format("%H:%M").to_string()),
-            created_at: self.created_at.to_rfc3339(),
-            updated_at: self.updated_at.to_rfc3339(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            received_block_count: None,
+            created_block_count: None,
         }
     }
 }
@@ -65,3 +137,130 @@ impl CreateUserPlateRequest {
         self.plate = normalize_plate(&self.plate);
     }
 }
+
+impl ClaimPlateRequest {
+    pub fn normalize(&mut self) {
+        self.plate = normalize_plate(&self.plate);
+    }
+}
+
+/// Состояние заявки на передачу номера новому владельцу
+pub mod plate_transfer_status {
+    /// Ожидает решения владельцев номера
+    pub const PENDING: &str = "pending";
+    /// Одобрено владельцем: номер передан заявителю
+    pub const APPROVED: &str = "approved";
+    /// Отклонено владельцем
+    pub const DECLINED: &str = "declined";
+}
+
+/// Заявка на передачу номера (смена владельца автомобиля).
+/// Номер переходит к заявителю только после согласия одного из текущих владельцев
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
+pub struct PlateTransferRequest {
+    pub id: Uuid,
+    pub plate: String,
+    pub claimant_id: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    /// Владелец, принявший решение
+    pub responded_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PlateTransferRequestResponse {
+    pub id: String,
+    pub plate: String,
+    pub status: String,
+    pub claimant_id: String,
+    /// Заявитель (для входящих заявок)
+    pub claimant: Option<crate::models::user::PublicUserInfo>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PlateTransferRequest {
+    pub fn to_response(
+        &self,
+        claimant: Option<crate::models::user::PublicUserInfo>,
+    ) -> PlateTransferRequestResponse {
+        PlateTransferRequestResponse {
+            id: self.id.to_string(),
+            plate: self.plate.clone(),
+            status: self.status.clone(),
+            claimant_id: self.claimant_id.to_string(),
+            claimant,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Состояние приглашения стать совладельцем номера
+pub mod plate_share_status {
+    /// Ожидает ответа приглашённого
+    pub const PENDING: &str = "pending";
+    /// Принято: номер добавлен приглашённому
+    pub const ACCEPTED: &str = "accepted";
+    /// Отклонено приглашённым
+    pub const DECLINED: &str = "declined";
+}
+
+/// Приглашение стать совладельцем номера
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "snake_case")]
+pub struct PlateShareInvite {
+    pub id: Uuid,
+    /// Номер пригласившего (user_plates.id)
+    pub plate_id: Uuid,
+    pub plate: String,
+    pub inviter_id: Uuid,
+    pub invitee_id: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+/// Пригласить пользователя (по ID или телефону) стать совладельцем номера
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SharePlateRequest {
+    /// Номер текущего пользователя (user_plates.id)
+    pub plate_id: Uuid,
+    pub user_id: Option<Uuid>,
+    /// Телефон приглашаемого в формате +7XXXXXXXXXX
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PlateShareInviteResponse {
+    pub id: String,
+    pub plate: String,
+    pub status: String,
+    pub inviter_id: String,
+    pub invitee_id: String,
+    /// Пригласивший (для входящих приглашений)
+    pub inviter: Option<crate::models::user::PublicUserInfo>,
+    #[serde(with = "crate::utils::time::rfc3339_utc")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PlateShareInvite {
+    pub fn to_response(
+        &self,
+        inviter: Option<crate::models::user::PublicUserInfo>,
+    ) -> PlateShareInviteResponse {
+        PlateShareInviteResponse {
+            id: self.id.to_string(),
+            plate: self.plate.clone(),
+            status: self.status.clone(),
+            inviter_id: self.inviter_id.to_string(),
+            invitee_id: self.invitee_id.to_string(),
+            inviter,
+            created_at: self.created_at,
+        }
+    }
+}
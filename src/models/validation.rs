@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+/// Поля формы для проверки без сохранения. Проверяются только переданные поля
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(example = json!({"plate": "a 123 bc 77", "phone": "8 (916) 518-09-00", "telegram": "@Driver"}))]
+pub struct ValidateRequest {
+    pub plate: Option<String>,
+    pub phone: Option<String>,
+    pub telegram: Option<String>,
+}
+
+/// Результат проверки одного поля
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FieldValidation {
+    pub valid: bool,
+    /// Значение в том виде, в каком его сохранит сервер
+    #[schema(example = "А123ВС77")]
+    pub normalized: String,
+    /// Машинный код ошибки (только для неверного значения)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "INVALID_PLATE")]
+    pub code: Option<String>,
+    /// Сообщение об ошибке для пользователя (только для неверного значения)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Результаты проверки по полям запроса (непереданные поля отсутствуют)
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ValidateResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plate: Option<FieldValidation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<FieldValidation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telegram: Option<FieldValidation>,
+}
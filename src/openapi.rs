@@ -1,12 +1,30 @@
 use utoipa::OpenApi;
 
+use crate::api::pagination::{ActiveBlockInfoPage, BlockPage, BlockWithBlockerInfoPage};
+
 use crate::models::{
+    admin::{
+        AdminCreateBlockRequest, AnnounceChannel, AnnounceJob, AnnounceJobStatus, AnnounceRequest,
+        AnnounceResponse, ChannelDeliveryStats, OverviewStats, SuspendUserRequest,
+        UserSuspensionResponse,
+    },
     auth::{
-        AuthStartRequest, AuthStartResponse, AuthVerifyRequest, AuthVerifyResponse,
-        RefreshTokenRequest, RefreshTokenResponse,
+        AuthCodeChannel, AuthStartRequest, AuthStartResponse, AuthVerifyRequest,
+        AuthVerifyResponse, RefreshTokenRequest, RefreshTokenResponse,
+    },
+    block::{
+        ActiveBlockInfo, Block, BlockNotifiabilityResponse, BlockPreviewResponse, BlockReason,
+        BlockReasonStat, BlockWithBlockerInfo, CallbackRequestResponse, CheckBlockResponse,
+        CreateBlockRequest, CreateBlockResponse, NotifySummary, OwnerNotifiability,
+        UpdateBlockRequest,
     },
-    block::{Block, BlockWithBlockerInfo, CheckBlockResponse, CreateBlockRequest},
-    user::{PublicUserInfo, UpdateUserRequest, UserResponse},
+    download::SignedUrlResponse,
+    session::SessionResponse,
+    user::{
+        AccountDeletionResponse, PublicUserInfo, ReencryptResponse, RenterOwnerInfo,
+        TelegramReachabilityResponse, UpdateUserRequest, UserResponse, UsersByPlatesRequest,
+    },
+    validation::{FieldValidation, ValidateRequest, ValidateResponse},
 };
 
 #[derive(OpenApi)]
@@ -25,20 +43,57 @@ use crate::models::{
     ),
     paths(
         crate::api::app_download::download_app,
+        crate::api::app_download::get_download_url,
+        crate::api::validation::validate_fields,
+        crate::api::file_download::download_block_photo,
+        crate::api::file_download::download_avatar,
         crate::api::auth::start_auth,
         crate::api::auth::verify_auth,
         crate::api::auth::refresh_token,
+        crate::api::auth::logout,
         crate::api::user::get_profile,
         crate::api::user::update_profile,
+        crate::api::user::delete_account,
         crate::api::user::get_user_by_plate,
+        crate::api::user::reencrypt_profile,
+        crate::api::user::list_sessions,
+        crate::api::user::revoke_session,
+        crate::api::user::upload_avatar,
+        crate::api::user::get_my_avatar,
+        crate::api::user::get_user_avatar,
+        crate::api::user::get_user_avatar_url,
+        crate::api::user::telegram_reachable,
+        crate::api::user::get_users_by_plates,
         crate::api::block::create_block,
+        crate::api::block::preview_block,
         crate::api::block::get_my_blocks,
         crate::api::block::get_blocks_for_my_plate,
         crate::api::block::check_block,
+        crate::api::block::check_block_by_plate,
+        crate::api::block::check_block_longpoll,
         crate::api::block::delete_block,
+        crate::api::block::update_block,
         crate::api::block::warn_owner,
+        crate::api::block::acknowledge_block,
+        crate::api::block::export_my_blocks_csv,
+        crate::api::block::request_callback,
+        crate::api::block::get_block_notifiability,
+        crate::api::block::upload_block_photo,
+        crate::api::block::get_block_photo,
+        crate::api::block::get_block_photo_url,
+        crate::api::admin::announce,
+        crate::api::admin::get_announce_job,
+        crate::api::admin::create_block_on_behalf,
+        crate::api::admin::block_reasons_stats,
+        crate::api::admin::export_blocks_csv,
+        crate::api::admin::stats_overview,
+        crate::api::admin::reencrypt_user,
+        crate::api::admin::suspend_user,
+        crate::api::admin::unsuspend_user,
+        crate::api::security::get_active_blocks,
     ),
     components(schemas(
+        AuthCodeChannel,
         AuthStartRequest,
         AuthStartResponse,
         AuthVerifyRequest,
@@ -48,17 +103,53 @@ use crate::models::{
         UserResponse,
         UpdateUserRequest,
         PublicUserInfo,
+        AccountDeletionResponse,
+        ReencryptResponse,
+        SessionResponse,
+        TelegramReachabilityResponse,
+        UsersByPlatesRequest,
+        RenterOwnerInfo,
         Block,
         CreateBlockRequest,
+        UpdateBlockRequest,
+        CreateBlockResponse,
+        BlockPreviewResponse,
+        BlockNotifiabilityResponse,
+        CallbackRequestResponse,
+        OwnerNotifiability,
+        NotifySummary,
         BlockWithBlockerInfo,
         CheckBlockResponse,
+        BlockReason,
+        BlockReasonStat,
+        BlockPage,
+        BlockWithBlockerInfoPage,
+        ActiveBlockInfo,
+        ActiveBlockInfoPage,
+        AnnounceChannel,
+        AnnounceRequest,
+        AdminCreateBlockRequest,
+        AnnounceResponse,
+        AnnounceJob,
+        AnnounceJobStatus,
+        OverviewStats,
+        SuspendUserRequest,
+        UserSuspensionResponse,
+        ChannelDeliveryStats,
+        SignedUrlResponse,
+        ValidateRequest,
+        ValidateResponse,
+        FieldValidation,
     )),
     tags(
         (name = "app", description = "API для работы с приложением"),
         (name = "auth", description = "API для аутентификации пользователей"),
         (name = "users", description = "API для управления профилем пользователя"),
         (name = "blocks", description = "API для управления блокировками автомобилей"),
+        (name = "files", description = "Скачивание фото и аватаров по подписанным ссылкам"),
         (name = "notifications", description = "API для работы с уведомлениями"),
+        (name = "admin", description = "Административное API"),
+        (name = "security", description = "API для охраны парковки"),
     ),
     modifiers(&SecurityAddon),
 )]
@@ -87,3 +178,83 @@ impl Modify for SecurityAddon {
         }
     }
 }
+
+/// HTTP-методы, которые могут встречаться в элементе `paths` спецификации
+const OPERATION_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Копия спецификации только с операциями указанного тега и схемами, на которые они
+/// ссылаются (напрямую или через другие схемы). `None`, если операций с таким тегом нет
+pub fn filter_spec_by_tag(spec: &serde_json::Value, tag: &str) -> Option<serde_json::Value> {
+    use serde_json::Value;
+
+    let mut filtered = spec.clone();
+
+    let paths = filtered.get_mut("paths")?.as_object_mut()?;
+    paths.retain(|_, item| {
+        let Some(item) = item.as_object_mut() else {
+            return false;
+        };
+        item.retain(|key, operation| {
+            !OPERATION_METHODS.contains(&key.as_str())
+                || operation
+                    .get("tags")
+                    .and_then(Value::as_array)
+                    .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag)))
+        });
+        item.keys()
+            .any(|key| OPERATION_METHODS.contains(&key.as_str()))
+    });
+    if paths.is_empty() {
+        return None;
+    }
+
+    if let Some(tags) = filtered.get_mut("tags").and_then(Value::as_array_mut) {
+        tags.retain(|t| t.get("name").and_then(Value::as_str) == Some(tag));
+    }
+
+    // Схемы, на которые ссылаются оставшиеся операции, затем - транзитивно из самих схем
+    let schema_prefix = "#/components/schemas/";
+    let mut used = std::collections::BTreeSet::new();
+    let mut pending = Vec::new();
+    collect_refs(&filtered["paths"], schema_prefix, &mut pending);
+    let schemas = spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object);
+    while let Some(name) = pending.pop() {
+        if used.insert(name.clone()) {
+            if let Some(schema) = schemas.and_then(|schemas| schemas.get(&name)) {
+                collect_refs(schema, schema_prefix, &mut pending);
+            }
+        }
+    }
+    if let Some(schemas) = filtered
+        .pointer_mut("/components/schemas")
+        .and_then(Value::as_object_mut)
+    {
+        schemas.retain(|name, _| used.contains(name));
+    }
+
+    Some(filtered)
+}
+
+/// Собирает имена схем из всех `$ref` вида `<prefix><имя>` внутри значения
+fn collect_refs(value: &serde_json::Value, prefix: &str, refs: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str().and_then(|r| r.strip_prefix(prefix)) {
+                    Some(name) if key == "$ref" => refs.push(name.to_string()),
+                    _ => collect_refs(value, prefix, refs),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_refs(item, prefix, refs);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,53 @@
+use crate::db::DbPool;
+use crate::error::AppResult;
+use crate::models::audit::AuditLogEntry;
+use uuid::Uuid;
+
+pub struct CreateAuditLogData {
+    pub actor_id: Uuid,
+    pub action: String,
+    pub subject_user_id: Option<Uuid>,
+    pub entity_id: Option<Uuid>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Трейт для журнала действий администраторов (DIP)
+#[async_trait::async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    async fn record(&self, data: &CreateAuditLogData) -> AppResult<AuditLogEntry>;
+}
+
+/// Реализация журнала действий администраторов
+#[derive(Clone)]
+pub struct PostgresAuditLogRepository {
+    db: DbPool,
+}
+
+impl PostgresAuditLogRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLogRepository for PostgresAuditLogRepository {
+    async fn record(&self, data: &CreateAuditLogData) -> AppResult<AuditLogEntry> {
+        let entry = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            INSERT INTO audit_log (id, actor_id, action, subject_user_id, entity_id, details, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            RETURNING id, actor_id, action, subject_user_id, entity_id, details, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(data.actor_id)
+        .bind(&data.action)
+        .bind(data.subject_user_id)
+        .bind(data.entity_id)
+        .bind(data.details.as_ref())
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(entry)
+    }
+}
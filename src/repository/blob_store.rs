@@ -0,0 +1,74 @@
+use crate::error::{AppError, AppResult};
+use std::path::{Component, Path, PathBuf};
+
+/// Трейт для хранения бинарных данных (фото и т.п.) по ключу (DIP)
+#[async_trait::async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: &[u8]) -> AppResult<()>;
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
+
+/// Реализация хранилища на локальной файловой системе
+#[derive(Clone)]
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Преобразует ключ в путь, не позволяя выйти за пределы корня хранилища
+    fn path_for(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        let is_safe = !key.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !is_safe {
+            return Err(AppError::Validation(format!("Invalid blob key: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, key: &str, data: &[u8]) -> AppResult<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to create blob dir: {}", e)))?;
+        }
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to write blob {}: {}", key, e)))
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::Internal(format!(
+                "Failed to read blob {}: {}",
+                key, e
+            ))),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Internal(format!(
+                "Failed to delete blob {}: {}",
+                key, e
+            ))),
+        }
+    }
+}
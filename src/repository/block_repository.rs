@@ -1,9 +1,20 @@
-use crate::db::DbPool;
+use crate::db::{DbPool, DbTransaction};
 use crate::error::AppResult;
-use crate::models::block::Block;
+use crate::models::admin::OverviewStats;
+use crate::models::block::{Block, BlockExportRow, BlockReasonStat, PlateBlockCounts};
+use crate::utils::normalize_plate;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-/// Трейт для работы с блокировками в БД (DIP)
+/// Изменяемые поля блокировки (None - поле не меняется)
+#[derive(Default)]
+pub struct UpdateBlockData {
+    pub note: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Трейт для работы с блокировками в БД (DIP).
+/// Методы, принимающие номера, сами нормализуют их через `normalize_plate`
 #[async_trait::async_trait]
 pub trait BlockRepository: Send + Sync {
     async fn create(
@@ -11,14 +22,113 @@ pub trait BlockRepository: Send + Sync {
         blocker_id: Uuid,
         blocker_plate: &str,
         blocked_plate: &str,
+        reason: Option<&str>,
+        reason_text: Option<&str>,
+        silent: bool,
+    ) -> AppResult<Block>;
+    /// Начинает транзакцию (блокировка и связанные с ней записи создаются атомарно)
+    async fn begin(&self) -> AppResult<DbTransaction>;
+    /// То же, что `create`, но в рамках переданной транзакции
+    #[allow(clippy::too_many_arguments)]
+    async fn create_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        blocker_id: Uuid,
+        blocker_plate: &str,
+        blocked_plate: &str,
+        reason: Option<&str>,
+        reason_text: Option<&str>,
+        silent: bool,
     ) -> AppResult<Block>;
-    async fn find_by_blocker_id(&self, blocker_id: Uuid) -> AppResult<Vec<Block>>;
-    async fn find_by_blocker_plates(&self, plates: &[String]) -> AppResult<Vec<Block>>;
+    /// Блокировки, созданные пользователем или с любого из номеров `blocker_plates`
+    /// (совладельцами того же автомобиля), новые сначала. `limit` None - все.
+    /// Второй элемент - общее количество
+    async fn find_created_by_user(
+        &self,
+        blocker_id: Uuid,
+        blocker_plates: &[String],
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<Block>, i64)>;
+    /// Блокировки, перекрывающие любой из номеров `blocked_plates`, новые сначала.
+    /// `limit` None - все. Второй элемент - общее количество
+    async fn find_by_blocked_plates(
+        &self,
+        blocked_plates: &[String],
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<Block>, i64)>;
     async fn find_by_blocked_plate(&self, blocked_plate: &str) -> AppResult<Vec<Block>>;
+    /// Страница всех действующих блокировок (новые сначала) и их общее количество
+    async fn find_active(&self, limit: i64, offset: i64) -> AppResult<(Vec<Block>, i64)>;
+    /// Удаляет блокировку, сохраняя её в block_history (для статистики продолжительности)
     async fn delete(&self, block_id: Uuid, blocker_plate: &str) -> AppResult<()>;
     async fn find_by_id(&self, block_id: Uuid) -> AppResult<Option<Block>>;
     /// Проверяет существование блокировки по номерам (оптимизированная проверка дубликатов)
     async fn exists(&self, blocker_plate: &str, blocked_plate: &str) -> AppResult<bool>;
+    /// Проверяет, перекрыт ли `blocked_plate` хотя бы одним из номеров `blocker_plates`
+    /// (все автомобили пользователя одним запросом)
+    async fn exists_from_any(
+        &self,
+        blocker_plates: &[String],
+        blocked_plate: &str,
+    ) -> AppResult<bool>;
+    /// Считает блокировки пользователя, созданные после `since`, включая уже снятые
+    /// (block_history), чтобы лимит нельзя было обойти, снимая блокировки сразу после создания.
+    /// Возвращает количество и время создания самой старой из них
+    async fn count_recent_by_blocker(
+        &self,
+        blocker_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> AppResult<(i64, Option<DateTime<Utc>>)>;
+    /// Сколько завершённых блокировок номера `blocked_plate` пользователь создавал раньше
+    /// (по block_history)
+    async fn count_past_blocks(&self, blocker_id: Uuid, blocked_plate: &str) -> AppResult<i64>;
+    /// Сохраняет ключи фото-доказательства (оригинал и превью) для блокировки
+    async fn set_photo(&self, block_id: Uuid, photo_key: &str, thumb_key: &str) -> AppResult<()>;
+    /// Считает блокировки по причинам (по убыванию количества)
+    async fn count_by_reason(&self) -> AppResult<Vec<BlockReasonStat>>;
+    /// Количество активных блокировок по каждому из номеров (полученных и созданных);
+    /// номера без блокировок возвращаются с нулями
+    async fn counts_by_plates(&self, plates: &[String]) -> AppResult<Vec<PlateBlockCounts>>;
+    /// Сводная статистика по парковке; часы суток считаются со сдвигом `utc_offset_minutes`
+    async fn overview_stats(&self, utc_offset_minutes: i32) -> AppResult<OverviewStats>;
+    /// Обновляет заметку и время окончания блокировки, возвращает обновлённую блокировку
+    async fn update_block(
+        &self,
+        block_id: Uuid,
+        update: &UpdateBlockData,
+    ) -> AppResult<Option<Block>>;
+    /// Отмечает, что владелец увидел уведомление (повторное подтверждение время не меняет)
+    async fn acknowledge(&self, block_id: Uuid) -> AppResult<Option<Block>>;
+    /// Отмечает просьбу владельца перезвонить, если с предыдущей прошло не меньше
+    /// `min_interval`. Возвращает false, если повтор пока не разрешён
+    async fn mark_callback_requested(
+        &self,
+        block_id: Uuid,
+        min_interval: chrono::Duration,
+    ) -> AppResult<bool>;
+    /// Неподтверждённые не тихие блокировки, созданные после `since`, у которых выполнено меньше `max_step` шагов эскалации
+    async fn find_unacknowledged(
+        &self,
+        since: DateTime<Utc>,
+        max_step: i16,
+        limit: i64,
+    ) -> AppResult<Vec<Block>>;
+    /// Переводит эскалацию на следующий шаг, если блокировка всё ещё на шаге `from_step`
+    /// и не подтверждена. Возвращает false, если шаг уже выполнил кто-то другой
+    async fn advance_escalation(&self, block_id: Uuid, from_step: i16) -> AppResult<bool>;
+    /// Окончательно удаляет из block_history не больше `limit` завершённых блокировок,
+    /// снятых раньше `before`. Возвращает количество удалённых
+    async fn purge_history_older_than(&self, before: DateTime<Utc>, limit: i64) -> AppResult<u64>;
+    /// Страница выгрузки блокировок (активных и снятых) в порядке (created_at, id), после `after`.
+    /// `user_id` - только блокировки, где пользователь блокирующий или владелец номера; None - все
+    async fn find_export_page(
+        &self,
+        user_id: Option<Uuid>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64,
+    ) -> AppResult<Vec<BlockExportRow>>;
 }
 
 /// Реализация репозитория блокировок
@@ -40,84 +150,196 @@ impl BlockRepository for PostgresBlockRepository {
         blocker_id: Uuid,
         blocker_plate: &str,
         blocked_plate: &str,
+        reason: Option<&str>,
+        reason_text: Option<&str>,
+        silent: bool,
+    ) -> AppResult<Block> {
+        let mut tx = self.begin().await?;
+        let block = self
+            .create_in_tx(
+                &mut tx,
+                blocker_id,
+                blocker_plate,
+                blocked_plate,
+                reason,
+                reason_text,
+                silent,
+            )
+            .await?;
+        tx.commit().await?;
+
+        Ok(block)
+    }
+
+    async fn begin(&self) -> AppResult<DbTransaction> {
+        Ok(self.db.begin().await?)
+    }
+
+    async fn create_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        blocker_id: Uuid,
+        blocker_plate: &str,
+        blocked_plate: &str,
+        reason: Option<&str>,
+        reason_text: Option<&str>,
+        silent: bool,
     ) -> AppResult<Block> {
         let block_id = uuid::Uuid::new_v4();
 
         // Используем RETURNING для избежания дополнительного SELECT
         let block = sqlx::query_as::<_, Block>(
             r#"
-            INSERT INTO blocks (id, blocker_id, blocker_plate, blocked_plate, created_at)
-            VALUES ($1, $2, $3, $4, NOW())
-            RETURNING id, blocker_id, blocker_plate, blocked_plate, created_at
+            INSERT INTO blocks (id, blocker_id, blocker_plate, blocked_plate, reason, reason_text, silent, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            RETURNING id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent
             "#,
         )
         .bind(block_id)
         .bind(blocker_id)
-        .bind(blocker_plate)
-        .bind(blocked_plate)
-        .fetch_one(&*self.db)
+        .bind(normalize_plate(blocker_plate))
+        .bind(normalize_plate(blocked_plate))
+        .bind(reason)
+        .bind(reason_text)
+        .bind(silent)
+        .fetch_one(&mut **tx)
         .await?;
 
         Ok(block)
     }
 
-    async fn find_by_blocker_id(&self, blocker_id: Uuid) -> AppResult<Vec<Block>> {
+    async fn find_created_by_user(
+        &self,
+        blocker_id: Uuid,
+        blocker_plates: &[String],
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<Block>, i64)> {
+        let normalized: Vec<String> = blocker_plates.iter().map(|p| normalize_plate(p)).collect();
+        // LIMIT NULL в PostgreSQL - без ограничения
         let blocks = sqlx::query_as::<_, Block>(
             r#"
-            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent
             FROM blocks
-            WHERE blocker_id = $1
-            ORDER BY created_at DESC
+            WHERE blocker_id = $1 OR UPPER(TRIM(blocker_plate)) = ANY($2)
+            ORDER BY created_at DESC, id
+            LIMIT $3 OFFSET $4
             "#,
         )
         .bind(blocker_id)
+        .bind(&normalized)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&*self.db)
         .await?;
 
-        Ok(blocks)
+        // Весь список - количество уже известно
+        if limit.is_none() && offset == 0 {
+            let total = blocks.len() as i64;
+            return Ok((blocks, total));
+        }
+        let (total,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM blocks
+            WHERE blocker_id = $1 OR UPPER(TRIM(blocker_plate)) = ANY($2)
+            "#,
+        )
+        .bind(blocker_id)
+        .bind(&normalized)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok((blocks, total))
     }
 
-    async fn find_by_blocker_plates(&self, plates: &[String]) -> AppResult<Vec<Block>> {
-        if plates.is_empty() {
-            return Ok(Vec::new());
+    async fn find_by_blocked_plates(
+        &self,
+        blocked_plates: &[String],
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<Block>, i64)> {
+        if blocked_plates.is_empty() {
+            return Ok((Vec::new(), 0));
         }
-        let normalized: Vec<String> = plates.iter().map(|p| p.trim().to_uppercase()).collect();
+        let normalized: Vec<String> = blocked_plates.iter().map(|p| normalize_plate(p)).collect();
         let blocks = sqlx::query_as::<_, Block>(
             r#"
-            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent
             FROM blocks
-            WHERE UPPER(TRIM(blocker_plate)) = ANY($1)
-            ORDER BY created_at DESC
+            WHERE UPPER(TRIM(blocked_plate)) = ANY($1)
+            ORDER BY created_at DESC, id
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(&normalized)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&*self.db)
         .await?;
 
-        Ok(blocks)
+        // Весь список - количество уже известно
+        if limit.is_none() && offset == 0 {
+            let total = blocks.len() as i64;
+            return Ok((blocks, total));
+        }
+        let (total,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM blocks
+            WHERE UPPER(TRIM(blocked_plate)) = ANY($1)
+            "#,
+        )
+        .bind(&normalized)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok((blocks, total))
     }
 
     async fn find_by_blocked_plate(&self, blocked_plate: &str) -> AppResult<Vec<Block>> {
-        // Используем нормализованное сравнение для использования индекса
+        // Нормализуем и в Rust, и в SQL (UPPER(TRIM()) также нужен для использования индекса)
         let blocks = sqlx::query_as::<_, Block>(
             r#"
-            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent
             FROM blocks
             WHERE UPPER(TRIM(blocked_plate)) = UPPER(TRIM($1))
             ORDER BY created_at DESC
             "#,
         )
-        .bind(blocked_plate)
+        .bind(normalize_plate(blocked_plate))
         .fetch_all(&*self.db)
         .await?;
 
         Ok(blocks)
     }
 
+    async fn find_active(&self, limit: i64, offset: i64) -> AppResult<(Vec<Block>, i64)> {
+        let blocks = sqlx::query_as::<_, Block>(
+            r#"
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent
+            FROM blocks
+            ORDER BY created_at DESC, id
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let (total,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM blocks")
+            .fetch_one(&*self.db)
+            .await?;
+
+        Ok((blocks, total))
+    }
+
     async fn find_by_id(&self, block_id: Uuid) -> AppResult<Option<Block>> {
         let block = sqlx::query_as::<_, Block>(
             r#"
-            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent,
+                   photo_key, photo_thumb_key, callback_requested_at
             FROM blocks
             WHERE id = $1
             "#,
@@ -132,12 +354,19 @@ impl BlockRepository for PostgresBlockRepository {
     async fn delete(&self, block_id: Uuid, blocker_plate: &str) -> AppResult<()> {
         let result = sqlx::query(
             r#"
-            DELETE FROM blocks
-            WHERE id = $1 AND UPPER(TRIM(blocker_plate)) = UPPER(TRIM($2))
+            WITH deleted AS (
+                DELETE FROM blocks
+                WHERE id = $1 AND UPPER(TRIM(blocker_plate)) = UPPER(TRIM($2))
+                RETURNING id, blocker_id, blocked_plate, reason, created_at
+            )
+            INSERT INTO block_history (id, blocker_id, blocked_plate, reason, created_at, deleted_at)
+            SELECT id, blocker_id, blocked_plate, reason, created_at, NOW()
+            FROM deleted
+            ON CONFLICT (id) DO NOTHING
             "#,
         )
         .bind(block_id)
-        .bind(blocker_plate)
+        .bind(normalize_plate(blocker_plate))
         .execute(&*self.db)
         .await?;
 
@@ -163,11 +392,376 @@ impl BlockRepository for PostgresBlockRepository {
             ) as exists
             "#,
         )
-        .bind(blocker_plate)
-        .bind(blocked_plate)
+        .bind(normalize_plate(blocker_plate))
+        .bind(normalize_plate(blocked_plate))
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(exists.0)
+    }
+
+    async fn exists_from_any(
+        &self,
+        blocker_plates: &[String],
+        blocked_plate: &str,
+    ) -> AppResult<bool> {
+        if blocker_plates.is_empty() {
+            return Ok(false);
+        }
+        let normalized: Vec<String> = blocker_plates.iter().map(|p| normalize_plate(p)).collect();
+        let exists: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM blocks
+                WHERE UPPER(TRIM(blocker_plate)) = ANY($1)
+                AND UPPER(TRIM(blocked_plate)) = UPPER(TRIM($2))
+            ) as exists
+            "#,
+        )
+        .bind(&normalized)
+        .bind(normalize_plate(blocked_plate))
         .fetch_one(&*self.db)
         .await?;
 
         Ok(exists.0)
     }
+
+    async fn count_recent_by_blocker(
+        &self,
+        blocker_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> AppResult<(i64, Option<DateTime<Utc>>)> {
+        // Использует индексы idx_blocks_blocker_created и idx_block_history_blocker_created
+        let result: (i64, Option<DateTime<Utc>>) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), MIN(created_at)
+            FROM (
+                SELECT id, created_at FROM blocks
+                WHERE blocker_id = $1 AND created_at > $2
+                UNION
+                SELECT id, created_at FROM block_history
+                WHERE blocker_id = $1 AND created_at > $2
+            ) recent
+            "#,
+        )
+        .bind(blocker_id)
+        .bind(since)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn count_past_blocks(&self, blocker_id: Uuid, blocked_plate: &str) -> AppResult<i64> {
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM block_history
+            WHERE blocker_id = $1 AND UPPER(TRIM(blocked_plate)) = $2
+            "#,
+        )
+        .bind(blocker_id)
+        .bind(normalize_plate(blocked_plate))
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn set_photo(&self, block_id: Uuid, photo_key: &str, thumb_key: &str) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE blocks
+            SET photo_key = $2, photo_thumb_key = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(block_id)
+        .bind(photo_key)
+        .bind(thumb_key)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(crate::error::AppError::NotFound(
+                "Block not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn count_by_reason(&self) -> AppResult<Vec<BlockReasonStat>> {
+        let stats = sqlx::query_as::<_, BlockReasonStat>(
+            r#"
+            SELECT reason, COUNT(*) AS count
+            FROM blocks
+            GROUP BY reason
+            ORDER BY count DESC
+            "#,
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(stats)
+    }
+
+    async fn counts_by_plates(&self, plates: &[String]) -> AppResult<Vec<PlateBlockCounts>> {
+        if plates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let normalized: Vec<String> = plates.iter().map(|plate| normalize_plate(plate)).collect();
+        let counts = sqlx::query_as::<_, PlateBlockCounts>(
+            r#"
+            SELECT p.plate,
+                   COALESCE(received.count, 0) AS received_block_count,
+                   COALESCE(created.count, 0) AS created_block_count
+            FROM (SELECT DISTINCT UNNEST($1::text[]) AS plate) p
+            LEFT JOIN (
+                SELECT UPPER(TRIM(blocked_plate)) AS plate, COUNT(*) AS count
+                FROM blocks
+                WHERE UPPER(TRIM(blocked_plate)) = ANY($1)
+                GROUP BY 1
+            ) received ON received.plate = p.plate
+            LEFT JOIN (
+                SELECT UPPER(TRIM(blocker_plate)) AS plate, COUNT(*) AS count
+                FROM blocks
+                WHERE UPPER(TRIM(blocker_plate)) = ANY($1)
+                GROUP BY 1
+            ) created ON created.plate = p.plate
+            "#,
+        )
+        .bind(&normalized)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(counts)
+    }
+
+    async fn overview_stats(&self, utc_offset_minutes: i32) -> AppResult<OverviewStats> {
+        let (active_blocks, unique_blocked_plates, unique_blockers): (i64, i64, i64) =
+            sqlx::query_as(
+                r#"
+                SELECT COUNT(*), COUNT(DISTINCT UPPER(TRIM(blocked_plate))), COUNT(DISTINCT blocker_id)
+                FROM blocks
+                "#,
+            )
+            .fetch_one(&*self.db)
+            .await?;
+
+        let (completed_blocks, average_block_duration_minutes): (i64, Option<f64>) =
+            sqlx::query_as(
+                r#"
+                SELECT COUNT(*), (AVG(EXTRACT(EPOCH FROM deleted_at - created_at)) / 60)::float8
+                FROM block_history
+                "#,
+            )
+            .fetch_one(&*self.db)
+            .await?;
+
+        // Блокировки по часу создания (активные и завершённые)
+        let by_hour: Vec<(i32, i64)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(HOUR FROM (created_at AT TIME ZONE 'UTC') + make_interval(mins => $1))::int AS hour,
+                   COUNT(*)
+            FROM (
+                SELECT created_at FROM blocks
+                UNION ALL
+                SELECT created_at FROM block_history
+            ) all_blocks
+            GROUP BY hour
+            "#,
+        )
+        .bind(utc_offset_minutes)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let mut blocks_per_hour = vec![0; 24];
+        for (hour, count) in by_hour {
+            if let Some(slot) = usize::try_from(hour)
+                .ok()
+                .and_then(|hour| blocks_per_hour.get_mut(hour))
+            {
+                *slot = count;
+            }
+        }
+
+        Ok(OverviewStats {
+            active_blocks,
+            unique_blocked_plates,
+            unique_blockers,
+            completed_blocks,
+            average_block_duration_minutes,
+            blocks_per_hour,
+            utc_offset_minutes,
+            // Заполняется по очереди уведомлений (см. AdminService::overview_stats)
+            delivery: Vec::new(),
+            generated_at: Utc::now(),
+        })
+    }
+
+    async fn update_block(
+        &self,
+        block_id: Uuid,
+        update: &UpdateBlockData,
+    ) -> AppResult<Option<Block>> {
+        let block = sqlx::query_as::<_, Block>(
+            r#"
+            UPDATE blocks
+            SET note = COALESCE($2, note),
+                expires_at = COALESCE($3, expires_at)
+            WHERE id = $1
+            RETURNING id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent,
+                      photo_key, photo_thumb_key
+            "#,
+        )
+        .bind(block_id)
+        .bind(update.note.as_deref())
+        .bind(update.expires_at)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(block)
+    }
+
+    async fn acknowledge(&self, block_id: Uuid) -> AppResult<Option<Block>> {
+        let block = sqlx::query_as::<_, Block>(
+            r#"
+            UPDATE blocks
+            SET acknowledged_at = COALESCE(acknowledged_at, NOW())
+            WHERE id = $1
+            RETURNING id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent,
+                      photo_key, photo_thumb_key
+            "#,
+        )
+        .bind(block_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(block)
+    }
+
+    async fn mark_callback_requested(
+        &self,
+        block_id: Uuid,
+        min_interval: chrono::Duration,
+    ) -> AppResult<bool> {
+        // Проверка и отметка одним UPDATE: параллельные запросы не пройдут оба
+        let result = sqlx::query(
+            r#"
+            UPDATE blocks
+            SET callback_requested_at = NOW()
+            WHERE id = $1 AND (callback_requested_at IS NULL OR callback_requested_at <= NOW() - $2)
+            "#,
+        )
+        .bind(block_id)
+        .bind(min_interval)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_unacknowledged(
+        &self,
+        since: DateTime<Utc>,
+        max_step: i16,
+        limit: i64,
+    ) -> AppResult<Vec<Block>> {
+        let blocks = sqlx::query_as::<_, Block>(
+            r#"
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, reason, reason_text, note, expires_at, acknowledged_at, silent,
+                   escalation_step
+            FROM blocks
+            WHERE acknowledged_at IS NULL
+              AND NOT silent
+              AND escalation_step < $2
+              AND created_at > $1
+            ORDER BY created_at
+            LIMIT $3
+            "#,
+        )
+        .bind(since)
+        .bind(max_step)
+        .bind(limit)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(blocks)
+    }
+
+    async fn advance_escalation(&self, block_id: Uuid, from_step: i16) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE blocks
+            SET escalation_step = escalation_step + 1
+            WHERE id = $1 AND escalation_step = $2 AND acknowledged_at IS NULL
+            "#,
+        )
+        .bind(block_id)
+        .bind(from_step)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn purge_history_older_than(&self, before: DateTime<Utc>, limit: i64) -> AppResult<u64> {
+        // Пачками по индексу idx_block_history_deleted_at
+        let result = sqlx::query(
+            r#"
+            DELETE FROM block_history
+            WHERE id IN (
+                SELECT id FROM block_history
+                WHERE deleted_at < $1
+                ORDER BY deleted_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_export_page(
+        &self,
+        user_id: Option<Uuid>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64,
+    ) -> AppResult<Vec<BlockExportRow>> {
+        let (after_created_at, after_id) = after.unzip();
+        let rows = sqlx::query_as::<_, BlockExportRow>(
+            r#"
+            SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, expires_at, acknowledged_at, removed_at
+            FROM (
+                SELECT id, blocker_id, blocker_plate, blocked_plate, created_at, expires_at, acknowledged_at,
+                       NULL::timestamptz AS removed_at
+                FROM blocks
+                UNION ALL
+                SELECT id, blocker_id, NULL, blocked_plate, created_at, NULL, NULL, deleted_at
+                FROM block_history
+            ) b
+            WHERE ($1::uuid IS NULL
+                   OR b.blocker_id = $1
+                   OR UPPER(TRIM(b.blocked_plate)) IN (
+                       SELECT UPPER(TRIM(plate)) FROM user_plates WHERE user_id = $1
+                   ))
+              AND ($2::timestamptz IS NULL OR (b.created_at, b.id) > ($2, $3))
+            ORDER BY b.created_at, b.id
+            LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(after_created_at)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows)
+    }
 }
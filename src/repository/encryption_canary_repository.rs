@@ -0,0 +1,53 @@
+use crate::db::DbPool;
+use crate::error::AppResult;
+
+/// Трейт для контрольного значения шифрования (одна строка, создаётся при первом запуске)
+#[async_trait::async_trait]
+pub trait EncryptionCanaryRepository: Send + Sync {
+    /// Возвращает зашифрованное контрольное значение, если оно уже сохранено
+    async fn find(&self) -> AppResult<Option<String>>;
+    /// Сохраняет (или заменяет) зашифрованное контрольное значение
+    async fn save(&self, value_encrypted: &str) -> AppResult<()>;
+}
+
+/// Реализация репозитория контрольного значения шифрования
+#[derive(Clone)]
+pub struct PostgresEncryptionCanaryRepository {
+    db: DbPool,
+}
+
+impl PostgresEncryptionCanaryRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl EncryptionCanaryRepository for PostgresEncryptionCanaryRepository {
+    async fn find(&self) -> AppResult<Option<String>> {
+        let value = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT value_encrypted FROM encryption_canary WHERE id = 1
+            "#,
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn save(&self, value_encrypted: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO encryption_canary (id, value_encrypted, created_at, updated_at)
+            VALUES (1, $1, NOW(), NOW())
+            ON CONFLICT (id) DO UPDATE SET value_encrypted = EXCLUDED.value_encrypted, updated_at = NOW()
+            "#,
+        )
+        .bind(value_encrypted)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+}
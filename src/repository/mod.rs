@@ -1,15 +1,45 @@
+pub mod audit_log_repository;
+pub mod blob_store;
 pub mod block_repository;
+pub mod encryption_canary_repository;
+pub mod notification_outbox_repository;
 pub mod notification_repository;
+pub mod plate_share_invite_repository;
+pub mod plate_transfer_request_repository;
+pub mod push_token_repository;
+pub mod revoked_token_repository;
 pub mod telegram_bot_repository;
 pub mod user_plate_repository;
 pub mod user_repository;
+pub mod user_session_repository;
 
-pub use block_repository::{BlockRepository, PostgresBlockRepository};
+pub use audit_log_repository::{
+    AuditLogRepository, CreateAuditLogData, PostgresAuditLogRepository,
+};
+pub use blob_store::{BlobStore, FsBlobStore};
+pub use block_repository::{BlockRepository, PostgresBlockRepository, UpdateBlockData};
+pub use encryption_canary_repository::{
+    EncryptionCanaryRepository, PostgresEncryptionCanaryRepository,
+};
+pub use notification_outbox_repository::{
+    NewOutboxMessage, NotificationOutboxRepository, PostgresNotificationOutboxRepository,
+};
 pub use notification_repository::{
     CreateNotificationData, NotificationRepository, PostgresNotificationRepository,
 };
+pub use plate_share_invite_repository::{
+    PlateShareInviteRepository, PostgresPlateShareInviteRepository,
+};
+pub use plate_transfer_request_repository::{
+    PlateTransferRequestRepository, PostgresPlateTransferRequestRepository,
+};
+pub use push_token_repository::{PostgresPushTokenRepository, PushTokenRepository};
+pub use revoked_token_repository::{PostgresRevokedTokenRepository, RevokedTokenRepository};
 pub use telegram_bot_repository::{
     PostgresTelegramBotRepository, TelegramBotRepository, TelegramBotUser,
 };
 pub use user_plate_repository::{PostgresUserPlateRepository, UserPlateRepository};
-pub use user_repository::{CreateUserData, PostgresUserRepository, UpdateUserData, UserRepository};
+pub use user_repository::{
+    CreateUserData, DeletedUserData, PostgresUserRepository, UpdateUserData, UserRepository,
+};
+pub use user_session_repository::{PostgresUserSessionRepository, UserSessionRepository};
@@ -0,0 +1,248 @@
+use crate::db::{DbPool, DbTransaction};
+use crate::error::AppResult;
+use crate::models::admin::ChannelDeliveryStats;
+use crate::models::outbox::{outbox_status, OutboxChannel, OutboxMessage};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Сообщение для постановки в очередь уведомлений
+pub struct NewOutboxMessage {
+    pub block_id: Option<Uuid>,
+    /// None - звонок администратору дома (только канал `Call`)
+    pub user_id: Option<Uuid>,
+    pub channel: OutboxChannel,
+    pub payload: serde_json::Value,
+}
+
+/// Трейт для очереди исходящих уведомлений (DIP)
+#[async_trait::async_trait]
+pub trait NotificationOutboxRepository: Send + Sync {
+    /// Ставит сообщения в очередь в рамках переданной транзакции
+    /// (они будут отправлены, только если транзакция зафиксирована)
+    async fn enqueue_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        messages: &[NewOutboxMessage],
+    ) -> AppResult<()>;
+    /// Ставит сообщения в очередь (одной транзакцией)
+    async fn enqueue(&self, messages: &[NewOutboxMessage]) -> AppResult<()>;
+    /// Забирает до `limit` сообщений, которым пора отправляться, и засчитывает им попытку.
+    /// На время `lease` сообщение не выдаётся повторно (другим экземплярам сервиса или после сбоя)
+    async fn claim_due(&self, limit: i64, lease: chrono::Duration)
+        -> AppResult<Vec<OutboxMessage>>;
+    async fn mark_delivered(&self, id: Uuid) -> AppResult<()>;
+    /// Записывает ошибку: при `retry_at` сообщение будет отправлено повторно,
+    /// иначе оно переходит в "dead"
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> AppResult<()>;
+    /// Отмечает последнее доставленное по блокировке сообщение получателя как полученное
+    /// приложением (и открытое, если `opened`)
+    async fn record_receipt(
+        &self,
+        user_id: Uuid,
+        block_id: Uuid,
+        channel: OutboxChannel,
+        opened: bool,
+    ) -> AppResult<()>;
+    /// Статистика доставки по каналам для сообщений, поставленных в очередь после `since`
+    async fn delivery_stats(&self, since: DateTime<Utc>) -> AppResult<Vec<ChannelDeliveryStats>>;
+}
+
+/// Реализация очереди уведомлений на PostgreSQL
+#[derive(Clone)]
+pub struct PostgresNotificationOutboxRepository {
+    db: DbPool,
+}
+
+impl PostgresNotificationOutboxRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationOutboxRepository for PostgresNotificationOutboxRepository {
+    async fn enqueue_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        messages: &[NewOutboxMessage],
+    ) -> AppResult<()> {
+        for message in messages {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_outbox (id, block_id, user_id, channel, payload, status, next_attempt_at, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW(), NOW())
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(message.block_id)
+            .bind(message.user_id)
+            .bind(message.channel.as_str())
+            .bind(&message.payload)
+            .bind(outbox_status::PENDING)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn enqueue(&self, messages: &[NewOutboxMessage]) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        self.enqueue_in_tx(&mut tx, messages).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease: chrono::Duration,
+    ) -> AppResult<Vec<OutboxMessage>> {
+        let messages = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            UPDATE notification_outbox
+            SET attempts = attempts + 1,
+                next_attempt_at = NOW() + $2,
+                updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM notification_outbox
+                WHERE status = $3 AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, block_id, user_id, channel, payload, status, attempts, last_error, next_attempt_at, created_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease)
+        .bind(outbox_status::PENDING)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(messages)
+    }
+
+    async fn mark_delivered(&self, id: Uuid) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET status = $2, last_error = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(outbox_status::DELIVERED)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> AppResult<()> {
+        let status = if retry_at.is_some() {
+            outbox_status::PENDING
+        } else {
+            outbox_status::DEAD
+        };
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET status = $2,
+                last_error = $3,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn record_receipt(
+        &self,
+        user_id: Uuid,
+        block_id: Uuid,
+        channel: OutboxChannel,
+        opened: bool,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE notification_outbox
+            SET confirmed_at = COALESCE(confirmed_at, NOW()),
+                opened_at = CASE WHEN $4 THEN COALESCE(opened_at, NOW()) ELSE opened_at END,
+                updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM notification_outbox
+                WHERE user_id = $1 AND block_id = $2 AND channel = $3 AND status = $5
+                ORDER BY created_at DESC
+                LIMIT 1
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(block_id)
+        .bind(channel.as_str())
+        .bind(opened)
+        .bind(outbox_status::DELIVERED)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delivery_stats(&self, since: DateTime<Utc>) -> AppResult<Vec<ChannelDeliveryStats>> {
+        let rows = sqlx::query_as::<_, (String, i64, i64, i64, i64)>(
+            r#"
+            SELECT channel,
+                   COUNT(*) FILTER (WHERE status = $2),
+                   COUNT(*) FILTER (WHERE status = $3),
+                   COUNT(confirmed_at),
+                   COUNT(opened_at)
+            FROM notification_outbox
+            WHERE created_at >= $1
+            GROUP BY channel
+            ORDER BY channel
+            "#,
+        )
+        .bind(since)
+        .bind(outbox_status::DELIVERED)
+        .bind(outbox_status::DEAD)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel, sent, failed, confirmed, opened)| {
+                // Подтверждать получение умеет только приложение, то есть только push
+                let confirmable = channel == OutboxChannel::Push.as_str();
+                ChannelDeliveryStats {
+                    delivery_rate: (confirmable && sent > 0)
+                        .then(|| confirmed as f64 / sent as f64),
+                    channel,
+                    sent,
+                    failed,
+                    confirmed,
+                    opened,
+                }
+            })
+            .collect())
+    }
+}
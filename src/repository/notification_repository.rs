@@ -1,24 +1,87 @@
 use crate::db::DbPool;
-use crate::error::AppResult;
-use crate::models::notification::Notification;
+use crate::error::{AppError, AppResult};
+use crate::models::notification::{Notification, NotificationType};
 use uuid::Uuid;
 
+/// Окно, в котором повторное уведомление пользователю того же типа по той же блокировке
+/// не создаётся (например, при одновременных событиях от разных каналов)
+pub const NOTIFICATION_DEDUP_WINDOW_SECS: i64 = 60;
+
+/// Строка результата `record_receipt`
+#[derive(sqlx::FromRow)]
+struct ReceiptRow {
+    #[sqlx(flatten)]
+    notification: Notification,
+    first_report: bool,
+}
+
 /// Трейт для работы с уведомлениями в БД
 #[async_trait::async_trait]
 pub trait NotificationRepository: Send + Sync {
+    /// Создаёт уведомление. Если у пользователя уже есть уведомление того же типа по той же
+    /// блокировке (`data.block_id`), созданное за последние `NOTIFICATION_DEDUP_WINDOW_SECS`,
+    /// новое не создаётся и возвращается существующее
     async fn create(&self, notification: &CreateNotificationData) -> AppResult<Notification>;
+    /// Возвращает страницу уведомлений пользователя (новые первыми) и их общее количество
     async fn find_by_user_id(
         &self,
         user_id: Uuid,
         unread_only: bool,
-    ) -> AppResult<Vec<Notification>>;
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<(Vec<Notification>, i64)>;
+    /// Возвращает уведомление, только если оно принадлежит пользователю
+    async fn find_by_id(
+        &self,
+        notification_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Option<Notification>>;
+    /// Возвращает уведомление независимо от владельца (для проверок доступа администратора)
+    async fn find_any_by_id(&self, notification_id: Uuid) -> AppResult<Option<Notification>>;
     async fn mark_as_read(&self, notification_id: Uuid, user_id: Uuid) -> AppResult<()>;
+    /// Отмечает повторную отправку уведомления, если с предыдущей прошло не меньше
+    /// `min_interval`. Возвращает false, если повтор пока не разрешён
+    async fn mark_resent(
+        &self,
+        notification_id: Uuid,
+        min_interval: chrono::Duration,
+    ) -> AppResult<bool>;
     async fn mark_all_as_read(&self, user_id: Uuid) -> AppResult<()>;
+    /// Удаляет уведомление пользователя; чужое или несуществующее - NotFound
+    async fn delete(&self, notification_id: Uuid, user_id: Uuid) -> AppResult<()>;
+    /// Удаляет все прочитанные уведомления пользователя. Возвращает количество удалённых
+    async fn delete_read(&self, user_id: Uuid) -> AppResult<u64>;
+    /// Записывает подтверждение получения уведомления приложением (при `opened` - и открытия,
+    /// уведомление становится прочитанным). Возвращает уведомление пользователя и признак того,
+    /// что это событие записано впервые (повторные подтверждения ничего не меняют)
+    async fn record_receipt(
+        &self,
+        notification_id: Uuid,
+        user_id: Uuid,
+        opened: bool,
+    ) -> AppResult<Option<(Notification, bool)>>;
+    /// Создаёт одинаковое уведомление для каждого из пользователей одним запросом
+    /// (несуществующие id пропускаются). Возвращает количество созданных уведомлений
+    async fn create_for_users(
+        &self,
+        user_ids: &[Uuid],
+        r#type: NotificationType,
+        title: &str,
+        message: &str,
+        data: Option<&serde_json::Value>,
+    ) -> AppResult<u64>;
+    /// Удаляет не больше `limit` уведомлений, созданных раньше `before`.
+    /// Возвращает количество удалённых (меньше `limit` - старых уведомлений не осталось)
+    async fn purge_older_than(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> AppResult<u64>;
 }
 
 pub struct CreateNotificationData {
     pub user_id: Uuid,
-    pub r#type: String,
+    pub r#type: NotificationType,
     pub title: String,
     pub message: String,
     pub data: Option<serde_json::Value>,
@@ -40,9 +103,58 @@ impl PostgresNotificationRepository {
 impl NotificationRepository for PostgresNotificationRepository {
     async fn create(&self, notification: &CreateNotificationData) -> AppResult<Notification> {
         let notification_id = uuid::Uuid::new_v4();
+        let block_id = notification
+            .data
+            .as_ref()
+            .and_then(|data| data.get("block_id"))
+            .and_then(|value| value.as_str());
+
+        let mut tx = self.db.begin().await?;
+
+        if let Some(block_id) = block_id {
+            // Сериализуем создание уведомлений с одним ключом, чтобы параллельные запросы
+            // не создали дубликат между проверкой и вставкой
+            sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                .bind(format!(
+                    "notification:{}:{}:{}",
+                    notification.user_id,
+                    block_id,
+                    notification.r#type.as_str()
+                ))
+                .execute(&mut *tx)
+                .await?;
+
+            let existing = sqlx::query_as::<_, Notification>(
+                r#"
+                SELECT id, user_id, type, title, message, data, read, created_at
+                FROM notifications
+                WHERE user_id = $1 AND type = $2 AND data->>'block_id' = $3
+                  AND created_at > NOW() - make_interval(secs => $4)
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(notification.user_id)
+            .bind(notification.r#type)
+            .bind(block_id)
+            .bind(NOTIFICATION_DEDUP_WINDOW_SECS as f64)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(existing) = existing {
+                tx.commit().await?;
+                tracing::debug!(
+                    "Notification {} for user {} (block {}) already exists, skipping duplicate",
+                    existing.id,
+                    notification.user_id,
+                    block_id
+                );
+                return Ok(existing);
+            }
+        }
 
         // Используем RETURNING для избежания дополнительного SELECT
-        let notification = sqlx::query_as::<_, Notification>(
+        let created = sqlx::query_as::<_, Notification>(
             r#"
             INSERT INTO notifications (id, user_id, type, title, message, data, read, created_at)
             VALUES ($1, $2, $3, $4, $5, $6, false, NOW())
@@ -51,21 +163,25 @@ impl NotificationRepository for PostgresNotificationRepository {
         )
         .bind(notification_id)
         .bind(notification.user_id)
-        .bind(&notification.r#type)
+        .bind(notification.r#type)
         .bind(&notification.title)
         .bind(&notification.message)
         .bind(&notification.data)
-        .fetch_one(&*self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(notification)
+        tx.commit().await?;
+
+        Ok(created)
     }
 
     async fn find_by_user_id(
         &self,
         user_id: Uuid,
         unread_only: bool,
-    ) -> AppResult<Vec<Notification>> {
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<(Vec<Notification>, i64)> {
         // Оптимизированный запрос с использованием составного индекса
         let query = if unread_only {
             r#"
@@ -73,7 +189,7 @@ impl NotificationRepository for PostgresNotificationRepository {
             FROM notifications
             WHERE user_id = $1 AND read = false
             ORDER BY created_at DESC
-            LIMIT 100
+            LIMIT $2 OFFSET $3
             "#
         } else {
             r#"
@@ -81,16 +197,86 @@ impl NotificationRepository for PostgresNotificationRepository {
             FROM notifications
             WHERE user_id = $1
             ORDER BY created_at DESC
-            LIMIT 100
+            LIMIT $2 OFFSET $3
             "#
         };
 
         let notifications = sqlx::query_as::<_, Notification>(query)
             .bind(user_id)
+            .bind(limit)
+            .bind(offset)
             .fetch_all(&*self.db)
             .await?;
 
-        Ok(notifications)
+        let (total,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM notifications
+            WHERE user_id = $1 AND ($2 = false OR read = false)
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok((notifications, total))
+    }
+
+    async fn find_by_id(
+        &self,
+        notification_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Option<Notification>> {
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, type, title, message, data, read, created_at
+            FROM notifications
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(notification)
+    }
+
+    async fn find_any_by_id(&self, notification_id: Uuid) -> AppResult<Option<Notification>> {
+        let notification = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, type, title, message, data, read, created_at, last_resent_at
+            FROM notifications
+            WHERE id = $1
+            "#,
+        )
+        .bind(notification_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(notification)
+    }
+
+    async fn mark_resent(
+        &self,
+        notification_id: Uuid,
+        min_interval: chrono::Duration,
+    ) -> AppResult<bool> {
+        // Проверка и отметка одним UPDATE: параллельные запросы не пройдут оба
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+            SET last_resent_at = NOW()
+            WHERE id = $1 AND (last_resent_at IS NULL OR last_resent_at <= NOW() - $2)
+            "#,
+        )
+        .bind(notification_id)
+        .bind(min_interval)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     async fn mark_as_read(&self, notification_id: Uuid, user_id: Uuid) -> AppResult<()> {
@@ -123,4 +309,130 @@ impl NotificationRepository for PostgresNotificationRepository {
 
         Ok(())
     }
+
+    async fn delete(&self, notification_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM notifications
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Notification not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_read(&self, user_id: Uuid) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM notifications
+            WHERE user_id = $1 AND read = true
+            "#,
+        )
+        .bind(user_id)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn record_receipt(
+        &self,
+        notification_id: Uuid,
+        user_id: Uuid,
+        opened: bool,
+    ) -> AppResult<Option<(Notification, bool)>> {
+        // target - значения до обновления; FOR UPDATE, чтобы из параллельных
+        // подтверждений первым считалось ровно одно
+        let row = sqlx::query_as::<_, ReceiptRow>(
+            r#"
+            WITH target AS (
+                SELECT id, delivered_at, opened_at
+                FROM notifications
+                WHERE id = $1 AND user_id = $2
+                FOR UPDATE
+            )
+            UPDATE notifications n
+            SET delivered_at = COALESCE(n.delivered_at, NOW()),
+                opened_at = CASE WHEN $3 THEN COALESCE(n.opened_at, NOW()) ELSE n.opened_at END,
+                read = n.read OR $3
+            FROM target
+            WHERE n.id = target.id
+            RETURNING n.id, n.user_id, n.type, n.title, n.message, n.data, n.read, n.created_at,
+                      CASE WHEN $3 THEN target.opened_at IS NULL
+                           ELSE target.delivered_at IS NULL END AS first_report
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .bind(opened)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(row.map(|row| (row.notification, row.first_report)))
+    }
+
+    async fn create_for_users(
+        &self,
+        user_ids: &[Uuid],
+        r#type: NotificationType,
+        title: &str,
+        message: &str,
+        data: Option<&serde_json::Value>,
+    ) -> AppResult<u64> {
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+
+        // Одним INSERT ... SELECT на пачку пользователей
+        let result = sqlx::query(
+            r#"
+            INSERT INTO notifications (id, user_id, type, title, message, data, read, created_at)
+            SELECT gen_random_uuid(), u.id, $2, $3, $4, $5, false, NOW()
+            FROM users u
+            WHERE u.id = ANY($1)
+            "#,
+        )
+        .bind(user_ids)
+        .bind(r#type)
+        .bind(title)
+        .bind(message)
+        .bind(data)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn purge_older_than(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> AppResult<u64> {
+        // Пачками по индексу idx_notifications_created_at, чтобы не держать долгих блокировок
+        let result = sqlx::query(
+            r#"
+            DELETE FROM notifications
+            WHERE id IN (
+                SELECT id FROM notifications
+                WHERE created_at < $1
+                ORDER BY created_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
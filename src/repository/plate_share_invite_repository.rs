@@ -0,0 +1,134 @@
+use crate::db::{DbPool, DbTransaction};
+use crate::error::AppResult;
+use crate::models::user_plate::{plate_share_status, PlateShareInvite};
+use uuid::Uuid;
+
+/// Трейт для приглашений стать совладельцем номера (DIP)
+#[async_trait::async_trait]
+pub trait PlateShareInviteRepository: Send + Sync {
+    /// Создаёт приглашение. Если такое же приглашение уже ожидает ответа, возвращает его.
+    /// Второй элемент - было ли приглашение создано сейчас
+    async fn create(
+        &self,
+        plate_id: Uuid,
+        plate: &str,
+        inviter_id: Uuid,
+        invitee_id: Uuid,
+    ) -> AppResult<(PlateShareInvite, bool)>;
+    /// Ожидающие ответа приглашения пользователя (новые сначала)
+    async fn find_pending_for_invitee(&self, invitee_id: Uuid) -> AppResult<Vec<PlateShareInvite>>;
+    async fn begin(&self) -> AppResult<DbTransaction>;
+    /// Записывает ответ приглашённого в рамках переданной транзакции.
+    /// None - приглашение не найдено, адресовано другому или на него уже ответили
+    async fn respond_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        invitee_id: Uuid,
+        status: &str,
+    ) -> AppResult<Option<PlateShareInvite>>;
+}
+
+/// Реализация репозитория приглашений на PostgreSQL
+#[derive(Clone)]
+pub struct PostgresPlateShareInviteRepository {
+    db: DbPool,
+}
+
+impl PostgresPlateShareInviteRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl PlateShareInviteRepository for PostgresPlateShareInviteRepository {
+    async fn create(
+        &self,
+        plate_id: Uuid,
+        plate: &str,
+        inviter_id: Uuid,
+        invitee_id: Uuid,
+    ) -> AppResult<(PlateShareInvite, bool)> {
+        let created = sqlx::query_as::<_, PlateShareInvite>(
+            r#"
+            INSERT INTO plate_share_invites (id, plate_id, plate, inviter_id, invitee_id, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            ON CONFLICT (plate_id, invitee_id) WHERE status = 'pending' DO NOTHING
+            RETURNING id, plate_id, plate, inviter_id, invitee_id, status, created_at, responded_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(plate_id)
+        .bind(plate)
+        .bind(inviter_id)
+        .bind(invitee_id)
+        .bind(plate_share_status::PENDING)
+        .fetch_optional(&*self.db)
+        .await?;
+        if let Some(invite) = created {
+            return Ok((invite, true));
+        }
+
+        let existing = sqlx::query_as::<_, PlateShareInvite>(
+            r#"
+            SELECT id, plate_id, plate, inviter_id, invitee_id, status, created_at, responded_at
+            FROM plate_share_invites
+            WHERE plate_id = $1 AND invitee_id = $2 AND status = $3
+            "#,
+        )
+        .bind(plate_id)
+        .bind(invitee_id)
+        .bind(plate_share_status::PENDING)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok((existing, false))
+    }
+
+    async fn find_pending_for_invitee(&self, invitee_id: Uuid) -> AppResult<Vec<PlateShareInvite>> {
+        let invites = sqlx::query_as::<_, PlateShareInvite>(
+            r#"
+            SELECT id, plate_id, plate, inviter_id, invitee_id, status, created_at, responded_at
+            FROM plate_share_invites
+            WHERE invitee_id = $1 AND status = $2
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(invitee_id)
+        .bind(plate_share_status::PENDING)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(invites)
+    }
+
+    async fn begin(&self) -> AppResult<DbTransaction> {
+        Ok(self.db.begin().await?)
+    }
+
+    async fn respond_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        invitee_id: Uuid,
+        status: &str,
+    ) -> AppResult<Option<PlateShareInvite>> {
+        let invite = sqlx::query_as::<_, PlateShareInvite>(
+            r#"
+            UPDATE plate_share_invites
+            SET status = $3, responded_at = NOW()
+            WHERE id = $1 AND invitee_id = $2 AND status = $4
+            RETURNING id, plate_id, plate, inviter_id, invitee_id, status, created_at, responded_at
+            "#,
+        )
+        .bind(id)
+        .bind(invitee_id)
+        .bind(status)
+        .bind(plate_share_status::PENDING)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(invite)
+    }
+}
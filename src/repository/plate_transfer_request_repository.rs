@@ -0,0 +1,142 @@
+use crate::db::{DbPool, DbTransaction};
+use crate::error::AppResult;
+use crate::models::user_plate::{plate_transfer_status, PlateTransferRequest};
+use crate::utils::normalize_plate;
+use uuid::Uuid;
+
+/// Трейт для заявок на передачу номера новому владельцу (DIP)
+#[async_trait::async_trait]
+pub trait PlateTransferRequestRepository: Send + Sync {
+    /// Создаёт заявку. Если заявка того же пользователя на этот номер уже ожидает решения,
+    /// возвращает её. Второй элемент - была ли заявка создана сейчас
+    async fn create(
+        &self,
+        plate: &str,
+        claimant_id: Uuid,
+    ) -> AppResult<(PlateTransferRequest, bool)>;
+    /// Ожидающие решения заявки на номера, которыми владеет пользователь (новые сначала)
+    async fn find_pending_for_owner(&self, owner_id: Uuid) -> AppResult<Vec<PlateTransferRequest>>;
+    async fn begin(&self) -> AppResult<DbTransaction>;
+    /// Записывает решение владельца в рамках переданной транзакции.
+    /// None - заявка не найдена, пользователь не владеет номером (или сам её подал)
+    /// либо по ней уже принято решение
+    async fn respond_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        owner_id: Uuid,
+        status: &str,
+    ) -> AppResult<Option<PlateTransferRequest>>;
+}
+
+/// Реализация репозитория заявок на передачу номера на PostgreSQL
+#[derive(Clone)]
+pub struct PostgresPlateTransferRequestRepository {
+    db: DbPool,
+}
+
+impl PostgresPlateTransferRequestRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl PlateTransferRequestRepository for PostgresPlateTransferRequestRepository {
+    async fn create(
+        &self,
+        plate: &str,
+        claimant_id: Uuid,
+    ) -> AppResult<(PlateTransferRequest, bool)> {
+        let plate = normalize_plate(plate);
+        let created = sqlx::query_as::<_, PlateTransferRequest>(
+            r#"
+            INSERT INTO plate_transfer_requests (id, plate, claimant_id, status, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (plate, claimant_id) WHERE status = 'pending' DO NOTHING
+            RETURNING id, plate, claimant_id, status, created_at, responded_at, responded_by
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&plate)
+        .bind(claimant_id)
+        .bind(plate_transfer_status::PENDING)
+        .fetch_optional(&*self.db)
+        .await?;
+        if let Some(request) = created {
+            return Ok((request, true));
+        }
+
+        let existing = sqlx::query_as::<_, PlateTransferRequest>(
+            r#"
+            SELECT id, plate, claimant_id, status, created_at, responded_at, responded_by
+            FROM plate_transfer_requests
+            WHERE plate = $1 AND claimant_id = $2 AND status = $3
+            "#,
+        )
+        .bind(&plate)
+        .bind(claimant_id)
+        .bind(plate_transfer_status::PENDING)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok((existing, false))
+    }
+
+    async fn find_pending_for_owner(&self, owner_id: Uuid) -> AppResult<Vec<PlateTransferRequest>> {
+        let requests = sqlx::query_as::<_, PlateTransferRequest>(
+            r#"
+            SELECT r.id, r.plate, r.claimant_id, r.status, r.created_at, r.responded_at, r.responded_by
+            FROM plate_transfer_requests r
+            WHERE r.status = $2
+              AND r.claimant_id <> $1
+              AND EXISTS (
+                  SELECT 1 FROM user_plates up
+                  WHERE up.user_id = $1 AND UPPER(TRIM(up.plate)) = r.plate
+              )
+            ORDER BY r.created_at DESC
+            "#,
+        )
+        .bind(owner_id)
+        .bind(plate_transfer_status::PENDING)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(requests)
+    }
+
+    async fn begin(&self) -> AppResult<DbTransaction> {
+        Ok(self.db.begin().await?)
+    }
+
+    async fn respond_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        owner_id: Uuid,
+        status: &str,
+    ) -> AppResult<Option<PlateTransferRequest>> {
+        let request = sqlx::query_as::<_, PlateTransferRequest>(
+            r#"
+            UPDATE plate_transfer_requests r
+            SET status = $3, responded_at = NOW(), responded_by = $2
+            WHERE r.id = $1
+              AND r.status = $4
+              AND r.claimant_id <> $2
+              AND EXISTS (
+                  SELECT 1 FROM user_plates up
+                  WHERE up.user_id = $2 AND UPPER(TRIM(up.plate)) = r.plate
+              )
+            RETURNING r.id, r.plate, r.claimant_id, r.status, r.created_at, r.responded_at, r.responded_by
+            "#,
+        )
+        .bind(id)
+        .bind(owner_id)
+        .bind(status)
+        .bind(plate_transfer_status::PENDING)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(request)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::db::DbPool;
+use crate::error::AppResult;
+use crate::models::push_token::PushToken;
+use uuid::Uuid;
+
+/// Трейт для работы с push токенами устройств (DIP)
+#[async_trait::async_trait]
+pub trait PushTokenRepository: Send + Sync {
+    /// Регистрирует токен устройства (идемпотентно): повторная регистрация того же токена
+    /// обновляет владельца, платформу, версию приложения и last_seen
+    async fn upsert(
+        &self,
+        user_id: Uuid,
+        token: &str,
+        platform: Option<&str>,
+        app_version: Option<&str>,
+    ) -> AppResult<PushToken>;
+    /// Находит запись о токене устройства
+    async fn find_by_token(&self, token: &str) -> AppResult<Option<PushToken>>;
+}
+
+/// Реализация репозитория push токенов
+#[derive(Clone)]
+pub struct PostgresPushTokenRepository {
+    db: DbPool,
+}
+
+impl PostgresPushTokenRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl PushTokenRepository for PostgresPushTokenRepository {
+    async fn upsert(
+        &self,
+        user_id: Uuid,
+        token: &str,
+        platform: Option<&str>,
+        app_version: Option<&str>,
+    ) -> AppResult<PushToken> {
+        let push_token = sqlx::query_as::<_, PushToken>(
+            r#"
+            INSERT INTO push_tokens (id, user_id, token, platform, app_version, last_seen, created_at)
+            VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
+            ON CONFLICT (token)
+            DO UPDATE SET
+                user_id = EXCLUDED.user_id,
+                platform = COALESCE(EXCLUDED.platform, push_tokens.platform),
+                app_version = COALESCE(EXCLUDED.app_version, push_tokens.app_version),
+                last_seen = NOW()
+            RETURNING id, user_id, token, platform, app_version, last_seen, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(token)
+        .bind(platform)
+        .bind(app_version)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(push_token)
+    }
+
+    async fn find_by_token(&self, token: &str) -> AppResult<Option<PushToken>> {
+        let push_token = sqlx::query_as::<_, PushToken>(
+            r#"
+            SELECT id, user_id, token, platform, app_version, last_seen, created_at
+            FROM push_tokens
+            WHERE token = $1
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(push_token)
+    }
+}
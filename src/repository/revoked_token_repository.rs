@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::AppResult;
+
+/// Трейт для отозванных JWT (выход из аккаунта)
+#[async_trait::async_trait]
+pub trait RevokedTokenRepository: Send + Sync {
+    /// Отзывает токен до `expires_at`. Повторный отзыв того же токена ничего не меняет
+    async fn revoke(&self, jti: Uuid, user_id: Uuid, expires_at: DateTime<Utc>) -> AppResult<()>;
+    /// Отозван ли токен
+    async fn is_revoked(&self, jti: Uuid) -> AppResult<bool>;
+    /// Удаляет записи о токенах, срок которых прошёл. Возвращает количество удалённых
+    async fn purge_expired(&self) -> AppResult<u64>;
+}
+
+/// Реализация репозитория отозванных токенов
+#[derive(Clone)]
+pub struct PostgresRevokedTokenRepository {
+    db: DbPool,
+}
+
+impl PostgresRevokedTokenRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl RevokedTokenRepository for PostgresRevokedTokenRepository {
+    async fn revoke(&self, jti: Uuid, user_id: Uuid, expires_at: DateTime<Utc>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at, revoked_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(jti)
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: Uuid) -> AppResult<bool> {
+        let revoked = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM revoked_tokens WHERE jti = $1)
+            "#,
+        )
+        .bind(jti)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(revoked)
+    }
+
+    async fn purge_expired(&self) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM revoked_tokens WHERE expires_at <= NOW()
+            "#,
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
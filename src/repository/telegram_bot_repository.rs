@@ -19,12 +19,16 @@ pub struct TelegramBotUser {
 /// Трейт для работы с регистрациями Telegram бота
 #[async_trait::async_trait]
 pub trait TelegramBotRepository: Send + Sync {
-    /// Находит регистрацию по phone_hash
+    /// Находит регистрацию по phone_hash (самую недавно активную, если чатов несколько)
     async fn find_by_phone_hash(&self, phone_hash: &str) -> AppResult<Option<TelegramBotUser>>;
 
-    /// Находит регистрацию по chat_id
+    /// Находит регистрацию по chat_id. Привязка к номеру важнее временной записи
     async fn find_by_chat_id(&self, chat_id: i64) -> AppResult<Option<TelegramBotUser>>;
 
+    /// Все chat_id, привязанные к phone_hash (с нескольких аккаунтов Telegram),
+    /// начиная с самого недавно активного
+    async fn find_chat_ids_by_phone_hash(&self, phone_hash: &str) -> AppResult<Vec<i64>>;
+
     /// Создает или обновляет регистрацию
     async fn upsert(
         &self,
@@ -64,11 +68,23 @@ pub trait TelegramBotRepository: Send + Sync {
     /// Удаляет временные записи для указанного user_id, кроме указанной записи
     async fn delete_temp_except(&self, user_id: Uuid, except_id: Uuid) -> AppResult<()>;
 
-    /// Находит регистрацию по telegram_username
+    /// Находит регистрацию по telegram_username. Привязка к номеру важнее временной записи
     async fn find_by_telegram_username(
         &self,
         telegram_username: &str,
     ) -> AppResult<Option<TelegramBotUser>>;
+
+    /// Удаляет остальные записи чата (временную и с прежним номером), кроме `keep_id`.
+    /// Возвращает количество удалённых
+    async fn delete_other_for_chat_id(&self, chat_id: i64, keep_id: Uuid) -> AppResult<u64>;
+
+    /// Удаляет записи phone_hash, не обновлявшиеся с `inactive_since`.
+    /// Самая недавно активная запись сохраняется всегда. Возвращает количество удалённых
+    async fn delete_stale_for_phone_hash(
+        &self,
+        phone_hash: &str,
+        inactive_since: DateTime<Utc>,
+    ) -> AppResult<u64>;
 }
 
 /// Реализация репозитория для PostgreSQL
@@ -110,7 +126,7 @@ impl TelegramBotRepository for PostgresTelegramBotRepository {
                 id, phone_hash, chat_id, telegram_username, user_id, created_at, updated_at
             FROM telegram_bot_users
             WHERE chat_id = $1
-            ORDER BY updated_at DESC
+            ORDER BY phone_hash LIKE 'temp_%', updated_at DESC
             LIMIT 1
             "#,
         )
@@ -121,6 +137,23 @@ impl TelegramBotRepository for PostgresTelegramBotRepository {
         Ok(result)
     }
 
+    async fn find_chat_ids_by_phone_hash(&self, phone_hash: &str) -> AppResult<Vec<i64>> {
+        let chat_ids = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT chat_id
+            FROM telegram_bot_users
+            WHERE phone_hash = $1
+            GROUP BY chat_id
+            ORDER BY MAX(updated_at) DESC
+            "#,
+        )
+        .bind(phone_hash)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(chat_ids)
+    }
+
     async fn upsert(
         &self,
         phone_hash: &str,
@@ -286,7 +319,7 @@ impl TelegramBotRepository for PostgresTelegramBotRepository {
             SELECT id, phone_hash, chat_id, telegram_username, user_id, created_at, updated_at
             FROM telegram_bot_users
             WHERE telegram_username = $1
-            ORDER BY updated_at DESC
+            ORDER BY phone_hash LIKE 'temp_%', updated_at DESC
             LIMIT 1
             "#,
         )
@@ -296,4 +329,45 @@ impl TelegramBotRepository for PostgresTelegramBotRepository {
 
         Ok(result)
     }
+
+    async fn delete_other_for_chat_id(&self, chat_id: i64, keep_id: Uuid) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM telegram_bot_users
+            WHERE chat_id = $1 AND id != $2
+            "#,
+        )
+        .bind(chat_id)
+        .bind(keep_id)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_stale_for_phone_hash(
+        &self,
+        phone_hash: &str,
+        inactive_since: DateTime<Utc>,
+    ) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM telegram_bot_users
+            WHERE phone_hash = $1
+              AND updated_at < $2
+              AND id != (
+                  SELECT id FROM telegram_bot_users
+                  WHERE phone_hash = $1
+                  ORDER BY updated_at DESC
+                  LIMIT 1
+              )
+            "#,
+        )
+        .bind(phone_hash)
+        .bind(inactive_since)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -1,9 +1,12 @@
-use crate::db::DbPool;
-use crate::error::AppResult;
+use crate::db::{DbPool, DbTransaction};
+use crate::error::{AppError, AppResult};
+use crate::models::user::User;
 use crate::models::user_plate::UserPlate;
+use crate::utils::normalize_plate;
 use uuid::Uuid;
 
-/// Трейт для работы с автомобилями пользователя (DIP)
+/// Трейт для работы с автомобилями пользователя (DIP).
+/// Методы, принимающие номера, сами нормализуют их через `normalize_plate`
 #[async_trait::async_trait]
 pub trait UserPlateRepository: Send + Sync {
     async fn create(
@@ -13,11 +16,57 @@ pub trait UserPlateRepository: Send + Sync {
         is_primary: bool,
         departure_time: Option<chrono::NaiveTime>,
     ) -> AppResult<UserPlate>;
+    /// То же, что `create`, но в рамках переданной транзакции
+    async fn create_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+        plate: &str,
+        is_primary: bool,
+        departure_time: Option<chrono::NaiveTime>,
+    ) -> AppResult<UserPlate>;
     async fn find_by_user_id(&self, user_id: Uuid) -> AppResult<Vec<UserPlate>>;
+    /// То же, что `find_by_user_id`, но в рамках переданной транзакции
+    async fn find_by_user_id_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+    ) -> AppResult<Vec<UserPlate>>;
+    /// Номера пользователя в том же порядке, что `find_by_user_id`, начиная с `offset`
+    /// (`limit` None - все). Второй элемент - общее количество номеров
+    async fn find_page_by_user_id(
+        &self,
+        user_id: Uuid,
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<UserPlate>, i64)>;
     async fn find_primary_by_user_id(&self, user_id: Uuid) -> AppResult<Option<UserPlate>>;
+    /// То же, что `find_primary_by_user_id`, но в рамках переданной транзакции. Как и смена
+    /// основного номера, блокирует пользователя, а найденная строка блокируется (FOR UPDATE):
+    /// параллельные изменения номеров пользователя ждут завершения транзакции
+    async fn find_primary_by_user_id_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+    ) -> AppResult<Option<UserPlate>>;
     async fn find_by_plate(&self, plate: &str) -> AppResult<Vec<UserPlate>>;
-    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<()>;
+    /// Записи для нескольких номеров одним запросом (в порядке добавления)
+    async fn find_by_plates(&self, plates: &[String]) -> AppResult<Vec<UserPlate>>;
+    /// Владельцы номера, которых можно уведомлять: без заблокированных администратором аккаунтов
+    async fn find_active_owner_ids(&self, plate: &str) -> AppResult<Vec<Uuid>>;
+    /// Находит других владельцев номера (совладельцев), исключая указанного пользователя
+    async fn find_co_owners(&self, plate: &str, exclude_user_id: Uuid) -> AppResult<Vec<User>>;
+    /// Удаляет номер пользователя. Если удалённый номер был основным, основным становится
+    /// самый свежий из оставшихся (в той же транзакции) - он и возвращается
+    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<UserPlate>>;
     async fn set_primary(&self, id: Uuid, user_id: Uuid) -> AppResult<()>;
+    /// То же, что `set_primary`, но в рамках переданной транзакции
+    async fn set_primary_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()>;
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<UserPlate>>;
     async fn update_departure_time(
         &self,
@@ -25,6 +74,69 @@ pub trait UserPlateRepository: Send + Sync {
         user_id: Uuid,
         time: Option<chrono::NaiveTime>,
     ) -> AppResult<UserPlate>;
+    /// Передаёт номер новому владельцу в рамках переданной транзакции: номер удаляется у всех
+    /// остальных владельцев (лишившимся основного номера назначается новый основной)
+    /// и добавляется новому владельцу
+    async fn transfer_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        plate: &str,
+        to_user_id: Uuid,
+    ) -> AppResult<PlateTransfer>;
+    /// Массово исправляет рассинхронизацию users.plate и user_plates (в одной транзакции)
+    async fn reconcile_primary_plates(&self) -> AppResult<PlateReconcileReport>;
+}
+
+/// Результат передачи номера новому владельцу
+#[derive(Debug, Clone)]
+pub struct PlateTransfer {
+    /// Номер нового владельца
+    pub plate: UserPlate,
+    /// Пользователи, у которых номер был удалён
+    pub previous_owner_ids: Vec<Uuid>,
+}
+
+/// Сколько строк исправлено при сверке users.plate и user_plates
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlateReconcileReport {
+    /// Номера, созданные в user_plates из users.plate
+    pub plates_created: u64,
+    /// Номера, назначенные основными пользователям без основного номера
+    pub primaries_promoted: u64,
+    /// Пользователи, у которых users.plate обновлён по основному номеру
+    pub users_synced: u64,
+}
+
+impl PlateReconcileReport {
+    pub fn total(&self) -> u64 {
+        self.plates_created + self.primaries_promoted + self.users_synced
+    }
+}
+
+/// Сериализует смену основного автомобиля пользователя: блокирует строку пользователя до конца
+/// транзакции. Без этого два параллельных запроса оба снимут старый флаг и оба попытаются
+/// поставить свой, и второй упадёт на уникальном индексе `idx_user_plates_primary`
+async fn lock_primary_plate(tx: &mut DbTransaction, user_id: Uuid) -> AppResult<()> {
+    // NO KEY UPDATE не мешает вставкам со ссылкой на пользователя (блокировки, уведомления)
+    sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Нарушение `idx_user_plates_primary`, если оно всё же случилось (например, запись
+/// в обход репозитория), отдаём клиенту как конфликт, который можно повторить, а не 500
+fn map_primary_conflict(error: sqlx::Error) -> AppError {
+    match &error {
+        sqlx::Error::Database(db_error)
+            if db_error.constraint() == Some("idx_user_plates_primary") =>
+        {
+            tracing::warn!("Concurrent primary plate change rejected: {}", db_error);
+            AppError::Conflict("Primary plate was changed concurrently, please retry".to_string())
+        }
+        _ => AppError::Database(error),
+    }
 }
 
 /// Реализация репозитория автомобилей пользователя
@@ -37,6 +149,46 @@ impl PostgresUserPlateRepository {
     pub fn new(db: DbPool) -> Self {
         Self { db }
     }
+
+    /// Назначает основным самый свежий из оставшихся номеров пользователя
+    /// и синхронизирует users.plate, чтобы удалённый номер не восстановился при чтении профиля
+    async fn promote_latest_to_primary(
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+    ) -> AppResult<Option<UserPlate>> {
+        lock_primary_plate(tx, user_id).await?;
+        let new_primary = sqlx::query_as::<_, UserPlate>(
+            r#"
+            UPDATE user_plates
+            SET is_primary = true, updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM user_plates
+                WHERE user_id = $1
+                ORDER BY created_at DESC
+                LIMIT 1
+            )
+            RETURNING id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(map_primary_conflict)?;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET plate = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(new_primary.as_ref().map(|p| p.plate.as_str()))
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(new_primary)
+    }
 }
 
 #[async_trait::async_trait]
@@ -47,12 +199,31 @@ impl UserPlateRepository for PostgresUserPlateRepository {
         plate: &str,
         is_primary: bool,
         departure_time: Option<chrono::NaiveTime>,
+    ) -> AppResult<UserPlate> {
+        let mut tx = self.db.begin().await?;
+        let user_plate = self
+            .create_in_tx(&mut tx, user_id, plate, is_primary, departure_time)
+            .await?;
+        tx.commit().await?;
+
+        Ok(user_plate)
+    }
+
+    async fn create_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+        plate: &str,
+        is_primary: bool,
+        departure_time: Option<chrono::NaiveTime>,
     ) -> AppResult<UserPlate> {
         let plate_id = uuid::Uuid::new_v4();
+        let plate = normalize_plate(plate);
 
         // Если это основной автомобиль, убираем флаг is_primary у других автомобилей
         // Используем более эффективный UPDATE с WHERE EXISTS
         if is_primary {
+            lock_primary_plate(tx, user_id).await?;
             sqlx::query(
                 r#"
                 UPDATE user_plates
@@ -61,7 +232,7 @@ impl UserPlateRepository for PostgresUserPlateRepository {
                 "#,
             )
             .bind(user_id)
-            .execute(&*self.db)
+            .execute(&mut **tx)
             .await?;
         }
 
@@ -81,11 +252,12 @@ impl UserPlateRepository for PostgresUserPlateRepository {
         )
         .bind(plate_id)
         .bind(user_id)
-        .bind(plate)
+        .bind(&plate)
         .bind(is_primary)
         .bind(departure_time)
-        .fetch_one(&*self.db)
-        .await?;
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(map_primary_conflict)?;
 
         Ok(user_plate)
     }
@@ -106,6 +278,56 @@ impl UserPlateRepository for PostgresUserPlateRepository {
         Ok(plates)
     }
 
+    async fn find_by_user_id_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+    ) -> AppResult<Vec<UserPlate>> {
+        let plates = sqlx::query_as::<_, UserPlate>(
+            r#"
+            SELECT id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            FROM user_plates
+            WHERE user_id = $1
+            ORDER BY is_primary DESC, created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(plates)
+    }
+
+    async fn find_page_by_user_id(
+        &self,
+        user_id: Uuid,
+        limit: Option<i64>,
+        offset: i64,
+    ) -> AppResult<(Vec<UserPlate>, i64)> {
+        let plates = sqlx::query_as::<_, UserPlate>(
+            r#"
+            SELECT id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            FROM user_plates
+            WHERE user_id = $1
+            ORDER BY is_primary DESC, created_at DESC, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let (total,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM user_plates WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(&*self.db)
+                .await?;
+
+        Ok((plates, total))
+    }
+
     async fn find_primary_by_user_id(&self, user_id: Uuid) -> AppResult<Option<UserPlate>> {
         let plate = sqlx::query_as::<_, UserPlate>(
             r#"
@@ -122,8 +344,30 @@ impl UserPlateRepository for PostgresUserPlateRepository {
         Ok(plate)
     }
 
+    async fn find_primary_by_user_id_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        user_id: Uuid,
+    ) -> AppResult<Option<UserPlate>> {
+        lock_primary_plate(tx, user_id).await?;
+        let plate = sqlx::query_as::<_, UserPlate>(
+            r#"
+            SELECT id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            FROM user_plates
+            WHERE user_id = $1 AND is_primary = true
+            LIMIT 1
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(plate)
+    }
+
     async fn find_by_plate(&self, plate: &str) -> AppResult<Vec<UserPlate>> {
-        // Используем нормализованное сравнение для использования индекса
+        // Нормализуем и в Rust, и в SQL (UPPER(TRIM()) также нужен для использования индекса)
         let plates = sqlx::query_as::<_, UserPlate>(
             r#"
             SELECT id, user_id, plate, is_primary, departure_time, created_at, updated_at
@@ -131,35 +375,123 @@ impl UserPlateRepository for PostgresUserPlateRepository {
             WHERE UPPER(TRIM(plate)) = UPPER(TRIM($1))
             "#,
         )
-        .bind(plate)
+        .bind(normalize_plate(plate))
         .fetch_all(&*self.db)
         .await?;
 
         Ok(plates)
     }
 
-    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<()> {
-        let result = sqlx::query(
+    async fn find_by_plates(&self, plates: &[String]) -> AppResult<Vec<UserPlate>> {
+        if plates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let normalized: Vec<String> = plates.iter().map(|plate| normalize_plate(plate)).collect();
+        let user_plates = sqlx::query_as::<_, UserPlate>(
+            r#"
+            SELECT id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            FROM user_plates
+            WHERE UPPER(TRIM(plate)) = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(&normalized)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(user_plates)
+    }
+
+    async fn find_active_owner_ids(&self, plate: &str) -> AppResult<Vec<Uuid>> {
+        let owner_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT up.user_id
+            FROM user_plates up
+            JOIN users u ON u.id = up.user_id
+            WHERE UPPER(TRIM(up.plate)) = UPPER(TRIM($1))
+              AND (u.suspended_at IS NULL OR u.suspended_until <= NOW())
+            GROUP BY up.user_id
+            ORDER BY MIN(up.created_at)
+            "#,
+        )
+        .bind(normalize_plate(plate))
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(owner_ids)
+    }
+
+    async fn find_co_owners(&self, plate: &str, exclude_user_id: Uuid) -> AppResult<Vec<User>> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT DISTINCT
+                u.id, u.phone_encrypted, u.phone_hash, u.telegram, u.plate, u.name, u.show_contacts,
+                u.owner_type, u.owner_info, u.departure_time, u.push_token, u.announcement_push,
+                u.announcement_telegram, u.avatar_key, u.suspended_at, u.suspended_until,
+                u.suspension_reason, u.created_at, u.updated_at
+            FROM user_plates up
+            JOIN users u ON u.id = up.user_id
+            WHERE UPPER(TRIM(up.plate)) = UPPER(TRIM($1)) AND up.user_id != $2
+            ORDER BY u.created_at
+            "#,
+        )
+        .bind(normalize_plate(plate))
+        .bind(exclude_user_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<Option<UserPlate>> {
+        let mut tx = self.db.begin().await?;
+
+        let removed: Option<(bool,)> = sqlx::query_as(
             r#"
             DELETE FROM user_plates
             WHERE id = $1 AND user_id = $2
+            RETURNING is_primary
             "#,
         )
         .bind(id)
         .bind(user_id)
-        .execute(&*self.db)
+        .fetch_optional(&mut *tx)
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(crate::error::AppError::NotFound(
-                "User plate not found or you don't have permission to delete it".to_string(),
-            ));
-        }
+        let was_primary = match removed {
+            Some((was_primary,)) => was_primary,
+            None => {
+                return Err(crate::error::AppError::NotFound(
+                    "User plate not found or you don't have permission to delete it".to_string(),
+                ));
+            }
+        };
 
-        Ok(())
+        let new_primary = if was_primary {
+            Self::promote_latest_to_primary(&mut tx, user_id).await?
+        } else {
+            None
+        };
+
+        tx.commit().await?;
+
+        Ok(new_primary)
     }
 
     async fn set_primary(&self, id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        self.set_primary_in_tx(&mut tx, id, user_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_primary_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<()> {
         // Сначала проверяем, что автомобиль принадлежит пользователю
         let plate = sqlx::query_as::<_, UserPlate>(
             r#"
@@ -170,7 +502,7 @@ impl UserPlateRepository for PostgresUserPlateRepository {
         )
         .bind(id)
         .bind(user_id)
-        .fetch_optional(&*self.db)
+        .fetch_optional(&mut **tx)
         .await?;
 
         if plate.is_none() {
@@ -179,6 +511,8 @@ impl UserPlateRepository for PostgresUserPlateRepository {
             ));
         }
 
+        lock_primary_plate(tx, user_id).await?;
+
         // Убираем флаг is_primary у всех автомобилей пользователя
         sqlx::query(
             r#"
@@ -188,7 +522,7 @@ impl UserPlateRepository for PostgresUserPlateRepository {
             "#,
         )
         .bind(user_id)
-        .execute(&*self.db)
+        .execute(&mut **tx)
         .await?;
 
         // Устанавливаем этот автомобиль как основной
@@ -200,8 +534,9 @@ impl UserPlateRepository for PostgresUserPlateRepository {
             "#,
         )
         .bind(id)
-        .execute(&*self.db)
-        .await?;
+        .execute(&mut **tx)
+        .await
+        .map_err(map_primary_conflict)?;
 
         Ok(())
     }
@@ -243,4 +578,129 @@ impl UserPlateRepository for PostgresUserPlateRepository {
 
         plate.ok_or_else(|| crate::error::AppError::NotFound("User plate not found".to_string()))
     }
+
+    async fn transfer_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        plate: &str,
+        to_user_id: Uuid,
+    ) -> AppResult<PlateTransfer> {
+        let plate = normalize_plate(plate);
+
+        // Удаляем номер у всех предыдущих владельцев
+        let removed: Vec<(Uuid, bool)> = sqlx::query_as(
+            r#"
+            DELETE FROM user_plates
+            WHERE UPPER(TRIM(plate)) = UPPER(TRIM($1)) AND user_id <> $2
+            RETURNING user_id, is_primary
+            "#,
+        )
+        .bind(&plate)
+        .bind(to_user_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // Если удалённый номер был основным - назначаем основным самый свежий из оставшихся
+        for (previous_owner_id, was_primary) in &removed {
+            if *was_primary {
+                Self::promote_latest_to_primary(tx, *previous_owner_id).await?;
+            }
+        }
+
+        // Добавляем номер новому владельцу (основным, если у него ещё нет основного)
+        lock_primary_plate(tx, to_user_id).await?;
+        let user_plate = sqlx::query_as::<_, UserPlate>(
+            r#"
+            INSERT INTO user_plates (id, user_id, plate, is_primary, created_at, updated_at)
+            VALUES (
+                $1, $2, $3,
+                NOT EXISTS (SELECT 1 FROM user_plates WHERE user_id = $2 AND is_primary = true),
+                NOW(), NOW()
+            )
+            ON CONFLICT (user_id, plate)
+            DO UPDATE SET updated_at = NOW()
+            RETURNING id, user_id, plate, is_primary, departure_time, created_at, updated_at
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(to_user_id)
+        .bind(&plate)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(map_primary_conflict)?;
+
+        let mut previous_owner_ids: Vec<Uuid> = removed.into_iter().map(|(id, _)| id).collect();
+        previous_owner_ids.sort();
+        previous_owner_ids.dedup();
+
+        Ok(PlateTransfer {
+            plate: user_plate,
+            previous_owner_ids,
+        })
+    }
+
+    async fn reconcile_primary_plates(&self) -> AppResult<PlateReconcileReport> {
+        let mut tx = self.db.begin().await?;
+
+        // 1. Номер есть только в users.plate - создаём основной номер в user_plates
+        let plates_created = sqlx::query(
+            r#"
+            INSERT INTO user_plates (id, user_id, plate, is_primary, created_at, updated_at)
+            SELECT gen_random_uuid(), u.id, n.plate, true, NOW(), NOW()
+            FROM users u
+            CROSS JOIN LATERAL (
+                SELECT UPPER(REPLACE(REPLACE(TRIM(u.plate), ' ', ''), '-', '')) AS plate
+            ) n
+            WHERE u.plate IS NOT NULL
+              AND LENGTH(n.plate) BETWEEN 8 AND 15
+              AND NOT EXISTS (SELECT 1 FROM user_plates up WHERE up.user_id = u.id)
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // 2. Номера есть, но ни один не основной - основным становится самый свежий
+        let primaries_promoted = sqlx::query(
+            r#"
+            UPDATE user_plates
+            SET is_primary = true, updated_at = NOW()
+            WHERE id IN (
+                SELECT DISTINCT ON (p.user_id) p.id
+                FROM user_plates p
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM user_plates q
+                    WHERE q.user_id = p.user_id AND q.is_primary
+                )
+                ORDER BY p.user_id, p.created_at DESC
+            )
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // 3. users.plate отличается от основного номера
+        let users_synced = sqlx::query(
+            r#"
+            UPDATE users u
+            SET plate = up.plate
+            FROM user_plates up
+            WHERE up.user_id = u.id
+              AND up.is_primary
+              AND u.plate IS DISTINCT FROM up.plate
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(PlateReconcileReport {
+            plates_created,
+            primaries_promoted,
+            users_synced,
+        })
+    }
 }
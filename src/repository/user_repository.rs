@@ -1,6 +1,7 @@
-use crate::db::DbPool;
+use crate::db::{DbPool, DbTransaction};
 use crate::error::{AppError, AppResult};
 use crate::models::user::User;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 /// Трейт для работы с пользователями в БД (DIP - Dependency Inversion Principle)
@@ -9,9 +10,41 @@ pub trait UserRepository: Send + Sync {
     async fn find_by_phone_hash(&self, phone_hash: &str) -> AppResult<Option<User>>;
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>>;
     async fn find_by_telegram(&self, telegram: &str) -> AppResult<Option<User>>;
+    /// Создаёт пользователя. Если пользователь с таким phone_hash уже есть
+    /// (например, параллельная регистрация), возвращает существующего
     async fn create(&self, user: &CreateUserData) -> AppResult<User>;
     async fn update(&self, id: Uuid, update_data: &UpdateUserData) -> AppResult<User>;
+    /// Начинает транзакцию для атомарного изменения нескольких таблиц
+    async fn begin(&self) -> AppResult<DbTransaction>;
+    /// То же, что `update`, но в рамках переданной транзакции
+    async fn update_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        update_data: &UpdateUserData,
+    ) -> AppResult<User>;
     async fn get_plate_by_id(&self, id: Uuid) -> AppResult<Option<String>>;
+    /// Количество всех пользователей
+    async fn count_all(&self) -> AppResult<i64>;
+    /// Постраничный обход всех пользователей по id (keyset pagination)
+    async fn find_page_after(&self, after_id: Option<Uuid>, limit: i64) -> AppResult<Vec<User>>;
+    /// Загружает пользователей одним запросом (несуществующие id пропускаются, порядок не гарантирован)
+    async fn find_by_ids(&self, ids: &[Uuid]) -> AppResult<Vec<User>>;
+    /// Сохраняет ключ аватара (None - удалить аватар)
+    async fn set_avatar(&self, id: Uuid, avatar_key: Option<&str>) -> AppResult<()>;
+    /// Блокирует аккаунт до `until` (None - бессрочно)
+    async fn suspend(
+        &self,
+        id: Uuid,
+        until: Option<DateTime<Utc>>,
+        reason: Option<&str>,
+    ) -> AppResult<()>;
+    /// Снимает блокировку аккаунта
+    async fn unsuspend(&self, id: Uuid) -> AppResult<()>;
+    /// Удаляет пользователя одной транзакцией; связанные строки удаляются каскадно
+    /// (ON DELETE CASCADE), история блокировок и регистрации в Telegram боте по номеру - явно.
+    /// None, если пользователя нет
+    async fn delete_with_related(&self, id: Uuid) -> AppResult<Option<DeletedUserData>>;
 }
 
 pub struct CreateUserData {
@@ -21,6 +54,19 @@ pub struct CreateUserData {
     pub plate: String,
 }
 
+/// Что удалено вместе с пользователем
+pub struct DeletedUserData {
+    pub plates: i64,
+    pub blocks: i64,
+    pub block_history: i64,
+    pub notifications: i64,
+    pub push_tokens: i64,
+    pub sessions: i64,
+    pub telegram_registrations: i64,
+    /// Ключи файлов в хранилище (аватар, фото блокировок и их превью): удаляются после коммита
+    pub blob_keys: Vec<String>,
+}
+
 #[derive(Default)]
 pub struct UpdateUserData {
     pub name: Option<String>,
@@ -33,6 +79,10 @@ pub struct UpdateUserData {
     pub owner_info: Option<serde_json::Value>,
     pub departure_time: Option<chrono::NaiveTime>,
     pub push_token: Option<String>,
+    pub announcement_push: Option<bool>,
+    pub announcement_telegram: Option<bool>,
+    /// `None` - не менять, `Some(None)` - очистить
+    pub utc_offset_minutes: Option<Option<i32>>,
 }
 
 /// Реализация репозитория пользователей
@@ -54,8 +104,8 @@ impl UserRepository for PostgresUserRepository {
             r#"
             SELECT 
                 id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, 
-                owner_type, owner_info, departure_time, push_token, created_at, updated_at
-            FROM users
+                owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users_with_primary_plate
             WHERE phone_hash = $1
             LIMIT 1
             "#,
@@ -70,8 +120,8 @@ impl UserRepository for PostgresUserRepository {
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, created_at, updated_at
-            FROM users
+            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users_with_primary_plate
             WHERE id = $1
             "#
         )
@@ -89,8 +139,8 @@ impl UserRepository for PostgresUserRepository {
     async fn find_by_telegram(&self, telegram: &str) -> AppResult<Option<User>> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, created_at, updated_at
-            FROM users
+            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users_with_primary_plate
             WHERE telegram = $1
             LIMIT 1
             "#
@@ -110,13 +160,15 @@ impl UserRepository for PostgresUserRepository {
             Some(data.plate.clone())
         };
 
-        // Используем RETURNING для избежания дополнительного SELECT
-        let user = sqlx::query_as::<_, User>(
+        // Используем RETURNING для избежания дополнительного SELECT.
+        // Конфликт по уникальному индексу phone_hash - пользователь уже создан параллельным запросом
+        let created = sqlx::query_as::<_, User>(
             r#"
             INSERT INTO users (id, phone_encrypted, phone_hash, plate, show_contacts, owner_type, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, 'renter', NOW(), NOW())
+            ON CONFLICT (phone_hash) WHERE phone_hash IS NOT NULL DO NOTHING
             RETURNING id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, 
-                      owner_type, owner_info, departure_time, push_token, created_at, updated_at
+                      owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
             "#
         )
         .bind(data.id)
@@ -124,18 +176,53 @@ impl UserRepository for PostgresUserRepository {
         .bind(&data.phone_hash)
         .bind(plate_value.as_ref())
         .bind(true) // Контакты открыты по умолчанию
-        .fetch_one(&*self.db)
+        .fetch_optional(&*self.db)
         .await?;
 
-        Ok(user)
+        match created {
+            Some(user) => Ok(user),
+            None => {
+                tracing::info!("User with this phone_hash already exists, returning it");
+                self.find_by_phone_hash(&data.phone_hash)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::Internal("User disappeared after phone_hash conflict".to_string())
+                    })
+            }
+        }
     }
 
     async fn update(&self, id: Uuid, update_data: &UpdateUserData) -> AppResult<User> {
-        // Сначала получаем текущего пользователя
-        let current_user = self
-            .find_by_id(id)
-            .await?
-            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let mut tx = self.begin().await?;
+        let updated_user = self.update_in_tx(&mut tx, id, update_data).await?;
+        tx.commit().await?;
+
+        Ok(updated_user)
+    }
+
+    async fn begin(&self) -> AppResult<DbTransaction> {
+        Ok(self.db.begin().await?)
+    }
+
+    async fn update_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        id: Uuid,
+        update_data: &UpdateUserData,
+    ) -> AppResult<User> {
+        // Сначала получаем текущего пользователя (блокируем строку до конца транзакции)
+        let current_user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
         // Объединяем данные: если значение передано, используем его, иначе оставляем текущее
         // Для полей, которые могут быть явно null (пустые строки), обновляем их
@@ -206,6 +293,9 @@ impl UserRepository for PostgresUserRepository {
 
         // ВСЕГДА обновляем все поля, включая owner_info и departure_time
         let departure_time = update_data.departure_time.or(current_user.departure_time);
+        let utc_offset_minutes = update_data
+            .utc_offset_minutes
+            .unwrap_or(current_user.utc_offset_minutes);
 
         // Используем RETURNING для избежания дополнительного SELECT
         let phone_hash = update_data
@@ -226,10 +316,13 @@ impl UserRepository for PostgresUserRepository {
                 owner_info = $7,
                 departure_time = $8,
                 push_token = COALESCE($9, push_token),
+                announcement_push = COALESCE($12, announcement_push),
+                announcement_telegram = COALESCE($13, announcement_telegram),
+                utc_offset_minutes = $14,
                 updated_at = NOW()
             WHERE id = $11
             RETURNING id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, 
-                      owner_type, owner_info, departure_time, push_token, created_at, updated_at
+                      owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
             "#,
         )
         .bind(name.as_ref())
@@ -243,7 +336,10 @@ impl UserRepository for PostgresUserRepository {
         .bind(update_data.push_token.as_ref())
         .bind(phone_hash.as_ref())
         .bind(id)
-        .fetch_optional(&*self.db)
+        .bind(update_data.announcement_push)
+        .bind(update_data.announcement_telegram)
+        .bind(utc_offset_minutes)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(|e| {
             tracing::error!("Database update error: {:?}", e);
@@ -256,7 +352,7 @@ impl UserRepository for PostgresUserRepository {
     async fn get_plate_by_id(&self, id: Uuid) -> AppResult<Option<String>> {
         let result: Option<(String,)> = sqlx::query_as(
             r#"
-            SELECT plate FROM users WHERE id = $1
+            SELECT plate FROM users_with_primary_plate WHERE id = $1
             "#,
         )
         .bind(id)
@@ -265,4 +361,196 @@ impl UserRepository for PostgresUserRepository {
 
         Ok(result.map(|r| r.0))
     }
+
+    async fn count_all(&self) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&*self.db)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn find_page_after(&self, after_id: Option<Uuid>, limit: i64) -> AppResult<Vec<User>> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users_with_primary_plate
+            WHERE $1::uuid IS NULL OR id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> AppResult<Vec<User>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, phone_encrypted, phone_hash, telegram, plate, name, show_contacts, owner_type, owner_info, departure_time, push_token, announcement_push, announcement_telegram, utc_offset_minutes, avatar_key, suspended_at, suspended_until, suspension_reason, created_at, updated_at
+            FROM users_with_primary_plate
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn set_avatar(&self, id: Uuid, avatar_key: Option<&str>) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET avatar_key = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(avatar_key)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn suspend(
+        &self,
+        id: Uuid,
+        until: Option<DateTime<Utc>>,
+        reason: Option<&str>,
+    ) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET suspended_at = NOW(), suspended_until = $2, suspension_reason = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(until)
+        .bind(reason)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn unsuspend(&self, id: Uuid) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET suspended_at = NULL, suspended_until = NULL, suspension_reason = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_with_related(&self, id: Uuid) -> AppResult<Option<DeletedUserData>> {
+        let mut tx = self.db.begin().await?;
+
+        // Блокируем строку, чтобы параллельные изменения не добавили данных после подсчёта
+        let Some((avatar_key, phone_hash)) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            r#"
+            SELECT avatar_key, phone_hash FROM users WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let (plates, blocks, notifications, push_tokens, sessions, mut blob_keys) =
+            sqlx::query_as::<_, (i64, i64, i64, i64, i64, Vec<String>)>(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM user_plates WHERE user_id = $1),
+                    (SELECT COUNT(*) FROM blocks WHERE blocker_id = $1),
+                    (SELECT COUNT(*) FROM notifications WHERE user_id = $1),
+                    (SELECT COUNT(*) FROM push_tokens WHERE user_id = $1),
+                    (SELECT COUNT(*) FROM user_sessions WHERE user_id = $1),
+                    ARRAY(
+                        SELECT key FROM blocks, LATERAL (VALUES (photo_key), (photo_thumb_key)) AS files(key)
+                        WHERE blocker_id = $1 AND key IS NOT NULL
+                    )
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        blob_keys.extend(avatar_key);
+
+        // У block_history нет внешнего ключа на users: каскад её не затрагивает
+        let block_history = sqlx::query(
+            r#"
+            DELETE FROM block_history WHERE blocker_id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        // Регистрации в Telegram боте по номеру могут быть не привязаны к ID, каскад их не удалит
+        let telegram_registrations = sqlx::query(
+            r#"
+            DELETE FROM telegram_bot_users WHERE user_id = $1 OR phone_hash = $2
+            "#,
+        )
+        .bind(id)
+        .bind(phone_hash)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        sqlx::query(
+            r#"
+            DELETE FROM users WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(DeletedUserData {
+            plates,
+            blocks,
+            block_history,
+            notifications,
+            push_tokens,
+            sessions,
+            telegram_registrations,
+            blob_keys,
+        }))
+    }
 }
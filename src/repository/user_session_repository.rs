@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::AppResult;
+use crate::models::session::UserSession;
+
+/// Трейт для сессий устройств пользователей
+#[async_trait::async_trait]
+pub trait UserSessionRepository: Send + Sync {
+    /// Создаёт сессию при входе
+    async fn create(&self, user_id: Uuid, device_id: Option<&str>) -> AppResult<UserSession>;
+    /// Отмечает активность сессии (`last_seen` обновляется не чаще раза в минуту).
+    /// Возвращает false, если сессии нет (она завершена)
+    async fn touch(&self, id: Uuid, user_id: Uuid) -> AppResult<bool>;
+    /// Сессии пользователя, активные после `active_since`, от последней активной к первой
+    async fn list_active(
+        &self,
+        user_id: Uuid,
+        active_since: DateTime<Utc>,
+    ) -> AppResult<Vec<UserSession>>;
+    /// Завершает сессию пользователя. Возвращает false, если такой сессии у него нет
+    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool>;
+    /// Удаляет сессии без активности с `inactive_since`. Возвращает количество удалённых
+    async fn purge_inactive(&self, inactive_since: DateTime<Utc>) -> AppResult<u64>;
+}
+
+/// Реализация репозитория сессий
+#[derive(Clone)]
+pub struct PostgresUserSessionRepository {
+    db: DbPool,
+}
+
+impl PostgresUserSessionRepository {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait::async_trait]
+impl UserSessionRepository for PostgresUserSessionRepository {
+    async fn create(&self, user_id: Uuid, device_id: Option<&str>) -> AppResult<UserSession> {
+        let session = sqlx::query_as::<_, UserSession>(
+            r#"
+            INSERT INTO user_sessions (id, user_id, device_id, created_at, last_seen)
+            VALUES ($1, $2, $3, NOW(), NOW())
+            RETURNING id, user_id, device_id, created_at, last_seen
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(device_id)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn touch(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        // Запись только при устаревшем last_seen, чтобы не писать в БД на каждый запрос
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            WITH touched AS (
+                UPDATE user_sessions SET last_seen = NOW()
+                WHERE id = $1 AND user_id = $2 AND last_seen < NOW() - INTERVAL '1 minute'
+                RETURNING id
+            )
+            SELECT EXISTS(SELECT 1 FROM user_sessions WHERE id = $1 AND user_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(exists)
+    }
+
+    async fn list_active(
+        &self,
+        user_id: Uuid,
+        active_since: DateTime<Utc>,
+    ) -> AppResult<Vec<UserSession>> {
+        let sessions = sqlx::query_as::<_, UserSession>(
+            r#"
+            SELECT id, user_id, device_id, created_at, last_seen
+            FROM user_sessions
+            WHERE user_id = $1 AND last_seen > $2
+            ORDER BY last_seen DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(active_since)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    async fn delete(&self, id: Uuid, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_sessions WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn purge_inactive(&self, inactive_since: DateTime<Utc>) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_sessions WHERE last_seen <= $1
+            "#,
+        )
+        .bind(inactive_since)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
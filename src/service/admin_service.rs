@@ -0,0 +1,461 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::admin::{
+    AnnounceChannel, AnnounceJob, AnnounceJobStatus, AnnounceRequest, AnnounceResponse,
+    OverviewStats, SuspendUserRequest, UserSuspensionResponse,
+};
+use crate::models::notification::NotificationType;
+use crate::models::user::User;
+use crate::repository::{
+    AuditLogRepository, BlockRepository, CreateAuditLogData, NotificationOutboxRepository,
+    NotificationRepository, UserRepository,
+};
+use crate::service::{push_service::PushService, telegram_service::TelegramService};
+use crate::utils::time::DEFAULT_UTC_OFFSET_MINUTES;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, Semaphore};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+/// Сколько хранится прогресс завершённой рассылки объявления
+const ANNOUNCE_JOB_RETENTION: chrono::Duration = chrono::Duration::hours(24);
+
+/// Максимальная длина причины блокировки аккаунта
+const SUSPENSION_REASON_MAX_LEN: usize = 200;
+
+/// Сколько хранится посчитанная сводная статистика
+const OVERVIEW_STATS_TTL: Duration = Duration::from_secs(30);
+
+/// За сколько дней считается статистика доставки уведомлений
+const DELIVERY_STATS_DAYS: i64 = 30;
+
+/// Сервис административных операций (SRP)
+#[derive(Clone)]
+pub struct AdminService {
+    push_service: PushService,
+    telegram_service: TelegramService,
+    overview_cache: Arc<RwLock<Option<(Instant, OverviewStats)>>>,
+    announce_jobs: Arc<RwLock<HashMap<Uuid, AnnounceJob>>>,
+    announce_batch_size: i64,
+    announce_semaphore: Arc<Semaphore>,
+}
+
+/// Итог рассылки объявления одной пачке пользователей
+#[derive(Default)]
+struct AnnounceDelivery {
+    push_sent: u64,
+    telegram_sent: u64,
+    failed: u64,
+}
+
+impl AdminService {
+    pub fn new(
+        push_service: PushService,
+        telegram_service: TelegramService,
+        config: &Config,
+    ) -> Self {
+        Self {
+            push_service,
+            telegram_service,
+            overview_cache: Arc::new(RwLock::new(None)),
+            announce_jobs: Arc::new(RwLock::new(HashMap::new())),
+            announce_batch_size: config.announce_batch_size,
+            announce_semaphore: Arc::new(Semaphore::new(config.announce_max_concurrency)),
+        }
+    }
+
+    /// Сводная статистика парковки (кэшируется на `OVERVIEW_STATS_TTL`).
+    /// Часы суток считаются по московскому времени
+    pub async fn overview_stats<BR: BlockRepository, OR: NotificationOutboxRepository>(
+        &self,
+        block_repository: &BR,
+        notification_outbox_repository: &OR,
+    ) -> AppResult<OverviewStats> {
+        if let Some((computed_at, stats)) = self.overview_cache.read().await.as_ref() {
+            if computed_at.elapsed() < OVERVIEW_STATS_TTL {
+                return Ok(stats.clone());
+            }
+        }
+
+        let mut stats = block_repository
+            .overview_stats(DEFAULT_UTC_OFFSET_MINUTES)
+            .await?;
+        stats.delivery = notification_outbox_repository
+            .delivery_stats(chrono::Utc::now() - chrono::Duration::days(DELIVERY_STATS_DAYS))
+            .await?;
+        *self.overview_cache.write().await = Some((Instant::now(), stats.clone()));
+
+        Ok(stats)
+    }
+
+    /// Блокирует аккаунт пользователя: он не сможет войти и пользоваться API,
+    /// а его номера не получают уведомлений о блокировках
+    pub async fn suspend_user<UR: UserRepository, AR: AuditLogRepository>(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        request: SuspendUserRequest,
+        user_repository: &UR,
+        audit_log_repository: &AR,
+    ) -> AppResult<UserSuspensionResponse> {
+        if user_id == admin_id {
+            return Err(AppError::Validation(
+                "Нельзя заблокировать свой аккаунт".to_string(),
+            ));
+        }
+        if request
+            .until
+            .is_some_and(|until| until <= chrono::Utc::now())
+        {
+            return Err(AppError::Validation(
+                "Срок блокировки должен быть в будущем".to_string(),
+            ));
+        }
+        let reason = request
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty());
+        if reason.is_some_and(|reason| reason.chars().count() > SUSPENSION_REASON_MAX_LEN) {
+            return Err(AppError::Validation(format!(
+                "Причина не должна превышать {} символов",
+                SUSPENSION_REASON_MAX_LEN
+            )));
+        }
+
+        user_repository
+            .suspend(user_id, request.until, reason)
+            .await?;
+        tracing::info!(
+            "Admin {} suspended user {} until {:?}",
+            admin_id,
+            user_id,
+            request.until
+        );
+        self.record_audit(
+            audit_log_repository,
+            admin_id,
+            "user_suspended",
+            user_id,
+            serde_json::json!({ "until": request.until, "reason": reason }),
+        )
+        .await;
+
+        Ok(UserSuspensionResponse {
+            user_id,
+            suspended: true,
+            suspended_until: request.until,
+            reason: reason.map(str::to_string),
+        })
+    }
+
+    /// Снимает блокировку аккаунта
+    pub async fn unsuspend_user<UR: UserRepository, AR: AuditLogRepository>(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        user_repository: &UR,
+        audit_log_repository: &AR,
+    ) -> AppResult<UserSuspensionResponse> {
+        user_repository.unsuspend(user_id).await?;
+        tracing::info!("Admin {} unsuspended user {}", admin_id, user_id);
+        self.record_audit(
+            audit_log_repository,
+            admin_id,
+            "user_unsuspended",
+            user_id,
+            serde_json::json!({}),
+        )
+        .await;
+
+        Ok(UserSuspensionResponse {
+            user_id,
+            suspended: false,
+            suspended_until: None,
+            reason: None,
+        })
+    }
+
+    /// Записывает действие в журнал; действие уже выполнено, поэтому ошибку только логируем
+    async fn record_audit<AR: AuditLogRepository>(
+        &self,
+        audit_log_repository: &AR,
+        admin_id: Uuid,
+        action: &str,
+        user_id: Uuid,
+        details: serde_json::Value,
+    ) {
+        if let Err(e) = audit_log_repository
+            .record(&CreateAuditLogData {
+                actor_id: admin_id,
+                action: action.to_string(),
+                subject_user_id: Some(user_id),
+                entity_id: None,
+                details: Some(details),
+            })
+            .await
+        {
+            tracing::error!("Failed to write audit log ({}): {:?}", action, e);
+        }
+    }
+
+    /// Проверяет объявление и запускает фоновую задачу рассылки: уведомления в приложении
+    /// создаются пачками по `announce_batch_size` пользователей (каждая пачка - отдельный запрос),
+    /// push/telegram отправляются по той же пачке. Прогресс - `announce_job`
+    pub async fn announce<UR, NR>(
+        &self,
+        request: AnnounceRequest,
+        user_repository: &UR,
+        notification_repository: &NR,
+    ) -> AppResult<AnnounceResponse>
+    where
+        UR: UserRepository + Clone + 'static,
+        NR: NotificationRepository + Clone + 'static,
+    {
+        let title = request.title.trim().to_string();
+        let message = request.message.trim().to_string();
+
+        if title.is_empty() || title.chars().count() > 100 {
+            return Err(AppError::Validation(
+                "Заголовок должен быть от 1 до 100 символов".to_string(),
+            ));
+        }
+        if message.is_empty() || message.chars().count() > 1000 {
+            return Err(AppError::Validation(
+                "Текст объявления должен быть от 1 до 1000 символов".to_string(),
+            ));
+        }
+
+        let send_push = request.channels.contains(&AnnounceChannel::Push);
+        let send_telegram = request.channels.contains(&AnnounceChannel::Telegram);
+        let total_users = user_repository.count_all().await?.max(0) as u64;
+
+        let job = AnnounceJob {
+            job_id: Uuid::new_v4(),
+            status: AnnounceJobStatus::Running,
+            total_users,
+            processed_users: 0,
+            notifications_created: 0,
+            push_sent: 0,
+            telegram_sent: 0,
+            delivery_failed: 0,
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        let job_id = job.job_id;
+        {
+            let mut jobs = self.announce_jobs.write().await;
+            // Завершённые задачи храним ограниченное время, чтобы карта не росла бесконечно
+            let retain_after = Utc::now() - ANNOUNCE_JOB_RETENTION;
+            jobs.retain(|_, job| job.finished_at.is_none_or(|at| at > retain_after));
+            jobs.insert(job_id, job);
+        }
+
+        tracing::info!(
+            "Announcement '{}' started as job {} for {} users, channels: {:?}",
+            title,
+            job_id,
+            total_users,
+            request.channels
+        );
+
+        let service = self.clone();
+        let user_repository = user_repository.clone();
+        let notification_repository = notification_repository.clone();
+        tokio::spawn(async move {
+            let result = service
+                .run_announcement(
+                    job_id,
+                    &title,
+                    &message,
+                    send_push,
+                    send_telegram,
+                    &user_repository,
+                    &notification_repository,
+                )
+                .await;
+            if let Err(e) = &result {
+                tracing::error!("Announcement job {} failed: {}", job_id, e);
+            }
+            service
+                .update_announce_job(job_id, |job| {
+                    job.finished_at = Some(Utc::now());
+                    match result {
+                        Ok(()) => job.status = AnnounceJobStatus::Completed,
+                        Err(e) => {
+                            job.status = AnnounceJobStatus::Failed;
+                            job.error = Some(e.to_string());
+                        }
+                    }
+                })
+                .await;
+        });
+
+        Ok(AnnounceResponse {
+            job_id,
+            total_users,
+            delivery_started: send_push || send_telegram,
+        })
+    }
+
+    /// Прогресс рассылки объявления. Задачи хранятся в памяти процесса,
+    /// который её запустил, и удаляются через `ANNOUNCE_JOB_RETENTION` после завершения
+    pub async fn announce_job(&self, job_id: Uuid) -> AppResult<AnnounceJob> {
+        self.announce_jobs
+            .read()
+            .await
+            .get(&job_id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound("Announcement job not found".to_string()))
+    }
+
+    async fn update_announce_job(&self, job_id: Uuid, update: impl FnOnce(&mut AnnounceJob)) {
+        if let Some(job) = self.announce_jobs.write().await.get_mut(&job_id) {
+            update(job);
+        }
+    }
+
+    /// Обходит пользователей пачками: создаёт уведомления пачки, рассылает её по выбранным
+    /// каналам (с учётом настроек пользователей) и обновляет прогресс задачи
+    #[allow(clippy::too_many_arguments)]
+    async fn run_announcement<UR: UserRepository, NR: NotificationRepository>(
+        &self,
+        job_id: Uuid,
+        title: &str,
+        message: &str,
+        send_push: bool,
+        send_telegram: bool,
+        user_repository: &UR,
+        notification_repository: &NR,
+    ) -> AppResult<()> {
+        let data = serde_json::json!({ "kind": "announcement" });
+        let telegram_text = format!("📢 {}\n\n{}", title, message);
+        let mut after_id = None;
+
+        loop {
+            let users = user_repository
+                .find_page_after(after_id, self.announce_batch_size)
+                .await?;
+            let Some(last) = users.last() else {
+                break;
+            };
+            after_id = Some(last.id);
+
+            let user_ids: Vec<Uuid> = users.iter().map(|user| user.id).collect();
+            let created = notification_repository
+                .create_for_users(
+                    &user_ids,
+                    NotificationType::System,
+                    title,
+                    message,
+                    Some(&data),
+                )
+                .await?;
+
+            let delivery = self
+                .deliver_announcement_batch(
+                    users,
+                    title,
+                    message,
+                    &telegram_text,
+                    send_push,
+                    send_telegram,
+                )
+                .await;
+
+            self.update_announce_job(job_id, |job| {
+                job.processed_users += user_ids.len() as u64;
+                job.notifications_created += created;
+                job.push_sent += delivery.push_sent;
+                job.telegram_sent += delivery.telegram_sent;
+                job.delivery_failed += delivery.failed;
+            })
+            .await;
+        }
+
+        if let Ok(job) = self.announce_job(job_id).await {
+            tracing::info!(
+                "Announcement '{}' finished: notifications={}, push={}, telegram={}, failed={}",
+                title,
+                job.notifications_created,
+                job.push_sent,
+                job.telegram_sent,
+                job.delivery_failed
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Рассылает объявление пачке пользователей по каналам, выбранным ими в профиле.
+    /// Число одновременных запросов к провайдерам ограничено общим для всех рассылок семафором
+    async fn deliver_announcement_batch(
+        &self,
+        users: Vec<User>,
+        title: &str,
+        message: &str,
+        telegram_text: &str,
+        send_push: bool,
+        send_telegram: bool,
+    ) -> AnnounceDelivery {
+        let mut delivery = AnnounceDelivery::default();
+        if !send_push && !send_telegram {
+            return delivery;
+        }
+
+        let mut tasks = JoinSet::new();
+        for user in users {
+            if send_push {
+                // Каналы, отключённые пользователем в профиле, пропускаем
+                if let Some(token) = user.announcement_push_token().map(str::to_string) {
+                    let push = self.push_service.clone();
+                    let semaphore = self.announce_semaphore.clone();
+                    let (title, body) = (title.to_string(), message.to_string());
+                    tasks.spawn(async move {
+                        let _permit = semaphore.acquire_owned().await;
+                        let data = serde_json::json!({ "kind": "announcement" });
+                        (
+                            AnnounceChannel::Push,
+                            push.send_fcm(&token, &title, &body, data).await,
+                        )
+                    });
+                }
+            }
+            if send_telegram {
+                if let Some(username) = user.announcement_telegram_username().map(str::to_string) {
+                    let telegram = self.telegram_service.clone();
+                    let semaphore = self.announce_semaphore.clone();
+                    let text = telegram_text.to_string();
+                    tasks.spawn(async move {
+                        let _permit = semaphore.acquire_owned().await;
+                        (
+                            AnnounceChannel::Telegram,
+                            telegram.send_message(&username, &text).await,
+                        )
+                    });
+                }
+            }
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok((AnnounceChannel::Push, Ok(()))) => delivery.push_sent += 1,
+                Ok((AnnounceChannel::Telegram, Ok(()))) => delivery.telegram_sent += 1,
+                Ok((channel, Err(e))) => {
+                    delivery.failed += 1;
+                    tracing::warn!("Failed to deliver announcement via {:?}: {}", channel, e);
+                }
+                Err(e) => {
+                    delivery.failed += 1;
+                    tracing::warn!("Announcement delivery task failed: {}", e);
+                }
+            }
+        }
+
+        delivery
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Обезличенное событие аналитики.
+/// Не содержит телефонов, номеров автомобилей и исходных идентификаторов пользователей
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsEvent {
+    /// Название события: "auth_verify", "block_created", "block_deleted", "notification_delivery"
+    pub name: &'static str,
+    /// HMAC идентификатора пользователя секретом ANALYTICS_SECRET (для подсчёта уникальных)
+    pub actor: Option<String>,
+    /// Агрегируемые свойства события (каналы, причины, счётчики, успех)
+    pub properties: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Приёмник событий аналитики (DIP)
+#[async_trait::async_trait]
+pub trait AnalyticsSink: Send + Sync {
+    async fn emit(&self, event: AnalyticsEvent);
+}
+
+/// Приёмник по умолчанию: аналитика отключена, события отбрасываются
+pub struct NoopAnalyticsSink;
+
+#[async_trait::async_trait]
+impl AnalyticsSink for NoopAnalyticsSink {
+    async fn emit(&self, _event: AnalyticsEvent) {}
+}
+
+/// Пишет события в лог (target "analytics"), когда внешний приёмник не настроен
+pub struct LogAnalyticsSink;
+
+#[async_trait::async_trait]
+impl AnalyticsSink for LogAnalyticsSink {
+    async fn emit(&self, event: AnalyticsEvent) {
+        match serde_json::to_string(&event) {
+            Ok(json) => tracing::info!(target: "analytics", "{}", json),
+            Err(e) => tracing::warn!("Failed to serialize analytics event: {}", e),
+        }
+    }
+}
+
+/// Отправляет события POST-запросом в JSON на внешний адрес (ANALYTICS_ENDPOINT)
+pub struct HttpAnalyticsSink {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl HttpAnalyticsSink {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AnalyticsSink for HttpAnalyticsSink {
+    async fn emit(&self, event: AnalyticsEvent) {
+        match self.client.post(&self.endpoint).json(&event).send().await {
+            Ok(response) if !response.status().is_success() => {
+                tracing::warn!("Analytics endpoint returned status {}", response.status());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to send analytics event: {}", e),
+        }
+    }
+}
+
+/// Сервис обезличенной аналитики: формирует события и отправляет их в фоне,
+/// не задерживая ответ и не влияя на основной сценарий при ошибках
+#[derive(Clone)]
+pub struct AnalyticsService {
+    sink: Arc<dyn AnalyticsSink>,
+    secret: Arc<[u8]>,
+}
+
+impl AnalyticsService {
+    /// `secret` - ключ HMAC для обезличивания идентификаторов пользователей
+    pub fn new(sink: Arc<dyn AnalyticsSink>, secret: &str) -> Self {
+        Self {
+            sink,
+            secret: Arc::from(secret.as_bytes()),
+        }
+    }
+
+    /// Выбирает приёмник по конфигурации (ANALYTICS_ENABLED, ANALYTICS_ENDPOINT, ANALYTICS_SECRET)
+    pub fn from_config(config: &Config) -> Self {
+        let sink: Arc<dyn AnalyticsSink> =
+            match (config.analytics_enabled, &config.analytics_endpoint) {
+                (false, _) => Arc::new(NoopAnalyticsSink),
+                (true, Some(endpoint)) => Arc::new(HttpAnalyticsSink::new(endpoint.clone())),
+                (true, None) => Arc::new(LogAnalyticsSink),
+            };
+        Self::new(sink, config.analytics_secret.as_deref().unwrap_or_default())
+    }
+
+    /// Обезличивает идентификатор пользователя для событий аналитики: HMAC-SHA256 с секретом.
+    /// Простой хэш UUID можно сопоставить с известными идентификаторами перебором,
+    /// без секрета HMAC - нельзя
+    pub fn anonymize(&self, user_id: Uuid) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key size");
+        mac.update(b"analytics:");
+        mac.update(user_id.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Регистрирует событие (отправка в фоне)
+    pub fn track(&self, name: &'static str, actor: Option<Uuid>, properties: serde_json::Value) {
+        let event = AnalyticsEvent {
+            name,
+            actor: actor.map(|user_id| self.anonymize(user_id)),
+            properties,
+            timestamp: Utc::now(),
+        };
+        let sink = self.sink.clone();
+        tokio::spawn(async move {
+            sink.emit(event).await;
+        });
+    }
+
+    /// Итог доставки уведомления по каналу ("push", "telegram", "call")
+    pub fn track_delivery(&self, channel: &'static str, success: bool) {
+        self.track(
+            "notification_delivery",
+            None,
+            serde_json::json!({ "channel": channel, "success": success }),
+        );
+    }
+}
@@ -1,11 +1,22 @@
-use crate::auth::jwt::create_token;
+use crate::auth::jwt::{
+    create_session_token, decode_with_accepted_secrets, token_validation, REFRESH_GRACE_MINUTES,
+};
+use crate::auth::middleware::AuthToken;
 use crate::auth::sms::SmsService;
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use crate::models::auth::{AuthStartResponse, AuthVerifyResponse, RefreshTokenResponse};
-use crate::repository::{CreateUserData, UserPlateRepository, UserRepository};
+use crate::models::auth::{
+    AuthCodeChannel, AuthStartResponse, AuthVerifyResponse, RefreshTokenResponse,
+};
+use crate::models::session::{SessionResponse, MAX_DEVICE_ID_LENGTH};
+use crate::repository::{
+    CreateUserData, RevokedTokenRepository, TelegramBotRepository, UserPlateRepository,
+    UserRepository, UserSessionRepository,
+};
+use crate::service::telegram_service::TelegramService;
+use crate::service::user_sessions::session_idle_timeout;
 use crate::service::validation_service::ValidationService;
-use crate::utils::encryption::Encryption;
+use crate::utils::encryption::{aad, Encryption};
 use reqwest::Client;
 use serde_json::json;
 use sha2::{Digest, Sha256};
@@ -66,31 +77,94 @@ impl AuthService {
         }
     }
 
-    fn phone_hash(phone: &str) -> String {
+    /// Хэш телефона для поиска пользователя (телефон хранится только зашифрованным)
+    pub fn phone_hash(phone: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(phone.as_bytes());
         format!("{:x}", hasher.finalize())
     }
 
-    /// Начинает процесс авторизации
-    pub async fn start_auth(&self, phone: &str) -> AppResult<AuthStartResponse> {
-        let normalized_phone = ValidationService::validate_phone(phone)?;
-
-        // Генерируем код
-        let code = self.sms_service.generate_code(&normalized_phone).await
-            .map_err(|e| {
-                tracing::error!("Failed to generate/send SMS code for {}: {}", normalized_phone, e);
-                AppError::Internal(format!(
-                    "Не удалось отправить SMS код. {}. Для разработки установите RETURN_SMS_CODE_IN_RESPONSE=true", e
-                ))
+    /// Отправляет код авторизации только через Telegram бота (без SMS) в чат,
+    /// привязанный к номеру телефона
+    async fn send_code_via_telegram_chat<TR: TelegramBotRepository>(
+        &self,
+        phone: &str,
+        telegram_bot_repository: &TR,
+        telegram_service: &TelegramService,
+    ) -> AppResult<String> {
+        let bot_user = telegram_bot_repository
+            .find_by_phone_hash(&Self::phone_hash(phone))
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "Номер не привязан к Telegram боту. Отправьте боту свой контакт или войдите по SMS"
+                        .to_string(),
+                )
             })?;
 
-        // Отправляем код в Telegram бот (если настроен)
-        if let Err(e) = self.send_code_to_telegram(&normalized_phone, &code).await {
-            tracing::warn!("Не удалось отправить код в Telegram бот: {}", e);
-            // Не прерываем процесс, если не удалось отправить в Telegram
+        let code = self
+            .sms_service
+            .store_code(phone, self.config.telegram_code_length)
+            .await?;
+        let message = format!("🔐 Код авторизации: {}", code);
+        match telegram_service
+            .send_to_chat(bot_user.chat_id, &message)
+            .await
+        {
+            Ok(()) => tracing::info!("Код отправлен в Telegram для {}", phone),
+            // В dev режиме код всё равно вернётся в ответе
+            Err(e) if self.config.return_sms_code_in_response => {
+                tracing::warn!("Не удалось отправить код в Telegram для {}: {}", phone, e);
+            }
+            Err(e) => {
+                tracing::error!("Не удалось отправить код в Telegram для {}: {}", phone, e);
+                return Err(AppError::Internal(
+                    "Не удалось отправить код в Telegram. Попробуйте войти по SMS".to_string(),
+                ));
+            }
         }
 
+        Ok(code)
+    }
+
+    /// Начинает процесс авторизации: отправляет код по SMS или через Telegram бота
+    pub async fn start_auth<TR: TelegramBotRepository>(
+        &self,
+        phone: &str,
+        channel: AuthCodeChannel,
+        telegram_bot_repository: &TR,
+        telegram_service: &TelegramService,
+    ) -> AppResult<AuthStartResponse> {
+        let normalized_phone = ValidationService::validate_phone(phone)?;
+
+        let code = match channel {
+            AuthCodeChannel::Sms => {
+                // Генерируем код
+                let code = self.sms_service.generate_code(&normalized_phone, self.config.sms_code_length).await
+                    .map_err(|e| {
+                        tracing::error!("Failed to generate/send SMS code for {}: {}", normalized_phone, e);
+                        AppError::Internal(format!(
+                            "Не удалось отправить SMS код. {}. Для разработки установите RETURN_SMS_CODE_IN_RESPONSE=true", e
+                        ))
+                    })?;
+
+                // Отправляем код в Telegram бот (если настроен)
+                if let Err(e) = self.send_code_to_telegram(&normalized_phone, &code).await {
+                    tracing::warn!("Не удалось отправить код в Telegram бот: {}", e);
+                    // Не прерываем процесс, если не удалось отправить в Telegram
+                }
+                code
+            }
+            AuthCodeChannel::Telegram => {
+                self.send_code_via_telegram_chat(
+                    &normalized_phone,
+                    telegram_bot_repository,
+                    telegram_service,
+                )
+                .await?
+            }
+        };
+
         let expires_in = (self.config.sms_code_expiration_minutes * 60) as u64;
 
         // Возвращаем код в ответе только если return_sms_code_in_response = true (dev режим)
@@ -113,30 +187,49 @@ impl AuthService {
         Ok(AuthStartResponse {
             code: response_code,
             expires_in,
+            channel,
         })
     }
 
-    /// Проверяет код и создаёт/находит пользователя
-    pub async fn verify_auth<R: UserRepository, RP: UserPlateRepository>(
+    /// Проверяет код, создаёт/находит пользователя и открывает сессию устройства
+    pub async fn verify_auth<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        SR: UserSessionRepository,
+    >(
         &self,
         phone: &str,
         code: &str,
+        device_id: Option<&str>,
         user_repository: &R,
         user_plate_repository: &RP,
+        user_session_repository: &SR,
     ) -> AppResult<AuthVerifyResponse> {
         let normalized_phone = ValidationService::validate_phone(phone)?;
+        // Проверяется до погашения кода, чтобы ошибка клиента не сжигала код
+        let device_id = device_id.map(str::trim).filter(|id| !id.is_empty());
+        if device_id.is_some_and(|id| id.chars().count() > MAX_DEVICE_ID_LENGTH) {
+            return Err(AppError::Validation(format!(
+                "device_id не может быть длиннее {} символов",
+                MAX_DEVICE_ID_LENGTH
+            )));
+        }
 
-        // Проверяем код
-        if !self.sms_service.verify_code(&normalized_phone, code).await {
+        // Проверяем и сразу погашаем код (одноразовый)
+        let consumed = match self.sms_service.consume_code(&normalized_phone, code).await {
+            Err(AppError::CodeAttemptsExceeded(_)) => {
+                return Err(AppError::CodeAttemptsExceeded(
+                    "Слишком много неверных попыток. Запросите новый код".to_string(),
+                ))
+            }
+            result => result?,
+        };
+        if !consumed {
             return Err(AppError::Auth("Неверный код подтверждения".to_string()));
         }
 
-        // Хэш и шифруем телефон
+        // Хэш телефона для поиска пользователя
         let phone_hash = Self::phone_hash(&normalized_phone);
-        let phone_encrypted = self
-            .encryption
-            .encrypt(&normalized_phone)
-            .map_err(|e| AppError::Encryption(e.to_string()))?;
 
         // Ищем или создаём пользователя
         let user = match user_repository.find_by_phone_hash(&phone_hash).await? {
@@ -144,45 +237,20 @@ impl AuthService {
                 // Пользователь существует - синхронизируем данные с user_plates
                 tracing::info!("Existing user found: {}", user.id);
 
+                if user.is_suspended() {
+                    tracing::warn!("Suspended user {} tried to log in", user.id);
+                    return Err(AppError::AccountSuspended(user.suspension_message()));
+                }
+
                 // Проверяем наличие основного автомобиля
                 let primary_plate = user_plate_repository
                     .find_primary_by_user_id(user.id)
                     .await?;
 
-                if let Some(primary) = primary_plate {
-                    // Основной автомобиль найден - синхронизируем номер в users.plate
-                    if user.plate.as_deref() != Some(primary.plate.as_str()) {
-                        tracing::info!(
-                            "Syncing user {} plate from primary: {:?} -> {}",
-                            user.id,
-                            user.plate,
-                            primary.plate
-                        );
-                        // Обновляем номер в users для обратной совместимости
-                        let update_data = crate::repository::UpdateUserData {
-                            name: None,
-                            phone_encrypted: None,
-                            phone_hash: None,
-                            telegram: None,
-                            plate: Some(primary.plate.clone()),
-                            show_contacts: None,
-                            owner_type: None,
-                            owner_info: None,
-                            departure_time: None,
-                            push_token: None,
-                        };
-                        if let Ok(updated_user) =
-                            user_repository.update(user.id, &update_data).await
-                        {
-                            tracing::info!("User plate synchronized successfully");
-                            updated_user
-                        } else {
-                            tracing::warn!("Failed to sync user plate, using existing user");
-                            user
-                        }
-                    } else {
-                        user
-                    }
+                if primary_plate.is_some() {
+                    // Номер пользователя уже читается из основного автомобиля
+                    // (представление users_with_primary_plate), синхронизировать нечего
+                    user
                 } else if let Some(ref plate) = user.plate {
                     // Нет основного автомобиля, но есть номер в users.plate - создаем его
                     tracing::info!(
@@ -228,6 +296,11 @@ impl AuthService {
             }
             None => {
                 let new_user_id = Uuid::new_v4();
+                // Телефон шифруется с привязкой к ID нового пользователя
+                let phone_encrypted = self
+                    .encryption
+                    .encrypt(&normalized_phone, Some(&aad::user_phone(new_user_id)))
+                    .map_err(|e| AppError::Encryption(e.to_string()))?;
                 let user = user_repository
                     .create(&CreateUserData {
                         id: new_user_id,
@@ -244,11 +317,9 @@ impl AuthService {
             }
         };
 
-        // Удаляем использованный код
-        self.sms_service.remove_code(&normalized_phone).await;
-
-        // Создаём токен
-        let token = create_token(user.id, &self.config)?;
+        // Создаём сессию и токен, привязанный к ней
+        let session = user_session_repository.create(user.id, device_id).await?;
+        let token = create_session_token(user.id, session.id, &self.config)?;
 
         Ok(AuthVerifyResponse {
             token,
@@ -256,17 +327,50 @@ impl AuthService {
         })
     }
 
-    /// Обновляет токен, если он еще действителен или истек недавно (в течение 30 минут)
-    pub async fn refresh_token(&self, token: &str) -> AppResult<RefreshTokenResponse> {
-        use jsonwebtoken::{decode, DecodingKey, Validation};
+    /// Выход: завершает сессию и отзывает текущий токен. Запись об отзыве хранится, пока токен
+    /// можно использовать или обменять на новый (`REFRESH_GRACE_MINUTES` после истечения)
+    pub async fn logout<RR: RevokedTokenRepository, SR: UserSessionRepository>(
+        &self,
+        user_id: Uuid,
+        token: &AuthToken,
+        revoked_token_repository: &RR,
+        user_session_repository: &SR,
+    ) -> AppResult<()> {
+        if let Some(session_id) = token.session_id {
+            user_session_repository.delete(session_id, user_id).await?;
+        }
+        let Some(jti) = token.jti else {
+            // Токен выдан до появления отзыва: он перестанет действовать сам по истечении
+            tracing::warn!("User {} logged out with a token without jti", user_id);
+            return Ok(());
+        };
+        let expires_at = chrono::DateTime::from_timestamp(token.expires_at, 0)
+            .unwrap_or_else(chrono::Utc::now)
+            + chrono::Duration::minutes(REFRESH_GRACE_MINUTES);
+
+        revoked_token_repository
+            .revoke(jti, user_id, expires_at)
+            .await?;
+        tracing::info!("User {} logged out, token {} revoked", user_id, jti);
+        Ok(())
+    }
+
+    /// Обновляет токен, если он еще действителен или истек недавно (в течение 30 минут).
+    /// Отозванный при выходе токен и токен завершённой сессии не обновляются;
+    /// новый токен остаётся в той же сессии
+    pub async fn refresh_token<RR: RevokedTokenRepository, SR: UserSessionRepository>(
+        &self,
+        token: &str,
+        revoked_token_repository: &RR,
+        user_session_repository: &SR,
+    ) -> AppResult<RefreshTokenResponse> {
         use serde_json::Value;
 
         // Декодируем токен без проверки времени истечения
-        let key = DecodingKey::from_secret(self.config.jwt_secret.as_ref());
-        let mut validation = Validation::default();
+        let mut validation = token_validation(&self.config);
         validation.validate_exp = false; // Отключаем проверку времени истечения
 
-        let token_data = decode::<Value>(token, &key, &validation)
+        let token_data = decode_with_accepted_secrets::<Value>(token, &self.config, &validation)
             .map_err(|e| AppError::Auth(format!("Invalid token format: {}", e)))?;
 
         // Извлекаем user_id и время создания
@@ -279,6 +383,17 @@ impl AuthService {
         let user_id = uuid::Uuid::parse_str(user_id_str)
             .map_err(|_| AppError::Auth("Invalid token: invalid user_id format".to_string()))?;
 
+        let jti = token_data
+            .claims
+            .get("jti")
+            .and_then(|v| v.as_str())
+            .and_then(|v| Uuid::parse_str(v).ok());
+        if let Some(jti) = jti {
+            if revoked_token_repository.is_revoked(jti).await? {
+                return Err(AppError::Auth("Token has been revoked".to_string()));
+            }
+        }
+
         let iat = token_data
             .claims
             .get("iat")
@@ -289,7 +404,7 @@ impl AuthService {
         // Это позволяет обновлять токен, если пользователь был неактивен недолго
         let now = chrono::Utc::now().timestamp();
         let token_age = now - iat;
-        let max_age_after_expiry = 30 * 60; // 30 минут в секундах
+        let max_age_after_expiry = REFRESH_GRACE_MINUTES * 60;
 
         // Если токен слишком старый (больше времени жизни + окно обновления), требуем повторного входа
         let max_total_age = (self.config.jwt_expiration_minutes * 60) + max_age_after_expiry;
@@ -299,12 +414,58 @@ impl AuthService {
             ));
         }
 
-        // Создаём новый токен
-        let new_token = create_token(user_id, &self.config)?;
+        let session_id = token_data
+            .claims
+            .get("sid")
+            .and_then(|v| v.as_str())
+            .and_then(|v| Uuid::parse_str(v).ok());
+
+        // Создаём новый токен. Токены, выданные до появления сессий, получают новую сессию
+        let session_id = match session_id {
+            Some(session_id) => {
+                if !user_session_repository.touch(session_id, user_id).await? {
+                    return Err(AppError::Auth("Session has been terminated".to_string()));
+                }
+                session_id
+            }
+            None => user_session_repository.create(user_id, None).await?.id,
+        };
+        let new_token = create_session_token(user_id, session_id, &self.config)?;
 
         Ok(RefreshTokenResponse {
             token: new_token,
             user_id,
         })
     }
+
+    /// Активные сессии пользователя; текущая помечена `current`
+    pub async fn list_sessions<SR: UserSessionRepository>(
+        &self,
+        user_id: Uuid,
+        current_session_id: Option<Uuid>,
+        user_session_repository: &SR,
+    ) -> AppResult<Vec<SessionResponse>> {
+        let active_since = chrono::Utc::now() - session_idle_timeout(&self.config);
+        let sessions = user_session_repository
+            .list_active(user_id, active_since)
+            .await?;
+        Ok(sessions
+            .into_iter()
+            .map(|session| SessionResponse::from_session(session, current_session_id))
+            .collect())
+    }
+
+    /// Завершает сессию пользователя: её токены перестают приниматься сразу
+    pub async fn revoke_session<SR: UserSessionRepository>(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        user_session_repository: &SR,
+    ) -> AppResult<()> {
+        if !user_session_repository.delete(session_id, user_id).await? {
+            return Err(AppError::NotFound("Сессия не найдена".to_string()));
+        }
+        tracing::info!("User {} terminated session {}", user_id, session_id);
+        Ok(())
+    }
 }
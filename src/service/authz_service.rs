@@ -0,0 +1,64 @@
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::block::Block;
+use crate::repository::UserPlateRepository;
+
+/// Проверки прав пользователя на номера и блокировки (SRP): одно место для правил доступа,
+/// которыми пользуются все операции над блокировками
+pub struct AuthzService;
+
+impl AuthzService {
+    /// Является ли пользователь одним из владельцев номера
+    pub async fn is_plate_owner<UPR: UserPlateRepository>(
+        user_id: Uuid,
+        plate: &str,
+        user_plate_repository: &UPR,
+    ) -> AppResult<bool> {
+        Ok(user_plate_repository
+            .find_by_plate(plate)
+            .await?
+            .iter()
+            .any(|user_plate| user_plate.user_id == user_id))
+    }
+
+    /// Создал ли пользователь блокировку или владеет автомобилем блокирующего вместе с ним
+    /// (совладельцы управляют блокировкой наравне с блокирующим)
+    pub async fn is_block_blocker_or_coowner<UPR: UserPlateRepository>(
+        user_id: Uuid,
+        block: &Block,
+        user_plate_repository: &UPR,
+    ) -> AppResult<bool> {
+        if block.blocker_id == user_id {
+            return Ok(true);
+        }
+        Self::is_plate_owner(user_id, &block.blocker_plate, user_plate_repository).await
+    }
+
+    /// То же, что `is_plate_owner`, но отказ - ошибка `Forbidden` с переданным сообщением
+    pub async fn require_plate_owner<UPR: UserPlateRepository>(
+        user_id: Uuid,
+        plate: &str,
+        user_plate_repository: &UPR,
+        message: &str,
+    ) -> AppResult<()> {
+        if !Self::is_plate_owner(user_id, plate, user_plate_repository).await? {
+            return Err(AppError::Forbidden(message.to_string()));
+        }
+        Ok(())
+    }
+
+    /// То же, что `is_block_blocker_or_coowner`, но отказ - ошибка `Forbidden`
+    /// с переданным сообщением
+    pub async fn require_block_blocker_or_coowner<UPR: UserPlateRepository>(
+        user_id: Uuid,
+        block: &Block,
+        user_plate_repository: &UPR,
+        message: &str,
+    ) -> AppResult<()> {
+        if !Self::is_block_blocker_or_coowner(user_id, block, user_plate_repository).await? {
+            return Err(AppError::Forbidden(message.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::AppResult;
+use crate::repository::{
+    BlockRepository, NotificationOutboxRepository, UserPlateRepository, UserRepository,
+};
+use crate::service::{BlockService, TelephonyService};
+
+/// Как часто проверять неподтверждённые блокировки
+const ESCALATION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Сколько блокировок обрабатывать за одну проверку
+const ESCALATION_BATCH_SIZE: i64 = 100;
+
+/// Шаги эскалации неподтверждённой блокировки (в порядке выполнения)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationStep {
+    /// Повторный push владельцам
+    RepeatPush,
+    /// Звонок владельцам (кроме их тихих часов)
+    Call,
+    /// Звонок администратору дома
+    AdminContact,
+}
+
+impl EscalationStep {
+    pub const ALL: [EscalationStep; 3] = [
+        EscalationStep::RepeatPush,
+        EscalationStep::Call,
+        EscalationStep::AdminContact,
+    ];
+
+    /// Шаг по количеству уже выполненных шагов
+    pub fn from_index(index: i16) -> Option<Self> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| Self::ALL.get(index).copied())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EscalationStep::RepeatPush => "repeat_push",
+            EscalationStep::Call => "call",
+            EscalationStep::AdminContact => "admin_contact",
+        }
+    }
+}
+
+/// Какой шаг эскалации пора выполнить для неподтверждённой блокировки.
+/// Шаг с номером N (с нуля) выполняется через (N + 1) окон после создания блокировки
+pub fn due_escalation_step(
+    created_at: DateTime<Utc>,
+    completed_steps: i16,
+    now: DateTime<Utc>,
+    window: chrono::Duration,
+) -> Option<EscalationStep> {
+    let step = EscalationStep::from_index(completed_steps)?;
+    let due_at = created_at + window * (i32::from(completed_steps) + 1);
+    (now >= due_at).then_some(step)
+}
+
+/// Однократно проверяет неподтверждённые блокировки и выполняет подошедшие шаги эскалации
+pub async fn escalate_unacknowledged_blocks<BR, UR, UPR, OR>(
+    block_service: &BlockService,
+    block_repository: &BR,
+    user_repository: &UR,
+    user_plate_repository: &UPR,
+    outbox_repository: &OR,
+    telephony_service: &TelephonyService,
+    window: chrono::Duration,
+) -> AppResult<usize>
+where
+    BR: BlockRepository,
+    UR: UserRepository,
+    UPR: UserPlateRepository,
+    OR: NotificationOutboxRepository,
+{
+    let now = Utc::now();
+    let total_steps = EscalationStep::ALL.len() as i16;
+    // Старые блокировки (например, созданные до включения эскалации) не трогаем
+    let since = now - window * (i32::from(total_steps) + 1);
+    let blocks = block_repository
+        .find_unacknowledged(since, total_steps, ESCALATION_BATCH_SIZE)
+        .await?;
+
+    let mut escalated = 0;
+    for block in blocks {
+        let Some(step) = due_escalation_step(block.created_at, block.escalation_step, now, window)
+        else {
+            continue;
+        };
+
+        // Сначала занимаем шаг, чтобы при нескольких экземплярах сервиса он выполнился один раз
+        if !block_repository
+            .advance_escalation(block.id, block.escalation_step)
+            .await?
+        {
+            continue;
+        }
+
+        tracing::info!(
+            "Block {} is not acknowledged, escalation step: {}",
+            block.id,
+            step.as_str()
+        );
+        if let Err(e) = block_service
+            .escalate_block(
+                &block,
+                step,
+                user_repository,
+                user_plate_repository,
+                outbox_repository,
+                telephony_service,
+            )
+            .await
+        {
+            tracing::error!("Failed to escalate block {}: {:?}", block.id, e);
+            continue;
+        }
+        escalated += 1;
+    }
+
+    Ok(escalated)
+}
+
+/// Запускает периодическую эскалацию неподтверждённых блокировок в фоне
+pub fn spawn_block_escalation<BR, UR, UPR, OR>(
+    block_service: BlockService,
+    block_repository: BR,
+    user_repository: UR,
+    user_plate_repository: UPR,
+    outbox_repository: OR,
+    telephony_service: TelephonyService,
+    window: chrono::Duration,
+) where
+    BR: BlockRepository + 'static,
+    UR: UserRepository + 'static,
+    UPR: UserPlateRepository + 'static,
+    OR: NotificationOutboxRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(ESCALATION_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = escalate_unacknowledged_blocks(
+                &block_service,
+                &block_repository,
+                &user_repository,
+                &user_plate_repository,
+                &outbox_repository,
+                &telephony_service,
+                window,
+            )
+            .await
+            {
+                tracing::error!("Block escalation failed: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,37 @@
+use tokio::sync::broadcast;
+
+/// Сколько событий может накопиться у отстающего подписчика (после этого он получает
+/// `Lagged` и просто перепроверяет состояние)
+const BLOCK_EVENTS_CAPACITY: usize = 256;
+
+/// Внутрипроцессная рассылка изменений блокировок: событие - нормализованный номер
+/// заблокированного автомобиля, состояние которого могло измениться.
+/// Используется long-poll проверкой номера, чтобы не опрашивать БД в цикле
+#[derive(Clone)]
+pub struct BlockEvents {
+    sender: broadcast::Sender<String>,
+}
+
+impl BlockEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BLOCK_EVENTS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Сообщает подписчикам, что блокировки номера изменились (без подписчиков ничего не делает)
+    pub fn publish(&self, blocked_plate: &str) {
+        let _ = self
+            .sender
+            .send(crate::utils::normalize_plate(blocked_plate));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for BlockEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
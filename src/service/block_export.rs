@@ -0,0 +1,163 @@
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::block::BlockExportRow;
+use crate::repository::BlockRepository;
+use crate::utils::time::format_timestamp;
+
+/// Поток CSV-фрагментов для тела ответа
+pub type CsvStream = ReceiverStream<Result<Vec<u8>, std::io::Error>>;
+
+/// Сколько строк читается из БД и отдаётся клиенту за раз
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Чьи блокировки выгружаются
+#[derive(Debug, Clone, Copy)]
+pub enum BlockExportScope {
+    /// Блокировки пользователя: созданные им и перекрывшие его номера. Без идентификаторов
+    /// других пользователей и контактов - только номера, которые и так видны на машине
+    User(Uuid),
+    /// Выгрузка для администратора: с blocker_id, по одному пользователю или по всем
+    Admin(Option<Uuid>),
+}
+
+impl BlockExportScope {
+    fn user_id(&self) -> Option<Uuid> {
+        match self {
+            BlockExportScope::User(user_id) => Some(*user_id),
+            BlockExportScope::Admin(user_id) => *user_id,
+        }
+    }
+
+    fn header(&self) -> &'static [&'static str] {
+        match self {
+            BlockExportScope::User(_) => &[
+                "id",
+                "role",
+                "blocker_plate",
+                "blocked_plate",
+                "status",
+                "created_at",
+                "expires_at",
+                "acknowledged_at",
+                "removed_at",
+            ],
+            BlockExportScope::Admin(_) => &[
+                "id",
+                "blocker_id",
+                "blocker_plate",
+                "blocked_plate",
+                "status",
+                "created_at",
+                "expires_at",
+                "acknowledged_at",
+                "removed_at",
+            ],
+        }
+    }
+
+    fn record(&self, row: &BlockExportRow, now: chrono::DateTime<Utc>) -> [String; 9] {
+        let second = match self {
+            // Блокировка попала в выгрузку либо как созданная пользователем, либо как
+            // перекрывшая его номер
+            BlockExportScope::User(user_id) if row.blocker_id == *user_id => "blocker".to_string(),
+            BlockExportScope::User(_) => "blocked".to_string(),
+            BlockExportScope::Admin(_) => row.blocker_id.to_string(),
+        };
+        let timestamp = |value: &Option<chrono::DateTime<Utc>>| {
+            value.as_ref().map(format_timestamp).unwrap_or_default()
+        };
+        [
+            row.id.to_string(),
+            second,
+            row.blocker_plate.clone().unwrap_or_default(),
+            row.blocked_plate.clone(),
+            row.status(now).to_string(),
+            format_timestamp(&row.created_at),
+            timestamp(&row.expires_at),
+            timestamp(&row.acknowledged_at),
+            timestamp(&row.removed_at),
+        ]
+    }
+}
+
+/// Кодирует строки в CSV (заголовок - только для первой пачки)
+fn encode_batch(
+    scope: &BlockExportScope,
+    rows: &[BlockExportRow],
+    with_header: bool,
+) -> AppResult<Vec<u8>> {
+    let now = Utc::now();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if with_header {
+        writer
+            .write_record(scope.header())
+            .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
+    }
+    for row in rows {
+        writer
+            .write_record(scope.record(row, now))
+            .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))
+}
+
+/// Выгружает блокировки в CSV потоком: строки читаются из БД пачками по мере того, как клиент
+/// забирает ответ, поэтому большая выгрузка не собирается в памяти целиком.
+/// Ошибка посреди выгрузки обрывает ответ (клиент получит неполный файл и ошибку соединения)
+pub fn stream_blocks_csv<BR: BlockRepository + Clone + 'static>(
+    block_repository: BR,
+    scope: BlockExportScope,
+) -> CsvStream {
+    let (tx, rx) = mpsc::channel(2);
+
+    tokio::spawn(async move {
+        let mut after = None;
+        let mut with_header = true;
+        loop {
+            let rows = match block_repository
+                .find_export_page(scope.user_id(), after, EXPORT_BATCH_SIZE)
+                .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::error!("Block export failed: {:?}", e);
+                    let _ = tx
+                        .send(Err(std::io::Error::other("block export failed")))
+                        .await;
+                    return;
+                }
+            };
+
+            let chunk = match encode_batch(&scope, &rows, with_header) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    tracing::error!("Block export failed: {:?}", e);
+                    let _ = tx
+                        .send(Err(std::io::Error::other("block export failed")))
+                        .await;
+                    return;
+                }
+            };
+            with_header = false;
+            if !chunk.is_empty() && tx.send(Ok(chunk)).await.is_err() {
+                // Клиент ушёл, дальше читать незачем
+                return;
+            }
+
+            match rows.last() {
+                Some(last) if rows.len() as i64 == EXPORT_BATCH_SIZE => {
+                    after = Some((last.created_at, last.id));
+                }
+                _ => return,
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
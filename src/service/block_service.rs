@@ -1,52 +1,158 @@
+use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use crate::models::block::{Block, BlockWithBlockerInfo, CheckBlockResponse, CreateBlockRequest};
+use crate::models::block::{
+    describe_block_reason, ActiveBlockInfo, Block, BlockNotifiabilityResponse, BlockPhotoSize,
+    BlockPreviewResponse, BlockReason, BlockWithBlockerInfo, CallbackRequestResponse,
+    CheckBlockResponse, CreateBlockRequest, CreateBlockResponse, NotifySummary, OwnerNotifiability,
+    UpdateBlockRequest, CALLBACK_REQUEST_INTERVAL_SECS,
+};
+use crate::models::notification::NotificationType;
+use crate::models::outbox::OutboxChannel;
+use crate::models::user::{PublicUserInfo, User};
 use crate::repository::{
-    BlockRepository, CreateNotificationData, NotificationRepository, UserPlateRepository,
-    UserRepository,
+    BlobStore, BlockRepository, CreateNotificationData, NewOutboxMessage,
+    NotificationOutboxRepository, NotificationRepository, PushTokenRepository, UpdateBlockData,
+    UserPlateRepository, UserRepository,
 };
+use crate::service::block_events::BlockEvents;
 use crate::service::{
-    telegram_service::TelegramService, telephony_service::TelephonyService,
+    analytics_service::AnalyticsService,
+    authz_service::AuthzService,
+    block_escalation::EscalationStep,
+    message_templates::{MessageContext, MessageTemplate},
+    telegram_service::TelegramService,
+    telephony_service::TelephonyService,
     validation_service::ValidationService,
 };
-use crate::utils::encryption::Encryption;
+use crate::utils::encryption::{aad, Encryption};
+use crate::utils::time::{
+    is_within_window, local_time, next_local_occurrence, DEFAULT_UTC_OFFSET_MINUTES,
+};
 use uuid::Uuid;
 
+/// Заголовок уведомления об изменении блокировки (новое время выезда, заметка)
+const BLOCK_UPDATED_TITLE: &str = "Блокировка обновлена";
+
+/// Заголовок повторного push-уведомления при эскалации неподтверждённой блокировки
+const BLOCK_ESCALATION_PUSH_TITLE: &str = "Вас всё ещё ждут";
+
+/// Заголовок просьбы владельца перезвонить блокирующему
+const CALLBACK_REQUEST_TITLE: &str = "Просят перезвонить";
+
+/// Каналы, по которым можно связаться с владельцем, и причины недоступности остальных
+struct OwnerReachability {
+    channels: Vec<&'static str>,
+    issues: Vec<&'static str>,
+}
+
+/// Результат проверки запроса на создание блокировки
+struct ValidatedBlock {
+    normalized_plate: String,
+    blocker_primary_plate: String,
+    reason: Option<BlockReason>,
+}
+
 /// Сервис работы с блокировками (SRP)
 #[derive(Clone)]
 pub struct BlockService {
     encryption: Encryption,
-    push_service: crate::service::push_service::PushService,
+    analytics: AnalyticsService,
+    config: Config,
+    events: BlockEvents,
 }
 
 impl BlockService {
-    pub fn new(
-        encryption: Encryption,
-        push_service: crate::service::push_service::PushService,
-    ) -> Self {
+    pub fn new(encryption: Encryption, analytics: AnalyticsService, config: Config) -> Self {
         Self {
             encryption,
-            push_service,
+            analytics,
+            config,
+            events: BlockEvents::new(),
         }
     }
 
-    /// Создаёт новую блокировку
-    #[allow(clippy::too_many_arguments)]
-    pub async fn create_block<
-        BR: BlockRepository,
-        NR: NotificationRepository,
-        UR: UserRepository,
-        UPR: UserPlateRepository,
-    >(
+    /// Рассылка изменений блокировок (создание, изменение, снятие) по номерам
+    pub fn events(&self) -> &BlockEvents {
+        &self.events
+    }
+
+    /// Проверяет, не превысил ли пользователь лимит блокировок в час (анти-спам)
+    async fn check_block_rate_limit<BR: BlockRepository>(
         &self,
         blocker_id: Uuid,
-        mut request: CreateBlockRequest,
         block_repository: &BR,
-        notification_repository: &NR,
-        user_repository: &UR,
+    ) -> AppResult<()> {
+        let limit = self.config.block_rate_limit_per_hour;
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let window = chrono::Duration::hours(1);
+        let now = chrono::Utc::now();
+        let (count, oldest) = block_repository
+            .count_recent_by_blocker(blocker_id, now - window)
+            .await?;
+
+        if count < i64::from(limit) {
+            return Ok(());
+        }
+
+        // Лимит освободится, когда самая старая блокировка выйдет из окна
+        let retry_after_secs = oldest
+            .map(|oldest| (oldest + window - now).num_seconds().max(1) as u64)
+            .unwrap_or(60);
+
+        tracing::warn!(
+            "User {} exceeded block rate limit ({} per hour), retry after {}s",
+            blocker_id,
+            limit,
+            retry_after_secs
+        );
+
+        Err(AppError::RateLimited {
+            message: format!(
+                "Слишком много блокировок. Попробуйте снова через {} мин.",
+                retry_after_secs.div_ceil(60)
+            ),
+            retry_after_secs,
+        })
+    }
+
+    /// Предупреждение, если пользователь уже перекрывал этот автомобиль не меньше
+    /// `REPEAT_BLOCK_FREE_COUNT` раз (первые блокировки проходят без вопросов)
+    async fn repeat_block_warning<BR: BlockRepository>(
+        &self,
+        blocker_id: Uuid,
+        blocked_plate: &str,
+        block_repository: &BR,
+    ) -> AppResult<Option<String>> {
+        let free_count = self.config.repeat_block_free_count;
+        if free_count == 0 {
+            return Ok(None);
+        }
+
+        let past_blocks = block_repository
+            .count_past_blocks(blocker_id, blocked_plate)
+            .await?;
+        if past_blocks < i64::from(free_count) {
+            return Ok(None);
+        }
+
+        Ok(Some(format!(
+            "Вы уже перекрывали автомобиль {} (блокировок: {}). Постарайтесь не перекрывать соседей регулярно - подтвердите, что это необходимо",
+            blocked_plate, past_blocks
+        )))
+    }
+
+    /// Проверяет запрос на создание блокировки: номер, причину, наличие своего авто,
+    /// самоблокировку, взаимную блокировку и дубликат
+    async fn validate_new_block<BR: BlockRepository, UPR: UserPlateRepository>(
+        &self,
+        blocker_id: Uuid,
+        request: &mut CreateBlockRequest,
+        block_repository: &BR,
         user_plate_repository: &UPR,
-        telephony_service: &TelephonyService,
-        telegram_service: &TelegramService,
-    ) -> AppResult<Block> {
+    ) -> AppResult<ValidatedBlock> {
         // Нормализация и валидация
         request.normalize();
         let normalized_plate =
@@ -60,6 +166,11 @@ impl BlockService {
                 e
             })?;
 
+        let reason = ValidationService::validate_block_reason(
+            request.reason.as_deref(),
+            request.reason_text.as_deref(),
+        )?;
+
         // Получаем все номера блокирующего пользователя
         let blocker_plates = user_plate_repository.find_by_user_id(blocker_id).await?;
         if blocker_plates.is_empty() {
@@ -76,11 +187,11 @@ impl BlockService {
             .or_else(|| blocker_plates.first().map(|p| p.plate.clone()))
             .ok_or_else(|| AppError::Validation("Сначала добавьте свой автомобиль".to_string()))?;
 
-        // Проверка 1: Запрещаем самоблокировку (нельзя перекрыть собственный авто)
+        // Проверка 1: Запрещаем самоблокировку - любым своим автомобилем, а не только основным
         let blocker_plate_strings: Vec<String> =
             blocker_plates.iter().map(|p| p.plate.clone()).collect();
         for blocker_plate in &blocker_plate_strings {
-            if blocker_plate.eq_ignore_ascii_case(&normalized_plate) {
+            if crate::utils::normalize_plate(blocker_plate) == normalized_plate {
                 tracing::warn!(
                     "User {} attempted to block their own plate {}, blocking denied",
                     blocker_id,
@@ -119,15 +230,10 @@ impl BlockService {
             }
         }
 
-        tracing::info!(
-            "Creating block for plate {} blocking {}",
-            blocker_primary_plate,
-            normalized_plate
-        );
-
-        // Оптимизированная проверка на дубликаты - проверяем по номерам, а не по пользователю
+        // Проверка на дубликаты по номерам, а не по пользователю: автомобиль уже перекрыт
+        // любым из автомобилей блокирующего (в том числе не основным)
         let exists = block_repository
-            .exists(&blocker_primary_plate, &normalized_plate)
+            .exists_from_any(&blocker_plate_strings, &normalized_plate)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to check existing blocks: {:?}", e);
@@ -136,8 +242,8 @@ impl BlockService {
 
         if exists {
             tracing::warn!(
-                "Block already exists for plate {} blocking {}",
-                blocker_primary_plate,
+                "Block already exists for one of user {} plates blocking {}",
+                blocker_id,
                 normalized_plate
             );
             return Err(crate::error::AppError::Validation(
@@ -145,19 +251,371 @@ impl BlockService {
             ));
         }
 
-        // Создание блокировки
+        Ok(ValidatedBlock {
+            normalized_plate,
+            blocker_primary_plate,
+            reason,
+        })
+    }
+
+    /// Владельцы номера, которых нужно уведомить о блокировке (без самого блокирующего,
+    /// без заблокированных аккаунтов и без дубликатов)
+    async fn find_owner_ids<UPR: UserPlateRepository>(
+        &self,
+        plate: &str,
+        blocker_id: Uuid,
+        user_plate_repository: &UPR,
+    ) -> Vec<Uuid> {
+        user_plate_repository
+            .find_active_owner_ids(plate)
+            .await
+            .map(|owner_ids| {
+                owner_ids
+                    .into_iter()
+                    .filter(|owner_id| *owner_id != blocker_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Время выезда из запроса (HH:MM), если оно указано корректно
+    fn departure_eta(request: &CreateBlockRequest) -> Option<String> {
+        request
+            .departure_time
+            .as_deref()
+            .and_then(|t| ValidationService::parse_departure_time(t).ok())
+            .map(|t| t.format("%H:%M").to_string())
+    }
+
+    /// Причина и время выезда для текста уведомлений ("погрузка/разгрузка, до 18:30")
+    fn reason_description(
+        reason: Option<BlockReason>,
+        request: &CreateBlockRequest,
+    ) -> Option<String> {
+        describe_block_reason(
+            reason,
+            request.reason_text.as_deref(),
+            Self::departure_eta(request).as_deref(),
+        )
+    }
+
+    /// Сообщение о блокировке по шаблону из конфигурации (`MESSAGE_TEMPLATE_*`)
+    fn render_message(&self, kind: MessageTemplate, context: &MessageContext) -> String {
+        self.config.message_templates.render(kind, context)
+    }
+
+    /// Время окончания блокировки (HH:MM) по местному времени владельца
+    fn local_eta(&self, block: &Block, owner: &User) -> Option<String> {
+        let offset = owner
+            .utc_offset_minutes
+            .unwrap_or(DEFAULT_UTC_OFFSET_MINUTES);
+        block
+            .expires_at
+            .map(|expires_at| local_time(expires_at, offset).format("%H:%M").to_string())
+    }
+
+    /// Тихие ли сейчас часы у владельца по его местному времени (звонить в это время не стоит).
+    /// Если часовой пояс не указан в профиле, считаем по московскому времени
+    fn is_owner_quiet_hours(&self, owner: &User) -> bool {
+        let Some((start, end)) = self.config.owner_quiet_hours else {
+            return false;
+        };
+        let offset = owner
+            .utc_offset_minutes
+            .unwrap_or(DEFAULT_UTC_OFFSET_MINUTES);
+        is_within_window(local_time(chrono::Utc::now(), offset), start, end)
+    }
+
+    /// Проверяет все каналы связи с владельцем: push (если токен не устарел), Telegram
+    /// и звонок (если есть телефон и у владельца не тихие часы)
+    fn owner_reachability(&self, owner: &User, push_token_stale: bool) -> OwnerReachability {
+        let mut reachability = OwnerReachability {
+            channels: Vec::new(),
+            issues: Vec::new(),
+        };
+
+        match &owner.push_token {
+            Some(_) if push_token_stale => reachability.issues.push("stale_push_token"),
+            Some(_) => reachability.channels.push("push"),
+            None => reachability.issues.push("no_push_token"),
+        }
+
+        if owner.telegram.is_some() {
+            reachability.channels.push("telegram");
+        } else {
+            reachability.issues.push("no_telegram");
+        }
+
+        let phone = owner.phone_encrypted.as_deref().and_then(|encrypted| {
+            self.encryption
+                .decrypt_for_user(encrypted, &aad::user_phone(owner.id), owner.id)
+        });
+        if phone.is_none() {
+            reachability.issues.push("no_phone");
+        } else if self.is_owner_quiet_hours(owner) {
+            reachability.issues.push("quiet_hours");
+        } else {
+            reachability.channels.push("call");
+        }
+
+        reachability
+    }
+
+    /// Каналы, по которым владелец получит уведомление о блокировке
+    fn owner_channels(
+        &self,
+        owner: &User,
+        notification_method: &str,
+        notify_owner: bool,
+    ) -> Vec<&'static str> {
+        let message_channel = if notification_method == "telegram" {
+            "telegram"
+        } else {
+            "push"
+        };
+        self.owner_reachability(owner, false)
+            .channels
+            .into_iter()
+            .filter(|&channel| channel == message_channel || (notify_owner && channel == "call"))
+            .collect()
+    }
+
+    /// Создаёт новую блокировку
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_block<
+        BR: BlockRepository,
+        NR: NotificationRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
+    >(
+        &self,
+        blocker_id: Uuid,
+        mut request: CreateBlockRequest,
+        block_repository: &BR,
+        notification_repository: &NR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+        notification_outbox_repository: &OR,
+        telephony_service: &TelephonyService,
+        telegram_service: &TelegramService,
+    ) -> AppResult<CreateBlockResponse> {
+        let ValidatedBlock {
+            normalized_plate,
+            blocker_primary_plate,
+            reason,
+        } = self
+            .validate_new_block(
+                blocker_id,
+                &mut request,
+                block_repository,
+                user_plate_repository,
+            )
+            .await?;
+
+        // Анти-спам: ограничиваем частоту создания блокировок одним пользователем
+        self.check_block_rate_limit(blocker_id, block_repository)
+            .await?;
+
+        // Регулярно перекрывающий одного и того же соседа должен явно подтвердить повтор
+        if !request.acknowledge_repeat {
+            if let Some(warning) = self
+                .repeat_block_warning(blocker_id, &normalized_plate, block_repository)
+                .await?
+            {
+                tracing::info!(
+                    "User {} repeatedly blocks {}, acknowledgment required",
+                    blocker_id,
+                    normalized_plate
+                );
+                return Err(AppError::RepeatBlockNotAcknowledged(warning));
+            }
+        }
+
+        tracing::info!(
+            "Creating block for plate {} blocking {}",
+            blocker_primary_plate,
+            normalized_plate
+        );
+
+        // Блокировка и очередь её уведомлений создаются в одной транзакции: отправкой
+        // занимается фоновый обработчик notification_outbox, с повторами при сбоях провайдеров
+        let mut tx = block_repository.begin().await?;
         let block = block_repository
-            .create(blocker_id, &blocker_primary_plate, &normalized_plate)
+            .create_in_tx(
+                &mut tx,
+                blocker_id,
+                &blocker_primary_plate,
+                &normalized_plate,
+                reason.map(|r| r.as_str()),
+                request.reason_text.as_deref(),
+                request.silent,
+            )
             .await
             .map_err(|e| {
                 tracing::error!("Failed to create block: {:?}", e);
                 e
             })?;
 
+        let reason_description = Self::reason_description(reason, &request);
+        let notification_method = request
+            .notification_method
+            .as_deref()
+            .unwrap_or("android_push");
+
+        // Итог рассылки: сами отправки асинхронные, но намерения доставки известны сразу
+        let mut notify_summary = NotifySummary::default();
+        let mut channels_used = std::collections::BTreeSet::new();
+        let mut notifications = Vec::new();
+        let mut outbox_messages = Vec::new();
+
+        if request.silent {
+            // Тихая блокировка: никаких уведомлений (ни в приложении, ни push/Telegram/звонка)
+            notify_summary.owners_total = self
+                .find_owner_ids(&normalized_plate, blocker_id, user_plate_repository)
+                .await
+                .len();
+            tracing::info!("Block {} is silent, owners are not notified", block.id);
+        } else if let Ok(Some(blocker_user)) = user_repository.find_by_id(blocker_id).await {
+            // Создаём уведомления для владельцев заблокированного автомобиля
+            let blocker_name = blocker_user.name.as_deref().unwrap_or("Неизвестно");
+            let departure_eta = Self::departure_eta(&request);
+            let message_context = MessageContext {
+                plate: &normalized_plate,
+                blocker: blocker_name,
+                note: reason_description.as_deref(),
+                eta: departure_eta.as_deref(),
+            };
+
+            // Находим пользователей, у которых этот номер в user_plates
+            for user_id in self
+                .find_owner_ids(&normalized_plate, blocker_id, user_plate_repository)
+                .await
+            {
+                notify_summary.owners_total += 1;
+
+                // Получаем владельца
+                let owner_user = user_repository.find_by_id(user_id).await.ok().flatten();
+                let owner_channels = owner_user
+                    .as_ref()
+                    .map(|owner| {
+                        self.owner_channels(owner, notification_method, request.notify_owner)
+                    })
+                    .unwrap_or_default();
+
+                notifications.push(CreateNotificationData {
+                    user_id,
+                    r#type: NotificationType::BlockCreated,
+                    title: self.render_message(MessageTemplate::InAppTitle, &message_context),
+                    message: self.render_message(MessageTemplate::InApp, &message_context),
+                    data: Some(serde_json::json!({
+                        "block_id": block.id,
+                        "blocked_plate": normalized_plate,
+                        "blocker_id": blocker_id,
+                        "blocker_name": blocker_name,
+                        "reason": block.reason,
+                        "reason_text": block.reason_text,
+                    })),
+                });
+
+                let Some(owner_user) = owner_user else {
+                    continue;
+                };
+
+                // Отправка уведомлений в зависимости от выбранного способа
+                if notification_method == "telegram" {
+                    if owner_user.telegram.is_some() {
+                        outbox_messages.push(NewOutboxMessage {
+                            block_id: Some(block.id),
+                            user_id: Some(user_id),
+                            channel: OutboxChannel::Telegram,
+                            payload: serde_json::json!({
+                                "message": telegram_service
+                                    .format_block_notification(&message_context),
+                            }),
+                        });
+                    } else {
+                        tracing::warn!(
+                            "User {} has no Telegram username for notification",
+                            user_id
+                        );
+                    }
+                } else if owner_user.push_token.is_some() {
+                    // Отправка через Android Push (по умолчанию)
+                    outbox_messages.push(NewOutboxMessage {
+                        block_id: Some(block.id),
+                        user_id: Some(user_id),
+                        channel: OutboxChannel::Push,
+                        payload: serde_json::json!({
+                            "title": self.render_message(MessageTemplate::PushTitle, &message_context),
+                            "body": self.render_message(MessageTemplate::Push, &message_context),
+                            "data": {
+                                "block_id": block.id.to_string(),
+                                "blocked_plate": normalized_plate,
+                                "blocker_name": blocker_name,
+                                "reason": block.reason.clone().unwrap_or_default(),
+                            },
+                        }),
+                    });
+                }
+
+                // Если запрошено уведомление владельца, звоним ему (кроме его тихих часов)
+                if request.notify_owner {
+                    if self.is_owner_quiet_hours(&owner_user) {
+                        tracing::info!(
+                            "Skipping call to user {}: quiet hours in owner's timezone",
+                            user_id
+                        );
+                    } else if owner_user.phone_encrypted.is_some() {
+                        outbox_messages.push(NewOutboxMessage {
+                            block_id: Some(block.id),
+                            user_id: Some(user_id),
+                            channel: OutboxChannel::Call,
+                            payload: serde_json::json!({
+                                "message": telephony_service
+                                    .format_block_notification_message(&message_context),
+                            }),
+                        });
+                        tracing::info!(
+                            "Calling owner {} about block on {}",
+                            user_id,
+                            normalized_plate
+                        );
+                    } else {
+                        tracing::warn!(
+                            "User {} has no phone number for notification call",
+                            user_id
+                        );
+                    }
+                }
+
+                if !owner_channels.is_empty() {
+                    notify_summary.owners_reachable += 1;
+                    channels_used.extend(owner_channels);
+                }
+            }
+        }
+
+        notification_outbox_repository
+            .enqueue_in_tx(&mut tx, &outbox_messages)
+            .await?;
+        tx.commit().await?;
+        tracing::info!("Block created successfully: {}", block.id);
+        self.events.publish(&block.blocked_plate);
+
+        for notification in &notifications {
+            let _ = notification_repository
+                .create(notification)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to create notification: {:?}", e);
+                });
+        }
+
         // Если передано время выезда — привязываем к основному номеру блокирующего
         if let Some(time_str) = request.departure_time.as_ref() {
             if !time_str.is_empty() {
-                match chrono::NaiveTime::parse_from_str(time_str, "%H:%M") {
+                match ValidationService::parse_departure_time(time_str) {
                     Ok(dt) => {
                         if let Ok(Some(primary_plate)) = user_plate_repository
                             .find_primary_by_user_id(blocker_id)
@@ -170,7 +628,7 @@ impl BlockService {
                     }
                     Err(_) => {
                         tracing::warn!(
-                            "Invalid departure_time format, expected HH:MM: {}",
+                            "Invalid departure_time format, expected HH:MM or HH:MM:SS: {}",
                             time_str
                         );
                     }
@@ -178,274 +636,603 @@ impl BlockService {
             }
         }
 
-        tracing::info!("Block created successfully: {}", block.id);
+        notify_summary.channels_used = channels_used.into_iter().map(String::from).collect();
+        tracing::info!(
+            "Block {}: {} of {} owners will be notified via {:?}",
+            block.id,
+            notify_summary.owners_reachable,
+            notify_summary.owners_total,
+            notify_summary.channels_used
+        );
 
-        // Создаём уведомления для владельцев заблокированного автомобиля
-        // Получаем информацию о блокирующем
-        if let Ok(Some(blocker_user)) = user_repository.find_by_id(blocker_id).await {
-            let blocker_name = blocker_user.name.as_deref().unwrap_or("Неизвестно");
-            let mut notified_users = std::collections::HashSet::new();
+        self.analytics.track(
+            "block_created",
+            Some(blocker_id),
+            serde_json::json!({
+                "reason": block.reason,
+                "notification_method": notification_method,
+                "notify_owner": request.notify_owner,
+                "silent": request.silent,
+                "owners_total": notify_summary.owners_total,
+                "owners_reachable": notify_summary.owners_reachable,
+                "channels_used": notify_summary.channels_used,
+            }),
+        );
 
-            // Находим пользователей, у которых этот номер в user_plates
-            if let Ok(user_plates) = user_plate_repository.find_by_plate(&normalized_plate).await {
-                for user_plate in user_plates {
-                    let user_id = user_plate.user_id;
+        Ok(CreateBlockResponse::new(block, notify_summary))
+    }
 
-                    // Не отправляем уведомление самому блокирующему и избегаем дубликатов
-                    if user_id == blocker_id || notified_users.contains(&user_id) {
-                        continue;
-                    }
+    /// Предпросмотр уведомлений о блокировке: те же проверки, что и при создании,
+    /// но без записи в БД и без отправки
+    #[allow(clippy::too_many_arguments)]
+    pub async fn preview_block<
+        BR: BlockRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+    >(
+        &self,
+        blocker_id: Uuid,
+        mut request: CreateBlockRequest,
+        block_repository: &BR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+        telephony_service: &TelephonyService,
+        telegram_service: &TelegramService,
+    ) -> AppResult<BlockPreviewResponse> {
+        let ValidatedBlock {
+            normalized_plate,
+            reason,
+            ..
+        } = self
+            .validate_new_block(
+                blocker_id,
+                &mut request,
+                block_repository,
+                user_plate_repository,
+            )
+            .await?;
 
-                    notified_users.insert(user_id);
+        let blocker_name = user_repository
+            .find_by_id(blocker_id)
+            .await?
+            .and_then(|u| u.name)
+            .unwrap_or_else(|| "Неизвестно".to_string());
+        let reason_description = Self::reason_description(reason, &request);
+        let departure_eta = Self::departure_eta(&request);
+        let message_context = MessageContext {
+            plate: &normalized_plate,
+            blocker: &blocker_name,
+            note: reason_description.as_deref(),
+            eta: departure_eta.as_deref(),
+        };
+        let notification_method = request
+            .notification_method
+            .clone()
+            .unwrap_or_else(|| "android_push".to_string());
+
+        let mut notify_summary = NotifySummary::default();
+        let mut channels_used = std::collections::BTreeSet::new();
+        for user_id in self
+            .find_owner_ids(&normalized_plate, blocker_id, user_plate_repository)
+            .await
+        {
+            notify_summary.owners_total += 1;
+            let owner_channels = match user_repository.find_by_id(user_id).await? {
+                Some(_) if request.silent => Vec::new(),
+                Some(owner) => {
+                    self.owner_channels(&owner, &notification_method, request.notify_owner)
+                }
+                None => Vec::new(),
+            };
+            if !owner_channels.is_empty() {
+                notify_summary.owners_reachable += 1;
+                channels_used.extend(owner_channels);
+            }
+        }
+        notify_summary.channels_used = channels_used.into_iter().map(String::from).collect();
 
-                    // Получаем владельца
-                    let owner_user = user_repository.find_by_id(user_id).await.ok().flatten();
+        let (push_body, telegram_message) = if request.silent {
+            (None, None)
+        } else if notification_method == "telegram" {
+            let message = telegram_service.format_block_notification(&message_context);
+            (None, Some(message))
+        } else {
+            let body = self.render_message(MessageTemplate::Push, &message_context);
+            (Some(body), None)
+        };
+        let repeat_warning = self
+            .repeat_block_warning(blocker_id, &normalized_plate, block_repository)
+            .await?;
+        let call_message = (request.notify_owner && !request.silent)
+            .then(|| telephony_service.format_block_notification_message(&message_context));
 
-                    // Создаём уведомление
-                    let _ = notification_repository
-                        .create(&CreateNotificationData {
-                            user_id,
-                            r#type: "block".to_string(),
-                            title: "Ваш автомобиль заблокирован".to_string(),
-                            message: format!(
-                                "Автомобиль {} заблокирован пользователем {}",
-                                normalized_plate, blocker_name
-                            ),
-                            data: Some(serde_json::json!({
-                                "block_id": block.id,
-                                "blocked_plate": normalized_plate,
-                                "blocker_id": blocker_id,
-                                "blocker_name": blocker_name,
-                            })),
-                        })
-                        .await
-                        .map_err(|e| {
-                            tracing::error!("Failed to create notification: {:?}", e);
-                        });
+        Ok(BlockPreviewResponse {
+            title: self.render_message(MessageTemplate::InAppTitle, &message_context),
+            message: self.render_message(MessageTemplate::InApp, &message_context),
+            push_title: push_body
+                .as_ref()
+                .map(|_| self.render_message(MessageTemplate::PushTitle, &message_context)),
+            push_body,
+            telegram_message,
+            call_message,
+            blocked_plate: normalized_plate,
+            notification_method,
+            notify_summary,
+            repeat_warning,
+        })
+    }
 
-                    // Отправка уведомлений в зависимости от выбранного способа
-                    let notification_method = request
-                        .notification_method
-                        .as_deref()
-                        .unwrap_or("android_push");
-
-                    if notification_method == "telegram" {
-                        // Отправка через Telegram
-                        if let Some(owner_user) = owner_user.as_ref() {
-                            if let Some(telegram_username) = owner_user.telegram.as_ref() {
-                                let telegram_service_clone = telegram_service.clone();
-                                let telegram_username_clone = telegram_username.clone();
-                                let normalized_plate_clone = normalized_plate.clone();
-                                let blocker_name_clone = blocker_name.to_string();
-
-                                tokio::spawn(async move {
-                                    if let Err(e) = telegram_service_clone
-                                        .send_block_notification(
-                                            &telegram_username_clone,
-                                            &normalized_plate_clone,
-                                            &blocker_name_clone,
-                                        )
-                                        .await
-                                    {
-                                        tracing::warn!(
-                                            "Failed to send Telegram notification: {}",
-                                            e
-                                        );
-                                    }
-                                });
-                            } else {
-                                tracing::warn!(
-                                    "User {} has no Telegram username for notification",
-                                    user_id
-                                );
-                            }
-                        }
-                    } else {
-                        // Отправка через Android Push (по умолчанию)
-                        if let Some(owner_user) = owner_user.as_ref() {
-                            if let Some(push_token) = owner_user.push_token.clone() {
-                                let title = "Ваш авто заблокирован";
-                                let body =
-                                    format!("{} перекрыл {}.", blocker_name, normalized_plate);
-                                let data = serde_json::json!({
-                                    "block_id": block.id.to_string(),
-                                    "blocked_plate": normalized_plate,
-                                    "blocker_name": blocker_name,
-                                });
-                                let push = self.push_service.clone();
-                                tokio::spawn(async move {
-                                    if let Err(e) =
-                                        push.send_fcm(&push_token, title, &body, data).await
-                                    {
-                                        tracing::warn!("Failed to send FCM push: {}", e);
-                                    }
-                                });
-                            }
-                        }
-                    }
+    /// Блокировки, созданные пользователем и совладельцами его автомобилей (новые сначала).
+    /// `limit` None - все. Второй элемент - общее количество
+    pub async fn get_my_blocks<BR: BlockRepository, UPR: UserPlateRepository>(
+        &self,
+        blocker_id: Uuid,
+        limit: Option<i64>,
+        offset: i64,
+        block_repository: &BR,
+        user_plate_repository: &UPR,
+    ) -> AppResult<(Vec<Block>, i64)> {
+        // Блокировки, созданные этим пользователем, и владельцами того же автомобиля (по номеру)
+        let plates: Vec<String> = user_plate_repository
+            .find_by_user_id(blocker_id)
+            .await?
+            .into_iter()
+            .map(|p| p.plate)
+            .collect();
 
-                    // Если запрошено уведомление владельца, звоним ему
-                    if request.notify_owner {
-                        if let Some(owner_user) = owner_user {
-                            if let Some(phone_encrypted) = owner_user.phone_encrypted {
-                                if let Ok(phone) = self.encryption.decrypt(&phone_encrypted) {
-                                    let message = telephony_service
-                                        .format_block_notification_message(
-                                            &normalized_plate,
-                                            blocker_name,
-                                        );
-
-                                    // Совершаем звонок в фоновом режиме (не блокируем ответ)
-                                    let telephony_service_clone = telephony_service.clone();
-                                    let phone_clone = phone.clone();
-                                    let message_clone = message.clone();
-
-                                    tokio::spawn(async move {
-                                        if let Err(e) = telephony_service_clone
-                                            .call_owner(&phone_clone, &message_clone)
-                                            .await
-                                        {
-                                            tracing::error!(
-                                                "Failed to call owner {}: {}",
-                                                phone_clone,
-                                                e
-                                            );
-                                        }
-                                    });
-
-                                    tracing::info!(
-                                        "Calling owner {} about block on {}",
-                                        phone,
-                                        normalized_plate
-                                    );
-                                } else {
-                                    tracing::warn!("Failed to decrypt phone for user {}", user_id);
-                                }
-                            } else {
-                                tracing::warn!(
-                                    "User {} has no phone number for notification call",
-                                    user_id
-                                );
-                            }
-                        }
-                    }
+        // Без автомобилей (например, после удаления последнего) остаются только свои блокировки
+        block_repository
+            .find_created_by_user(blocker_id, &plates, limit, offset)
+            .await
+    }
+
+    /// Получает блокировки для номера автомобиля пользователя (или всех его номеров),
+    /// новые сначала. `limit` None - все. Второй элемент - общее количество
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_blocks_for_my_plate<
+        BR: BlockRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        my_plate: Option<String>,
+        limit: Option<i64>,
+        offset: i64,
+        block_repository: &BR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+    ) -> AppResult<(Vec<BlockWithBlockerInfo>, i64)> {
+        // Если указан конкретный номер, проверяем только его, иначе все номера пользователя
+        let plates = match my_plate {
+            Some(plate) => vec![ValidationService::validate_plate(&plate)?],
+            None => user_plate_repository
+                .find_by_user_id(user_id)
+                .await?
+                .into_iter()
+                .map(|p| p.plate)
+                .collect(),
+        };
+
+        let (blocks, total) = block_repository
+            .find_by_blocked_plates(&plates, limit, offset)
+            .await?;
+        let blocks = self.with_blocker_info(blocks, user_repository).await;
+
+        Ok((blocks, total))
+    }
+
+    /// Дополняет блокировки публичной информацией о блокирующих
+    async fn with_blocker_info<UR: UserRepository>(
+        &self,
+        blocks: Vec<Block>,
+        user_repository: &UR,
+    ) -> Vec<BlockWithBlockerInfo> {
+        // Загружаем всех блокирующих одним запросом вместо запроса на каждую блокировку.
+        // Сведения о блокирующих вторичны: если их не удалось загрузить, владелец всё равно
+        // должен увидеть, что его перекрыли (с номером блокирующего)
+        let mut blocker_ids: Vec<Uuid> = blocks.iter().map(|b| b.blocker_id).collect();
+        blocker_ids.sort_unstable();
+        blocker_ids.dedup();
+        let blockers: std::collections::HashMap<Uuid, User> =
+            match user_repository.find_by_ids(&blocker_ids).await {
+                Ok(users) => users.into_iter().map(|user| (user.id, user)).collect(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load blockers of {} blocks: {:?}",
+                        blocks.len(),
+                        e
+                    );
+                    std::collections::HashMap::new()
                 }
+            };
+
+        let mut result = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let Some(blocker_user) = blockers.get(&block.blocker_id) else {
+                tracing::warn!(
+                    "Blocker {} of block {} not loaded, returning minimal info",
+                    block.blocker_id,
+                    block.id
+                );
+                result.push(BlockWithBlockerInfo {
+                    id: block.id,
+                    blocked_plate: block.blocked_plate,
+                    created_at: block.created_at,
+                    blocker: PublicUserInfo::minimal(block.blocker_id, block.blocker_plate),
+                    blocker_owner_type: None,
+                    blocker_owner_info: None,
+                    enrichment_failed: true,
+                });
+                continue;
+            };
+
+            let phone_decrypted = blocker_user.phone_encrypted.as_ref().and_then(|enc| {
+                self.encryption.decrypt_for_user(
+                    enc,
+                    &aad::user_phone(blocker_user.id),
+                    blocker_user.id,
+                )
+            });
+            // Телефон, который владелец должен был увидеть, не расшифровался
+            let phone_failed = blocker_user.show_contacts
+                && blocker_user.phone_encrypted.is_some()
+                && phone_decrypted.is_none();
+            if phone_failed {
+                tracing::warn!(
+                    "Failed to decrypt phone of blocker {} (block {})",
+                    blocker_user.id,
+                    block.id
+                );
             }
+
+            result.push(BlockWithBlockerInfo {
+                id: block.id,
+                blocked_plate: block.blocked_plate,
+                created_at: block.created_at,
+                blocker: blocker_user.to_public_info(phone_decrypted),
+                blocker_owner_type: blocker_user.owner_type.clone(),
+                blocker_owner_info: blocker_user.public_owner_info(),
+                enrichment_failed: phone_failed,
+            });
         }
 
-        Ok(block)
+        result
     }
 
-    /// Получает блокировки пользователя
-    pub async fn get_my_blocks<BR: BlockRepository, UPR: UserPlateRepository>(
+    /// Страница всех действующих блокировок для охраны (новые сначала) и их общее количество
+    pub async fn get_active_blocks<BR: BlockRepository, UR: UserRepository>(
         &self,
-        blocker_id: Uuid,
+        limit: i64,
+        offset: i64,
         block_repository: &BR,
+        user_repository: &UR,
+    ) -> AppResult<(Vec<ActiveBlockInfo>, i64)> {
+        let (blocks, total) = block_repository.find_active(limit, offset).await?;
+
+        let mut blocker_ids: Vec<Uuid> = blocks.iter().map(|b| b.blocker_id).collect();
+        blocker_ids.sort_unstable();
+        blocker_ids.dedup();
+        let blockers: std::collections::HashMap<Uuid, User> = user_repository
+            .find_by_ids(&blocker_ids)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user))
+            .collect();
+
+        let mut result = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let Some(blocker_user) = blockers.get(&block.blocker_id) else {
+                continue;
+            };
+            let phone_decrypted = blocker_user.phone_encrypted.as_ref().and_then(|enc| {
+                self.encryption.decrypt_for_user(
+                    enc,
+                    &aad::user_phone(blocker_user.id),
+                    blocker_user.id,
+                )
+            });
+
+            result.push(ActiveBlockInfo {
+                id: block.id,
+                blocker_plate: block.blocker_plate,
+                blocked_plate: block.blocked_plate,
+                created_at: block.created_at,
+                expires_at: block.expires_at,
+                blocker: blocker_user.to_public_info(phone_decrypted),
+            });
+        }
+
+        Ok((result, total))
+    }
+
+    /// Проверяет, можно ли связаться с владельцами заблокированного авто, и почему нет
+    /// (чтобы блокирующий знал, что владельца придётся искать самостоятельно)
+    pub async fn get_block_notifiability<
+        BR: BlockRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        PTR: PushTokenRepository,
+    >(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        block_repository: &BR,
+        user_repository: &UR,
         user_plate_repository: &UPR,
-    ) -> AppResult<Vec<Block>> {
-        // Блокировки, созданные этим пользователем
-        let mut result = block_repository.find_by_blocker_id(blocker_id).await?;
-
-        // Блокировки, созданные владельцами того же автомобиля (по номеру)
-        let plates = user_plate_repository.find_by_user_id(blocker_id).await?;
-        let plate_strings: Vec<String> = plates.iter().map(|p| p.plate.clone()).collect();
-        let mut shared = block_repository
-            .find_by_blocker_plates(&plate_strings)
-            .await?;
+        push_token_repository: &PTR,
+    ) -> AppResult<BlockNotifiabilityResponse> {
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        AuthzService::require_block_blocker_or_coowner(
+            user_id,
+            &block,
+            user_plate_repository,
+            "You don't have permission to view this block",
+        )
+        .await?;
+
+        let now = chrono::Utc::now();
+        let mut owners = Vec::new();
+        for owner_id in self
+            .find_owner_ids(
+                &block.blocked_plate,
+                block.blocker_id,
+                user_plate_repository,
+            )
+            .await
+        {
+            let Some(owner) = user_repository.find_by_id(owner_id).await? else {
+                continue;
+            };
+            let push_token_stale = match owner.push_token.as_deref() {
+                Some(token) => push_token_repository
+                    .find_by_token(token)
+                    .await?
+                    .is_some_and(|push_token| push_token.is_stale(now)),
+                None => false,
+            };
+
+            let reachability = self.owner_reachability(&owner, push_token_stale);
+            owners.push(OwnerNotifiability {
+                reachable: !reachability.channels.is_empty(),
+                channels: reachability
+                    .channels
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                issues: reachability.issues.into_iter().map(String::from).collect(),
+            });
+        }
+
+        let owners_reachable = owners.iter().filter(|owner| owner.reachable).count();
+        if owners_reachable == 0 {
+            tracing::info!(
+                "Block {}: none of {} owners of {} can be notified",
+                block.id,
+                owners.len(),
+                block.blocked_plate
+            );
+        }
+
+        Ok(BlockNotifiabilityResponse {
+            block_id: block.id,
+            blocked_plate: block.blocked_plate,
+            owners_total: owners.len(),
+            owners_reachable,
+            owners,
+        })
+    }
+
+    /// Изменяет активную блокировку: время выезда (или продолжительность) и заметку.
+    /// При `notify_owners` владельцы получают уведомление с новым временем выезда
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_block<
+        BR: BlockRepository,
+        NR: NotificationRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
+    >(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        mut request: UpdateBlockRequest,
+        block_repository: &BR,
+        notification_repository: &NR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+        outbox_repository: &OR,
+    ) -> AppResult<Block> {
+        request.normalize();
+        let departure_time = ValidationService::validate_block_update(&request)?;
+
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        AuthzService::require_block_blocker_or_coowner(
+            user_id,
+            &block,
+            user_plate_repository,
+            "You don't have permission to update this block",
+        )
+        .await?;
+
+        let user = user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        // Время выезда указывается по местному времени блокирующего
+        let now = chrono::Utc::now();
+        let expires_at = match (departure_time, request.duration_minutes) {
+            (Some(time), _) => Some(next_local_occurrence(
+                now,
+                time,
+                user.utc_offset_minutes
+                    .unwrap_or(DEFAULT_UTC_OFFSET_MINUTES),
+            )),
+            (None, Some(minutes)) => Some(now + chrono::Duration::minutes(minutes)),
+            (None, None) => None,
+        };
+
+        // Как и при создании блокировки, время выезда сохраняем у основного номера блокирующего
+        if let Some(time) = departure_time {
+            if let Some(primary_plate) = user_plate_repository
+                .find_primary_by_user_id(user_id)
+                .await?
+            {
+                user_plate_repository
+                    .update_departure_time(primary_plate.id, user_id, Some(time))
+                    .await?;
+            }
+        }
+
+        let updated = block_repository
+            .update_block(
+                block_id,
+                &UpdateBlockData {
+                    note: request.note.clone(),
+                    expires_at,
+                },
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+        self.events.publish(&updated.blocked_plate);
 
-        result.append(&mut shared);
-        result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        result.dedup_by(|a, b| a.id == b.id);
+        tracing::info!(
+            "Block {} updated by user {}: expires_at={:?}",
+            block_id,
+            user_id,
+            updated.expires_at
+        );
+
+        if request.notify_owners {
+            let blocker_name = user.name.as_deref().unwrap_or("Неизвестно");
+            self.notify_owners_about_update(
+                &updated,
+                user_id,
+                blocker_name,
+                notification_repository,
+                user_repository,
+                user_plate_repository,
+                outbox_repository,
+            )
+            .await;
+        }
 
-        Ok(result)
+        Ok(updated)
     }
 
-    /// Получает блокировки для номера автомобиля пользователя
-    pub async fn get_blocks_for_my_plate<
-        BR: BlockRepository,
+    /// Уведомляет владельцев заблокированного авто о новом времени выезда и заметке.
+    /// Время выезда показывается каждому владельцу по его местному времени
+    #[allow(clippy::too_many_arguments)]
+    async fn notify_owners_about_update<
+        NR: NotificationRepository,
         UR: UserRepository,
         UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
     >(
         &self,
-        user_id: Uuid,
-        my_plate: Option<String>,
-        block_repository: &BR,
+        block: &Block,
+        updated_by: Uuid,
+        blocker_name: &str,
+        notification_repository: &NR,
         user_repository: &UR,
         user_plate_repository: &UPR,
-    ) -> AppResult<Vec<BlockWithBlockerInfo>> {
-        // Если указан конкретный номер, проверяем только его
-        if let Some(plate) = my_plate {
-            let normalized_plate = ValidationService::validate_plate(&plate)?;
-            return self
-                .get_blocks_for_plate(&normalized_plate, block_repository, user_repository)
-                .await;
+        outbox_repository: &OR,
+    ) {
+        // О тихой блокировке владельцы не узнают и при её изменении
+        if block.silent {
+            return;
         }
-
-        // Иначе проверяем все номера пользователя из user_plates
-        let user_plates = user_plate_repository.find_by_user_id(user_id).await?;
-
-        // Собираем все блокировки для всех номеров пользователя
-        let mut all_blocks = Vec::new();
-        let mut seen_block_ids = std::collections::HashSet::new();
-
-        for user_plate in user_plates {
-            let blocks = self
-                .get_blocks_for_plate(&user_plate.plate, block_repository, user_repository)
-                .await?;
-            for block in blocks {
-                // Избегаем дубликатов блокировок (если один номер заблокирован несколько раз)
-                if !seen_block_ids.contains(&block.id) {
-                    seen_block_ids.insert(block.id);
-                    all_blocks.push(block);
+        let mut outbox_messages = Vec::new();
+        for user_id in self
+            .find_owner_ids(&block.blocked_plate, updated_by, user_plate_repository)
+            .await
+        {
+            let Ok(Some(owner)) = user_repository.find_by_id(user_id).await else {
+                continue;
+            };
+
+            let mut message = match block.expires_at {
+                Some(expires_at) => {
+                    let offset = owner
+                        .utc_offset_minutes
+                        .unwrap_or(DEFAULT_UTC_OFFSET_MINUTES);
+                    format!(
+                        "{} перекрыл {} и уедет примерно в {}",
+                        blocker_name,
+                        block.blocked_plate,
+                        local_time(expires_at, offset).format("%H:%M")
+                    )
                 }
+                None => format!(
+                    "{} обновил информацию о блокировке {}",
+                    blocker_name, block.blocked_plate
+                ),
+            };
+            if let Some(note) = &block.note {
+                message.push_str(&format!(". {}", note));
             }
-        }
-
-        // Сортируем по дате создания (новые сначала)
-        all_blocks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-
-        Ok(all_blocks)
-    }
-
-    /// Вспомогательный метод для получения блокировок по номеру
-    async fn get_blocks_for_plate<BR: BlockRepository, UR: UserRepository>(
-        &self,
-        plate: &str,
-        block_repository: &BR,
-        user_repository: &UR,
-    ) -> AppResult<Vec<BlockWithBlockerInfo>> {
-        // Получаем блокировки
-        let blocks = block_repository.find_by_blocked_plate(plate).await?;
 
-        // Для каждой блокировки получаем информацию о блокирующем
-        let mut result = Vec::new();
-        for block in blocks {
-            if let Some(blocker_user) = user_repository.find_by_id(block.blocker_id).await? {
-                let phone_decrypted = blocker_user
-                    .phone_encrypted
-                    .as_ref()
-                    .and_then(|enc| self.encryption.decrypt(enc).ok());
+            let _ = notification_repository
+                .create(&CreateNotificationData {
+                    user_id,
+                    r#type: NotificationType::System,
+                    title: BLOCK_UPDATED_TITLE.to_string(),
+                    message: message.clone(),
+                    data: Some(serde_json::json!({
+                        "block_id": block.id,
+                        "blocked_plate": block.blocked_plate,
+                        "blocker_name": blocker_name,
+                        "expires_at": block.expires_at.as_ref().map(crate::utils::time::format_timestamp),
+                        "note": block.note,
+                    })),
+                })
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to create block update notification: {:?}", e);
+                });
 
-                result.push(BlockWithBlockerInfo {
-                    id: block.id,
-                    blocked_plate: block.blocked_plate,
-                    created_at: block.created_at,
-                    blocker: blocker_user.to_public_info(phone_decrypted),
-                    blocker_owner_type: blocker_user.owner_type.clone(),
-                    blocker_owner_info: blocker_user.owner_info.clone(),
+            if owner.push_token.is_some() {
+                outbox_messages.push(NewOutboxMessage {
+                    block_id: Some(block.id),
+                    user_id: Some(user_id),
+                    channel: OutboxChannel::Push,
+                    payload: serde_json::json!({
+                        "title": BLOCK_UPDATED_TITLE,
+                        "body": message,
+                        "data": {
+                            "block_id": block.id.to_string(),
+                            "blocked_plate": block.blocked_plate,
+                            "status": "updated",
+                        },
+                    }),
                 });
             }
         }
 
-        Ok(result)
+        if !outbox_messages.is_empty() {
+            if let Err(e) = outbox_repository.enqueue(&outbox_messages).await {
+                tracing::error!("Failed to enqueue block update pushes: {:?}", e);
+            }
+        }
     }
 
     /// Удаляет блокировку (только если пользователь является её создателем)
+    #[allow(clippy::too_many_arguments)]
     pub async fn delete_block<
         BR: BlockRepository,
         NR: NotificationRepository,
         UR: UserRepository,
         UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
     >(
         &self,
         block_id: Uuid,
@@ -454,6 +1241,7 @@ impl BlockService {
         notification_repository: &NR,
         user_repository: &UR,
         user_plate_repository: &UPR,
+        outbox_repository: &OR,
     ) -> AppResult<()> {
         // Проверяем, что блокировка существует
         let block = block_repository
@@ -461,26 +1249,33 @@ impl BlockService {
             .await?
             .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
 
-        // Получаем все номера пользователя для проверки прав
-        let user_plates = user_plate_repository.find_by_user_id(blocker_id).await?;
-        let user_plate_strings: Vec<String> = user_plates.iter().map(|p| p.plate.clone()).collect();
-
-        // Проверяем, что пользователь имеет право удалить блокировку (его номер должен совпадать с blocker_plate)
-        let normalized_blocker_plate = crate::utils::normalize_plate(&block.blocker_plate);
-        let has_permission = user_plate_strings.iter().any(|plate| {
-            crate::utils::normalize_plate(plate).eq_ignore_ascii_case(&normalized_blocker_plate)
-        });
-
-        if !has_permission {
-            return Err(AppError::Auth(
-                "You don't have permission to delete this block".to_string(),
-            ));
-        }
+        AuthzService::require_block_blocker_or_coowner(
+            blocker_id,
+            &block,
+            user_plate_repository,
+            "You don't have permission to delete this block",
+        )
+        .await?;
 
         // Выполняем удаление по номеру, а не по пользователю
         block_repository
             .delete(block_id, &block.blocker_plate)
             .await?;
+        self.events.publish(&block.blocked_plate);
+
+        self.analytics.track(
+            "block_deleted",
+            Some(blocker_id),
+            serde_json::json!({
+                "reason": block.reason,
+                "lifetime_minutes": (chrono::Utc::now() - block.created_at).num_minutes(),
+            }),
+        );
+
+        // О тихой блокировке владельцы не уведомлялись, не уведомляем и о снятии
+        if block.silent {
+            return Ok(());
+        }
 
         // Рассылаем уведомления и пуш владельцам, чьи машины были разблокированы
         let blocked_plate = block.blocked_plate.clone();
@@ -493,6 +1288,7 @@ impl BlockService {
             .unwrap_or_else(|| "Неизвестно".to_string());
 
         // Находим всех владельцев номера
+        let mut outbox_messages = Vec::new();
         if let Ok(user_plates) = user_plate_repository.find_by_plate(&blocked_plate).await {
             let mut notified_users = std::collections::HashSet::new();
 
@@ -505,12 +1301,16 @@ impl BlockService {
                 }
                 notified_users.insert(user_id);
 
-                if let Some(owner_user) = user_repository.find_by_id(user_id).await? {
+                if let Some(owner_user) = user_repository
+                    .find_by_id(user_id)
+                    .await?
+                    .filter(|owner| !owner.is_suspended())
+                {
                     // Сохраняем уведомление в БД
                     let _ = notification_repository
                         .create(&CreateNotificationData {
                             user_id,
-                            r#type: "unblock".to_string(),
+                            r#type: NotificationType::BlockDeleted,
                             title: "Автомобиль разблокирован".to_string(),
                             message: format!(
                                 "Автомобиль {} разблокирован пользователем {}",
@@ -530,27 +1330,36 @@ impl BlockService {
                         });
 
                     // Пуш-уведомление через FCM, если есть токен
-                    if let Some(push_token) = owner_user.push_token.clone() {
-                        let title = "Ваш авто разблокирован";
-                        let body =
-                            format!("{} больше не перекрывает {}.", blocker_name, blocked_plate);
-                        let data = serde_json::json!({
-                            "block_id": block_id.to_string(),
-                            "blocked_plate": blocked_plate,
-                            "blocker_name": blocker_name,
-                            "status": "unblocked"
-                        });
-                        let push = self.push_service.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = push.send_fcm(&push_token, title, &body, data).await {
-                                tracing::warn!("Failed to send FCM push (unblock): {}", e);
-                            }
+                    if owner_user.push_token.is_some() {
+                        outbox_messages.push(NewOutboxMessage {
+                            block_id: Some(block_id),
+                            user_id: Some(user_id),
+                            channel: OutboxChannel::Push,
+                            payload: serde_json::json!({
+                                "title": "Ваш авто разблокирован",
+                                "body": format!(
+                                    "{} больше не перекрывает {}.",
+                                    blocker_name, blocked_plate
+                                ),
+                                "data": {
+                                    "block_id": block_id.to_string(),
+                                    "blocked_plate": blocked_plate,
+                                    "blocker_name": blocker_name,
+                                    "status": "unblocked"
+                                },
+                            }),
                         });
                     }
                 }
             }
         }
 
+        if !outbox_messages.is_empty() {
+            if let Err(e) = outbox_repository.enqueue(&outbox_messages).await {
+                tracing::error!("Failed to enqueue unblock pushes: {:?}", e);
+            }
+        }
+
         Ok(())
     }
 
@@ -569,8 +1378,11 @@ impl BlockService {
 
         if blocks.is_empty() {
             return Ok(CheckBlockResponse {
+                input_plate: plate.to_string(),
+                normalized_plate,
                 is_blocked: false,
                 block: None,
+                is_own_plate: None,
             });
         }
 
@@ -582,12 +1394,17 @@ impl BlockService {
 
         // Получаем информацию о блокирующем
         if let Some(blocker_user) = user_repository.find_by_id(latest_block.blocker_id).await? {
-            let phone_decrypted = blocker_user
-                .phone_encrypted
-                .as_ref()
-                .and_then(|enc| self.encryption.decrypt(enc).ok());
+            let phone_decrypted = blocker_user.phone_encrypted.as_ref().and_then(|enc| {
+                self.encryption.decrypt_for_user(
+                    enc,
+                    &aad::user_phone(blocker_user.id),
+                    blocker_user.id,
+                )
+            });
 
             Ok(CheckBlockResponse {
+                input_plate: plate.to_string(),
+                normalized_plate,
                 is_blocked: true,
                 block: Some(BlockWithBlockerInfo {
                     id: latest_block.id,
@@ -595,25 +1412,61 @@ impl BlockService {
                     created_at: latest_block.created_at,
                     blocker: blocker_user.to_public_info(phone_decrypted),
                     blocker_owner_type: blocker_user.owner_type.clone(),
-                    blocker_owner_info: blocker_user.owner_info.clone(),
+                    blocker_owner_info: blocker_user.public_owner_info(),
+                    enrichment_failed: false,
                 }),
+                is_own_plate: None,
             })
         } else {
             Ok(CheckBlockResponse {
+                input_plate: plate.to_string(),
+                normalized_plate,
                 is_blocked: true,
                 block: None,
+                is_own_plate: None,
             })
         }
     }
 
+    /// То же, что `check_block`, но с признаком `is_own_plate`: принадлежит ли номер
+    /// проверяющему (чтобы приложение показало "это ваш автомобиль")
+    pub async fn check_block_for_user<
+        BR: BlockRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        plate: &str,
+        block_repository: &BR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+    ) -> AppResult<CheckBlockResponse> {
+        let mut response = self
+            .check_block(plate, block_repository, user_repository)
+            .await?;
+        let is_own_plate =
+            AuthzService::is_plate_owner(user_id, plate, user_plate_repository).await?;
+        response.is_own_plate = Some(is_own_plate);
+
+        Ok(response)
+    }
+
     /// Предупреждает владельца заблокированного автомобиля (звонок)
-    pub async fn warn_owner<BR: BlockRepository, UR: UserRepository, UPR: UserPlateRepository>(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn warn_owner<
+        BR: BlockRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
+    >(
         &self,
         block_id: Uuid,
         blocker_id: Uuid,
         block_repository: &BR,
         user_repository: &UR,
         user_plate_repository: &UPR,
+        outbox_repository: &OR,
         telephony_service: &TelephonyService,
     ) -> AppResult<()> {
         // Проверяем, что блокировка существует и принадлежит пользователю
@@ -622,11 +1475,13 @@ impl BlockService {
             .await?
             .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
 
-        if block.blocker_id != blocker_id {
-            return Err(AppError::Auth(
-                "You don't have permission to warn owner for this block".to_string(),
-            ));
-        }
+        AuthzService::require_block_blocker_or_coowner(
+            blocker_id,
+            &block,
+            user_plate_repository,
+            "You don't have permission to warn owner for this block",
+        )
+        .await?;
 
         // Получаем информацию о блокирующем
         let blocker_user = user_repository
@@ -649,35 +1504,74 @@ impl BlockService {
                 continue;
             }
 
-            // Находим пользователя и звоним ему
-            if let Some(owner_user) = user_repository.find_by_id(user_id).await? {
-                if let Some(phone_encrypted) = owner_user.phone_encrypted {
-                    if let Ok(phone) = self.encryption.decrypt(&phone_encrypted) {
-                        let message = telephony_service
-                            .format_block_notification_message(&block.blocked_plate, blocker_name);
-
-                        // Совершаем звонок в фоновом режиме (не блокируем ответ)
-                        let telephony_service_clone = telephony_service.clone();
-                        let phone_clone = phone.clone();
-                        let message_clone = message.clone();
-
-                        tokio::spawn(async move {
-                            if let Err(e) = telephony_service_clone
-                                .call_owner(&phone_clone, &message_clone)
-                                .await
-                            {
-                                tracing::error!("Failed to call owner {}: {}", phone_clone, e);
-                            }
-                        });
-
+            // Находим пользователя и звоним ему (заблокированным аккаунтам не звоним)
+            if let Some(owner_user) = user_repository
+                .find_by_id(user_id)
+                .await?
+                .filter(|owner| !owner.is_suspended())
+            {
+                let eta = self.local_eta(&block, &owner_user);
+                let message_context = MessageContext {
+                    plate: &block.blocked_plate,
+                    blocker: blocker_name,
+                    note: block.note.as_deref(),
+                    eta: eta.as_deref(),
+                };
+                let message = telephony_service.format_block_notification_message(&message_context);
+
+                // В тихие часы владельца (по его часовому поясу) вместо звонка отправляем push
+                if self.is_owner_quiet_hours(&owner_user) {
+                    if owner_user.push_token.is_none() {
                         tracing::info!(
-                            "Calling owner {} about block on {}",
-                            phone,
-                            block.blocked_plate
+                            "Quiet hours for owner {} and no push token, skipping warning",
+                            user_id
                         );
-                        called = true;
-                        break; // Звоним только первому найденному владельцу
+                        continue;
                     }
+                    outbox_repository
+                        .enqueue(&[NewOutboxMessage {
+                            block_id: Some(block.id),
+                            user_id: Some(user_id),
+                            channel: OutboxChannel::Push,
+                            payload: serde_json::json!({
+                                "title": self.render_message(MessageTemplate::PushTitle, &message_context),
+                                "body": message,
+                                "data": {
+                                    "block_id": block.id.to_string(),
+                                    "blocked_plate": block.blocked_plate,
+                                    "blocker_name": blocker_name,
+                                },
+                            }),
+                        }])
+                        .await?;
+
+                    tracing::info!(
+                        "Quiet hours for owner {}: sending push instead of call about block on {}",
+                        user_id,
+                        block.blocked_plate
+                    );
+                    called = true;
+                    break;
+                }
+
+                if owner_user.phone_encrypted.is_some() {
+                    // Звонок совершает очередь уведомлений (не блокируем ответ)
+                    outbox_repository
+                        .enqueue(&[NewOutboxMessage {
+                            block_id: Some(block.id),
+                            user_id: Some(user_id),
+                            channel: OutboxChannel::Call,
+                            payload: serde_json::json!({ "message": message }),
+                        }])
+                        .await?;
+
+                    tracing::info!(
+                        "Calling owner {} about block on {}",
+                        user_id,
+                        block.blocked_plate
+                    );
+                    called = true;
+                    break; // Звоним только первому найденному владельцу
                 }
             }
         }
@@ -692,4 +1586,440 @@ impl BlockService {
 
         Ok(())
     }
+
+    /// Владелец заблокированного автомобиля подтверждает, что увидел уведомление
+    /// (после этого эскалация по блокировке прекращается)
+    pub async fn acknowledge_block<BR: BlockRepository, UPR: UserPlateRepository>(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        block_repository: &BR,
+        user_plate_repository: &UPR,
+    ) -> AppResult<Block> {
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        AuthzService::require_plate_owner(
+            user_id,
+            &block.blocked_plate,
+            user_plate_repository,
+            "Only the owner of the blocked car can acknowledge this block",
+        )
+        .await?;
+
+        let acknowledged = block_repository
+            .acknowledge(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        if block.acknowledged_at.is_none() {
+            tracing::info!("Block {} acknowledged by owner {}", block_id, user_id);
+            self.analytics.track(
+                "block_acknowledged",
+                Some(user_id),
+                serde_json::json!({
+                    "seconds_since_created": (chrono::Utc::now() - block.created_at).num_seconds(),
+                }),
+            );
+        }
+
+        Ok(acknowledged)
+    }
+
+    /// Владелец заблокированного авто просит блокирующего перезвонить: уведомление в
+    /// приложении и через очередь доставки (push, Telegram). Телефон владельца передаётся,
+    /// только если он разрешил показывать контакты. Не чаще раза в
+    /// `CALLBACK_REQUEST_INTERVAL_SECS` по одной блокировке
+    #[allow(clippy::too_many_arguments)]
+    pub async fn request_callback<
+        BR: BlockRepository,
+        NR: NotificationRepository,
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
+    >(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        block_repository: &BR,
+        notification_repository: &NR,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+        outbox_repository: &OR,
+    ) -> AppResult<CallbackRequestResponse> {
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        AuthzService::require_plate_owner(
+            user_id,
+            &block.blocked_plate,
+            user_plate_repository,
+            "Only the owner of the blocked car can request a callback",
+        )
+        .await?;
+
+        let owner = user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let blocker = user_repository
+            .find_by_id(block.blocker_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Blocker not found".to_string()))?;
+
+        let interval = chrono::Duration::seconds(CALLBACK_REQUEST_INTERVAL_SECS);
+        if !block_repository
+            .mark_callback_requested(block_id, interval)
+            .await?
+        {
+            let retry_after_secs = block
+                .callback_requested_at
+                .map(|at| (at + interval - chrono::Utc::now()).num_seconds())
+                .unwrap_or(CALLBACK_REQUEST_INTERVAL_SECS)
+                .max(1) as u64;
+            return Err(AppError::RateLimited {
+                message: "Callback was requested recently".to_string(),
+                retry_after_secs,
+            });
+        }
+
+        // Контакт владельца - только с его согласия, иначе просьба без номера
+        let owner_phone = if owner.show_contacts {
+            owner.phone_encrypted.as_deref().and_then(|encrypted| {
+                self.encryption
+                    .decrypt_for_user(encrypted, &aad::user_phone(owner.id), owner.id)
+            })
+        } else {
+            None
+        };
+        let owner_name = owner.name.clone().unwrap_or_else(|| "Владелец".to_string());
+        let message = match &owner_phone {
+            Some(phone) => format!(
+                "{} ({}) просит вас перезвонить: {}",
+                owner_name, block.blocked_plate, phone
+            ),
+            None => format!(
+                "{} ({}) просит вас связаться с ним через приложение",
+                owner_name, block.blocked_plate
+            ),
+        };
+
+        let notification = notification_repository
+            .create(&CreateNotificationData {
+                user_id: blocker.id,
+                r#type: NotificationType::System,
+                title: CALLBACK_REQUEST_TITLE.to_string(),
+                message,
+                data: Some(serde_json::json!({
+                    "block_id": block.id.to_string(),
+                    "blocked_plate": block.blocked_plate,
+                    "owner_name": owner.name,
+                    "owner_phone": owner_phone,
+                    "status": "callback_requested",
+                })),
+            })
+            .await?;
+
+        let messages =
+            crate::service::notification_outbox::resend_messages(&notification, &blocker);
+        let mut channels_used = vec!["in_app".to_string()];
+        channels_used.extend(messages.iter().map(|m| m.channel.as_str().to_string()));
+        if !messages.is_empty() {
+            outbox_repository.enqueue(&messages).await?;
+        }
+
+        tracing::info!(
+            "Callback requested for block {} by owner {}",
+            block_id,
+            user_id
+        );
+        self.analytics.track(
+            "block_callback_requested",
+            Some(user_id),
+            serde_json::json!({ "phone_shared": owner_phone.is_some() }),
+        );
+
+        Ok(CallbackRequestResponse {
+            channels_used,
+            phone_shared: owner_phone.is_some(),
+        })
+    }
+
+    /// Выполняет шаг эскалации неподтверждённой блокировки: повторный push владельцам,
+    /// звонок владельцам (кроме их тихих часов) или звонок администратору дома
+    pub async fn escalate_block<
+        UR: UserRepository,
+        UPR: UserPlateRepository,
+        OR: NotificationOutboxRepository,
+    >(
+        &self,
+        block: &Block,
+        step: EscalationStep,
+        user_repository: &UR,
+        user_plate_repository: &UPR,
+        outbox_repository: &OR,
+        telephony_service: &TelephonyService,
+    ) -> AppResult<()> {
+        if step == EscalationStep::AdminContact {
+            return self.call_building_admin(block, outbox_repository).await;
+        }
+
+        let blocker_name = user_repository
+            .find_by_id(block.blocker_id)
+            .await?
+            .and_then(|u| u.name)
+            .unwrap_or_else(|| "Неизвестно".to_string());
+
+        let mut outbox_messages = Vec::new();
+        for user_id in self
+            .find_owner_ids(
+                &block.blocked_plate,
+                block.blocker_id,
+                user_plate_repository,
+            )
+            .await
+        {
+            let Some(owner) = user_repository.find_by_id(user_id).await? else {
+                continue;
+            };
+
+            if step == EscalationStep::RepeatPush {
+                if owner.push_token.is_none() {
+                    continue;
+                }
+                outbox_messages.push(NewOutboxMessage {
+                    block_id: Some(block.id),
+                    user_id: Some(user_id),
+                    channel: OutboxChannel::Push,
+                    payload: serde_json::json!({
+                        "title": BLOCK_ESCALATION_PUSH_TITLE,
+                        "body": format!(
+                            "{} перекрыл {} и ждёт ответа. Подтвердите, что видели уведомление.",
+                            blocker_name, block.blocked_plate
+                        ),
+                        "data": {
+                            "block_id": block.id.to_string(),
+                            "blocked_plate": block.blocked_plate,
+                            "blocker_name": blocker_name,
+                            "status": "escalation",
+                        },
+                    }),
+                });
+            } else {
+                if self.is_owner_quiet_hours(&owner) {
+                    tracing::info!(
+                        "Skipping escalation call to user {}: quiet hours in owner's timezone",
+                        user_id
+                    );
+                    continue;
+                }
+                if owner.phone_encrypted.is_none() {
+                    continue;
+                }
+                let eta = self.local_eta(block, &owner);
+                let message =
+                    telephony_service.format_block_notification_message(&MessageContext {
+                        plate: &block.blocked_plate,
+                        blocker: &blocker_name,
+                        note: block.note.as_deref(),
+                        eta: eta.as_deref(),
+                    });
+                outbox_messages.push(NewOutboxMessage {
+                    block_id: Some(block.id),
+                    user_id: Some(user_id),
+                    channel: OutboxChannel::Call,
+                    payload: serde_json::json!({ "message": message }),
+                });
+            }
+        }
+
+        if !outbox_messages.is_empty() {
+            outbox_repository.enqueue(&outbox_messages).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Последний шаг эскалации: звонок администратору дома (если его телефон настроен).
+    /// Сообщение ставится в очередь без получателя: телефон берётся из конфигурации при отправке
+    async fn call_building_admin<OR: NotificationOutboxRepository>(
+        &self,
+        block: &Block,
+        outbox_repository: &OR,
+    ) -> AppResult<()> {
+        if self.config.escalation_admin_phone.is_none() {
+            tracing::info!(
+                "Block {} is still unacknowledged, but ESCALATION_ADMIN_PHONE is not set",
+                block.id
+            );
+            return Ok(());
+        }
+        outbox_repository
+            .enqueue(&[NewOutboxMessage {
+                block_id: Some(block.id),
+                user_id: None,
+                channel: OutboxChannel::Call,
+                payload: serde_json::json!({
+                    "message": format!(
+                        "Владелец автомобиля {} не отвечает на уведомления. Его перекрыл автомобиль {}.",
+                        block.blocked_plate, block.blocker_plate
+                    ),
+                }),
+            }])
+            .await?;
+        tracing::info!(
+            "Escalating block {} to building admin: owner of {} did not respond",
+            block.id,
+            block.blocked_plate
+        );
+
+        Ok(())
+    }
+
+    /// Загружает фото-доказательство блокировки: сохраняет оригинал и JPEG превью
+    pub async fn upload_block_photo<
+        BR: BlockRepository,
+        UPR: UserPlateRepository,
+        BS: BlobStore,
+    >(
+        &self,
+        block_id: Uuid,
+        blocker_id: Uuid,
+        image_data: &[u8],
+        block_repository: &BR,
+        user_plate_repository: &UPR,
+        blob_store: &BS,
+    ) -> AppResult<()> {
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        AuthzService::require_block_blocker_or_coowner(
+            blocker_id,
+            &block,
+            user_plate_repository,
+            "You don't have permission to upload photo for this block",
+        )
+        .await?;
+
+        let format = crate::utils::image::detect_image_format(image_data)?;
+        let (extension, _) = crate::utils::image::image_format_info(format);
+
+        // Декодирование и масштабирование нагружают CPU, поэтому выносим из async контекста
+        let data = image_data.to_vec();
+        let thumbnail = tokio::task::spawn_blocking(move || {
+            crate::utils::image::make_thumbnail(&data, crate::utils::image::THUMBNAIL_MAX_SIZE)
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Thumbnail task failed: {}", e)))??;
+
+        let photo_key = format!("blocks/{}/original.{}", block_id, extension);
+        let thumb_key = format!("blocks/{}/thumb.jpg", block_id);
+
+        blob_store.put(&photo_key, image_data).await?;
+        blob_store.put(&thumb_key, &thumbnail).await?;
+
+        // Удаляем старый оригинал, если у нового другое расширение
+        if let Some(old_key) = block.photo_key.filter(|key| key != &photo_key) {
+            if let Err(e) = blob_store.delete(&old_key).await {
+                tracing::warn!("Failed to delete old block photo {}: {}", old_key, e);
+            }
+        }
+
+        block_repository
+            .set_photo(block_id, &photo_key, &thumb_key)
+            .await?;
+
+        tracing::info!(
+            "Photo uploaded for block {} ({} bytes, thumbnail {} bytes)",
+            block_id,
+            image_data.len(),
+            thumbnail.len()
+        );
+
+        Ok(())
+    }
+
+    /// Возвращает фото-доказательство блокировки и его MIME тип.
+    /// Доступно блокирующему и владельцам заблокированного номера
+    pub async fn get_block_photo<BR: BlockRepository, UPR: UserPlateRepository, BS: BlobStore>(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        size: BlockPhotoSize,
+        block_repository: &BR,
+        user_plate_repository: &UPR,
+        blob_store: &BS,
+    ) -> AppResult<(Vec<u8>, &'static str)> {
+        let block = self
+            .find_block_for_photo(block_id, user_id, block_repository, user_plate_repository)
+            .await?;
+
+        Self::read_block_photo(block, size, blob_store).await
+    }
+
+    /// Находит блокировку, фото которой может смотреть пользователь
+    /// (блокирующий или владелец заблокированного номера)
+    pub async fn find_block_for_photo<BR: BlockRepository, UPR: UserPlateRepository>(
+        &self,
+        block_id: Uuid,
+        user_id: Uuid,
+        block_repository: &BR,
+        user_plate_repository: &UPR,
+    ) -> AppResult<Block> {
+        let block = block_repository
+            .find_by_id(block_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+        let has_access =
+            AuthzService::is_block_blocker_or_coowner(user_id, &block, user_plate_repository)
+                .await?
+                || AuthzService::is_plate_owner(
+                    user_id,
+                    &block.blocked_plate,
+                    user_plate_repository,
+                )
+                .await?;
+
+        if !has_access {
+            return Err(AppError::Forbidden(
+                "You don't have permission to view photo for this block".to_string(),
+            ));
+        }
+
+        Ok(block)
+    }
+
+    /// Фото блокировки из хранилища без проверки прав
+    /// (права уже проверены, например, при выдаче подписанной ссылки)
+    pub async fn read_block_photo<BS: BlobStore>(
+        block: Block,
+        size: BlockPhotoSize,
+        blob_store: &BS,
+    ) -> AppResult<(Vec<u8>, &'static str)> {
+        let key = match size {
+            BlockPhotoSize::Thumb => block.photo_thumb_key,
+            BlockPhotoSize::Full => block.photo_key,
+        }
+        .ok_or_else(|| AppError::NotFound("Block photo not found".to_string()))?;
+
+        let data = blob_store
+            .get(&key)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Block photo not found".to_string()))?;
+
+        let content_type = if key.ends_with(".png") {
+            "image/png"
+        } else {
+            "image/jpeg"
+        };
+
+        Ok((data, content_type))
+    }
 }
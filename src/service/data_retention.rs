@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::error::AppResult;
+use crate::repository::{BlockRepository, NotificationRepository};
+
+/// Как часто запускается очистка устаревших данных
+pub const RETENTION_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Сколько строк удаляется одним запросом
+const PURGE_BATCH_SIZE: i64 = 1000;
+
+/// Итог одного прохода очистки
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RetentionReport {
+    pub notifications_deleted: u64,
+    pub block_history_deleted: u64,
+}
+
+/// Сроки хранения в днях (0 - хранить бессрочно)
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionPolicy {
+    pub notification_days: u32,
+    pub block_days: u32,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.notification_days > 0 || self.block_days > 0
+    }
+}
+
+/// Удаляет уведомления и историю снятых блокировок старше сроков хранения.
+/// Удаление идёт пачками, пока старых строк не останется
+pub async fn purge_expired_data<NR: NotificationRepository, BR: BlockRepository>(
+    policy: RetentionPolicy,
+    notification_repository: &NR,
+    block_repository: &BR,
+) -> AppResult<RetentionReport> {
+    let mut report = RetentionReport::default();
+
+    if policy.notification_days > 0 {
+        let before = Utc::now() - chrono::Duration::days(policy.notification_days as i64);
+        loop {
+            let deleted = notification_repository
+                .purge_older_than(before, PURGE_BATCH_SIZE)
+                .await?;
+            report.notifications_deleted += deleted;
+            if deleted < PURGE_BATCH_SIZE as u64 {
+                break;
+            }
+        }
+    }
+
+    if policy.block_days > 0 {
+        let before = Utc::now() - chrono::Duration::days(policy.block_days as i64);
+        loop {
+            let deleted = block_repository
+                .purge_history_older_than(before, PURGE_BATCH_SIZE)
+                .await?;
+            report.block_history_deleted += deleted;
+            if deleted < PURGE_BATCH_SIZE as u64 {
+                break;
+            }
+        }
+    }
+
+    if report.notifications_deleted > 0 || report.block_history_deleted > 0 {
+        tracing::info!(
+            "Retention purge: notifications deleted: {}, block history deleted: {}",
+            report.notifications_deleted,
+            report.block_history_deleted
+        );
+    } else {
+        tracing::debug!("Retention purge: nothing to delete");
+    }
+
+    Ok(report)
+}
+
+/// Запускает периодическую очистку устаревших данных в фоне
+pub fn spawn_data_retention<NR, BR>(
+    policy: RetentionPolicy,
+    notification_repository: NR,
+    block_repository: BR,
+) where
+    NR: NotificationRepository + 'static,
+    BR: BlockRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(RETENTION_PURGE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) =
+                purge_expired_data(policy, &notification_repository, &block_repository).await
+            {
+                tracing::error!("Retention purge failed: {:?}", e);
+            }
+        }
+    });
+}
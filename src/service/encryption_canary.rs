@@ -0,0 +1,72 @@
+use crate::error::{AppError, AppResult};
+use crate::repository::EncryptionCanaryRepository;
+use crate::utils::encryption::{aad, Encryption};
+
+/// Открытый текст контрольного значения
+const CANARY_PLAINTEXT: &str = "rimskiy-encryption-canary";
+
+/// Проверяет при запуске, что текущими ключами расшифровываются уже сохранённые данные.
+///
+/// При первом запуске сохраняет зашифрованное контрольное значение. Если позже его не удаётся
+/// расшифровать (ENCRYPTION_KEY сменили без ENCRYPTION_PREVIOUS_KEYS), возвращает ошибку,
+/// чтобы сервер не работал с нечитаемыми телефонами. `allow_mismatch` - осознанная смена
+/// ключа: контрольное значение перезаписывается новым ключом
+pub async fn verify_encryption_key<R: EncryptionCanaryRepository>(
+    encryption: &Encryption,
+    repository: &R,
+    allow_mismatch: bool,
+) -> AppResult<()> {
+    let encrypt_canary = || {
+        encryption
+            .encrypt(CANARY_PLAINTEXT, Some(aad::ENCRYPTION_CANARY))
+            .map_err(|e| AppError::Encryption(format!("Encryption self-test failed: {}", e)))
+    };
+
+    // Самопроверка: текущий ключ шифрует и расшифровывает
+    let probe = encrypt_canary()?;
+    let roundtrip = encryption
+        .decrypt(&probe, Some(aad::ENCRYPTION_CANARY))
+        .map_err(|e| AppError::Encryption(format!("Encryption self-test failed: {}", e)))?;
+    if roundtrip != CANARY_PLAINTEXT {
+        return Err(AppError::Encryption(
+            "Encryption self-test failed: decrypted value does not match".to_string(),
+        ));
+    }
+
+    let Some(stored) = repository.find().await? else {
+        repository.save(&probe).await?;
+        tracing::info!(
+            "Encryption canary stored (key version {})",
+            encryption.current_version()
+        );
+        return Ok(());
+    };
+
+    match encryption.decrypt(&stored, Some(aad::ENCRYPTION_CANARY)) {
+        Ok(value) if value == CANARY_PLAINTEXT => {
+            // Расшифровано предыдущим ключом: после ротации контрольное значение
+            // переводится на текущий ключ
+            let reencrypted = encryption
+                .re_encrypt(&stored, Some(aad::ENCRYPTION_CANARY))
+                .map_err(|e| AppError::Encryption(e.to_string()))?;
+            if let Some(reencrypted) = reencrypted {
+                repository.save(&reencrypted).await?;
+                tracing::info!(
+                    "Encryption canary re-encrypted with key version {}",
+                    encryption.current_version()
+                );
+            }
+            Ok(())
+        }
+        _ if allow_mismatch => {
+            tracing::warn!(
+                "Encryption canary cannot be decrypted with current keys; ENCRYPTION_ALLOW_KEY_MISMATCH is set, storing a new canary. Data encrypted with the old key stays unreadable"
+            );
+            repository.save(&probe).await?;
+            Ok(())
+        }
+        _ => Err(AppError::Encryption(
+            "ENCRYPTION_KEY does not match the key existing data was encrypted with. Restore the previous key, add it to ENCRYPTION_PREVIOUS_KEYS for rotation, or set ENCRYPTION_ALLOW_KEY_MISMATCH=true to accept the loss".to_string(),
+        )),
+    }
+}
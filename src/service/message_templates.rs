@@ -0,0 +1,270 @@
+//! Шаблоны сообщений о блокировке для всех каналов (в приложении, push, Telegram, звонок).
+//!
+//! Подстановки: `{plate}` - номер заблокированного автомобиля, `{blocker}` - имя блокирующего,
+//! `{note}` - причина и время выезда ("погрузка/разгрузка, до 18:30"), `{eta}` - время выезда (HH:MM).
+//! Текст в квадратных скобках выводится, только если все подстановки внутри него непустые:
+//! `"{blocker} перекрыл {plate}[ ({note})]."`
+
+use anyhow::{bail, Result};
+
+/// Сообщение, для которого задаётся шаблон
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageTemplate {
+    /// Заголовок уведомления в приложении
+    InAppTitle,
+    /// Текст уведомления в приложении
+    InApp,
+    /// Заголовок push-уведомления
+    PushTitle,
+    /// Текст push-уведомления
+    Push,
+    /// Сообщение в Telegram
+    Telegram,
+    /// Текст звонка владельцу
+    Call,
+}
+
+impl MessageTemplate {
+    pub const ALL: [MessageTemplate; 6] = [
+        MessageTemplate::InAppTitle,
+        MessageTemplate::InApp,
+        MessageTemplate::PushTitle,
+        MessageTemplate::Push,
+        MessageTemplate::Telegram,
+        MessageTemplate::Call,
+    ];
+
+    /// Переменная окружения, которой переопределяется шаблон
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            MessageTemplate::InAppTitle => "MESSAGE_TEMPLATE_IN_APP_TITLE",
+            MessageTemplate::InApp => "MESSAGE_TEMPLATE_IN_APP",
+            MessageTemplate::PushTitle => "MESSAGE_TEMPLATE_PUSH_TITLE",
+            MessageTemplate::Push => "MESSAGE_TEMPLATE_PUSH",
+            MessageTemplate::Telegram => "MESSAGE_TEMPLATE_TELEGRAM",
+            MessageTemplate::Call => "MESSAGE_TEMPLATE_CALL",
+        }
+    }
+
+    fn default_template(&self) -> &'static str {
+        match self {
+            MessageTemplate::InAppTitle => "Ваш автомобиль заблокирован",
+            MessageTemplate::InApp => "Автомобиль {plate} заблокирован пользователем {blocker}[ ({note})]",
+            MessageTemplate::PushTitle => "Ваш авто заблокирован",
+            MessageTemplate::Push => "{blocker} перекрыл {plate}[ ({note})].",
+            MessageTemplate::Telegram => {
+                "🚗 Ваш автомобиль {plate} заблокирован\n\n\
+                👤 Блокирующий: {blocker}\n\n\
+                [📝 Причина: {note}\n\n]\
+                📱 Проверьте приложение для подробностей"
+            }
+            MessageTemplate::Call => {
+                "Здравствуйте! Ваш автомобиль {plate} заблокирован пользователем {blocker}. Пожалуйста, проверьте приложение."
+            }
+        }
+    }
+}
+
+/// Значения подстановок для шаблона
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessageContext<'a> {
+    pub plate: &'a str,
+    pub blocker: &'a str,
+    pub note: Option<&'a str>,
+    pub eta: Option<&'a str>,
+}
+
+impl MessageContext<'_> {
+    fn value(&self, placeholder: &str) -> Option<&str> {
+        match placeholder {
+            "plate" => Some(self.plate),
+            "blocker" => Some(self.blocker),
+            "note" => self.note,
+            "eta" => self.eta,
+            _ => None,
+        }
+        .filter(|value| !value.is_empty())
+    }
+}
+
+/// Известные подстановки
+const PLACEHOLDERS: [&str; 4] = ["plate", "blocker", "note", "eta"];
+
+/// Часть разобранного шаблона
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+    /// Необязательный фрагмент в квадратных скобках
+    Optional(Vec<Part<'a>>),
+}
+
+/// Разбирает шаблон, проверяя подстановки и скобки
+fn parse(template: &str) -> Result<Vec<Part<'_>>> {
+    let mut parts = Vec::new();
+    let mut optional: Option<Vec<Part>> = None;
+    let mut rest = template;
+
+    while let Some(index) = rest.find(['{', '[', ']']) {
+        let target = optional.as_mut().unwrap_or(&mut parts);
+        if index > 0 {
+            target.push(Part::Text(&rest[..index]));
+        }
+        match rest.as_bytes()[index] {
+            b'{' => {
+                let Some(end) = rest[index..].find('}') else {
+                    bail!("unclosed '{{' in template: {}", template);
+                };
+                let name = &rest[index + 1..index + end];
+                if !PLACEHOLDERS.contains(&name) {
+                    bail!(
+                        "unknown placeholder {{{}}} in template (allowed: {{{}}}): {}",
+                        name,
+                        PLACEHOLDERS.join("}, {"),
+                        template
+                    );
+                }
+                target.push(Part::Placeholder(name));
+                rest = &rest[index + end + 1..];
+                continue;
+            }
+            b'[' => {
+                if optional.is_some() {
+                    bail!("nested '[' in template: {}", template);
+                }
+                optional = Some(Vec::new());
+            }
+            _ => match optional.take() {
+                Some(inner) => parts.push(Part::Optional(inner)),
+                None => bail!("unexpected ']' in template: {}", template),
+            },
+        }
+        rest = &rest[index + 1..];
+    }
+
+    if optional.is_some() {
+        bail!("unclosed '[' in template: {}", template);
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+
+    Ok(parts)
+}
+
+/// Подставляет значения. Возвращает None, если какая-то подстановка пустая
+fn render_parts(parts: &[Part], context: &MessageContext) -> Option<String> {
+    let mut result = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => result.push_str(text),
+            Part::Placeholder(name) => result.push_str(context.value(name)?),
+            Part::Optional(inner) => {
+                if let Some(text) = render_parts(inner, context) {
+                    result.push_str(&text);
+                }
+            }
+        }
+    }
+    Some(result)
+}
+
+/// Шаблоны сообщений о блокировке (`MESSAGE_TEMPLATE_*`, по умолчанию - стандартные тексты)
+#[derive(Debug, Clone)]
+pub struct MessageTemplates {
+    in_app_title: String,
+    in_app: String,
+    push_title: String,
+    push: String,
+    telegram: String,
+    call: String,
+}
+
+impl Default for MessageTemplates {
+    fn default() -> Self {
+        Self {
+            in_app_title: MessageTemplate::InAppTitle.default_template().to_string(),
+            in_app: MessageTemplate::InApp.default_template().to_string(),
+            push_title: MessageTemplate::PushTitle.default_template().to_string(),
+            push: MessageTemplate::Push.default_template().to_string(),
+            telegram: MessageTemplate::Telegram.default_template().to_string(),
+            call: MessageTemplate::Call.default_template().to_string(),
+        }
+    }
+}
+
+impl MessageTemplates {
+    /// Загружает шаблоны из переменных окружения; `\n` в значении заменяется переводом строки
+    pub fn from_env() -> Self {
+        let mut templates = Self::default();
+        for kind in MessageTemplate::ALL {
+            if let Ok(value) = std::env::var(kind.env_var()) {
+                templates = templates.with_template(kind, value.replace("\\n", "\n"));
+            }
+        }
+        templates
+    }
+
+    /// Заменяет шаблон сообщения
+    pub fn with_template(mut self, kind: MessageTemplate, template: impl Into<String>) -> Self {
+        *self.slot(kind) = template.into();
+        self
+    }
+
+    fn slot(&mut self, kind: MessageTemplate) -> &mut String {
+        match kind {
+            MessageTemplate::InAppTitle => &mut self.in_app_title,
+            MessageTemplate::InApp => &mut self.in_app,
+            MessageTemplate::PushTitle => &mut self.push_title,
+            MessageTemplate::Push => &mut self.push,
+            MessageTemplate::Telegram => &mut self.telegram,
+            MessageTemplate::Call => &mut self.call,
+        }
+    }
+
+    /// Шаблон сообщения
+    pub fn template(&self, kind: MessageTemplate) -> &str {
+        match kind {
+            MessageTemplate::InAppTitle => &self.in_app_title,
+            MessageTemplate::InApp => &self.in_app,
+            MessageTemplate::PushTitle => &self.push_title,
+            MessageTemplate::Push => &self.push,
+            MessageTemplate::Telegram => &self.telegram,
+            MessageTemplate::Call => &self.call,
+        }
+    }
+
+    /// Проверяет все шаблоны (вызывается при старте)
+    pub fn validate(&self) -> Result<()> {
+        for kind in MessageTemplate::ALL {
+            let template = self.template(kind);
+            if template.trim().is_empty() {
+                bail!("{} must not be empty", kind.env_var());
+            }
+            if let Err(e) = parse(template) {
+                bail!("{}: {}", kind.env_var(), e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Формирует сообщение по шаблону
+    pub fn render(&self, kind: MessageTemplate, context: &MessageContext) -> String {
+        let template = self.template(kind);
+        match parse(template) {
+            // Обязательная часть выводится и с пустыми подстановками
+            Ok(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    Part::Text(text) => text.to_string(),
+                    Part::Placeholder(name) => context.value(name).unwrap_or_default().to_string(),
+                    Part::Optional(inner) => render_parts(inner, context).unwrap_or_default(),
+                })
+                .collect(),
+            // Шаблоны проверяются при старте, сюда попасть не должны
+            Err(e) => {
+                tracing::error!("Invalid message template {:?}: {}", kind, e);
+                template.to_string()
+            }
+        }
+    }
+}
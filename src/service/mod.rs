@@ -1,12 +1,28 @@
+pub mod admin_service;
+pub mod analytics_service;
 pub mod auth_service;
+pub mod authz_service;
+pub mod block_escalation;
+pub mod block_events;
+pub mod block_export;
 pub mod block_service;
+pub mod data_retention;
+pub mod encryption_canary;
+pub mod message_templates;
+pub mod notification_outbox;
+pub mod plate_reconciliation;
 pub mod push_service;
+pub mod revoked_tokens;
 pub mod telegram_service;
 pub mod telephony_service;
 pub mod user_service;
+pub mod user_sessions;
 pub mod validation_service;
 
+pub use admin_service::AdminService;
+pub use analytics_service::AnalyticsService;
 pub use auth_service::AuthService;
+pub use authz_service::AuthzService;
 pub use block_service::BlockService;
 pub use push_service::PushService;
 pub use telegram_service::TelegramService;
@@ -0,0 +1,368 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::AppResult;
+use crate::models::notification::Notification;
+use crate::models::outbox::{OutboxChannel, OutboxMessage};
+use crate::models::user::User;
+use crate::repository::{
+    NewOutboxMessage, NotificationOutboxRepository, TelegramBotRepository, UserRepository,
+};
+use crate::service::{AnalyticsService, PushService, TelegramService, TelephonyService};
+use crate::utils::encryption::{aad, Encryption};
+
+/// Как часто проверять очередь уведомлений
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Сколько сообщений забирать за одну проверку
+const OUTBOX_BATCH_SIZE: i64 = 50;
+
+/// Сколько сообщение не выдаётся повторно после того, как его забрали на отправку
+const OUTBOX_LEASE_MINUTES: i64 = 5;
+
+/// Максимум попыток отправки, после которого сообщение переходит в "dead"
+pub const OUTBOX_MAX_ATTEMPTS: i32 = 5;
+
+/// Пауза перед первой повторной попыткой (дальше удваивается)
+const OUTBOX_RETRY_BASE_SECS: i64 = 30;
+
+/// Когда повторить отправку после неудачной попытки номер `attempts` (с единицы).
+/// None - попытки исчерпаны
+pub fn outbox_retry_at(attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= OUTBOX_MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.clamp(1, OUTBOX_MAX_ATTEMPTS) - 1;
+    Some(now + chrono::Duration::seconds(OUTBOX_RETRY_BASE_SECS << exponent))
+}
+
+/// Сообщения для повторной отправки уведомления из приложения его получателю:
+/// push (если есть токен) и Telegram (если указан), с исходными заголовком, текстом и `data`
+pub fn resend_messages(notification: &Notification, recipient: &User) -> Vec<NewOutboxMessage> {
+    let block_id = notification
+        .data
+        .as_ref()
+        .and_then(|data| data.get("block_id"))
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.parse().ok());
+    let mut data = notification
+        .data
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({}));
+    if let Some(fields) = data.as_object_mut() {
+        fields.insert(
+            "notification_id".to_string(),
+            serde_json::json!(notification.id),
+        );
+    }
+
+    let mut messages = Vec::new();
+    if recipient.push_token.is_some() {
+        messages.push(NewOutboxMessage {
+            block_id,
+            user_id: Some(recipient.id),
+            channel: OutboxChannel::Push,
+            payload: serde_json::json!({
+                "title": notification.title,
+                "body": notification.message,
+                "data": data,
+            }),
+        });
+    }
+    if recipient.telegram.is_some() {
+        messages.push(NewOutboxMessage {
+            block_id,
+            user_id: Some(recipient.id),
+            channel: OutboxChannel::Telegram,
+            payload: serde_json::json!({
+                "message": format!("{}\n\n{}", notification.title, notification.message),
+            }),
+        });
+    }
+
+    messages
+}
+
+/// Ошибка отправки сообщения из очереди
+struct DeliveryError {
+    message: String,
+    /// Повторять бессмысленно (например, у получателя больше нет push token)
+    permanent: bool,
+}
+
+impl DeliveryError {
+    fn retry(message: String) -> Self {
+        Self {
+            message,
+            permanent: false,
+        }
+    }
+
+    fn permanent(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            permanent: true,
+        }
+    }
+}
+
+/// Отправляет сообщения из очереди уведомлений через провайдеров (FCM, Telegram, телефония)
+#[derive(Clone)]
+pub struct OutboxDispatcher {
+    push_service: PushService,
+    telegram_service: TelegramService,
+    telephony_service: TelephonyService,
+    encryption: Encryption,
+    analytics: AnalyticsService,
+}
+
+impl OutboxDispatcher {
+    pub fn new(
+        push_service: PushService,
+        telegram_service: TelegramService,
+        telephony_service: TelephonyService,
+        encryption: Encryption,
+        analytics: AnalyticsService,
+    ) -> Self {
+        Self {
+            push_service,
+            telegram_service,
+            telephony_service,
+            encryption,
+            analytics,
+        }
+    }
+
+    /// Отправляет одно сообщение. Адрес получателя берётся из его текущего профиля,
+    /// телефон администратора дома - из конфигурации
+    async fn send<UR: UserRepository, TR: TelegramBotRepository>(
+        &self,
+        message: &OutboxMessage,
+        channel: OutboxChannel,
+        user_repository: &UR,
+        telegram_bot_repository: &TR,
+    ) -> Result<(), DeliveryError> {
+        let text = |key: &str| {
+            message
+                .payload
+                .get(key)
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        // Без получателя-пользователя ставится только звонок администратору дома
+        let Some(user_id) = message.user_id else {
+            if channel != OutboxChannel::Call {
+                return Err(DeliveryError::permanent("Message has no recipient"));
+            }
+            let phone = self
+                .telephony_service
+                .building_admin_phone()
+                .ok_or_else(|| DeliveryError::permanent("ESCALATION_ADMIN_PHONE is not set"))?;
+            return self
+                .telephony_service
+                .call_owner(phone, &text("message"))
+                .await
+                .map_err(DeliveryError::retry);
+        };
+
+        let user = user_repository
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| DeliveryError::retry(format!("Failed to load recipient: {:?}", e)))?
+            .ok_or_else(|| DeliveryError::permanent("Recipient not found"))?;
+
+        match channel {
+            OutboxChannel::Push => {
+                let token = user
+                    .push_token
+                    .ok_or_else(|| DeliveryError::permanent("Recipient has no push token"))?;
+                let data = message
+                    .payload
+                    .get("data")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                self.push_service
+                    .send_fcm(&token, &text("title"), &text("body"), data)
+                    .await
+                    .map_err(DeliveryError::retry)
+            }
+            OutboxChannel::Telegram => {
+                let chat_ids = match user.phone_hash.as_deref() {
+                    Some(phone_hash) => telegram_bot_repository
+                        .find_chat_ids_by_phone_hash(phone_hash)
+                        .await
+                        .map_err(|e| {
+                            DeliveryError::retry(format!("Failed to load Telegram chats: {:?}", e))
+                        })?,
+                    None => Vec::new(),
+                };
+                if chat_ids.is_empty() {
+                    // Пользователь не писал боту: пробуем по username из профиля
+                    let username = user
+                        .telegram
+                        .ok_or_else(|| DeliveryError::permanent("Recipient has no Telegram"))?;
+                    return self
+                        .telegram_service
+                        .send_message(&username, &text("message"))
+                        .await
+                        .map_err(DeliveryError::retry);
+                }
+                self.send_to_chats(&chat_ids, &text("message")).await
+            }
+            OutboxChannel::Call => {
+                let phone = user
+                    .phone_encrypted
+                    .as_deref()
+                    .and_then(|encrypted| {
+                        self.encryption.decrypt_for_user(
+                            encrypted,
+                            &aad::user_phone(user.id),
+                            user.id,
+                        )
+                    })
+                    .ok_or_else(|| DeliveryError::permanent("Recipient has no phone"))?;
+                self.telephony_service
+                    .call_owner(&phone, &text("message"))
+                    .await
+                    .map_err(DeliveryError::retry)
+            }
+        }
+    }
+
+    /// Отправляет сообщение во все чаты получателя (у него может быть несколько аккаунтов
+    /// Telegram). Доставка удалась, если сообщение дошло хотя бы в один чат
+    async fn send_to_chats(&self, chat_ids: &[i64], message: &str) -> Result<(), DeliveryError> {
+        let mut errors = Vec::new();
+        for &chat_id in chat_ids {
+            if let Err(e) = self.telegram_service.send_to_chat(chat_id, message).await {
+                errors.push(format!("chat {}: {}", chat_id, e));
+            }
+        }
+
+        if errors.len() == chat_ids.len() {
+            return Err(DeliveryError::retry(errors.join("; ")));
+        }
+        if !errors.is_empty() {
+            tracing::warn!(
+                "Telegram message delivered to {} of {} chats: {}",
+                chat_ids.len() - errors.len(),
+                chat_ids.len(),
+                errors.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Однократно забирает подошедшие сообщения из очереди и отправляет их.
+    /// Возвращает количество доставленных
+    pub async fn deliver_due<OR, UR, TR>(
+        &self,
+        outbox_repository: &OR,
+        user_repository: &UR,
+        telegram_bot_repository: &TR,
+    ) -> AppResult<usize>
+    where
+        OR: NotificationOutboxRepository,
+        UR: UserRepository,
+        TR: TelegramBotRepository,
+    {
+        let messages = outbox_repository
+            .claim_due(
+                OUTBOX_BATCH_SIZE,
+                chrono::Duration::minutes(OUTBOX_LEASE_MINUTES),
+            )
+            .await?;
+
+        let mut delivered = 0;
+        for message in messages {
+            let result = match OutboxChannel::parse(&message.channel) {
+                Some(channel) => {
+                    self.send(&message, channel, user_repository, telegram_bot_repository)
+                        .await
+                }
+                None => Err(DeliveryError::permanent(format!(
+                    "Unknown channel: {}",
+                    message.channel
+                ))),
+            };
+
+            match result {
+                Ok(()) => {
+                    outbox_repository.mark_delivered(message.id).await?;
+                    self.track_delivery(&message.channel, true);
+                    delivered += 1;
+                }
+                Err(error) => {
+                    let retry_at = if error.permanent {
+                        None
+                    } else {
+                        outbox_retry_at(message.attempts, Utc::now())
+                    };
+                    match retry_at {
+                        Some(retry_at) => tracing::warn!(
+                            "Outbox {} ({}) attempt {} failed, retry at {}: {}",
+                            message.id,
+                            message.channel,
+                            message.attempts,
+                            retry_at,
+                            error.message
+                        ),
+                        None => {
+                            tracing::error!(
+                                "Outbox {} ({}) dead after {} attempts: {}",
+                                message.id,
+                                message.channel,
+                                message.attempts,
+                                error.message
+                            );
+                            self.track_delivery(&message.channel, false);
+                        }
+                    }
+                    outbox_repository
+                        .mark_failed(message.id, &error.message, retry_at)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    fn track_delivery(&self, channel: &str, success: bool) {
+        if let Some(channel) = OutboxChannel::parse(channel) {
+            self.analytics.track_delivery(channel.as_str(), success);
+        }
+    }
+}
+
+/// Запускает фоновую отправку уведомлений из очереди
+pub fn spawn_outbox_worker<OR, UR, TR>(
+    dispatcher: OutboxDispatcher,
+    outbox_repository: OR,
+    user_repository: UR,
+    telegram_bot_repository: TR,
+) where
+    OR: NotificationOutboxRepository + 'static,
+    UR: UserRepository + 'static,
+    TR: TelegramBotRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(OUTBOX_POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = dispatcher
+                .deliver_due(
+                    &outbox_repository,
+                    &user_repository,
+                    &telegram_bot_repository,
+                )
+                .await
+            {
+                tracing::error!("Notification outbox delivery failed: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use crate::error::AppResult;
+use crate::repository::user_plate_repository::{PlateReconcileReport, UserPlateRepository};
+
+/// Однократно сверяет users.plate с основными номерами из user_plates и исправляет расхождения
+pub async fn reconcile_plates<RP: UserPlateRepository>(
+    user_plate_repository: &RP,
+) -> AppResult<PlateReconcileReport> {
+    let report = user_plate_repository.reconcile_primary_plates().await?;
+
+    if report.total() > 0 {
+        tracing::info!(
+            "Plate reconciliation corrected {} rows: plates created: {}, primaries promoted: {}, users synced: {}",
+            report.total(),
+            report.plates_created,
+            report.primaries_promoted,
+            report.users_synced
+        );
+    } else {
+        tracing::debug!("Plate reconciliation: no drift found");
+    }
+
+    Ok(report)
+}
+
+/// Запускает периодическую сверку номеров в фоне
+pub fn spawn_plate_reconciliation<RP>(user_plate_repository: RP, interval: Duration)
+where
+    RP: UserPlateRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = reconcile_plates(&user_plate_repository).await {
+                tracing::error!("Plate reconciliation failed: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use crate::error::AppResult;
+use crate::repository::RevokedTokenRepository;
+
+/// Как часто удаляются записи об истёкших отозванных токенах
+pub const REVOKED_TOKEN_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Однократно удаляет записи о токенах, которые уже нельзя использовать
+pub async fn purge_revoked_tokens<RR: RevokedTokenRepository>(
+    revoked_token_repository: &RR,
+) -> AppResult<u64> {
+    let deleted = revoked_token_repository.purge_expired().await?;
+    if deleted > 0 {
+        tracing::info!("Removed {} expired revoked tokens", deleted);
+    }
+    Ok(deleted)
+}
+
+/// Запускает периодическую очистку отозванных токенов в фоне
+pub fn spawn_revoked_token_cleanup<RR>(revoked_token_repository: RR)
+where
+    RR: RevokedTokenRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(REVOKED_TOKEN_CLEANUP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = purge_revoked_tokens(&revoked_token_repository).await {
+                tracing::error!("Revoked token cleanup failed: {:?}", e);
+            }
+        }
+    });
+}
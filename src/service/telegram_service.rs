@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::service::message_templates::{MessageContext, MessageTemplate, MessageTemplates};
 use reqwest::Client;
 use serde_json::json;
 
@@ -6,18 +7,42 @@ use serde_json::json;
 #[derive(Clone)]
 pub struct TelegramService {
     bot_token: Option<String>,
+    /// Username бота без "@" (TELEGRAM_BOT_USERNAME)
+    bot_username: Option<String>,
+    /// Базовый адрес Bot API (TELEGRAM_API_URL)
+    api_url: String,
     client: Client,
+    message_templates: MessageTemplates,
 }
 
 impl TelegramService {
-    pub fn new(_config: &Config) -> Self {
+    pub fn new(config: &Config) -> Self {
         let bot_token = std::env::var("TELEGRAM_BOT_TOKEN").ok();
+        let bot_username = std::env::var("TELEGRAM_BOT_USERNAME")
+            .ok()
+            .map(|username| username.trim().trim_start_matches('@').to_string())
+            .filter(|username| !username.is_empty());
         Self {
             bot_token,
+            bot_username,
+            api_url: config.telegram_api_url.clone(),
             client: Client::new(),
+            message_templates: config.message_templates.clone(),
         }
     }
 
+    /// Username бота (без "@"), если задан TELEGRAM_BOT_USERNAME
+    pub fn bot_username(&self) -> Option<&str> {
+        self.bot_username.as_deref()
+    }
+
+    /// Ссылка, открывающая диалог с ботом (чтобы пользователь нажал /start)
+    pub fn bot_start_link(&self) -> Option<String> {
+        self.bot_username
+            .as_ref()
+            .map(|username| format!("https://t.me/{}", username))
+    }
+
     /// Отправляет уведомление о блокировке через Telegram
     ///
     /// Примечание: Для отправки сообщений через Telegram Bot API нужен chat_id пользователя.
@@ -27,9 +52,20 @@ impl TelegramService {
     pub async fn send_block_notification(
         &self,
         telegram_username: &str,
-        blocked_plate: &str,
-        blocker_name: &str,
+        context: &MessageContext<'_>,
     ) -> Result<(), String> {
+        let message = self.format_block_notification(context);
+        self.send_message(telegram_username, &message).await
+    }
+
+    /// Формирует текст Telegram-уведомления о блокировке (шаблон `MESSAGE_TEMPLATE_TELEGRAM`)
+    pub fn format_block_notification(&self, context: &MessageContext<'_>) -> String {
+        self.message_templates
+            .render(MessageTemplate::Telegram, context)
+    }
+
+    /// Отправляет произвольное текстовое сообщение пользователю по username
+    pub async fn send_message(&self, telegram_username: &str, message: &str) -> Result<(), String> {
         let token = match &self.bot_token {
             Some(t) if !t.is_empty() => t,
             _ => {
@@ -38,17 +74,9 @@ impl TelegramService {
             }
         };
 
-        // Формируем сообщение
-        let message = format!(
-            "🚗 Ваш автомобиль {} заблокирован\n\n\
-            👤 Блокирующий: {}\n\n\
-            📱 Проверьте приложение для подробностей",
-            blocked_plate, blocker_name
-        );
-
         // Отправляем через Telegram Bot API
         // Пытаемся отправить по username (работает только если пользователь начал диалог с ботом)
-        let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
+        let url = format!("{}/bot{}/sendMessage", self.api_url, token);
         let clean_username = telegram_username.trim_start_matches('@');
 
         let response = self
@@ -79,4 +107,34 @@ impl TelegramService {
             Ok(())
         }
     }
+
+    /// Отправляет сообщение в чат по chat_id (известен для пользователей, написавших боту).
+    /// В отличие от `send_message`, сообщает об ошибке: используется там, где доставка обязательна
+    pub async fn send_to_chat(&self, chat_id: i64, message: &str) -> Result<(), String> {
+        let token = match &self.bot_token {
+            Some(t) if !t.is_empty() => t,
+            _ => return Err("TELEGRAM_BOT_TOKEN not configured".to_string()),
+        };
+
+        let url = format!("{}/bot{}/sendMessage", self.api_url, token);
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({
+                "chat_id": chat_id,
+                "text": message
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Telegram API request failed: {}", e))?;
+
+        let status = response.status();
+        if status.is_success() {
+            tracing::info!("Telegram message sent to chat {}", chat_id);
+            Ok(())
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(format!("Telegram API error: {} - {}", status, error_text))
+        }
+    }
 }
@@ -1,10 +1,10 @@
 use crate::config::Config;
+use crate::service::message_templates::{MessageContext, MessageTemplate};
 use reqwest::Client;
 
 /// Сервис для звонков через API телефонии
 #[derive(Clone)]
 pub struct TelephonyService {
-    #[allow(dead_code)] // Поле может использоваться в будущем для конфигурации
     config: Config,
     client: Client,
 }
@@ -20,26 +20,23 @@ impl TelephonyService {
     /// Совершает звонок и проигрывает сообщение
     pub async fn call_owner(&self, phone: &str, message: &str) -> Result<(), String> {
         // Проверяем, есть ли настройки API телефонии
-        let telephony_api_url = std::env::var("TELEPHONY_API_URL").ok();
-        let telephony_api_key = std::env::var("TELEPHONY_API_KEY").ok();
-
-        if telephony_api_url.is_none() || telephony_api_key.is_none() {
+        let (Some(telephony_api_url), Some(telephony_api_key)) = (
+            &self.config.telephony_api_url,
+            &self.config.telephony_api_key,
+        ) else {
             tracing::warn!("Telephony provider not configured. Set TELEPHONY_API_URL and TELEPHONY_API_KEY environment variables");
             tracing::info!("[DEV MODE] Would call {} with message: {}", phone, message);
             // В dev режиме продолжаем без ошибки
             return Ok(());
-        }
+        };
 
         tracing::info!("Calling {} with message: {}", phone, message);
 
         // Пример использования API телефонии (можно адаптировать под любой провайдер, например Twilio)
         let response = self
             .client
-            .post(telephony_api_url.as_ref().unwrap())
-            .header(
-                "Authorization",
-                format!("Bearer {}", telephony_api_key.as_ref().unwrap()),
-            )
+            .post(telephony_api_url)
+            .header("Authorization", format!("Bearer {}", telephony_api_key))
             .json(&serde_json::json!({
                 "phone": phone,
                 "message": message,
@@ -60,16 +57,15 @@ impl TelephonyService {
         Ok(())
     }
 
-    /// Формирует сообщение для звонка владельцу о блокировке
-    pub fn format_block_notification_message(
-        &self,
-        blocked_plate: &str,
-        blocker_name: &str,
-    ) -> String {
-        format!(
-            "Здравствуйте! Ваш автомобиль {} заблокирован пользователем {}. Пожалуйста, проверьте приложение.",
-            blocked_plate,
-            blocker_name
-        )
+    /// Телефон администратора дома для последнего шага эскалации (`ESCALATION_ADMIN_PHONE`)
+    pub fn building_admin_phone(&self) -> Option<&str> {
+        self.config.escalation_admin_phone.as_deref()
+    }
+
+    /// Формирует сообщение для звонка владельцу о блокировке (шаблон `MESSAGE_TEMPLATE_CALL`)
+    pub fn format_block_notification_message(&self, context: &MessageContext<'_>) -> String {
+        self.config
+            .message_templates
+            .render(MessageTemplate::Call, context)
     }
 }
@@ -1,20 +1,43 @@
 use crate::error::{AppError, AppResult};
-use crate::models::user::{UpdateUserRequest, UserResponse};
-use crate::repository::{UpdateUserData, UserPlateRepository, UserRepository};
+use crate::models::notification::NotificationType;
+use crate::models::user::{
+    AccountDeletionResponse, ReencryptResponse, TelegramReachabilityResponse, UpdateUserRequest,
+    UserResponse, MAX_PLATES_PER_LOOKUP,
+};
+use crate::models::user_plate::{
+    claim_status, plate_share_status, plate_transfer_status, CheckPlateResponse, ClaimPlateRequest,
+    ClaimPlateResponse, PlateShareInviteResponse, PlateTransferRequestResponse, SharePlateRequest,
+    UserPlateResponse,
+};
+use crate::repository::{
+    BlobStore, BlockRepository, CreateNotificationData, NotificationRepository,
+    PlateShareInviteRepository, PlateTransferRequestRepository, TelegramBotRepository,
+    UpdateUserData, UserPlateRepository, UserRepository,
+};
+use crate::service::push_service::PushService;
+use crate::service::telegram_service::TelegramService;
 use crate::service::validation_service::ValidationService;
-use crate::utils::encryption::Encryption;
+use crate::utils::encryption::{aad, Encryption};
+use crate::utils::normalize_plate;
+use crate::utils::plate::mask_plate;
+use crate::utils::time::{MAX_UTC_OFFSET_MINUTES, MIN_UTC_OFFSET_MINUTES};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 /// Сервис работы с пользователями (SRP)
 #[derive(Clone)]
 pub struct UserService {
     encryption: Encryption,
+    mask_public_plates: bool,
 }
 
 impl UserService {
-    pub fn new(encryption: Encryption) -> Self {
-        Self { encryption }
+    pub fn new(encryption: Encryption, mask_public_plates: bool) -> Self {
+        Self {
+            encryption,
+            mask_public_plates,
+        }
     }
 
     fn phone_hash(phone: &str) -> String {
@@ -31,42 +54,16 @@ impl UserService {
         user_plate_repository: &RP,
     ) -> AppResult<UserResponse> {
         tracing::info!("get_profile called for user_id: {}", user_id);
-        let mut user = repository.find_by_id(user_id).await?.ok_or_else(|| {
+        let user = repository.find_by_id(user_id).await?.ok_or_else(|| {
             tracing::error!("User not found: {}", user_id);
             AppError::NotFound("User not found".to_string())
         })?;
         tracing::info!("User found: {} (plate: {:?})", user_id, user.plate);
 
-        // Получаем актуальный основной номер из user_plates (источник истины)
-        if let Ok(Some(primary_plate)) =
-            user_plate_repository.find_primary_by_user_id(user_id).await
-        {
-            // Синхронизируем номер пользователя из основного автомобиля
-            if user.plate.as_deref() != Some(primary_plate.plate.as_str()) {
-                tracing::info!(
-                    "Syncing user {} plate from user_plates: {:?} -> {}",
-                    user_id,
-                    user.plate,
-                    primary_plate.plate
-                );
-                // Обновляем номер в users для обратной совместимости
-                let update_data = UpdateUserData {
-                    name: None,
-                    phone_encrypted: None,
-                    phone_hash: None,
-                    telegram: None,
-                    plate: Some(primary_plate.plate.clone()),
-                    show_contacts: None,
-                    owner_type: None,
-                    owner_info: None,
-                    departure_time: None, // Не изменяем время выезда при синхронизации
-                    push_token: None,
-                };
-                if let Ok(updated) = repository.update(user_id, &update_data).await {
-                    user = updated;
-                }
-            }
-        } else {
+        // Номер в профиле уже берётся из основного автомобиля в user_plates
+        // (представление users_with_primary_plate), поэтому синхронизация при чтении не нужна.
+        // Для старых пользователей без записей в user_plates создаём основной номер из users.plate
+        if let Ok(None) = user_plate_repository.find_primary_by_user_id(user_id).await {
             // Если нет основного автомобиля, создаем его из users.plate (если номер есть и валиден)
             if let Some(ref plate) = user.plate {
                 tracing::info!(
@@ -104,10 +101,10 @@ impl UserService {
             }
         }
 
-        let phone_decrypted = user
-            .phone_encrypted
-            .as_ref()
-            .and_then(|enc| self.encryption.decrypt(enc).ok());
+        let phone_decrypted = user.phone_encrypted.as_ref().and_then(|enc| {
+            self.encryption
+                .decrypt_for_user(enc, &aad::user_phone(user.id), user.id)
+        });
 
         Ok(user.to_response(phone_decrypted))
     }
@@ -131,6 +128,15 @@ impl UserService {
             request.owner_info.is_some()
         );
 
+        // Раньше push токен можно было передать в профиле; молча игнорировать его нельзя,
+        // иначе старый клиент решит, что токен сохранён
+        if request.push_token.is_some() {
+            return Err(AppError::Validation(
+                "push_token не обновляется через профиль, используйте POST /api/users/push-token"
+                    .to_string(),
+            ));
+        }
+
         // Валидация полей вручную (проверяем только непустые значения)
         if let Some(ref name) = request.name {
             if !name.is_empty() && name.len() > 20 {
@@ -147,10 +153,8 @@ impl UserService {
         }
 
         if let Some(ref telegram) = request.telegram {
-            if !telegram.is_empty() && telegram.len() > 32 {
-                return Err(AppError::Validation(
-                    "Telegram username должен быть до 32 символов".to_string(),
-                ));
+            if !telegram.trim().is_empty() {
+                ValidationService::validate_telegram(telegram)?;
             }
         }
 
@@ -160,9 +164,23 @@ impl UserService {
             }
         }
 
+        // Сведения о собственнике сохраняются в виде RenterOwnerInfo
+        // (для owner_type = "owner" репозиторий их очищает)
+        let owner_info = request
+            .owner_info
+            .as_ref()
+            .map(ValidationService::validate_owner_info)
+            .transpose()?;
+
         // Нормализация данных
         let mut normalized_request = request;
         normalized_request.normalize();
+        if let Some(owner_info) = owner_info {
+            normalized_request.owner_info =
+                Some(serde_json::to_value(owner_info).map_err(|e| {
+                    AppError::Internal(format!("Failed to serialize owner_info: {}", e))
+                })?);
+        }
 
         // Преобразуем пустые строки в None для корректной обработки
         if let Some(ref name) = normalized_request.name {
@@ -185,7 +203,7 @@ impl UserService {
         let (phone_encrypted, phone_hash) = if let Some(phone) = normalized_request.phone {
             let enc = self
                 .encryption
-                .encrypt(&phone)
+                .encrypt(&phone, Some(&aad::user_phone(user_id)))
                 .map_err(|e| AppError::Encryption(e.to_string()))?;
             let hash = Self::phone_hash(&phone);
             (Some(enc), Some(hash))
@@ -194,13 +212,19 @@ impl UserService {
         };
 
         // ВСЕГДА синхронизируем номер автомобиля с user_plates
+        // Запись номера и обновление пользователя выполняются в одной транзакции,
+        // чтобы ошибка на любом шаге не оставила их рассинхронизированными
+        let mut tx = repository.begin().await?;
+
         // Если plate передан - обновляем/создаем основной автомобиль
         if let Some(new_plate) = &normalized_request.plate {
             let normalized_plate = crate::utils::normalize_plate(new_plate);
 
-            // Находим текущий основной автомобиль
-            if let Ok(Some(primary_plate)) =
-                user_plate_repository.find_primary_by_user_id(user_id).await
+            // Находим текущий основной автомобиль (в транзакции, чтобы параллельное
+            // обновление профиля не создало второй основной номер)
+            if let Some(primary_plate) = user_plate_repository
+                .find_primary_by_user_id_in_tx(&mut tx, user_id)
+                .await?
             {
                 if primary_plate.plate != normalized_plate {
                     // Номер изменился - обновляем основной автомобиль
@@ -212,19 +236,21 @@ impl UserService {
                     );
 
                     // Проверяем, есть ли уже такой номер у пользователя
-                    let existing_plates = user_plate_repository.find_by_user_id(user_id).await?;
+                    let existing_plates = user_plate_repository
+                        .find_by_user_id_in_tx(&mut tx, user_id)
+                        .await?;
                     let existing_plate =
                         existing_plates.iter().find(|p| p.plate == normalized_plate);
 
                     if let Some(existing) = existing_plate {
                         // Номер уже существует - делаем его основным
                         user_plate_repository
-                            .set_primary(existing.id, user_id)
+                            .set_primary_in_tx(&mut tx, existing.id, user_id)
                             .await?;
                     } else {
                         // Создаем новый номер как основной
                         user_plate_repository
-                            .create(user_id, &normalized_plate, true, None)
+                            .create_in_tx(&mut tx, user_id, &normalized_plate, true, None)
                             .await?;
                     }
                 }
@@ -236,13 +262,14 @@ impl UserService {
                     normalized_plate
                 );
                 user_plate_repository
-                    .create(user_id, &normalized_plate, true, None)
+                    .create_in_tx(&mut tx, user_id, &normalized_plate, true, None)
                     .await?;
             }
         } else {
             // Если plate не передан, используем номер из основного автомобиля
-            if let Ok(Some(primary_plate)) =
-                user_plate_repository.find_primary_by_user_id(user_id).await
+            if let Some(primary_plate) = user_plate_repository
+                .find_primary_by_user_id_in_tx(&mut tx, user_id)
+                .await?
             {
                 tracing::info!(
                     "No plate in request, using primary plate for user {}: {}",
@@ -258,14 +285,20 @@ impl UserService {
             if time_str.is_empty() {
                 None
             } else {
-                chrono::NaiveTime::parse_from_str(time_str, "%H:%M")
-                    .map_err(|_| AppError::Validation("Неверный формат времени выезда. Используйте формат HH:MM (например, 18:30)".to_string()))?
-                    .into()
+                Some(ValidationService::parse_departure_time(time_str)?)
             }
         } else {
             None
         };
 
+        if let Some(Some(offset)) = normalized_request.utc_offset_minutes {
+            if !(MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&offset) {
+                return Err(AppError::Validation(
+                    "Смещение часового пояса должно быть от -720 до 840 минут".to_string(),
+                ));
+            }
+        }
+
         // Обновление в БД
         let update_data = UpdateUserData {
             name: normalized_request.name,
@@ -278,25 +311,43 @@ impl UserService {
             owner_info: normalized_request.owner_info,
             departure_time,
             push_token: None,
+            announcement_push: normalized_request.announcement_push,
+            announcement_telegram: normalized_request.announcement_telegram,
+            utc_offset_minutes: normalized_request.utc_offset_minutes,
         };
 
-        let updated_user = repository.update(user_id, &update_data).await?;
+        let updated_user = repository
+            .update_in_tx(&mut tx, user_id, &update_data)
+            .await?;
+        tx.commit().await?;
 
         // Расшифровка телефона для ответа
-        let phone_decrypted = updated_user
-            .phone_encrypted
-            .as_ref()
-            .and_then(|enc| self.encryption.decrypt(enc).ok());
+        let phone_decrypted = updated_user.phone_encrypted.as_ref().and_then(|enc| {
+            self.encryption.decrypt_for_user(
+                enc,
+                &aad::user_phone(updated_user.id),
+                updated_user.id,
+            )
+        });
 
         Ok(updated_user.to_response(phone_decrypted))
     }
 
     /// Получает публичную информацию о пользователе по номеру автомобиля
-    pub async fn get_user_by_plate<R: UserRepository, RP: UserPlateRepository>(
+    ///
+    /// При включённом `MASK_PUBLIC_PLATES` номер частично скрывается,
+    /// если запрашивающий не владелец номера и не участник блокировки с ним
+    pub async fn get_user_by_plate<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        BR: BlockRepository,
+    >(
         &self,
+        viewer_id: Uuid,
         plate: &str,
         repository: &R,
         user_plate_repository: &RP,
+        block_repository: &BR,
     ) -> AppResult<Option<crate::models::user::PublicUserInfo>> {
         let normalized_plate = ValidationService::validate_plate(plate)?;
 
@@ -308,15 +359,907 @@ impl UserService {
         // Берем первого пользователя с этим номером (обычно он один)
         if let Some(user_plate) = user_plates.first() {
             if let Some(user) = repository.find_by_id(user_plate.user_id).await? {
-                let phone_decrypted = user
-                    .phone_encrypted
-                    .as_ref()
-                    .and_then(|enc| self.encryption.decrypt(enc).ok());
+                let phone_decrypted = user.phone_encrypted.as_ref().and_then(|enc| {
+                    self.encryption
+                        .decrypt_for_user(enc, &aad::user_phone(user.id), user.id)
+                });
 
-                return Ok(Some(user.to_public_info(phone_decrypted)));
+                let mut info = user.to_public_info(phone_decrypted);
+                if self.mask_public_plates {
+                    let is_owner = user_plates.iter().any(|up| up.user_id == viewer_id);
+                    let is_related = is_owner
+                        || self
+                            .is_block_participant(
+                                viewer_id,
+                                &normalized_plate,
+                                user_plate_repository,
+                                block_repository,
+                            )
+                            .await?;
+                    if !is_related {
+                        info.plate = mask_plate(&info.plate);
+                    }
+                }
+
+                return Ok(Some(info));
             }
         }
 
         Ok(None)
     }
+
+    /// Публичная информация о владельцах нескольких номеров: нормализованный номер -> владелец
+    /// (null, если номер не зарегистрирован). Контакты и маскирование номера - как в
+    /// `get_user_by_plate`
+    pub async fn get_users_by_plates<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        BR: BlockRepository,
+    >(
+        &self,
+        viewer_id: Uuid,
+        plates: &[String],
+        repository: &R,
+        user_plate_repository: &RP,
+        block_repository: &BR,
+    ) -> AppResult<BTreeMap<String, Option<crate::models::user::PublicUserInfo>>> {
+        if plates.len() > MAX_PLATES_PER_LOOKUP {
+            return Err(AppError::Validation(format!(
+                "Не более {} номеров за один запрос",
+                MAX_PLATES_PER_LOOKUP
+            )));
+        }
+        let mut normalized_plates = plates
+            .iter()
+            .map(|plate| ValidationService::validate_plate(plate))
+            .collect::<AppResult<Vec<String>>>()?;
+        normalized_plates.sort();
+        normalized_plates.dedup();
+
+        let user_plates = user_plate_repository
+            .find_by_plates(&normalized_plates)
+            .await?;
+        let mut owner_ids: Vec<Uuid> = user_plates.iter().map(|up| up.user_id).collect();
+        owner_ids.sort();
+        owner_ids.dedup();
+        let owners = repository.find_by_ids(&owner_ids).await?;
+        let viewer_plates = if self.mask_public_plates {
+            user_plate_repository.find_by_user_id(viewer_id).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut result = BTreeMap::new();
+        for plate in normalized_plates {
+            let plate_owners: Vec<_> = user_plates
+                .iter()
+                .filter(|up| normalize_plate(&up.plate) == plate)
+                .collect();
+            // Берем первого пользователя с этим номером (обычно он один)
+            let owner = plate_owners
+                .first()
+                .and_then(|up| owners.iter().find(|user| user.id == up.user_id));
+            let Some(owner) = owner else {
+                result.insert(plate, None);
+                continue;
+            };
+
+            let phone_decrypted = owner.phone_encrypted.as_ref().and_then(|enc| {
+                self.encryption
+                    .decrypt_for_user(enc, &aad::user_phone(owner.id), owner.id)
+            });
+            let mut info = owner.to_public_info(phone_decrypted);
+            if self.mask_public_plates {
+                let mut is_related = plate_owners.iter().any(|up| up.user_id == viewer_id);
+                for viewer_plate in &viewer_plates {
+                    if is_related {
+                        break;
+                    }
+                    is_related = block_repository.exists(&viewer_plate.plate, &plate).await?
+                        || block_repository.exists(&plate, &viewer_plate.plate).await?;
+                }
+                if !is_related {
+                    info.plate = mask_plate(&info.plate);
+                }
+            }
+            result.insert(plate, Some(info));
+        }
+
+        Ok(result)
+    }
+
+    /// Проверяет, участвует ли пользователь (любым своим номером) в блокировке с указанным номером
+    async fn is_block_participant<RP: UserPlateRepository, BR: BlockRepository>(
+        &self,
+        user_id: Uuid,
+        plate: &str,
+        user_plate_repository: &RP,
+        block_repository: &BR,
+    ) -> AppResult<bool> {
+        let user_plates = user_plate_repository.find_by_user_id(user_id).await?;
+        for user_plate in &user_plates {
+            if block_repository.exists(&user_plate.plate, plate).await?
+                || block_repository.exists(plate, &user_plate.plate).await?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Перешифровывает зашифрованные данные пользователя (телефон) текущей версией ключа
+    /// с привязкой к контексту поля (AAD).
+    /// Ничего не делает, если данные уже зашифрованы текущим ключом с этим контекстом
+    pub async fn reencrypt_user_data<R: UserRepository>(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+    ) -> AppResult<ReencryptResponse> {
+        let user = repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let phone_encrypted = match user.phone_encrypted.as_deref() {
+            Some(ciphertext) => self
+                .encryption
+                .re_encrypt(ciphertext, Some(&aad::user_phone(user_id)))
+                .map_err(|e| AppError::Encryption(e.to_string()))?,
+            None => None,
+        };
+
+        let reencrypted = phone_encrypted.is_some();
+        if reencrypted {
+            let update = UpdateUserData {
+                name: None,
+                phone_encrypted,
+                phone_hash: None,
+                telegram: None,
+                plate: None,
+                show_contacts: None,
+                owner_type: None,
+                owner_info: None,
+                departure_time: None,
+                push_token: None,
+                announcement_push: None,
+                announcement_telegram: None,
+                utc_offset_minutes: None,
+            };
+            repository.update(user_id, &update).await?;
+            tracing::info!(
+                "Re-encrypted data for user {} with key version {}",
+                user_id,
+                self.encryption.current_version()
+            );
+        }
+
+        Ok(ReencryptResponse {
+            reencrypted,
+            key_version: self.encryption.current_version(),
+        })
+    }
+
+    /// Проверяет, может ли Telegram бот писать пользователю: для этого пользователь должен
+    /// запустить бота и отправить ему свой контакт (регистрация в telegram_bot_users по phone_hash)
+    pub async fn telegram_reachability<R: UserRepository, TR: TelegramBotRepository>(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+        telegram_bot_repository: &TR,
+        telegram_service: &TelegramService,
+    ) -> AppResult<TelegramReachabilityResponse> {
+        let user = repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let reachable = match user.phone_hash.as_deref() {
+            Some(phone_hash) => telegram_bot_repository
+                .find_by_phone_hash(phone_hash)
+                .await?
+                .is_some(),
+            None => false,
+        };
+
+        Ok(TelegramReachabilityResponse {
+            reachable,
+            bot_username: telegram_service.bot_username().map(str::to_string),
+            start_link: if reachable {
+                None
+            } else {
+                telegram_service.bot_start_link()
+            },
+        })
+    }
+
+    /// Удаляет аккаунт со всеми данными пользователя. Блокировки других пользователей
+    /// его автомобилей остаются: это данные тех, кто их создал.
+    /// Строки БД удаляются одной транзакцией, файлы - после неё: ошибка удаления файла
+    /// только пишется в лог и не учитывается в `files`
+    pub async fn delete_account<R: UserRepository, BS: BlobStore>(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+        blob_store: &BS,
+    ) -> AppResult<AccountDeletionResponse> {
+        let deleted = repository
+            .delete_with_related(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let mut files = 0;
+        for key in &deleted.blob_keys {
+            match blob_store.delete(key).await {
+                Ok(()) => files += 1,
+                Err(e) => tracing::warn!("Failed to delete file {} of deleted user: {}", key, e),
+            }
+        }
+
+        tracing::info!(
+            "User {} deleted account: {} plates, {} blocks, {} notifications, {} telegram registrations",
+            user_id,
+            deleted.plates,
+            deleted.blocks,
+            deleted.notifications,
+            deleted.telegram_registrations
+        );
+
+        Ok(AccountDeletionResponse {
+            plates: deleted.plates,
+            blocks: deleted.blocks,
+            block_history: deleted.block_history,
+            notifications: deleted.notifications,
+            push_tokens: deleted.push_tokens,
+            sessions: deleted.sessions,
+            telegram_registrations: deleted.telegram_registrations,
+            files,
+        })
+    }
+
+    /// Загружает аватар пользователя: сохраняет уменьшенную JPEG копию
+    /// (большая сторона не более `THUMBNAIL_MAX_SIZE`) и возвращает ссылку на неё
+    pub async fn upload_avatar<R: UserRepository, BS: BlobStore>(
+        &self,
+        user_id: Uuid,
+        image_data: &[u8],
+        repository: &R,
+        blob_store: &BS,
+    ) -> AppResult<String> {
+        let user = repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        crate::utils::image::detect_image_format(image_data)?;
+
+        // Декодирование и масштабирование нагружают CPU, поэтому выносим из async контекста
+        let data = image_data.to_vec();
+        let avatar = tokio::task::spawn_blocking(move || {
+            crate::utils::image::make_thumbnail(&data, crate::utils::image::THUMBNAIL_MAX_SIZE)
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Thumbnail task failed: {}", e)))??;
+
+        // Новый ключ при каждой загрузке, чтобы клиенты не показывали старый аватар из кэша
+        let avatar_key = format!("avatars/{}/{}.jpg", user_id, Uuid::new_v4());
+        blob_store.put(&avatar_key, &avatar).await?;
+        repository.set_avatar(user_id, Some(&avatar_key)).await?;
+
+        if let Some(old_key) = user.avatar_key {
+            if let Err(e) = blob_store.delete(&old_key).await {
+                tracing::warn!("Failed to delete old avatar {}: {}", old_key, e);
+            }
+        }
+
+        tracing::info!(
+            "Avatar uploaded for user {} ({} bytes, stored {} bytes)",
+            user_id,
+            image_data.len(),
+            avatar.len()
+        );
+
+        Ok(format!("/api/users/{}/avatar", user_id))
+    }
+
+    /// Возвращает аватар пользователя (JPEG). Чужой аватар доступен,
+    /// только если его владелец разрешил показывать контакты
+    pub async fn get_avatar<R: UserRepository, BS: BlobStore>(
+        &self,
+        user_id: Uuid,
+        requester_id: Uuid,
+        repository: &R,
+        blob_store: &BS,
+    ) -> AppResult<Vec<u8>> {
+        self.check_avatar_access(user_id, requester_id, repository)
+            .await?;
+
+        self.read_avatar(user_id, repository, blob_store).await
+    }
+
+    /// Проверяет, что у пользователя есть аватар, видимый запрашивающему
+    pub async fn check_avatar_access<R: UserRepository>(
+        &self,
+        user_id: Uuid,
+        requester_id: Uuid,
+        repository: &R,
+    ) -> AppResult<()> {
+        repository
+            .find_by_id(user_id)
+            .await?
+            .filter(|user| user.id == requester_id || user.show_contacts)
+            .filter(|user| user.avatar_key.is_some())
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound("Avatar not found".to_string()))
+    }
+
+    /// Аватар пользователя из хранилища без проверки видимости
+    /// (права уже проверены, например, при выдаче подписанной ссылки)
+    pub async fn read_avatar<R: UserRepository, BS: BlobStore>(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+        blob_store: &BS,
+    ) -> AppResult<Vec<u8>> {
+        let avatar_key = repository
+            .find_by_id(user_id)
+            .await?
+            .and_then(|user| user.avatar_key)
+            .ok_or_else(|| AppError::NotFound("Avatar not found".to_string()))?;
+
+        blob_store
+            .get(&avatar_key)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Avatar not found".to_string()))
+    }
+
+    /// Заявка на номер (смена владельца автомобиля). Свободный номер добавляется сразу,
+    /// номер других пользователей - только после согласия одного из владельцев:
+    /// создаётся заявка, владельцы получают уведомление
+    #[allow(clippy::too_many_arguments)]
+    pub async fn claim_plate<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        TR: PlateTransferRequestRepository,
+        NR: NotificationRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        mut request: ClaimPlateRequest,
+        repository: &R,
+        user_plate_repository: &RP,
+        transfer_repository: &TR,
+        notification_repository: &NR,
+        push_service: &PushService,
+    ) -> AppResult<ClaimPlateResponse> {
+        request.normalize();
+        let normalized_plate = ValidationService::validate_plate(&request.plate)?;
+
+        let current_owners = user_plate_repository
+            .find_by_plate(&normalized_plate)
+            .await?;
+
+        if current_owners.iter().all(|p| p.user_id == user_id) {
+            // Номер никому больше не принадлежит - просто добавляем его пользователю
+            if let Some(existing) = current_owners.first() {
+                return Ok(ClaimPlateResponse {
+                    status: claim_status::CLAIMED.to_string(),
+                    plate: Some(existing.to_response()),
+                    transfer_request: None,
+                });
+            }
+            let has_primary = user_plate_repository
+                .find_primary_by_user_id(user_id)
+                .await?
+                .is_some();
+            let user_plate = user_plate_repository
+                .create(user_id, &normalized_plate, !has_primary, None)
+                .await?;
+            return Ok(ClaimPlateResponse {
+                status: claim_status::CLAIMED.to_string(),
+                plate: Some(user_plate.to_response()),
+                transfer_request: None,
+            });
+        }
+
+        let (transfer_request, created) = transfer_repository
+            .create(&normalized_plate, user_id)
+            .await?;
+        // Повторная заявка не уведомляет владельцев ещё раз
+        if created {
+            tracing::info!(
+                "User {} requested transfer of plate {} ({})",
+                user_id,
+                normalized_plate,
+                transfer_request.id
+            );
+            let mut owner_ids: Vec<Uuid> = current_owners
+                .iter()
+                .map(|p| p.user_id)
+                .filter(|id| *id != user_id)
+                .collect();
+            owner_ids.sort();
+            owner_ids.dedup();
+            for owner_id in owner_ids {
+                self.notify_plate_transfer(
+                    owner_id,
+                    "Запрос на передачу автомобиля",
+                    &format!(
+                        "Другой пользователь сообщает, что теперь владеет автомобилем {}. \
+                         Подтвердите или отклоните передачу в приложении",
+                        normalized_plate
+                    ),
+                    serde_json::json!({
+                        "transfer_request_id": transfer_request.id,
+                        "plate": normalized_plate,
+                        "status": "transfer_requested"
+                    }),
+                    repository,
+                    notification_repository,
+                    push_service,
+                )
+                .await;
+            }
+        }
+
+        Ok(ClaimPlateResponse {
+            status: claim_status::PENDING_APPROVAL.to_string(),
+            plate: None,
+            transfer_request: Some(transfer_request.to_response(None)),
+        })
+    }
+
+    /// Входящие заявки на передачу номеров текущего пользователя, ожидающие решения
+    pub async fn get_plate_transfer_requests<
+        R: UserRepository,
+        TR: PlateTransferRequestRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+        transfer_repository: &TR,
+    ) -> AppResult<Vec<PlateTransferRequestResponse>> {
+        let requests = transfer_repository.find_pending_for_owner(user_id).await?;
+
+        // Заявителей загружаем одним запросом
+        let mut claimant_ids: Vec<Uuid> = requests.iter().map(|r| r.claimant_id).collect();
+        claimant_ids.sort();
+        claimant_ids.dedup();
+        let claimants = repository.find_by_ids(&claimant_ids).await?;
+
+        Ok(requests
+            .iter()
+            .map(|request| {
+                let claimant = claimants
+                    .iter()
+                    .find(|user| user.id == request.claimant_id)
+                    .map(|user| {
+                        let phone_decrypted = user.phone_encrypted.as_ref().and_then(|enc| {
+                            self.encryption.decrypt_for_user(
+                                enc,
+                                &aad::user_phone(user.id),
+                                user.id,
+                            )
+                        });
+                        user.to_public_info(phone_decrypted)
+                    });
+                request.to_response(claimant)
+            })
+            .collect())
+    }
+
+    /// Одобряет заявку: в одной транзакции номер удаляется у всех текущих владельцев
+    /// и добавляется заявителю, после чего участники получают уведомления
+    #[allow(clippy::too_many_arguments)]
+    pub async fn approve_plate_transfer<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        TR: PlateTransferRequestRepository,
+        NR: NotificationRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        request_id: Uuid,
+        repository: &R,
+        user_plate_repository: &RP,
+        transfer_repository: &TR,
+        notification_repository: &NR,
+        push_service: &PushService,
+    ) -> AppResult<PlateTransferRequestResponse> {
+        let mut tx = transfer_repository.begin().await?;
+        let request = transfer_repository
+            .respond_in_tx(
+                &mut tx,
+                request_id,
+                user_id,
+                plate_transfer_status::APPROVED,
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("Transfer request not found".to_string()))?;
+        let transfer = user_plate_repository
+            .transfer_in_tx(&mut tx, &request.plate, request.claimant_id)
+            .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "User {} approved transfer {} of plate {} to user {} (previous owners: {})",
+            user_id,
+            request.id,
+            request.plate,
+            request.claimant_id,
+            transfer.previous_owner_ids.len()
+        );
+
+        for previous_owner_id in transfer
+            .previous_owner_ids
+            .iter()
+            .filter(|id| **id != user_id)
+        {
+            self.notify_plate_transfer(
+                *previous_owner_id,
+                "Автомобиль передан другому владельцу",
+                &format!(
+                    "Номер {} передан новому владельцу и удалён из вашего профиля",
+                    request.plate
+                ),
+                serde_json::json!({
+                    "plate": request.plate,
+                    "status": "transferred"
+                }),
+                repository,
+                notification_repository,
+                push_service,
+            )
+            .await;
+        }
+        self.notify_plate_transfer(
+            request.claimant_id,
+            "Передача автомобиля подтверждена",
+            &format!("Номер {} добавлен в ваш профиль", request.plate),
+            serde_json::json!({
+                "transfer_request_id": request.id,
+                "plate": request.plate,
+                "status": "transfer_approved"
+            }),
+            repository,
+            notification_repository,
+            push_service,
+        )
+        .await;
+
+        Ok(request.to_response(None))
+    }
+
+    /// Отклоняет заявку на передачу номера и уведомляет заявителя
+    pub async fn decline_plate_transfer<
+        R: UserRepository,
+        TR: PlateTransferRequestRepository,
+        NR: NotificationRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        request_id: Uuid,
+        repository: &R,
+        transfer_repository: &TR,
+        notification_repository: &NR,
+        push_service: &PushService,
+    ) -> AppResult<()> {
+        let mut tx = transfer_repository.begin().await?;
+        let request = transfer_repository
+            .respond_in_tx(
+                &mut tx,
+                request_id,
+                user_id,
+                plate_transfer_status::DECLINED,
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("Transfer request not found".to_string()))?;
+        tx.commit().await?;
+
+        self.notify_plate_transfer(
+            request.claimant_id,
+            "Передача автомобиля отклонена",
+            &format!("Владелец номера {} отклонил передачу", request.plate),
+            serde_json::json!({
+                "transfer_request_id": request.id,
+                "plate": request.plate,
+                "status": "transfer_declined"
+            }),
+            repository,
+            notification_repository,
+            push_service,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Уведомление участнику передачи номера (в приложении и push). Ошибки только логируются:
+    /// передача уже состоялась и не должна откатываться из-за недоставленного уведомления
+    #[allow(clippy::too_many_arguments)]
+    async fn notify_plate_transfer<R: UserRepository, NR: NotificationRepository>(
+        &self,
+        user_id: Uuid,
+        title: &str,
+        message: &str,
+        data: serde_json::Value,
+        repository: &R,
+        notification_repository: &NR,
+        push_service: &PushService,
+    ) {
+        let _ = notification_repository
+            .create(&CreateNotificationData {
+                user_id,
+                r#type: NotificationType::System,
+                title: title.to_string(),
+                message: message.to_string(),
+                data: Some(data.clone()),
+            })
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create plate transfer notification: {:?}", e);
+            });
+
+        if let Ok(Some(user)) = repository.find_by_id(user_id).await {
+            if let Some(push_token) = user.push_token {
+                let title = title.to_string();
+                let body = message.to_string();
+                let push = push_service.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = push.send_fcm(&push_token, &title, &body, data).await {
+                        tracing::warn!("Failed to send FCM push (plate transfer): {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Получает публичную информацию о совладельцах автомобиля пользователя
+    pub async fn get_co_owners<RP: UserPlateRepository>(
+        &self,
+        user_id: Uuid,
+        plate_id: Uuid,
+        user_plate_repository: &RP,
+    ) -> AppResult<Vec<crate::models::user::PublicUserInfo>> {
+        let user_plate = user_plate_repository
+            .find_by_id(plate_id)
+            .await?
+            .filter(|p| p.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound("User plate not found".to_string()))?;
+
+        let co_owners = user_plate_repository
+            .find_co_owners(&user_plate.plate, user_id)
+            .await?;
+
+        Ok(co_owners
+            .iter()
+            .map(|user| {
+                let phone_decrypted = user.phone_encrypted.as_ref().and_then(|enc| {
+                    self.encryption
+                        .decrypt_for_user(enc, &aad::user_phone(user.id), user.id)
+                });
+                user.to_public_info(phone_decrypted)
+            })
+            .collect())
+    }
+
+    /// Проверяет, зарегистрирован ли номер у текущего пользователя и/или у других
+    pub async fn check_plate<RP: UserPlateRepository>(
+        &self,
+        user_id: Uuid,
+        plate: &str,
+        user_plate_repository: &RP,
+    ) -> AppResult<CheckPlateResponse> {
+        let normalized = ValidationService::validate_plate(plate)?;
+
+        let owners = user_plate_repository.find_by_plate(&normalized).await?;
+
+        Ok(CheckPlateResponse {
+            exists_for_me: owners.iter().any(|p| p.user_id == user_id),
+            exists_for_others: owners.iter().any(|p| p.user_id != user_id),
+            normalized,
+        })
+    }
+
+    /// Приглашает пользователя (по ID или телефону) стать совладельцем номера текущего пользователя.
+    /// Номер добавится приглашённому только после того, как он примет приглашение
+    #[allow(clippy::too_many_arguments)]
+    pub async fn share_plate<
+        R: UserRepository,
+        RP: UserPlateRepository,
+        IR: PlateShareInviteRepository,
+        NR: NotificationRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        request: SharePlateRequest,
+        repository: &R,
+        user_plate_repository: &RP,
+        invite_repository: &IR,
+        notification_repository: &NR,
+        push_service: &PushService,
+    ) -> AppResult<PlateShareInviteResponse> {
+        let user_plate = user_plate_repository
+            .find_by_id(request.plate_id)
+            .await?
+            .filter(|p| p.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound("User plate not found".to_string()))?;
+
+        let invitee = match (request.user_id, request.phone.as_deref()) {
+            (Some(invitee_id), None) => repository.find_by_id(invitee_id).await?,
+            (None, Some(phone)) => {
+                let normalized_phone = ValidationService::validate_phone(phone)?;
+                repository
+                    .find_by_phone_hash(&Self::phone_hash(&normalized_phone))
+                    .await?
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Укажите либо user_id, либо phone приглашаемого".to_string(),
+                ))
+            }
+        }
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        if invitee.id == user_id {
+            return Err(AppError::Validation(
+                "Нельзя пригласить самого себя".to_string(),
+            ));
+        }
+        let owners = user_plate_repository
+            .find_by_plate(&user_plate.plate)
+            .await?;
+        if owners.iter().any(|p| p.user_id == invitee.id) {
+            return Err(AppError::Validation(
+                "Пользователь уже является владельцем этого номера".to_string(),
+            ));
+        }
+
+        let (invite, created) = invite_repository
+            .create(user_plate.id, &user_plate.plate, user_id, invitee.id)
+            .await?;
+        // Повторное приглашение не уведомляет приглашённого ещё раз
+        if !created {
+            return Ok(invite.to_response(None));
+        }
+
+        let data = serde_json::json!({
+            "invite_id": invite.id,
+            "plate": invite.plate,
+            "status": "share_invite"
+        });
+        let _ = notification_repository
+            .create(&CreateNotificationData {
+                user_id: invitee.id,
+                r#type: NotificationType::System,
+                title: "Приглашение стать совладельцем".to_string(),
+                message: format!(
+                    "Вас пригласили стать совладельцем автомобиля {}. Примите приглашение в приложении",
+                    invite.plate
+                ),
+                data: Some(data.clone()),
+            })
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create share invite notification: {:?}", e);
+            });
+
+        if let Some(push_token) = invitee.push_token {
+            let body = format!(
+                "Вас пригласили стать совладельцем автомобиля {}.",
+                invite.plate
+            );
+            let push = push_service.clone();
+            tokio::spawn(async move {
+                if let Err(e) = push
+                    .send_fcm(&push_token, "Совместный автомобиль", &body, data)
+                    .await
+                {
+                    tracing::warn!("Failed to send FCM push (share invite): {}", e);
+                }
+            });
+        }
+
+        Ok(invite.to_response(None))
+    }
+
+    /// Входящие приглашения стать совладельцем, ожидающие ответа
+    pub async fn get_plate_share_invites<R: UserRepository, IR: PlateShareInviteRepository>(
+        &self,
+        user_id: Uuid,
+        repository: &R,
+        invite_repository: &IR,
+    ) -> AppResult<Vec<PlateShareInviteResponse>> {
+        let invites = invite_repository.find_pending_for_invitee(user_id).await?;
+
+        let mut inviter_ids: Vec<Uuid> = invites.iter().map(|i| i.inviter_id).collect();
+        inviter_ids.sort();
+        inviter_ids.dedup();
+        let inviters = repository.find_by_ids(&inviter_ids).await?;
+
+        Ok(invites
+            .iter()
+            .map(|invite| {
+                let inviter = inviters
+                    .iter()
+                    .find(|user| user.id == invite.inviter_id)
+                    .map(|user| {
+                        let phone_decrypted = user.phone_encrypted.as_ref().and_then(|enc| {
+                            self.encryption.decrypt_for_user(
+                                enc,
+                                &aad::user_phone(user.id),
+                                user.id,
+                            )
+                        });
+                        user.to_public_info(phone_decrypted)
+                    });
+                invite.to_response(inviter)
+            })
+            .collect())
+    }
+
+    /// Принимает приглашение: номер добавляется текущему пользователю как совместный
+    /// (основным - если основного номера у него ещё нет)
+    pub async fn accept_plate_share_invite<
+        RP: UserPlateRepository,
+        IR: PlateShareInviteRepository,
+    >(
+        &self,
+        user_id: Uuid,
+        invite_id: Uuid,
+        user_plate_repository: &RP,
+        invite_repository: &IR,
+    ) -> AppResult<UserPlateResponse> {
+        let mut tx = invite_repository.begin().await?;
+        let invite = invite_repository
+            .respond_in_tx(&mut tx, invite_id, user_id, plate_share_status::ACCEPTED)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Invite not found".to_string()))?;
+
+        // Пригласивший мог за это время удалить или передать номер
+        user_plate_repository
+            .find_by_id(invite.plate_id)
+            .await?
+            .filter(|p| p.user_id == invite.inviter_id && p.plate == invite.plate)
+            .ok_or_else(|| {
+                AppError::Validation("Пригласивший больше не владеет этим номером".to_string())
+            })?;
+
+        let my_plates = user_plate_repository.find_by_user_id(user_id).await?;
+        let user_plate = match my_plates.iter().find(|p| p.plate == invite.plate) {
+            // Номер уже добавлен самим пользователем - просто закрываем приглашение
+            Some(existing) => existing.clone(),
+            None => {
+                let has_primary = my_plates.iter().any(|p| p.is_primary);
+                user_plate_repository
+                    .create_in_tx(&mut tx, user_id, &invite.plate, !has_primary, None)
+                    .await?
+            }
+        };
+        tx.commit().await?;
+
+        tracing::info!(
+            "User {} accepted share invite {} for plate {}",
+            user_id,
+            invite.id,
+            invite.plate
+        );
+        Ok(user_plate.to_response())
+    }
+
+    /// Отклоняет приглашение стать совладельцем
+    pub async fn decline_plate_share_invite<IR: PlateShareInviteRepository>(
+        &self,
+        user_id: Uuid,
+        invite_id: Uuid,
+        invite_repository: &IR,
+    ) -> AppResult<()> {
+        let mut tx = invite_repository.begin().await?;
+        invite_repository
+            .respond_in_tx(&mut tx, invite_id, user_id, plate_share_status::DECLINED)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Invite not found".to_string()))?;
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
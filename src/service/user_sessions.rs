@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use crate::auth::jwt::REFRESH_GRACE_MINUTES;
+use crate::config::Config;
+use crate::error::AppResult;
+use crate::repository::UserSessionRepository;
+
+/// Как часто удаляются неактивные сессии
+pub const SESSION_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Сколько сессия может быть без активности, прежде чем её токены станут бесполезны:
+/// время жизни токена, окно обновления и минута, на которую может отставать `last_seen`
+pub fn session_idle_timeout(config: &Config) -> chrono::Duration {
+    chrono::Duration::minutes(config.jwt_expiration_minutes + REFRESH_GRACE_MINUTES + 1)
+}
+
+/// Однократно удаляет сессии без активности дольше `idle_timeout`
+pub async fn purge_inactive_sessions<SR: UserSessionRepository>(
+    user_session_repository: &SR,
+    idle_timeout: chrono::Duration,
+) -> AppResult<u64> {
+    let deleted = user_session_repository
+        .purge_inactive(chrono::Utc::now() - idle_timeout)
+        .await?;
+    if deleted > 0 {
+        tracing::info!("Removed {} inactive user sessions", deleted);
+    }
+    Ok(deleted)
+}
+
+/// Запускает периодическую очистку неактивных сессий в фоне
+pub fn spawn_session_cleanup<SR>(user_session_repository: SR, idle_timeout: chrono::Duration)
+where
+    SR: UserSessionRepository + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SESSION_CLEANUP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = purge_inactive_sessions(&user_session_repository, idle_timeout).await {
+                tracing::error!("User session cleanup failed: {:?}", e);
+            }
+        }
+    });
+}
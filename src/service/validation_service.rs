@@ -1,4 +1,12 @@
+use chrono::{NaiveTime, Timelike};
+
 use crate::error::{AppError, AppResult};
+use crate::models::block::{
+    BlockReason, UpdateBlockRequest, BLOCK_MAX_DURATION_MINUTES, BLOCK_NOTE_MAX_LEN,
+    BLOCK_REASON_TEXT_MAX_LEN,
+};
+use crate::models::user::{RenterOwnerInfo, OWNER_INFO_FIELD_MAX_LEN};
+use crate::models::validation::{FieldValidation, ValidateRequest, ValidateResponse};
 use crate::utils::{
     normalize_phone, normalize_plate, validate_phone as validate_phone_util,
     validate_plate as validate_plate_util,
@@ -9,6 +17,19 @@ pub struct ValidationService;
 
 impl ValidationService {
     pub fn validate_phone(phone: &str) -> AppResult<String> {
+        #[cfg(feature = "e164")]
+        {
+            crate::utils::phone::parse_phone_e164(phone).map_err(AppError::Validation)
+        }
+
+        #[cfg(not(feature = "e164"))]
+        {
+            Self::validate_phone_heuristic(phone)
+        }
+    }
+
+    #[cfg_attr(feature = "e164", allow(dead_code))]
+    fn validate_phone_heuristic(phone: &str) -> AppResult<String> {
         let normalized = normalize_phone(phone);
         if !validate_phone_util(&normalized) {
             return Err(AppError::Validation(
@@ -18,6 +39,33 @@ impl ValidationService {
         Ok(normalized)
     }
 
+    /// Разбирает время выезда: `HH:MM`, `H:MM` или `HH:MM:SS` (секунды отбрасываются)
+    pub fn parse_departure_time(time: &str) -> AppResult<NaiveTime> {
+        let time = time.trim();
+        let parts: Vec<&str> = time.split(':').collect();
+        // chrono принимает и одну цифру в минутах ("18:3"), поэтому длину частей проверяем сами
+        let well_formed = (2..=3).contains(&parts.len())
+            && (1..=2).contains(&parts[0].len())
+            && parts[1..].iter().all(|part| part.len() == 2)
+            && parts
+                .iter()
+                .all(|part| part.bytes().all(|b| b.is_ascii_digit()));
+        well_formed
+            .then(|| {
+                ["%H:%M", "%H:%M:%S"]
+                    .iter()
+                    .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+            })
+            .flatten()
+            .and_then(|parsed| parsed.with_second(0))
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "Неверный формат времени выезда. Используйте формат HH:MM (например, 18:30)"
+                        .to_string(),
+                )
+            })
+    }
+
     pub fn validate_plate(plate: &str) -> AppResult<String> {
         let normalized = normalize_plate(plate);
         if !validate_plate_util(&normalized) {
@@ -27,4 +75,196 @@ impl ValidationService {
         }
         Ok(normalized)
     }
+
+    /// Проверяет Telegram username и возвращает его в том виде, в каком он сохраняется в профиле
+    pub fn validate_telegram(telegram: &str) -> AppResult<String> {
+        let normalized = telegram.trim().to_lowercase();
+        if normalized.is_empty() {
+            return Err(AppError::Validation(
+                "Telegram username не может быть пустым".to_string(),
+            ));
+        }
+        if normalized.len() > 32 {
+            return Err(AppError::Validation(
+                "Telegram username должен быть до 32 символов".to_string(),
+            ));
+        }
+        Ok(normalized)
+    }
+
+    /// Проверяет поля формы без побочных эффектов теми же правилами, что и при сохранении
+    pub fn check_fields(request: &ValidateRequest) -> ValidateResponse {
+        ValidateResponse {
+            plate: request.plate.as_deref().map(|plate| {
+                Self::field_result(
+                    Self::validate_plate(plate),
+                    normalize_plate(plate),
+                    "INVALID_PLATE",
+                )
+            }),
+            phone: request.phone.as_deref().map(|phone| {
+                Self::field_result(
+                    Self::validate_phone(phone),
+                    normalize_phone(phone),
+                    "INVALID_PHONE",
+                )
+            }),
+            telegram: request.telegram.as_deref().map(|telegram| {
+                Self::field_result(
+                    Self::validate_telegram(telegram),
+                    telegram.trim().to_lowercase(),
+                    "INVALID_TELEGRAM",
+                )
+            }),
+        }
+    }
+
+    /// Для неверного значения `normalized` - результат нормализации без проверки формата
+    fn field_result(result: AppResult<String>, normalized: String, code: &str) -> FieldValidation {
+        match result {
+            Ok(normalized) => FieldValidation {
+                valid: true,
+                normalized,
+                code: None,
+                message: None,
+            },
+            Err(e) => FieldValidation {
+                valid: false,
+                normalized,
+                code: Some(code.to_string()),
+                message: Some(match e {
+                    AppError::Validation(message) => message,
+                    other => other.to_string(),
+                }),
+            },
+        }
+    }
+
+    /// Проверяет причину блокировки и пояснение к ней.
+    /// Для причины "other" пояснение обязательно
+    pub fn validate_block_reason(
+        reason: Option<&str>,
+        reason_text: Option<&str>,
+    ) -> AppResult<Option<BlockReason>> {
+        let reason = match reason.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(value) => Some(BlockReason::parse(value).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Неизвестная причина блокировки: {}. Допустимо: {}",
+                    value,
+                    BlockReason::ALL.map(|r| r.as_str()).join(", ")
+                ))
+            })?),
+            None => None,
+        };
+
+        if let Some(text) = reason_text {
+            if text.chars().count() > BLOCK_REASON_TEXT_MAX_LEN {
+                return Err(AppError::Validation(format!(
+                    "Пояснение к причине не должно превышать {} символов",
+                    BLOCK_REASON_TEXT_MAX_LEN
+                )));
+            }
+        }
+
+        if reason == Some(BlockReason::Other) && reason_text.is_none() {
+            return Err(AppError::Validation(
+                "Для причины \"other\" укажите пояснение".to_string(),
+            ));
+        }
+
+        Ok(reason)
+    }
+
+    /// Проверяет изменение блокировки и возвращает разобранное время выезда.
+    /// Время выезда и продолжительность взаимоисключающие
+    pub fn validate_block_update(
+        request: &UpdateBlockRequest,
+    ) -> AppResult<Option<chrono::NaiveTime>> {
+        if request.departure_time.is_none()
+            && request.note.is_none()
+            && request.duration_minutes.is_none()
+        {
+            return Err(AppError::Validation(
+                "Укажите departure_time, duration_minutes или note".to_string(),
+            ));
+        }
+
+        if request.departure_time.is_some() && request.duration_minutes.is_some() {
+            return Err(AppError::Validation(
+                "Укажите либо departure_time, либо duration_minutes".to_string(),
+            ));
+        }
+
+        if let Some(duration) = request.duration_minutes {
+            if !(1..=BLOCK_MAX_DURATION_MINUTES).contains(&duration) {
+                return Err(AppError::Validation(format!(
+                    "duration_minutes должно быть от 1 до {}",
+                    BLOCK_MAX_DURATION_MINUTES
+                )));
+            }
+        }
+
+        if let Some(note) = &request.note {
+            if note.chars().count() > BLOCK_NOTE_MAX_LEN {
+                return Err(AppError::Validation(format!(
+                    "Заметка не должна превышать {} символов",
+                    BLOCK_NOTE_MAX_LEN
+                )));
+            }
+        }
+
+        request
+            .departure_time
+            .as_deref()
+            .map(Self::parse_departure_time)
+            .transpose()
+    }
+
+    /// Проверяет сведения о собственнике, которые указывает арендатор: объект только с полями
+    /// `RenterOwnerInfo`, строки или null. Пустые строки считаются отсутствующими,
+    /// телефон нормализуется
+    pub fn validate_owner_info(value: &serde_json::Value) -> AppResult<RenterOwnerInfo> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| AppError::Validation("owner_info должен быть объектом".to_string()))?;
+
+        if let Some(unknown) = object
+            .keys()
+            .find(|key| !RenterOwnerInfo::FIELDS.contains(&key.as_str()))
+        {
+            return Err(AppError::Validation(format!(
+                "Неизвестное поле owner_info: {} (допустимы: {})",
+                unknown,
+                RenterOwnerInfo::FIELDS.join(", ")
+            )));
+        }
+
+        let field = |name: &str| -> AppResult<Option<String>> {
+            match object.get(name) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(serde_json::Value::String(text)) => {
+                    let text = text.trim();
+                    if text.chars().count() > OWNER_INFO_FIELD_MAX_LEN {
+                        return Err(AppError::Validation(format!(
+                            "owner_info.{} не должно превышать {} символов",
+                            name, OWNER_INFO_FIELD_MAX_LEN
+                        )));
+                    }
+                    Ok(Some(text.to_string()).filter(|text| !text.is_empty()))
+                }
+                Some(_) => Err(AppError::Validation(format!(
+                    "owner_info.{} должно быть строкой",
+                    name
+                ))),
+            }
+        };
+
+        Ok(RenterOwnerInfo {
+            owner_name: field("owner_name")?,
+            owner_phone: field("owner_phone")?
+                .map(|phone| Self::validate_phone(&phone))
+                .transpose()?,
+            agency: field("agency")?,
+        })
+    }
 }
@@ -1,61 +1,318 @@
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use anyhow::{Context, Result};
 use base64::Engine;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use uuid::Uuid;
 
-#[derive(Clone)]
-pub struct Encryption {
-    cipher: Arc<Aes256Gcm>,
+use crate::config::{Config, EncryptionBackend};
+
+/// Длина nonce для AES-GCM
+const NONCE_LEN: usize = 12;
+
+/// Версия ключа по умолчанию (ключ из ENCRYPTION_KEY без явной версии)
+pub const DEFAULT_KEY_VERSION: u8 = 1;
+
+/// Наибольшая версия ключа: старший бит байта версии отмечает шифротексты с AAD
+pub const MAX_KEY_VERSION: u8 = 0x7f;
+
+/// Старший бит байта версии: шифротекст привязан к контексту (AAD)
+const AAD_BOUND_FLAG: u8 = 0x80;
+
+/// Контексты (AAD) зашифрованных полей: шифротекст, привязанный к полю одного пользователя,
+/// не расшифровывается ни в другом поле, ни у другого пользователя
+pub mod aad {
+    use uuid::Uuid;
+
+    /// Телефон пользователя (users.phone_encrypted): `users.phone:<id пользователя>`
+    pub fn user_phone(user_id: Uuid) -> Vec<u8> {
+        format!("users.phone:{}", user_id).into_bytes()
+    }
+
+    /// Контрольное значение для проверки ключа при запуске (encryption_canary)
+    pub const ENCRYPTION_CANARY: &[u8] = b"encryption_canary";
 }
 
-impl Encryption {
-    pub fn new(key: &str) -> Result<Self> {
+/// Алгоритм шифрования персональных данных (выбирается `ENCRYPTION_BACKEND`).
+/// Позволяет добавить, например, envelope encryption через KMS, не меняя вызывающий код.
+///
+/// `aad` - необязательный контекст (см. модуль `aad`), который не шифруется,
+/// но должен совпасть при расшифровке
+pub trait Encryptor: Send + Sync {
+    fn encrypt(&self, plaintext: &str, aad: Option<&[u8]>) -> Result<String>;
+    /// Расшифровывает данные. Без контекста расшифровываются только шифротексты,
+    /// созданные без AAD (до его появления); привязанные к контексту требуют тот же `aad`
+    fn decrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<String>;
+    /// Перешифровывает данные текущим ключом с контекстом `aad`.
+    /// Возвращает `None`, если данные уже зашифрованы текущей версией ключа с этим контекстом
+    fn re_encrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<Option<String>>;
+    /// Версия ключа, которым шифруются новые данные
+    fn current_version(&self) -> u8;
+}
+
+/// Результат расшифровки AES-GCM
+struct Opened {
+    plaintext: String,
+    /// Версия ключа (`None` - старый формат без байта версии)
+    version: Option<u8>,
+    /// Шифротекст привязан к контексту (false - создан без AAD)
+    bound: bool,
+}
+
+/// AES-256-GCM с поддержкой ротации ключей (алгоритм по умолчанию).
+///
+/// Формат шифротекста: base64(версия ключа (1 байт) || nonce || ciphertext).
+/// У шифротекстов с AAD в байте версии выставлен старший бит.
+/// Старый формат без байта версии (base64(nonce || ciphertext)) по-прежнему расшифровывается.
+/// Nonce генерируется заново (OsRng) для каждого шифрования и никогда не переиспользуется
+pub struct AesGcmEncryptor {
+    current_version: u8,
+    ciphers: BTreeMap<u8, Aes256Gcm>,
+}
+
+impl AesGcmEncryptor {
+    /// Создаёт шифрование с текущим ключом `key` версии `current_version`
+    /// и предыдущими ключами (версия, hex ключ), которые используются только для расшифровки
+    pub fn with_keys(
+        current_version: u8,
+        key: &str,
+        previous_keys: &[(u8, String)],
+    ) -> Result<Self> {
+        let versions = previous_keys.iter().map(|(version, _)| version);
+        if let Some(version) = std::iter::once(&current_version)
+            .chain(versions)
+            .find(|version| **version > MAX_KEY_VERSION)
+        {
+            anyhow::bail!(
+                "Encryption key version {} is out of range 0-{}",
+                version,
+                MAX_KEY_VERSION
+            );
+        }
+
+        let mut ciphers = BTreeMap::new();
+        for (version, previous_key) in previous_keys {
+            ciphers.insert(*version, Self::cipher_from_hex(previous_key)?);
+        }
+        ciphers.insert(current_version, Self::cipher_from_hex(key)?);
+
+        Ok(Self {
+            current_version,
+            ciphers,
+        })
+    }
+
+    fn cipher_from_hex(key: &str) -> Result<Aes256Gcm> {
         // Преобразуем hex строку в 32 байта
         let key_bytes = hex::decode(key)?;
         if key_bytes.len() != 32 {
             anyhow::bail!("Encryption key must be 32 bytes (64 hex characters)");
         }
 
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-            .map_err(|e| anyhow::anyhow!("Invalid encryption key: {}", e))?;
-        Ok(Self {
-            cipher: Arc::new(cipher),
-        })
+        Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid encryption key: {}", e))
     }
 
-    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+    /// Расшифровывает данные, определяя версию ключа и привязку к контексту
+    fn decrypt_with_version(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<Opened> {
+        let combined = base64::engine::general_purpose::STANDARD
+            .decode(ciphertext)
+            .context("Base64 decode failed")?;
+
+        if combined.len() < NONCE_LEN {
+            anyhow::bail!("Invalid ciphertext length");
+        }
+
+        // Новый формат: первый байт - версия ключа (старший бит - признак AAD).
+        // Без контекста расшифровываются только шифротексты без этого признака
+        if combined.len() > NONCE_LEN {
+            let version = combined[0] & !AAD_BOUND_FLAG;
+            let bound = combined[0] & AAD_BOUND_FLAG != 0;
+            if let Some(cipher) = self.ciphers.get(&version) {
+                let context = if bound { aad.unwrap_or_default() } else { &[] };
+                if let Ok(plaintext) = Self::open(cipher, &combined[1..], context) {
+                    return Ok(Opened {
+                        plaintext,
+                        version: Some(version),
+                        bound,
+                    });
+                }
+            }
+        }
+
+        // Старый формат без версии: пробуем все известные ключи, начиная с текущего
+        let current = self.ciphers.get(&self.current_version);
+        let others = self
+            .ciphers
+            .iter()
+            .filter(|(version, _)| **version != self.current_version)
+            .map(|(_, cipher)| cipher);
+        for cipher in current.into_iter().chain(others) {
+            if let Ok(plaintext) = Self::open(cipher, &combined, &[]) {
+                return Ok(Opened {
+                    plaintext,
+                    version: None,
+                    bound: false,
+                });
+            }
+        }
+
+        anyhow::bail!("Decryption failed: no matching key")
+    }
+
+    fn open(cipher: &Aes256Gcm, combined: &[u8], aad: &[u8]) -> Result<String> {
+        if combined.len() < NONCE_LEN {
+            anyhow::bail!("Invalid ciphertext length");
+        }
+        let nonce = Nonce::from_slice(&combined[..NONCE_LEN]);
+        let plaintext = cipher
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: &combined[NONCE_LEN..],
+                    aad,
+                },
+            )
+            .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+}
+
+impl Encryptor for AesGcmEncryptor {
+    fn encrypt(&self, plaintext: &str, aad: Option<&[u8]>) -> Result<String> {
+        let cipher = &self.ciphers[&self.current_version];
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-        let ciphertext = self
-            .cipher
-            .encrypt(&nonce, plaintext.as_bytes())
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: aad.unwrap_or_default(),
+                },
+            )
             .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
 
-        // Объединяем nonce и ciphertext в одну строку (base64)
-        let mut combined = nonce.to_vec();
+        // Объединяем версию ключа, nonce и ciphertext в одну строку (base64)
+        let header = if aad.is_some() {
+            self.current_version | AAD_BOUND_FLAG
+        } else {
+            self.current_version
+        };
+        let mut combined = vec![header];
+        combined.extend_from_slice(&nonce);
         combined.extend_from_slice(&ciphertext);
 
         Ok(base64::engine::general_purpose::STANDARD.encode(combined))
     }
 
-    pub fn decrypt(&self, ciphertext: &str) -> Result<String> {
-        let combined = base64::engine::general_purpose::STANDARD
-            .decode(ciphertext)
-            .context("Base64 decode failed")?;
+    fn decrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<String> {
+        self.decrypt_with_version(ciphertext, aad)
+            .map(|opened| opened.plaintext)
+    }
 
-        if combined.len() < 12 {
-            anyhow::bail!("Invalid ciphertext length");
+    fn re_encrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<Option<String>> {
+        let opened = self.decrypt_with_version(ciphertext, aad)?;
+        if opened.version == Some(self.current_version) && opened.bound == aad.is_some() {
+            return Ok(None);
         }
+        self.encrypt(&opened.plaintext, aad).map(Some)
+    }
 
-        let nonce = Nonce::from_slice(&combined[..12]);
-        let ciphertext_bytes = &combined[12..];
+    fn current_version(&self) -> u8 {
+        self.current_version
+    }
+}
 
-        let plaintext = self
-            .cipher
-            .decrypt(nonce, ciphertext_bytes)
-            .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))?;
-        Ok(String::from_utf8(plaintext)?)
+/// Шифрование персональных данных: выбранный алгоритм (`Encryptor`)
+/// плюс учёт ошибок расшифровки
+#[derive(Clone)]
+pub struct Encryption {
+    backend: Arc<dyn Encryptor>,
+    /// Счётчик ошибок расшифровки (метрика для выявления проблем с ротацией ключа)
+    decrypt_failures: Arc<AtomicU64>,
+}
+
+impl Encryption {
+    pub fn new(key: &str) -> Result<Self> {
+        Self::with_keys(DEFAULT_KEY_VERSION, key, &[])
+    }
+
+    /// AES-GCM с текущим ключом `key` версии `current_version`
+    /// и предыдущими ключами (версия, hex ключ), которые используются только для расшифровки
+    pub fn with_keys(
+        current_version: u8,
+        key: &str,
+        previous_keys: &[(u8, String)],
+    ) -> Result<Self> {
+        Ok(Self::with_backend(Arc::new(AesGcmEncryptor::with_keys(
+            current_version,
+            key,
+            previous_keys,
+        )?)))
+    }
+
+    pub fn with_backend(backend: Arc<dyn Encryptor>) -> Self {
+        Self {
+            backend,
+            decrypt_failures: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Создаёт шифрование с алгоритмом и ключами из конфигурации
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config.encryption_backend {
+            EncryptionBackend::AesGcm => Self::with_keys(
+                config.encryption_key_version,
+                &config.encryption_key,
+                &config.encryption_previous_keys,
+            ),
+        }
+    }
+
+    /// Версия ключа, которым шифруются новые данные
+    pub fn current_version(&self) -> u8 {
+        self.backend.current_version()
+    }
+
+    pub fn encrypt(&self, plaintext: &str, aad: Option<&[u8]>) -> Result<String> {
+        self.backend.encrypt(plaintext, aad)
+    }
+
+    pub fn decrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<String> {
+        self.backend.decrypt(ciphertext, aad)
+    }
+
+    /// Перешифровывает данные текущим ключом с контекстом `aad`.
+    /// Возвращает `None`, если данные уже зашифрованы текущей версией ключа с этим контекстом
+    pub fn re_encrypt(&self, ciphertext: &str, aad: Option<&[u8]>) -> Result<Option<String>> {
+        self.backend.re_encrypt(ciphertext, aad)
+    }
+
+    /// Расшифровывает поле пользователя. В отличие от `decrypt(..).ok()` не теряет ошибку молча:
+    /// пишет предупреждение с ID пользователя (без шифротекста) и увеличивает счётчик ошибок.
+    /// Типичная причина - данные зашифрованы старым ключом
+    pub fn decrypt_for_user(&self, ciphertext: &str, aad: &[u8], user_id: Uuid) -> Option<String> {
+        match self.decrypt(ciphertext, Some(aad)) {
+            Ok(plaintext) => Some(plaintext),
+            Err(e) => {
+                let total = self.decrypt_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::warn!(
+                    "Failed to decrypt data for user {}: {} (total decrypt failures: {})",
+                    user_id,
+                    e,
+                    total
+                );
+                None
+            }
+        }
+    }
+
+    /// Количество ошибок расшифровки с момента запуска
+    pub fn decrypt_failures(&self) -> u64 {
+        self.decrypt_failures.load(Ordering::Relaxed)
     }
 }
@@ -0,0 +1,46 @@
+use crate::error::{AppError, AppResult};
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
+use std::io::Cursor;
+
+/// Максимальный размер загружаемого изображения (10 МБ)
+pub const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Максимальная сторона превью в пикселях
+pub const THUMBNAIL_MAX_SIZE: u32 = 320;
+
+/// Качество JPEG для превью
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// Определяет формат изображения (поддерживаются только JPEG и PNG)
+pub fn detect_image_format(data: &[u8]) -> AppResult<ImageFormat> {
+    match image::guess_format(data) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png)) => Ok(format),
+        _ => Err(AppError::Validation(
+            "Поддерживаются только изображения JPEG и PNG".to_string(),
+        )),
+    }
+}
+
+/// Возвращает расширение файла и MIME тип для формата изображения
+pub fn image_format_info(format: ImageFormat) -> (&'static str, &'static str) {
+    match format {
+        ImageFormat::Png => ("png", "image/png"),
+        _ => ("jpg", "image/jpeg"),
+    }
+}
+
+/// Создаёт JPEG превью, у которого большая сторона не превышает `max_size` (с сохранением пропорций)
+pub fn make_thumbnail(data: &[u8], max_size: u32) -> AppResult<Vec<u8>> {
+    let image = image::load_from_memory(data)
+        .map_err(|e| AppError::Validation(format!("Не удалось прочитать изображение: {}", e)))?;
+
+    // JPEG не поддерживает прозрачность, поэтому приводим к RGB
+    let thumbnail = DynamicImage::ImageRgb8(image.thumbnail(max_size, max_size).to_rgb8());
+
+    let mut buffer = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut buffer, ImageOutputFormat::Jpeg(THUMBNAIL_JPEG_QUALITY))
+        .map_err(|e| AppError::Internal(format!("Failed to encode thumbnail: {}", e)))?;
+
+    Ok(buffer.into_inner())
+}
@@ -1,8 +1,12 @@
 pub mod encryption;
+pub mod image;
 pub mod network;
+pub mod nullable;
 pub mod ocr;
 pub mod phone;
 pub mod plate;
+pub mod projection;
+pub mod time;
 
 pub use encryption::*;
 pub use phone::*;
@@ -18,3 +18,140 @@ pub fn get_server_url(port: u16) -> String {
     let ip = get_local_ip().unwrap_or_else(|| "192.168.1.1".to_string());
     format!("http://{}:{}", ip, port)
 }
+
+/// Подсеть доверенных прокси в формате CIDR ("10.0.0.0/8") или отдельный адрес ("203.0.113.7")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: std::net::IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Разбирает адрес или подсеть, None - если формат неверный
+    pub fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value.trim(), None),
+        };
+        let addr: std::net::IpAddr = addr.parse().ok()?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse().ok().filter(|p| *p <= max_prefix)?,
+            None => max_prefix,
+        };
+        Some(Self { addr, prefix })
+    }
+
+    /// Входит ли адрес в подсеть (IPv4-mapped IPv6 сравниваются как IPv4)
+    pub fn contains(&self, ip: std::net::IpAddr) -> bool {
+        use std::net::IpAddr;
+
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Определяет реальный IP клиента с учётом доверенных прокси.
+///
+/// `X-Forwarded-For` учитывается только если соединение пришло от доверенного прокси:
+/// цепочка просматривается справа налево, доверенные хопы пропускаются, первый недоверенный
+/// адрес и есть клиент. От недоверенного источника заголовок игнорируется (защита от подмены)
+pub fn resolve_client_ip(
+    peer: std::net::IpAddr,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpNetwork],
+) -> std::net::IpAddr {
+    let is_trusted = |ip: std::net::IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+
+    if !is_trusted(peer) {
+        if forwarded_for.is_some() {
+            tracing::debug!("Ignoring X-Forwarded-For from untrusted peer {}", peer);
+        }
+        return peer;
+    }
+
+    let Some(forwarded_for) = forwarded_for else {
+        return peer;
+    };
+
+    let mut client = peer;
+    for hop in forwarded_for.rsplit(',') {
+        match hop.trim().parse::<std::net::IpAddr>() {
+            Ok(ip) => {
+                client = ip;
+                if !is_trusted(ip) {
+                    break;
+                }
+            }
+            Err(_) => {
+                // Мусор в цепочке - дальше неё доверять нельзя
+                tracing::warn!("Malformed X-Forwarded-For hop: {:?}", hop.trim());
+                break;
+            }
+        }
+    }
+    client
+}
+
+/// Определяет внешний адрес сервиса (`схема://хост`) для абсолютных ссылок.
+///
+/// Приоритет у `PUBLIC_BASE_URL`. Без него адрес собирается из `X-Forwarded-Proto` и
+/// `X-Forwarded-Host` (или `Host`), но только если соединение пришло от доверенного прокси:
+/// за прокси с TLS сервис сам видит лишь `http`. Иначе None - ссылки остаются относительными
+pub fn resolve_public_base_url(
+    configured: Option<&str>,
+    peer: Option<std::net::IpAddr>,
+    headers: &axum::http::HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> Option<String> {
+    if let Some(url) = configured {
+        return Some(url.to_string());
+    }
+
+    let peer = peer?;
+    if !trusted_proxies.iter().any(|net| net.contains(peer)) {
+        return None;
+    }
+
+    // При цепочке прокси берём значение, выставленное первым (ближайшим к клиенту)
+    let first_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let scheme = match first_value("x-forwarded-proto") {
+        Some(proto) if proto.eq_ignore_ascii_case("https") => "https",
+        _ => "http",
+    };
+    let host = first_value("x-forwarded-host").or_else(|| first_value("host"))?;
+    // Хост подставляется в ссылки как есть - отбрасываем всё, что на хост не похоже
+    if !host
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+    {
+        tracing::warn!("Ignoring malformed forwarded host: {:?}", host);
+        return None;
+    }
+
+    Some(format!("{}://{}", scheme, host))
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Deserializer};
+
+/// Serde-хелпер для полей частичного обновления, которые можно очистить:
+/// `#[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]` на `Option<Option<T>>`.
+/// Поле отсутствует - `None` (не менять), `null` - `Some(None)` (очистить), значение - `Some(Some(v))`
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
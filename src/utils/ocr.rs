@@ -1,11 +1,14 @@
 use crate::error::{AppError, AppResult};
 use base64::Engine;
 
-/// Распознаёт номер автомобиля с изображения
-pub async fn recognize_plate_from_image(image_data: &[u8]) -> AppResult<String> {
+/// Распознаёт номер автомобиля с изображения через OCR сервис по адресу `ocr_api_url` (OCR_API_URL)
+pub async fn recognize_plate_from_image(
+    ocr_api_url: Option<&str>,
+    image_data: &[u8],
+) -> AppResult<String> {
     // Пробуем использовать внешний OCR сервис, если настроен
-    if let Ok(ocr_api_url) = std::env::var("OCR_API_URL") {
-        return recognize_via_api(&ocr_api_url, image_data).await;
+    if let Some(ocr_api_url) = ocr_api_url {
+        return recognize_via_api(ocr_api_url, image_data).await;
     }
 
     // Fallback: возвращаем ошибку, чтобы пользователь вводил номер вручную
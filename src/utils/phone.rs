@@ -1,6 +1,36 @@
-/// Нормализует номер телефона (удаляет пробелы, дефисы, скобки)
-/// Автоматически заменяет 8 или 7 на +7
+/// Нормализует номер телефона.
+/// С фичей `e164` приводит номер к E.164 через libphonenumber,
+/// а если номер не разбирается - использует эвристику `normalize_phone_heuristic`
 pub fn normalize_phone(phone: &str) -> String {
+    #[cfg(feature = "e164")]
+    if let Ok(e164) = parse_phone_e164(phone) {
+        return e164;
+    }
+
+    normalize_phone_heuristic(phone)
+}
+
+/// Регион по умолчанию для номеров без кода страны
+#[cfg(feature = "e164")]
+pub const DEFAULT_PHONE_REGION: phonenumber::country::Id = phonenumber::country::Id::RU;
+
+/// Разбирает номер телефона в формат E.164 (`+7XXXXXXXXXX`) с регионом по умолчанию RU.
+/// Возвращает точную причину, если номер не удалось разобрать или он не существует
+#[cfg(feature = "e164")]
+pub fn parse_phone_e164(phone: &str) -> Result<String, String> {
+    let number = phonenumber::parse(Some(DEFAULT_PHONE_REGION), phone)
+        .map_err(|e| format!("Не удалось разобрать номер телефона: {}", e))?;
+
+    if !phonenumber::is_valid(&number) {
+        return Err("Номер телефона не существует в плане нумерации".to_string());
+    }
+
+    Ok(number.format().mode(phonenumber::Mode::E164).to_string())
+}
+
+/// Эвристическая нормализация номера (удаляет пробелы, дефисы, скобки)
+/// Автоматически заменяет 8 или 7 на +7
+pub fn normalize_phone_heuristic(phone: &str) -> String {
     let cleaned: String = phone
         .chars()
         .filter(|c| c.is_ascii_digit() || *c == '+')
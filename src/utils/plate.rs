@@ -1,18 +1,25 @@
-/// Нормализует номер автомобиля (удаляет пробелы, приводит к верхнему регистру)
+/// Нормализует номер автомобиля (удаляет пробелы и дефисы, приводит к верхнему регистру).
+/// Буквы Ё на номерах не бывает, её вводят вместо Е - заменяем, чтобы номер находился
 pub fn normalize_plate(plate: &str) -> String {
-    plate.replace([' ', '-'], "").to_uppercase()
+    plate
+        .replace([' ', '-'], "")
+        .to_uppercase()
+        .replace('Ё', "Е")
 }
 
 /// Проверяет формат российского номера автомобиля
 /// Формат: А123БВ777 (1 буква, 3 цифры, 2 буквы, 2-3 цифры)
 /// Поддерживает как кириллические, так и латинские буквы
+///
+/// Вызывается на каждой блокировке/проверке, поэтому пошаговые логи пишутся на уровне `trace`,
+/// а итог проверки - на уровне `debug`
 pub fn validate_plate(plate: &str) -> bool {
     let normalized = normalize_plate(plate);
 
     // Используем chars().count() вместо len() для правильного подсчета символов (не байт)
     let char_count = normalized.chars().count();
     if !(8..=9).contains(&char_count) {
-        tracing::warn!(
+        tracing::debug!(
             "Plate length invalid: {} chars (expected 8-9) for '{}'",
             char_count,
             normalized
@@ -23,7 +30,7 @@ pub fn validate_plate(plate: &str) -> bool {
     // Проверяем формат: буква, 3 цифры, 2 буквы, 2-3 цифры
     let chars: Vec<char> = normalized.chars().collect();
 
-    tracing::info!(
+    tracing::trace!(
         "Validating plate '{}', length: {}, chars: {:?}",
         normalized,
         chars.len(),
@@ -31,17 +38,17 @@ pub fn validate_plate(plate: &str) -> bool {
     );
 
     // Функция для проверки буквы (кириллица или латиница)
-    // В России используются кириллические буквы (А-Я, Ё)
+    // В России используются кириллические буквы (А-Я; Ё уже заменена на Е при нормализации)
     // Также возможны латинские буквы в некоторых случаях
     let is_letter = |c: &char| -> bool {
-        // Проверяем кириллические буквы (А-Я, Ё)
+        // Проверяем кириллические буквы (А-Я)
         let code = *c as u32;
-        // А = 0x0410, Я = 0x042F, Ё = 0x0401
-        let is_cyrillic = (0x0410..=0x042F).contains(&code) || code == 0x0401;
+        // А = 0x0410, Я = 0x042F
+        let is_cyrillic = (0x0410..=0x042F).contains(&code);
         let is_latin = c.is_ascii_alphabetic();
 
         if !is_cyrillic && !is_latin {
-            tracing::warn!(
+            tracing::trace!(
                 "Character '{}' (U+{:04X}) is not a letter (cyrillic: {}, latin: {})",
                 c,
                 code,
@@ -55,7 +62,7 @@ pub fn validate_plate(plate: &str) -> bool {
 
     // Первая буква
     if !is_letter(&chars[0]) {
-        tracing::warn!(
+        tracing::debug!(
             "First character '{}' (U+{:04X}) is not a letter for plate '{}'",
             chars[0],
             chars[0] as u32,
@@ -64,11 +71,11 @@ pub fn validate_plate(plate: &str) -> bool {
         return false;
     }
 
-    tracing::info!("First char '{}' is a letter", chars[0]);
+    tracing::trace!("First char '{}' is a letter", chars[0]);
 
     // Три цифры
     if !chars[1..4].iter().all(|c| c.is_ascii_digit()) {
-        tracing::warn!(
+        tracing::debug!(
             "Characters 1-3 are not all digits: {:?} for plate '{}'",
             &chars[1..4],
             normalized
@@ -76,12 +83,12 @@ pub fn validate_plate(plate: &str) -> bool {
         return false;
     }
 
-    tracing::info!("Chars 1-3 are digits: {:?}", &chars[1..4]);
+    tracing::trace!("Chars 1-3 are digits: {:?}", &chars[1..4]);
 
     // Две буквы
     for (i, c) in chars[4..6].iter().enumerate() {
         if !is_letter(c) {
-            tracing::warn!(
+            tracing::debug!(
                 "Character {} '{}' (U+{:04X}) at position {} is not a letter for plate '{}'",
                 i + 4,
                 c,
@@ -93,11 +100,11 @@ pub fn validate_plate(plate: &str) -> bool {
         }
     }
 
-    tracing::info!("Chars 4-5 are letters: {:?}", &chars[4..6]);
+    tracing::trace!("Chars 4-5 are letters: {:?}", &chars[4..6]);
 
     // Последние 2-3 цифры
     if !chars[6..].iter().all(|c| c.is_ascii_digit()) {
-        tracing::warn!(
+        tracing::debug!(
             "Last characters are not all digits: {:?} for plate '{}'",
             &chars[6..],
             normalized
@@ -105,33 +112,38 @@ pub fn validate_plate(plate: &str) -> bool {
         return false;
     }
 
-    tracing::info!("Last chars are digits: {:?}", &chars[6..]);
-    tracing::info!("Plate '{}' validated successfully", normalized);
+    tracing::trace!("Last chars are digits: {:?}", &chars[6..]);
+    tracing::debug!("Plate '{}' validated successfully", normalized);
     true
 }
 
 /// Форматирует номер автомобиля для отображения
 /// А123БВ777 -> А 123 БВ 777
+/// Работает по символам: кириллические буквы занимают в UTF-8 по два байта
 pub fn format_plate(plate: &str) -> String {
     let normalized = normalize_plate(plate);
+    let chars: Vec<char> = normalized.chars().collect();
 
-    if normalized.len() == 9 {
-        format!(
-            "{} {} {} {}",
-            &normalized[0..1],
-            &normalized[1..4],
-            &normalized[4..6],
-            &normalized[6..9]
-        )
-    } else if normalized.len() == 8 {
+    if chars.len() == 8 || chars.len() == 9 {
+        let part = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
         format!(
             "{} {} {} {}",
-            &normalized[0..1],
-            &normalized[1..4],
-            &normalized[4..6],
-            &normalized[6..8]
+            part(0..1),
+            part(1..4),
+            part(4..6),
+            part(6..chars.len())
         )
     } else {
         normalized
     }
 }
+
+/// Частично скрывает номер автомобиля для публичной выдачи
+/// А123БВ777 -> А12*БВ*** (скрываются последняя цифра номера и регион)
+pub fn mask_plate(plate: &str) -> String {
+    normalize_plate(plate)
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if i == 3 || i >= 6 { '*' } else { c })
+        .collect()
+}
@@ -0,0 +1,31 @@
+use crate::error::{AppError, AppResult};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Оставляет в сериализованном объекте только поля из списка `fields` (через запятую).
+/// Поле `id` сохраняется всегда. Неизвестные поля приводят к ошибке валидации
+pub fn project_fields<T: Serialize>(value: &T, fields: &str) -> AppResult<Value> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize response: {}", e)))?;
+
+    let Value::Object(mut object) = value else {
+        return Ok(value);
+    };
+
+    let requested: Vec<&str> = fields
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .collect();
+
+    if let Some(unknown) = requested.iter().find(|f| !object.contains_key(**f)) {
+        return Err(AppError::Validation(format!(
+            "Неизвестное поле: {}",
+            unknown
+        )));
+    }
+
+    object.retain(|key, _| key == "id" || requested.contains(&key.as_str()));
+
+    Ok(Value::Object(object))
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, NaiveTime, SecondsFormat, Utc};
+
+/// Допустимое смещение часового пояса от UTC в минутах (от UTC-12:00 до UTC+14:00)
+pub const MIN_UTC_OFFSET_MINUTES: i32 = -720;
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 840;
+
+/// Смещение по умолчанию, если пользователь не указал часовой пояс (Москва, UTC+3)
+pub const DEFAULT_UTC_OFFSET_MINUTES: i32 = 180;
+
+/// Локальное время пользователя по его смещению от UTC
+pub fn local_time(now: DateTime<Utc>, utc_offset_minutes: i32) -> NaiveTime {
+    (now + chrono::Duration::minutes(i64::from(utc_offset_minutes))).time()
+}
+
+/// Попадает ли время в интервал [start, end), в том числе через полночь (22:00-08:00)
+pub fn is_within_window(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+/// Разбирает интервал времени вида "22:00-08:00"
+pub fn parse_time_window(value: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = value.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    Some((start, end))
+}
+
+/// Единый формат временных меток в API: RFC3339 в UTC с миллисекундами и суффиксом `Z`
+/// (например, `2024-11-17T12:30:00.000Z`)
+pub fn format_timestamp(value: &DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Serde-хелпер для полей `DateTime<Utc>`: `#[serde(with = "crate::utils::time::rfc3339_utc")]`
+pub mod rfc3339_utc {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_timestamp(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        DateTime::parse_from_rfc3339(&value)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serde-хелпер для полей `Option<DateTime<Utc>>` (тот же формат, что и `rfc3339_utc`)
+pub mod rfc3339_utc_option {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&super::format_timestamp(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| {
+                DateTime::parse_from_rfc3339(&value)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(serde::de::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+/// Ближайший момент (не раньше `now`), когда по местному времени пользователя наступит `time`
+pub fn next_local_occurrence(
+    now: DateTime<Utc>,
+    time: NaiveTime,
+    utc_offset_minutes: i32,
+) -> DateTime<Utc> {
+    let offset = chrono::Duration::minutes(i64::from(utc_offset_minutes));
+    let local_now = (now + offset).naive_utc();
+    let mut local_target = local_now.date().and_time(time);
+    if local_target < local_now {
+        local_target += chrono::Duration::days(1);
+    }
+    DateTime::from_naive_utc_and_offset(local_target - offset, Utc)
+}
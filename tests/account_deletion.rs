@@ -0,0 +1,150 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use rimskiy_service::api::{user_router, AppState};
+use rimskiy_service::auth::jwt::create_session_token;
+use rimskiy_service::models::notification::NotificationType;
+use rimskiy_service::repository::{
+    BlobStore, BlockRepository, CreateNotificationData, NotificationRepository,
+    TelegramBotRepository, UserPlateRepository, UserSessionRepository,
+};
+use uuid::Uuid;
+
+async fn send(state: &AppState, method: &str, token: &str) -> (StatusCode, serde_json::Value) {
+    let app = common::authed_app(state, Router::new().nest("/api/users", user_router()));
+    common::send_authed(app, method, "/api/users/me", token).await
+}
+
+async fn count(pool: &rimskiy_service::db::DbPool, table: &str, column: &str, id: Uuid) -> i64 {
+    sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM {} WHERE {} = $1",
+        table, column
+    ))
+    .bind(id)
+    .fetch_one(&**pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn deleting_account_removes_all_related_rows() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+    let other = common::create_user(&pool).await;
+
+    let (plate, second_plate) = (common::random_plate(), common::random_plate());
+    state
+        .user_plate_repository
+        .create(user.id, &plate, true, None)
+        .await
+        .unwrap();
+    state
+        .user_plate_repository
+        .create(user.id, &second_plate, false, None)
+        .await
+        .unwrap();
+    let other_plate = common::random_plate();
+    state
+        .user_plate_repository
+        .create(other.id, &other_plate, true, None)
+        .await
+        .unwrap();
+
+    let blocks = &state.block_repository;
+    let active = blocks
+        .create(user.id, &plate, &common::random_plate(), None, None, false)
+        .await
+        .unwrap();
+    let (photo_key, thumb_key) = (
+        format!("blocks/{}.jpg", active.id),
+        format!("blocks/{}_thumb.jpg", active.id),
+    );
+    for key in [&photo_key, &thumb_key] {
+        state.blob_store.put(key, b"jpeg").await.unwrap();
+    }
+    blocks
+        .set_photo(active.id, &photo_key, &thumb_key)
+        .await
+        .unwrap();
+    let finished = blocks
+        .create(user.id, &plate, &common::random_plate(), None, None, false)
+        .await
+        .unwrap();
+    blocks.delete(finished.id, &plate).await.unwrap();
+    // Блокировка, созданная другим пользователем, остаётся
+    let foreign = blocks
+        .create(other.id, &other_plate, &plate, None, None, false)
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        state
+            .notification_repository
+            .create(&CreateNotificationData {
+                user_id: user.id,
+                r#type: NotificationType::System,
+                title: "Тест".to_string(),
+                message: "Уведомление".to_string(),
+                data: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let phone_hash = user.phone_hash.clone().unwrap();
+    let chat_id = (Uuid::new_v4().as_u128() % 1_000_000_000_000) as i64 + 1;
+    state
+        .telegram_bot_repository
+        .upsert(&phone_hash, chat_id, Some("driver"), None)
+        .await
+        .unwrap();
+
+    let session = state
+        .user_session_repository
+        .create(user.id, Some("phone"))
+        .await
+        .unwrap();
+    let token = create_session_token(user.id, session.id, &state.config).unwrap();
+
+    let (status, summary) = send(&state, "DELETE", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(summary["plates"], 2);
+    assert_eq!(summary["blocks"], 1);
+    assert_eq!(summary["block_history"], 1);
+    assert_eq!(summary["notifications"], 3);
+    assert_eq!(summary["sessions"], 1);
+    assert_eq!(summary["telegram_registrations"], 1);
+    // Фото блокировки и его превью
+    assert_eq!(summary["files"], 2);
+    for key in [&photo_key, &thumb_key] {
+        assert!(
+            state.blob_store.get(key).await.unwrap().is_none(),
+            "{}",
+            key
+        );
+    }
+
+    for (table, column) in [
+        ("users", "id"),
+        ("user_plates", "user_id"),
+        ("blocks", "blocker_id"),
+        ("block_history", "blocker_id"),
+        ("notifications", "user_id"),
+        ("user_sessions", "user_id"),
+    ] {
+        assert_eq!(count(&pool, table, column, user.id).await, 0, "{}", table);
+    }
+    assert!(state
+        .telegram_bot_repository
+        .find_by_phone_hash(&phone_hash)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(blocks.find_by_id(foreign.id).await.unwrap().is_some());
+
+    // Токен удалённого аккаунта больше не принимается (его сессия удалена каскадно)
+    let (status, _) = send(&state, "GET", &token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use rimskiy_service::api::create_block_on_behalf;
+use rimskiy_service::auth::middleware::AuthState;
+use rimskiy_service::models::admin::AdminCreateBlockRequest;
+use rimskiy_service::repository::UserPlateRepository;
+use rimskiy_service::AppError;
+use uuid::Uuid;
+
+fn request(blocker_user_id: Uuid, blocked_plate: &str) -> AdminCreateBlockRequest {
+    serde_json::from_value(serde_json::json!({
+        "blocker_user_id": blocker_user_id,
+        "blocked_plate": blocked_plate,
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn admin_creates_block_on_behalf_of_resident() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let admin = common::create_user(&pool).await;
+    let resident = common::create_user(&pool).await;
+    let resident_plate = common::random_plate();
+    state
+        .user_plate_repository
+        .create(resident.id, &resident_plate, true, None)
+        .await
+        .unwrap();
+    let blocked_plate = common::random_plate();
+
+    let Json(response) = create_block_on_behalf(
+        State(state.clone()),
+        Extension(AuthState { user_id: admin.id }),
+        Json(request(resident.id, &blocked_plate)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.block.blocker_id, resident.id);
+    assert_eq!(response.block.blocker_plate, resident_plate);
+    assert_eq!(response.block.blocked_plate, blocked_plate);
+
+    let (actor_id, action, subject_user_id): (Option<Uuid>, String, Option<Uuid>) = sqlx::query_as(
+        "SELECT actor_id, action, subject_user_id FROM audit_log WHERE entity_id = $1",
+    )
+    .bind(response.block.id)
+    .fetch_one(&*pool)
+    .await
+    .unwrap();
+    assert_eq!(actor_id, Some(admin.id));
+    assert_eq!(action, "block_created_on_behalf");
+    assert_eq!(subject_user_id, Some(resident.id));
+
+    // Несуществующий жилец
+    let result = create_block_on_behalf(
+        State(state),
+        Extension(AuthState { user_id: admin.id }),
+        Json(request(Uuid::new_v4(), &common::random_plate())),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
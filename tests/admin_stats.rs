@@ -0,0 +1,69 @@
+mod common;
+
+use rimskiy_service::repository::{BlockRepository, PostgresBlockRepository};
+use rimskiy_service::utils::time::DEFAULT_UTC_OFFSET_MINUTES;
+
+#[tokio::test]
+async fn overview_stats_count_seeded_blocks() {
+    let pool = require_db!();
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let before = blocks
+        .overview_stats(DEFAULT_UTC_OFFSET_MINUTES)
+        .await
+        .unwrap();
+
+    // Два блокирующих перекрыли два номера; одна блокировка снята через 30 минут
+    let first = common::create_user(&pool).await;
+    let second = common::create_user(&pool).await;
+    let (first_plate, second_plate) = (common::random_plate(), common::random_plate());
+    let blocked = [common::random_plate(), common::random_plate()];
+    blocks
+        .create(first.id, &first_plate, &blocked[0], None, None, false)
+        .await
+        .unwrap();
+    blocks
+        .create(first.id, &first_plate, &blocked[1], None, None, false)
+        .await
+        .unwrap();
+    blocks
+        .create(second.id, &second_plate, &blocked[0], None, None, false)
+        .await
+        .unwrap();
+    let finished = blocks
+        .create(second.id, &second_plate, &blocked[1], None, None, false)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE blocks SET created_at = NOW() - INTERVAL '30 minutes' WHERE id = $1")
+        .bind(finished.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+    blocks.delete(finished.id, &second_plate).await.unwrap();
+
+    let after = blocks
+        .overview_stats(DEFAULT_UTC_OFFSET_MINUTES)
+        .await
+        .unwrap();
+    assert_eq!(after.active_blocks - before.active_blocks, 3);
+    assert_eq!(
+        after.unique_blocked_plates - before.unique_blocked_plates,
+        2
+    );
+    assert_eq!(after.unique_blockers - before.unique_blockers, 2);
+    assert_eq!(after.completed_blocks - before.completed_blocks, 1);
+
+    // Продолжительность новой завершённой блокировки - 30 минут
+    let total_minutes = |count: i64, average: Option<f64>| count as f64 * average.unwrap_or(0.0);
+    let added = total_minutes(after.completed_blocks, after.average_block_duration_minutes)
+        - total_minutes(
+            before.completed_blocks,
+            before.average_block_duration_minutes,
+        );
+    assert!((added - 30.0).abs() < 0.5, "{}", added);
+
+    assert_eq!(after.blocks_per_hour.len(), 24);
+    assert_eq!(
+        after.blocks_per_hour.iter().sum::<i64>() - before.blocks_per_hour.iter().sum::<i64>(),
+        4
+    );
+}
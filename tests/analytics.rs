@@ -0,0 +1,129 @@
+mod common;
+
+use rimskiy_service::models::block::CreateBlockRequest;
+use rimskiy_service::repository::{
+    PostgresBlockRepository, PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository,
+};
+use rimskiy_service::service::analytics_service::{
+    AnalyticsEvent, AnalyticsService, AnalyticsSink, NoopAnalyticsSink,
+};
+use rimskiy_service::service::{BlockService, TelegramService, TelephonyService};
+use rimskiy_service::utils::encryption::aad;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+const SECRET: &str = "analytics-secret-at-least-32-characters";
+
+/// Запоминает все события, чтобы тест мог их проверить
+#[derive(Default)]
+struct RecordingSink(Mutex<Vec<AnalyticsEvent>>);
+
+#[async_trait::async_trait]
+impl AnalyticsSink for RecordingSink {
+    async fn emit(&self, event: AnalyticsEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+fn anonymize_is_keyed() {
+    let service = AnalyticsService::new(Arc::new(NoopAnalyticsSink), SECRET);
+    let user_id = Uuid::new_v4();
+
+    // Стабилен для одного пользователя (подсчёт уникальных) и различает пользователей
+    assert_eq!(service.anonymize(user_id), service.anonymize(user_id));
+    assert_ne!(
+        service.anonymize(user_id),
+        service.anonymize(Uuid::new_v4())
+    );
+
+    // Без секрета значение не воспроизвести: другой секрет и простой SHA-256 дают другое
+    let other = AnalyticsService::new(
+        Arc::new(NoopAnalyticsSink),
+        "another-secret-at-least-32-characters",
+    );
+    assert_ne!(service.anonymize(user_id), other.anonymize(user_id));
+    let mut hasher = Sha256::new();
+    hasher.update(b"analytics:");
+    hasher.update(user_id.as_bytes());
+    assert_ne!(
+        service.anonymize(user_id),
+        format!("{:x}", hasher.finalize())
+    );
+}
+
+#[tokio::test]
+async fn block_creation_emits_one_anonymous_event() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let sink = Arc::new(RecordingSink::default());
+    let service = BlockService::new(
+        common::encryption(),
+        AnalyticsService::new(sink.clone(), SECRET),
+        config.clone(),
+    );
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+
+    let request: CreateBlockRequest =
+        serde_json::from_value(serde_json::json!({ "blocked_plate": owner_plate })).unwrap();
+    service
+        .create_block(
+            blocker.id,
+            request,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &PostgresNotificationOutboxRepository::new(pool.clone()),
+            &TelephonyService::new(config.clone()),
+            &TelegramService::new(&config),
+        )
+        .await
+        .unwrap();
+
+    // События отправляются в фоне
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let events = sink.0.lock().unwrap().clone();
+    assert_eq!(events.len(), 1, "{:?}", events);
+    assert_eq!(events[0].name, "block_created");
+
+    let json = serde_json::to_string(&events[0]).unwrap();
+    let encryption = common::encryption();
+    let mut secrets = vec![
+        blocker_plate,
+        owner_plate,
+        blocker.id.to_string(),
+        owner.id.to_string(),
+    ];
+    for user in [&blocker, &owner] {
+        let phone = encryption
+            .decrypt(
+                user.phone_encrypted.as_deref().unwrap(),
+                Some(&aad::user_phone(user.id)),
+            )
+            .unwrap();
+        secrets.push(phone.trim_start_matches('+').to_string());
+    }
+    for secret in secrets {
+        assert!(!json.contains(&secret), "{} leaked in {}", secret, json);
+    }
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use rimskiy_service::models::admin::{AnnounceJob, AnnounceJobStatus, AnnounceRequest};
+use rimskiy_service::repository::{
+    PostgresNotificationRepository, PostgresUserRepository, UpdateUserData, UserRepository,
+};
+use rimskiy_service::service::{AdminService, PushService, TelegramService};
+use rimskiy_service::AppError;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Ждёт завершения рассылки, проверяя, что прогресс не убывает
+async fn wait_for_job(service: &AdminService, job_id: Uuid) -> AnnounceJob {
+    let mut processed = 0;
+    for _ in 0..500 {
+        let job = service.announce_job(job_id).await.unwrap();
+        assert!(job.processed_users >= processed);
+        processed = job.processed_users;
+        if job.status != AnnounceJobStatus::Running {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("announcement job {} did not finish", job_id);
+}
+
+#[tokio::test]
+async fn every_user_gets_one_announcement_notification() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    // Маленькие пачки, чтобы рассылка прошла несколько страниц
+    config.announce_batch_size = 7;
+    let users = PostgresUserRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let service = AdminService::new(
+        PushService::new(None),
+        TelegramService::new(&config),
+        &config,
+    );
+    let mut seeded = Vec::new();
+    for _ in 0..30 {
+        seeded.push(common::create_user(&pool).await.id);
+    }
+    let title = format!("Тест {}", Uuid::new_v4().simple());
+
+    let response = service
+        .announce(
+            AnnounceRequest {
+                title: title.clone(),
+                message: "Парковка закрыта на уборку".to_string(),
+                channels: Vec::new(),
+            },
+            &users,
+            &notifications,
+        )
+        .await
+        .unwrap();
+    assert!(response.total_users >= seeded.len() as u64);
+    assert!(!response.delivery_started);
+
+    let job = wait_for_job(&service, response.job_id).await;
+    assert_eq!(job.status, AnnounceJobStatus::Completed);
+    assert!(job.processed_users >= seeded.len() as u64);
+    assert!(job.notifications_created >= seeded.len() as u64);
+    assert!(job.finished_at.is_some());
+
+    let counts: Vec<i64> = sqlx::query_scalar(
+        "SELECT COUNT(n.id) FROM UNNEST($1::uuid[]) AS u(id) \
+         LEFT JOIN notifications n ON n.user_id = u.id AND n.title = $2 GROUP BY u.id",
+    )
+    .bind(&seeded)
+    .bind(&title)
+    .fetch_all(&*pool)
+    .await
+    .unwrap();
+    assert_eq!(counts, vec![1; seeded.len()]);
+}
+
+#[tokio::test]
+async fn unknown_announcement_job_is_not_found() {
+    let config = common::test_config();
+    let service = AdminService::new(
+        PushService::new(None),
+        TelegramService::new(&config),
+        &config,
+    );
+
+    let result = service.announce_job(Uuid::new_v4()).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn announcement_channels_follow_stored_preferences() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    users
+        .update(
+            user.id,
+            &UpdateUserData {
+                telegram: Some("rimskiy_test".to_string()),
+                push_token: Some("push-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    // По умолчанию объявления приходят по всем каналам
+    let user = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(user.announcement_push_token(), Some("push-token"));
+    assert_eq!(user.announcement_telegram_username(), Some("rimskiy_test"));
+
+    users
+        .update(
+            user.id,
+            &UpdateUserData {
+                announcement_push: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let user = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(user.announcement_push_token(), None);
+    assert_eq!(user.announcement_telegram_username(), Some("rimskiy_test"));
+
+    users
+        .update(
+            user.id,
+            &UpdateUserData {
+                announcement_push: Some(true),
+                announcement_telegram: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let user = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(user.announcement_push_token(), Some("push-token"));
+    assert_eq!(user.announcement_telegram_username(), None);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Extension, Router};
+use rimskiy_service::api::AppState;
+use rimskiy_service::auth::jwt::create_token;
+use rimskiy_service::auth::middleware::{auth_middleware, AuthState, MAX_TOKEN_LENGTH};
+use tower::ServiceExt;
+
+fn app(state: &AppState) -> Router {
+    Router::new()
+        .route(
+            "/me",
+            get(|Extension(auth): Extension<AuthState>| async move { auth.user_id.to_string() }),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state.clone())
+}
+
+async fn status_with_header(state: &AppState, header: HeaderValue) -> StatusCode {
+    let request = Request::get("/me")
+        .header("Authorization", header)
+        .body(Body::empty())
+        .unwrap();
+    app(state).oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn malformed_authorization_headers_are_rejected() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+
+    // Многобайтовые символы (граница 20-го байта внутри символа), не Bearer и пустой токен
+    let headers = [
+        HeaderValue::from_bytes("Токен ключ".as_bytes()).unwrap(),
+        HeaderValue::from_bytes("Bearer ключ-ключ-ключ".as_bytes()).unwrap(),
+        HeaderValue::from_str("Basic abc").unwrap(),
+        HeaderValue::from_str("Bearer    ").unwrap(),
+    ];
+    for header in headers {
+        assert_eq!(
+            status_with_header(&state, header).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    let too_long = format!("Bearer {}", "a".repeat(MAX_TOKEN_LENGTH + 1));
+    assert_eq!(
+        status_with_header(&state, HeaderValue::from_str(&too_long).unwrap()).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn valid_token_passes() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+    let token = create_token(user.id, &state.config).unwrap();
+
+    let header = HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+    assert_eq!(status_with_header(&state, header).await, StatusCode::OK);
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use rimskiy_service::error::AppError;
+use rimskiy_service::models::auth::AuthCodeChannel;
+use rimskiy_service::repository::{
+    PostgresTelegramBotRepository, PostgresUserPlateRepository, PostgresUserRepository,
+    PostgresUserSessionRepository, TelegramBotRepository,
+};
+use rimskiy_service::service::{AuthService, TelegramService};
+use sha2::{Digest, Sha256};
+
+#[tokio::test]
+async fn phone_formats_resolve_to_one_account() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config,
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let sessions = PostgresUserSessionRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+    let telegram = TelegramService::new(&common::test_config());
+
+    // +79XXXXXXXXX -> "8 (9XX) XXX-XX-XX" и "+7 9XXXXXXXXX"
+    let phone = common::random_phone();
+    let digits = &phone[2..];
+    let local = format!(
+        "8 ({}) {}-{}-{}",
+        &digits[..3],
+        &digits[3..6],
+        &digits[6..8],
+        &digits[8..]
+    );
+    let international = format!("+7 {}", digits);
+
+    let mut user_ids = Vec::new();
+    for (start, verify) in [(&local, &international), (&international, &local)] {
+        let code = auth
+            .start_auth(start, AuthCodeChannel::Sms, &bots, &telegram)
+            .await
+            .unwrap()
+            .code;
+        let response = auth
+            .verify_auth(verify, &code, None, &users, &plates, &sessions)
+            .await
+            .unwrap();
+        user_ids.push(response.user_id);
+    }
+
+    assert_eq!(user_ids[0], user_ids[1]);
+}
+
+#[tokio::test]
+async fn telegram_channel_sends_code_through_bot_only() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.return_sms_code_in_response = true;
+    let telegram = TelegramService::new(&config);
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config.clone(),
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let sessions = PostgresUserSessionRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+    let phone = common::random_phone();
+
+    // Номер не привязан к боту
+    let result = auth
+        .start_auth(&phone, AuthCodeChannel::Telegram, &bots, &telegram)
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let phone_hash = format!("{:x}", Sha256::digest(phone.as_bytes()));
+    bots.upsert(&phone_hash, rand_chat_id(), None, None)
+        .await
+        .unwrap();
+    let response = auth
+        .start_auth(&phone, AuthCodeChannel::Telegram, &bots, &telegram)
+        .await
+        .unwrap();
+    assert_eq!(response.channel, AuthCodeChannel::Telegram);
+    auth.verify_auth(&phone, &response.code, None, &users, &plates, &sessions)
+        .await
+        .unwrap();
+
+    // Без dev режима неудачная отправка ботом - ошибка, а не тихий переход на SMS
+    config.return_sms_code_in_response = false;
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config,
+    );
+    let result = auth
+        .start_auth(&phone, AuthCodeChannel::Telegram, &bots, &telegram)
+        .await;
+    assert!(matches!(result, Err(AppError::Internal(_))));
+}
+
+#[tokio::test]
+async fn telegram_code_length_overrides_sms_length() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.return_sms_code_in_response = true;
+    config.sms_code_length = 4;
+    config.telegram_code_length = 8;
+    config.validate().unwrap();
+    let telegram = TelegramService::new(&config);
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config,
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let sessions = PostgresUserSessionRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+    let phone = common::random_phone();
+    let phone_hash = format!("{:x}", Sha256::digest(phone.as_bytes()));
+    bots.upsert(&phone_hash, rand_chat_id(), None, None)
+        .await
+        .unwrap();
+
+    let sms = auth
+        .start_auth(&phone, AuthCodeChannel::Sms, &bots, &telegram)
+        .await
+        .unwrap();
+    assert_eq!(sms.code.len(), 4);
+    let telegram_code = auth
+        .start_auth(&phone, AuthCodeChannel::Telegram, &bots, &telegram)
+        .await
+        .unwrap();
+    assert_eq!(telegram_code.code.len(), 8);
+
+    // Проверка кода одна для обоих каналов
+    auth.verify_auth(
+        &phone,
+        &telegram_code.code,
+        None,
+        &users,
+        &plates,
+        &sessions,
+    )
+    .await
+    .unwrap();
+}
+
+fn rand_chat_id() -> i64 {
+    use rand::Rng;
+    rand::thread_rng().gen_range(1..i64::MAX)
+}
+
+#[tokio::test]
+async fn concurrent_verify_creates_one_user() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let phone = common::random_phone();
+    let code = state
+        .sms_service
+        .store_code(&phone, state.config.sms_code_length)
+        .await
+        .unwrap();
+
+    let verify = || {
+        state.auth_service.verify_auth(
+            &phone,
+            &code,
+            None,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.user_session_repository,
+        )
+    };
+    let (first, second) = tokio::join!(verify(), verify());
+
+    // Код одноразовый: проходит ровно одна проверка, вторая получает обычную ошибку кода
+    let (ok, failed): (Vec<_>, Vec<_>) = [first, second].into_iter().partition(Result::is_ok);
+    assert_eq!(ok.len(), 1);
+    assert!(matches!(failed[0], Err(AppError::Auth(_))));
+
+    let (users,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE phone_hash = $1")
+        .bind(format!("{:x}", Sha256::digest(phone.as_bytes())))
+        .fetch_one(&*pool)
+        .await
+        .unwrap();
+    assert_eq!(users, 1);
+}
+
+#[tokio::test]
+async fn verify_reports_locked_code_clearly() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.return_sms_code_in_response = true;
+    config.sms_code_max_attempts = 2;
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config.clone(),
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let sessions = PostgresUserSessionRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+    let phone = common::random_phone();
+    let code = auth
+        .start_auth(&phone, AuthCodeChannel::Sms, &bots, &telegram)
+        .await
+        .unwrap()
+        .code;
+    let wrong = if code == "0000" { "1111" } else { "0000" };
+
+    let result = auth
+        .verify_auth(&phone, wrong, None, &users, &plates, &sessions)
+        .await;
+    assert!(matches!(result, Err(AppError::Auth(_))));
+    let result = auth
+        .verify_auth(&phone, wrong, None, &users, &plates, &sessions)
+        .await;
+    assert!(
+        matches!(&result, Err(AppError::CodeAttemptsExceeded(msg)) if msg.contains("Запросите новый код"))
+    );
+    let result = auth
+        .verify_auth(&phone, &code, None, &users, &plates, &sessions)
+        .await;
+    assert!(matches!(result, Err(AppError::CodeAttemptsExceeded(_))));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, UserPlateRepository,
+};
+use rimskiy_service::service::AuthzService;
+use rimskiy_service::AppError;
+
+#[tokio::test]
+async fn owner_co_owner_and_stranger() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+
+    let owner = common::create_user(&pool).await;
+    let co_owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let plate = common::random_plate();
+    plates.create(owner.id, &plate, true, None).await.unwrap();
+    plates
+        .create(co_owner.id, &plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(stranger.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let block = blocks
+        .create(owner.id, &plate, &common::random_plate(), None, None, false)
+        .await
+        .unwrap();
+
+    for (user, expected) in [(&owner, true), (&co_owner, true), (&stranger, false)] {
+        // Номер сравнивается после нормализации
+        assert_eq!(
+            AuthzService::is_plate_owner(user.id, &plate.to_lowercase(), &plates)
+                .await
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            AuthzService::is_block_blocker_or_coowner(user.id, &block, &plates)
+                .await
+                .unwrap(),
+            expected
+        );
+    }
+
+    assert!(matches!(
+        AuthzService::require_plate_owner(stranger.id, &plate, &plates, "нет прав").await,
+        Err(AppError::Forbidden(_))
+    ));
+    assert!(matches!(
+        AuthzService::require_block_blocker_or_coowner(stranger.id, &block, &plates, "нет прав")
+            .await,
+        Err(AppError::Forbidden(_))
+    ));
+    assert!(AuthzService::require_block_blocker_or_coowner(
+        co_owner.id,
+        &block,
+        &plates,
+        "нет прав"
+    )
+    .await
+    .is_ok());
+}
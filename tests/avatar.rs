@@ -0,0 +1,116 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, FsBlobStore, PostgresBlockRepository, PostgresUserRepository, UserRepository,
+};
+use rimskiy_service::service::UserService;
+use rimskiy_service::AppError;
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    image::DynamicImage::new_rgb8(width, height)
+        .write_to(
+            &mut std::io::Cursor::new(&mut data),
+            image::ImageOutputFormat::Png,
+        )
+        .unwrap();
+    data
+}
+
+async fn set_show_contacts(pool: &rimskiy_service::db::DbPool, user_id: uuid::Uuid, show: bool) {
+    sqlx::query("UPDATE users SET show_contacts = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(show)
+        .execute(&**pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn avatar_is_visible_only_with_show_contacts() {
+    let pool = require_db!();
+    let service = UserService::new(common::encryption(), false);
+    let block_service = common::block_service(&common::test_config());
+    let users = PostgresUserRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let blob_dir = std::env::temp_dir().join(format!("rimskiy-test-{}", uuid::Uuid::new_v4()));
+    let blob_store = FsBlobStore::new(&blob_dir);
+
+    let blocker = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    set_show_contacts(&pool, blocker.id, false).await;
+
+    let url = service
+        .upload_avatar(blocker.id, &png(4, 4), &users, &blob_store)
+        .await
+        .unwrap();
+    assert_eq!(url, format!("/api/users/{}/avatar", blocker.id));
+
+    // Владелец видит свой аватар всегда
+    let own = service
+        .get_avatar(blocker.id, blocker.id, &users, &blob_store)
+        .await
+        .unwrap();
+    assert!(!own.is_empty());
+
+    // Скрытые контакты скрывают и аватар
+    let hidden = service
+        .get_avatar(blocker.id, stranger.id, &users, &blob_store)
+        .await;
+    assert!(matches!(hidden, Err(AppError::NotFound(_))));
+
+    let blocked_plate = common::random_plate();
+    blocks
+        .create(
+            blocker.id,
+            &common::random_plate(),
+            &blocked_plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+    let check = block_service
+        .check_block(&blocked_plate, &blocks, &users)
+        .await
+        .unwrap();
+    assert_eq!(check.block.unwrap().blocker.avatar_url, None);
+
+    set_show_contacts(&pool, blocker.id, true).await;
+    let shared = service
+        .get_avatar(blocker.id, stranger.id, &users, &blob_store)
+        .await
+        .unwrap();
+    assert_eq!(shared, own);
+    let check = block_service
+        .check_block(&blocked_plate, &blocks, &users)
+        .await
+        .unwrap();
+    assert_eq!(check.block.unwrap().blocker.avatar_url, Some(url));
+
+    let _ = std::fs::remove_dir_all(blob_dir);
+}
+
+#[tokio::test]
+async fn missing_avatar_is_not_found() {
+    let pool = require_db!();
+    let service = UserService::new(common::encryption(), false);
+    let users = PostgresUserRepository::new(pool.clone());
+    let blob_dir = std::env::temp_dir().join(format!("rimskiy-test-{}", uuid::Uuid::new_v4()));
+    let blob_store = FsBlobStore::new(&blob_dir);
+
+    let user = common::create_user(&pool).await;
+    assert!(users
+        .find_by_id(user.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .avatar_key
+        .is_none());
+
+    let result = service
+        .get_avatar(user.id, user.id, &users, &blob_store)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use rimskiy_service::repository::{BlockRepository, NotificationRepository, UserPlateRepository};
+use rimskiy_service::AppError;
+
+#[tokio::test]
+async fn only_owner_requests_callback_once_per_interval() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+
+    let blocker = common::create_user(&pool).await;
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    let blocked_plate = common::random_plate();
+    state
+        .user_plate_repository
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    state
+        .user_plate_repository
+        .create(owner.id, &blocked_plate, true, None)
+        .await
+        .unwrap();
+    state
+        .user_plate_repository
+        .create(stranger.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET show_contacts = true WHERE id = $1")
+        .bind(owner.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+    let block = state
+        .block_repository
+        .create(
+            blocker.id,
+            &blocker_plate,
+            &blocked_plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let request = |user_id| {
+        let state = state.clone();
+        async move {
+            state
+                .block_service
+                .request_callback(
+                    block.id,
+                    user_id,
+                    &state.block_repository,
+                    &state.notification_repository,
+                    &state.user_repository,
+                    &state.user_plate_repository,
+                    &state.notification_outbox_repository,
+                )
+                .await
+        }
+    };
+    let blocker_notifications = || async {
+        state
+            .notification_repository
+            .find_by_user_id(blocker.id, false, 10, 0)
+            .await
+            .unwrap()
+    };
+
+    for user_id in [stranger.id, blocker.id] {
+        assert!(matches!(
+            request(user_id).await,
+            Err(AppError::Forbidden(_))
+        ));
+    }
+    assert_eq!(blocker_notifications().await.1, 0);
+
+    let response = request(owner.id).await.unwrap();
+    assert!(response.phone_shared);
+    assert_eq!(response.channels_used[0], "in_app");
+    let (notifications, total) = blocker_notifications().await;
+    assert_eq!(total, 1);
+    assert_eq!(notifications[0].title, "Просят перезвонить");
+
+    // Повторная просьба по той же блокировке - только после интервала
+    assert!(matches!(
+        request(owner.id).await,
+        Err(AppError::RateLimited { .. })
+    ));
+    assert_eq!(blocker_notifications().await.1, 1);
+}
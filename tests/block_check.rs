@@ -0,0 +1,148 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, Response, StatusCode};
+use axum::Router;
+use rimskiy_service::api::block_router;
+use rimskiy_service::repository::{BlockRepository, UserPlateRepository};
+use tower::ServiceExt;
+
+/// Номер в пути запроса (кириллица кодируется)
+fn plate_path(plate: &str) -> String {
+    let encoded: String = plate.bytes().map(|b| format!("%{:02X}", b)).collect();
+    format!("/api/blocks/check/{}", encoded)
+}
+
+async fn check(app: &Router, plate: &str, if_none_match: Option<&str>) -> Response<Body> {
+    let mut request = Request::get(plate_path(plate));
+    if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn etag(response: &Response<Body>) -> String {
+    response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn unchanged_state_is_not_modified() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let app = Router::new()
+        .nest("/api/blocks", block_router())
+        .with_state(state.clone());
+    let blocker = common::create_user(&pool).await;
+    let plate = common::random_plate();
+
+    let response = check(&app, &plate, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let first = etag(&response);
+
+    let response = check(&app, &plate, Some(&first)).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(etag(&response), first);
+
+    // Номер в другом регистре - то же состояние
+    let response = check(&app, &plate.to_lowercase(), Some(&first)).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // Появилась блокировка - ETag меняется
+    state
+        .block_repository
+        .create(
+            blocker.id,
+            &common::random_plate(),
+            &plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+    let response = check(&app, &plate, Some(&first)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(etag(&response), first);
+}
+
+#[tokio::test]
+async fn owner_sees_own_plate_flag() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let plate = common::random_plate();
+    state
+        .user_plate_repository
+        .create(owner.id, &plate, true, None)
+        .await
+        .unwrap();
+    state
+        .block_repository
+        .create(
+            stranger.id,
+            &common::random_plate(),
+            &plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    for (user_id, expected) in [(owner.id, true), (stranger.id, false)] {
+        let response = state
+            .block_service
+            .check_block_for_user(
+                user_id,
+                &plate.to_lowercase(),
+                &state.block_repository,
+                &state.user_repository,
+                &state.user_plate_repository,
+            )
+            .await
+            .unwrap();
+        assert!(response.is_blocked);
+        assert_eq!(response.is_own_plate, Some(expected));
+    }
+
+    // Без авторизации признака нет
+    let response = state
+        .block_service
+        .check_block(&plate, &state.block_repository, &state.user_repository)
+        .await
+        .unwrap();
+    assert_eq!(response.is_own_plate, None);
+    let json = serde_json::to_value(&response).unwrap();
+    assert!(json.get("is_own_plate").is_none());
+}
+
+#[tokio::test]
+async fn response_echoes_input_and_normalized_plate() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let plate = common::random_plate();
+    let chars: Vec<char> = plate.to_lowercase().chars().collect();
+    let input = format!(
+        "{} {} {} {}",
+        chars[0],
+        chars[1..4].iter().collect::<String>(),
+        chars[4..6].iter().collect::<String>(),
+        chars[6..].iter().collect::<String>()
+    );
+
+    let response = state
+        .block_service
+        .check_block(&input, &state.block_repository, &state.user_repository)
+        .await
+        .unwrap();
+    assert_eq!(response.input_plate, input);
+    assert_eq!(response.normalized_plate, plate);
+    assert!(!response.is_blocked);
+}
@@ -0,0 +1,1011 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::{middleware, Router};
+use rimskiy_service::api::block::block_router;
+use rimskiy_service::auth::jwt::create_token;
+use rimskiy_service::auth::middleware::auth_middleware;
+use rimskiy_service::middleware::public_url_middleware;
+use rimskiy_service::models::block::{
+    describe_block_reason, BlockReason, CreateBlockRequest, UpdateBlockRequest,
+};
+use rimskiy_service::models::notification::NotificationType;
+use rimskiy_service::repository::user_repository::UpdateUserData;
+use rimskiy_service::repository::{
+    BlockRepository, NotificationRepository, PostgresBlockRepository,
+    PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository, UserRepository,
+};
+use rimskiy_service::service::{TelegramService, TelephonyService, ValidationService};
+use rimskiy_service::AppError;
+use tower::ServiceExt;
+
+fn request(blocked_plate: &str) -> CreateBlockRequest {
+    serde_json::from_value(serde_json::json!({ "blocked_plate": blocked_plate })).unwrap()
+}
+
+#[tokio::test]
+async fn blocks_over_hourly_limit_are_rejected() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.block_rate_limit_per_hour = 2;
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    for attempt in 0..3 {
+        let result = service
+            .create_block(
+                blocker.id,
+                request(&common::random_plate()),
+                &blocks,
+                &notifications,
+                &users,
+                &plates,
+                &outbox,
+                &telephony,
+                &telegram,
+            )
+            .await;
+        if attempt < 2 {
+            assert!(result.is_ok(), "block {} must be allowed", attempt + 1);
+        } else {
+            match result {
+                Err(AppError::RateLimited {
+                    retry_after_secs, ..
+                }) => assert!((1..=3600).contains(&retry_after_secs)),
+                other => panic!("expected rate limit, got {:?}", other.map(|_| ())),
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn deleted_blocks_still_count_towards_limit() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.block_rate_limit_per_hour = 2;
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    // Создаём и сразу снимаем блокировки - лимит всё равно исчерпан
+    for _ in 0..2 {
+        let block = service
+            .create_block(
+                blocker.id,
+                request(&common::random_plate()),
+                &blocks,
+                &notifications,
+                &users,
+                &plates,
+                &outbox,
+                &telephony,
+                &telegram,
+            )
+            .await
+            .unwrap()
+            .block;
+        service
+            .delete_block(
+                block.id,
+                blocker.id,
+                &blocks,
+                &notifications,
+                &users,
+                &plates,
+                &outbox,
+            )
+            .await
+            .unwrap();
+    }
+
+    let result = service
+        .create_block(
+            blocker.id,
+            request(&common::random_plate()),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::RateLimited { .. })));
+}
+
+#[tokio::test]
+async fn repeated_blocks_of_same_car_require_acknowledgment() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.block_rate_limit_per_hour = 0;
+    config.repeat_block_free_count = 2;
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let victim_plate = common::random_plate();
+    let repeat_request = |acknowledge_repeat: bool| {
+        serde_json::from_value::<CreateBlockRequest>(serde_json::json!({
+            "blocked_plate": victim_plate,
+            "acknowledge_repeat": acknowledge_repeat,
+        }))
+        .unwrap()
+    };
+
+    // Первые REPEAT_BLOCK_FREE_COUNT блокировок проходят без подтверждения
+    for _ in 0..2 {
+        let block = service
+            .create_block(
+                blocker.id,
+                repeat_request(false),
+                &blocks,
+                &notifications,
+                &users,
+                &plates,
+                &outbox,
+                &telephony,
+                &telegram,
+            )
+            .await
+            .unwrap()
+            .block;
+        service
+            .delete_block(
+                block.id,
+                blocker.id,
+                &blocks,
+                &notifications,
+                &users,
+                &plates,
+                &outbox,
+            )
+            .await
+            .unwrap();
+    }
+
+    let preview = service
+        .preview_block(
+            blocker.id,
+            repeat_request(false),
+            &blocks,
+            &users,
+            &plates,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+    assert!(preview.repeat_warning.is_some());
+
+    let result = service
+        .create_block(
+            blocker.id,
+            repeat_request(false),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(AppError::RepeatBlockNotAcknowledged(_))
+    ));
+
+    // С подтверждением повтор разрешён; другой автомобиль - без вопросов
+    service
+        .create_block(
+            blocker.id,
+            repeat_request(true),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+    service
+        .create_block(
+            blocker.id,
+            request(&common::random_plate()),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+}
+
+#[test]
+fn block_reason_validation() {
+    assert_eq!(
+        ValidationService::validate_block_reason(Some(" Loading "), None).unwrap(),
+        Some(BlockReason::Loading)
+    );
+    assert_eq!(
+        ValidationService::validate_block_reason(None, None).unwrap(),
+        None
+    );
+    assert_eq!(
+        ValidationService::validate_block_reason(Some("other"), Some("Жду эвакуатор")).unwrap(),
+        Some(BlockReason::Other)
+    );
+
+    let too_long = "а".repeat(201);
+    for (reason, text) in [
+        (Some("parking"), None),
+        (Some("other"), None),
+        (Some("loading"), Some(too_long.as_str())),
+    ] {
+        assert!(matches!(
+            ValidationService::validate_block_reason(reason, text),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    assert_eq!(
+        describe_block_reason(Some(BlockReason::Loading), None, Some("18:30")).as_deref(),
+        Some("погрузка/разгрузка, до 18:30")
+    );
+    assert_eq!(
+        describe_block_reason(Some(BlockReason::Other), Some("Жду эвакуатор"), None).as_deref(),
+        Some("Жду эвакуатор")
+    );
+    assert_eq!(describe_block_reason(None, None, None), None);
+}
+
+#[tokio::test]
+async fn block_reason_is_stored_and_counted() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let request: CreateBlockRequest = serde_json::from_value(serde_json::json!({
+        "blocked_plate": owner_plate,
+        "departure_time": "18:30",
+        "reason": "loading",
+        "reason_text": "  Разгружаю мебель  ",
+    }))
+    .unwrap();
+    let block = service
+        .create_block(
+            blocker.id,
+            request,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+    let block = block.block;
+    assert_eq!(block.reason.as_deref(), Some("loading"));
+    assert_eq!(block.reason_text.as_deref(), Some("Разгружаю мебель"));
+
+    let stats = blocks.count_by_reason().await.unwrap();
+    assert!(stats
+        .iter()
+        .any(|stat| stat.reason.as_deref() == Some("loading") && stat.count >= 1));
+
+    // Уведомление владельцу действительно записано, с причиной в тексте
+    let (owner_notifications, total) = notifications
+        .find_by_user_id(owner.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(
+        owner_notifications[0].r#type,
+        NotificationType::BlockCreated
+    );
+    let reason = describe_block_reason(
+        Some(BlockReason::Loading),
+        Some("Разгружаю мебель"),
+        Some("18:30"),
+    )
+    .unwrap();
+    assert!(
+        owner_notifications[0]
+            .message
+            .ends_with(&format!("({})", reason)),
+        "{}",
+        owner_notifications[0].message
+    );
+
+    service
+        .delete_block(
+            block.id,
+            blocker.id,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await
+        .unwrap();
+    let (owner_notifications, total) = notifications
+        .find_by_user_id(owner.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 2);
+    assert!(owner_notifications
+        .iter()
+        .any(|notification| notification.r#type == NotificationType::BlockDeleted));
+    // Push о снятии блокировки отправляется через очередь уведомлений
+    let unblocked: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notification_outbox WHERE block_id = $1 AND user_id = $2 AND payload->'data'->>'status' = 'unblocked'",
+    )
+    .bind(block.id)
+    .bind(owner.id)
+    .fetch_one(&*pool)
+    .await
+    .unwrap();
+    assert_eq!(unblocked, 1);
+}
+
+#[tokio::test]
+async fn notify_summary_counts_reachable_owners() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    // Три совладельца номера: с push токеном, только с Telegram (по умолчанию уведомления
+    // идут push, Telegram - только по выбору блокирующего) и без каналов связи
+    let blocked_plate = common::random_plate();
+    let with_push = common::create_user(&pool).await;
+    let with_telegram = common::create_user(&pool).await;
+    let unreachable = common::create_user(&pool).await;
+    for owner in [&with_push, &with_telegram, &unreachable] {
+        plates
+            .create(owner.id, &blocked_plate, true, None)
+            .await
+            .unwrap();
+    }
+    users
+        .update(
+            with_push.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    users
+        .update(
+            with_telegram.id,
+            &UpdateUserData {
+                telegram: Some("@owner".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let summary = service
+        .create_block(
+            blocker.id,
+            request(&blocked_plate),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap()
+        .notify_summary;
+    assert_eq!(summary.owners_total, 3);
+    assert_eq!(summary.owners_reachable, 1);
+    assert_eq!(summary.channels_used, ["push"]);
+}
+
+#[tokio::test]
+async fn preview_describes_notification_without_writes() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            blocker.id,
+            &UpdateUserData {
+                name: Some("Иван".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let request: CreateBlockRequest = serde_json::from_value(serde_json::json!({
+        "blocked_plate": owner_plate,
+        "reason": "loading",
+    }))
+    .unwrap();
+    let preview = service
+        .preview_block(
+            blocker.id, request, &blocks, &users, &plates, &telephony, &telegram,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(preview.blocked_plate, owner_plate);
+    assert_eq!(
+        preview.message,
+        format!(
+            "Автомобиль {} заблокирован пользователем Иван (погрузка/разгрузка)",
+            owner_plate
+        )
+    );
+    assert!(preview.push_body.unwrap().contains(&owner_plate));
+    assert!(preview.telegram_message.is_none());
+    assert!(preview.call_message.is_none());
+    assert_eq!(preview.notify_summary.owners_total, 1);
+    assert_eq!(preview.notify_summary.channels_used, ["push"]);
+
+    // Блокировка не создана, уведомления не записаны
+    assert!(!blocks.exists(&blocker_plate, &owner_plate).await.unwrap());
+    let notifications: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1")
+            .bind(owner.id)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(notifications, 0);
+}
+
+#[tokio::test]
+async fn owner_quiet_hours_follow_owner_timezone() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+
+    // Тихие часы - два часа вокруг текущего времени в UTC+12, в UTC сейчас день
+    let owner_local = (chrono::Utc::now() + chrono::Duration::hours(12)).time();
+    let mut config = common::test_config();
+    config.owner_quiet_hours = Some((
+        owner_local - chrono::Duration::hours(1),
+        owner_local + chrono::Duration::hours(1),
+    ));
+    let service = common::block_service(&config);
+    let telephony = TelephonyService::new(config.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+
+    // У владельца ночь - вместо звонка только push; по UTC у владельца день - звонок разрешён
+    for (utc_offset_minutes, expected) in [(720, vec!["push"]), (0, vec!["call", "push"])] {
+        users
+            .update(
+                owner.id,
+                &UpdateUserData {
+                    push_token: Some("device-token".to_string()),
+                    utc_offset_minutes: Some(Some(utc_offset_minutes)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let request: CreateBlockRequest = serde_json::from_value(serde_json::json!({
+            "blocked_plate": owner_plate,
+            "notify_owner": true,
+        }))
+        .unwrap();
+        let summary = service
+            .preview_block(
+                blocker.id, request, &blocks, &users, &plates, &telephony, &telegram,
+            )
+            .await
+            .unwrap()
+            .notify_summary;
+        assert_eq!(
+            summary.channels_used, expected,
+            "offset {}",
+            utc_offset_minutes
+        );
+    }
+}
+
+#[tokio::test]
+async fn silent_block_notifies_nobody() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                telegram: Some("@owner".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let request: CreateBlockRequest = serde_json::from_value(serde_json::json!({
+        "blocked_plate": owner_plate,
+        "notify_owner": true,
+        "silent": true,
+    }))
+    .unwrap();
+    let response = service
+        .create_block(
+            blocker.id,
+            request,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &TelephonyService::new(config.clone()),
+            &TelegramService::new(&config),
+        )
+        .await
+        .unwrap();
+    assert!(response.block.silent);
+    assert_eq!(response.notify_summary.owners_total, 1);
+    assert_eq!(response.notify_summary.owners_reachable, 0);
+    assert!(response.notify_summary.channels_used.is_empty());
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1")
+        .bind(owner.id)
+        .fetch_one(&*pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0);
+
+    // Владелец всё равно видит блокировку, если проверит свой номер
+    let check = service
+        .check_block(&owner_plate, &blocks, &users)
+        .await
+        .unwrap();
+    assert!(check.is_blocked);
+
+    // Изменение и снятие тихой блокировки тоже проходят без уведомлений
+    let update: UpdateBlockRequest = serde_json::from_value(serde_json::json!({
+        "duration_minutes": 30,
+        "notify_owners": true,
+    }))
+    .unwrap();
+    service
+        .update_block(
+            response.block.id,
+            blocker.id,
+            update,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await
+        .unwrap();
+    service
+        .delete_block(
+            response.block.id,
+            blocker.id,
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await
+        .unwrap();
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1")
+        .bind(owner.id)
+        .fetch_one(&*pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0);
+    let queued: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM notification_outbox WHERE user_id = $1")
+            .bind(owner.id)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(queued, 0);
+}
+
+#[tokio::test]
+async fn plate_less_user_sees_only_own_blocks() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let user = common::create_user(&pool).await;
+    let co_owner = common::create_user(&pool).await;
+    let shared_plate = common::random_plate();
+    let user_plate = plates
+        .create(user.id, &shared_plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(co_owner.id, &shared_plate, true, None)
+        .await
+        .unwrap();
+    let own = blocks
+        .create(
+            user.id,
+            &shared_plate,
+            &common::random_plate(),
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+    blocks
+        .create(
+            co_owner.id,
+            &shared_plate,
+            &common::random_plate(),
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let (_, total) = service
+        .get_my_blocks(user.id, None, 0, &blocks, &plates)
+        .await
+        .unwrap();
+    assert_eq!(total, 2);
+
+    // После удаления последнего автомобиля блокировки совладельца больше не видны
+    plates.delete(user_plate.id, user.id).await.unwrap();
+    let (list, total) = service
+        .get_my_blocks(user.id, None, 0, &blocks, &plates)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(list[0].id, own.id);
+
+    // Без автомобиля создать блокировку нельзя
+    let result = service
+        .create_block(
+            user.id,
+            request(&common::random_plate()),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn response_includes_formatted_plates() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    let blocked_plate = common::random_plate();
+
+    let response = service
+        .create_block(
+            blocker.id,
+            request(&blocked_plate.to_lowercase()),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+
+    // 9 символов: "А 123 БВ 777"
+    let expected: String = {
+        let chars: Vec<char> = blocked_plate.chars().collect();
+        format!(
+            "{} {} {} {}",
+            chars[0],
+            chars[1..4].iter().collect::<String>(),
+            chars[4..6].iter().collect::<String>(),
+            chars[6..].iter().collect::<String>()
+        )
+    };
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["blocked_plate"], blocked_plate);
+    assert_eq!(json["blocked_plate_formatted"], expected);
+    assert_eq!(
+        response.blocker_plate_formatted.replace(' ', ""),
+        blocker_plate
+    );
+}
+
+#[tokio::test]
+async fn self_block_and_duplicates_cover_all_owned_plates() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+
+    let blocker = common::create_user(&pool).await;
+    let primary = common::random_plate();
+    let secondary = common::random_plate();
+    for (plate, is_primary) in [(&primary, true), (&secondary, false)] {
+        state
+            .user_plate_repository
+            .create(blocker.id, plate, is_primary, None)
+            .await
+            .unwrap();
+    }
+
+    let create = |plate: String| {
+        let state = state.clone();
+        async move {
+            state
+                .block_service
+                .create_block(
+                    blocker.id,
+                    request(&plate),
+                    &state.block_repository,
+                    &state.notification_repository,
+                    &state.user_repository,
+                    &state.user_plate_repository,
+                    &state.notification_outbox_repository,
+                    &state.telephony_service,
+                    &state.telegram_service,
+                )
+                .await
+        }
+    };
+
+    // Свой не основной автомобиль перекрыть нельзя
+    match create(secondary.to_lowercase()).await {
+        Err(AppError::Validation(message)) => assert!(message.contains("свой")),
+        other => panic!("expected self-block rejection, got {:?}", other.map(|_| ())),
+    }
+
+    // Блокировка с не основного автомобиля считается существующей
+    let target = common::random_plate();
+    state
+        .block_repository
+        .create(blocker.id, &secondary, &target, None, None, false)
+        .await
+        .unwrap();
+    assert!(state
+        .block_repository
+        .exists_from_any(&[primary.clone(), secondary.clone()], &target)
+        .await
+        .unwrap());
+    match create(target).await {
+        Err(AppError::Validation(message)) => assert!(message.contains("уже существует")),
+        other => panic!("expected duplicate rejection, got {:?}", other.map(|_| ())),
+    }
+
+    assert!(create(common::random_plate()).await.is_ok());
+}
+
+#[tokio::test]
+async fn created_block_location_uses_public_base_url() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.public_base_url = Some("https://api.example.com".to_string());
+    let state = common::test_state(&pool, config);
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .nest("/api/blocks", block_router())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            public_url_middleware,
+        ))
+        .with_state(state.clone());
+    let token = create_token(blocker.id, &state.config).unwrap();
+    let body = serde_json::json!({ "blocked_plate": common::random_plate() });
+    let response = app
+        .oneshot(
+            Request::post("/api/blocks")
+                .header("Authorization", format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let location = response.headers()[header::LOCATION]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        location,
+        format!(
+            "https://api.example.com/api/blocks/{}",
+            body["id"].as_str().unwrap()
+        )
+    );
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use rimskiy_service::db::DbPool;
+use rimskiy_service::repository::user_repository::UpdateUserData;
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresNotificationOutboxRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository, UserRepository,
+};
+use rimskiy_service::service::block_escalation::{
+    due_escalation_step, escalate_unacknowledged_blocks, EscalationStep,
+};
+use rimskiy_service::service::TelephonyService;
+use uuid::Uuid;
+
+async fn escalation_step(pool: &DbPool, block_id: Uuid) -> i16 {
+    sqlx::query_scalar("SELECT escalation_step FROM blocks WHERE id = $1")
+        .bind(block_id)
+        .fetch_one(&**pool)
+        .await
+        .unwrap()
+}
+
+async fn queued_channels(pool: &DbPool, block_id: Uuid) -> Vec<(Option<Uuid>, String)> {
+    sqlx::query_as("SELECT user_id, channel FROM notification_outbox WHERE block_id = $1")
+        .bind(block_id)
+        .fetch_all(&**pool)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn steps_follow_each_window() {
+    let window = Duration::minutes(10);
+    let created_at = Utc::now() - Duration::minutes(25);
+    let now = Utc::now();
+
+    assert_eq!(
+        due_escalation_step(created_at, 0, now, window),
+        Some(EscalationStep::RepeatPush)
+    );
+    assert_eq!(
+        due_escalation_step(created_at, 1, now, window),
+        Some(EscalationStep::Call)
+    );
+    // Третий шаг - только через три окна
+    assert_eq!(due_escalation_step(created_at, 2, now, window), None);
+    // Все шаги выполнены
+    assert_eq!(
+        due_escalation_step(created_at - window * 10, 3, now, window),
+        None
+    );
+}
+
+#[tokio::test]
+async fn unacknowledged_block_escalates_to_next_step() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let window = Duration::minutes(10);
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    // Две блокировки, созданные 15 минут назад; одну владелец подтвердил
+    let mut block_ids = Vec::new();
+    for _ in 0..2 {
+        let block = blocks
+            .create(
+                blocker.id,
+                &common::random_plate(),
+                &owner_plate,
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        sqlx::query("UPDATE blocks SET created_at = NOW() - INTERVAL '15 minutes' WHERE id = $1")
+            .bind(block.id)
+            .execute(&*pool)
+            .await
+            .unwrap();
+        block_ids.push(block.id);
+    }
+    let (pending, acknowledged) = (block_ids[0], block_ids[1]);
+    service
+        .acknowledge_block(acknowledged, owner.id, &blocks, &plates)
+        .await
+        .unwrap();
+
+    // Свежая блокировка ещё не требует эскалации
+    let fresh = blocks
+        .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+        .await
+        .unwrap();
+
+    let escalated = escalate_unacknowledged_blocks(
+        &service, &blocks, &users, &plates, &outbox, &telephony, window,
+    )
+    .await
+    .unwrap();
+    assert!(escalated >= 1);
+    assert_eq!(escalation_step(&pool, pending).await, 1);
+    assert_eq!(escalation_step(&pool, acknowledged).await, 0);
+    assert_eq!(escalation_step(&pool, fresh.id).await, 0);
+    // Повторный push поставлен в очередь уведомлений
+    assert_eq!(
+        queued_channels(&pool, pending).await,
+        vec![(Some(owner.id), "push".to_string())]
+    );
+
+    // Следующий шаг (звонок) - только после второго окна
+    escalate_unacknowledged_blocks(
+        &service, &blocks, &users, &plates, &outbox, &telephony, window,
+    )
+    .await
+    .unwrap();
+    assert_eq!(escalation_step(&pool, pending).await, 1);
+}
+
+#[tokio::test]
+async fn admin_call_is_queued_without_recipient() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.escalation_admin_phone = Some("+79990000000".to_string());
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let window = Duration::minutes(10);
+
+    let blocker = common::create_user(&pool).await;
+    let block = blocks
+        .create(
+            blocker.id,
+            &common::random_plate(),
+            &common::random_plate(),
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+    // Два шага уже выполнены, прошло три окна: пора звонить администратору
+    sqlx::query(
+        "UPDATE blocks SET created_at = NOW() - INTERVAL '35 minutes', escalation_step = 2 WHERE id = $1",
+    )
+    .bind(block.id)
+    .execute(&*pool)
+    .await
+    .unwrap();
+
+    escalate_unacknowledged_blocks(
+        &service, &blocks, &users, &plates, &outbox, &telephony, window,
+    )
+    .await
+    .unwrap();
+    assert_eq!(escalation_step(&pool, block.id).await, 3);
+    assert_eq!(
+        queued_channels(&pool, block.id).await,
+        vec![(None, "call".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn only_owner_can_acknowledge() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocker = common::create_user(&pool).await;
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    let block = blocks
+        .create(
+            blocker.id,
+            &common::random_plate(),
+            &owner_plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let result = service
+        .acknowledge_block(block.id, blocker.id, &blocks, &plates)
+        .await;
+    assert!(matches!(
+        result,
+        Err(rimskiy_service::AppError::Forbidden(_))
+    ));
+
+    let first = service
+        .acknowledge_block(block.id, owner.id, &blocks, &plates)
+        .await
+        .unwrap();
+    let acknowledged_at = first.acknowledged_at.expect("not acknowledged");
+    // Повторное подтверждение время не меняет
+    let second = service
+        .acknowledge_block(block.id, owner.id, &blocks, &plates)
+        .await
+        .unwrap();
+    assert_eq!(second.acknowledged_at, Some(acknowledged_at));
+}
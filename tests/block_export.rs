@@ -0,0 +1,68 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, UserPlateRepository,
+};
+use rimskiy_service::service::block_export::{stream_blocks_csv, BlockExportScope};
+use tokio_stream::StreamExt;
+
+async fn export(blocks: &PostgresBlockRepository, scope: BlockExportScope) -> Vec<String> {
+    let mut stream = stream_blocks_csv(blocks.clone(), scope);
+    let mut body = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        body.extend(chunk.unwrap());
+    }
+    String::from_utf8(body)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn csv_export_has_header_and_seeded_block() {
+    let pool = require_db!();
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    let owner = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    let owner_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    let block = blocks
+        .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+        .await
+        .unwrap();
+
+    let lines = export(&blocks, BlockExportScope::User(owner.id)).await;
+    assert_eq!(
+        lines[0],
+        "id,role,blocker_plate,blocked_plate,status,created_at,expires_at,acknowledged_at,removed_at"
+    );
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with(&format!(
+        "{},blocked,{},{},active,",
+        block.id, blocker_plate, owner_plate
+    )));
+    // Выгрузка пользователя не раскрывает идентификатор блокирующего
+    assert!(!lines[1].contains(&blocker.id.to_string()));
+
+    let lines = export(&blocks, BlockExportScope::User(blocker.id)).await;
+    assert!(lines[1].starts_with(&format!("{},blocker,", block.id)));
+
+    // Снятая блокировка остаётся в выгрузке со статусом removed
+    blocks.delete(block.id, &blocker_plate).await.unwrap();
+    let lines = export(&blocks, BlockExportScope::Admin(Some(owner.id))).await;
+    assert!(lines[0].starts_with("id,blocker_id,"));
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with(&format!("{},{},", block.id, blocker.id)));
+    assert!(lines[1].contains(",removed,"));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use rimskiy_service::repository::user_repository::UpdateUserData;
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresPushTokenRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, PushTokenRepository, UserPlateRepository,
+    UserRepository,
+};
+use rimskiy_service::AppError;
+
+#[tokio::test]
+async fn owner_with_only_stale_token_is_unreachable() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let push_tokens = PostgresPushTokenRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+
+    // У владельца нет телефона и Telegram, а push токен давно не обновлялся
+    let token = format!("stale-{}", uuid::Uuid::new_v4());
+    push_tokens
+        .upsert(owner.id, &token, Some("android"), None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some(token.clone()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET phone_encrypted = NULL WHERE id = $1")
+        .bind(owner.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE push_tokens SET last_seen = NOW() - INTERVAL '90 days' WHERE token = $1")
+        .bind(&token)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let block = blocks
+        .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+        .await
+        .unwrap();
+
+    let report = service
+        .get_block_notifiability(block.id, blocker.id, &blocks, &users, &plates, &push_tokens)
+        .await
+        .unwrap();
+    assert_eq!(report.owners_total, 1);
+    assert_eq!(report.owners_reachable, 0);
+    let owner_report = &report.owners[0];
+    assert!(!owner_report.reachable);
+    assert!(owner_report.channels.is_empty());
+    assert_eq!(
+        owner_report.issues,
+        ["stale_push_token", "no_telegram", "no_phone"]
+    );
+
+    // Свежий токен снова делает владельца доступным
+    push_tokens
+        .upsert(owner.id, &token, Some("android"), None)
+        .await
+        .unwrap();
+    let report = service
+        .get_block_notifiability(block.id, blocker.id, &blocks, &users, &plates, &push_tokens)
+        .await
+        .unwrap();
+    assert_eq!(report.owners_reachable, 1);
+    assert_eq!(report.owners[0].channels, ["push"]);
+
+    // Отчёт доступен только блокирующему
+    let result = service
+        .get_block_notifiability(block.id, owner.id, &blocks, &users, &plates, &push_tokens)
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, FsBlobStore, PostgresBlockRepository, PostgresUserPlateRepository,
+    UserPlateRepository,
+};
+use rimskiy_service::utils::image::{make_thumbnail, THUMBNAIL_MAX_SIZE};
+use rimskiy_service::AppError;
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    image::DynamicImage::new_rgb8(width, height)
+        .write_to(
+            &mut std::io::Cursor::new(&mut data),
+            image::ImageOutputFormat::Png,
+        )
+        .unwrap();
+    data
+}
+
+#[test]
+fn thumbnail_is_bounded_and_keeps_aspect_ratio() {
+    let original = png(1280, 640);
+
+    let thumbnail = make_thumbnail(&original, THUMBNAIL_MAX_SIZE).unwrap();
+
+    assert_eq!(
+        image::guess_format(&thumbnail).unwrap(),
+        image::ImageFormat::Jpeg
+    );
+    let decoded = image::load_from_memory(&thumbnail).unwrap();
+    assert_eq!(decoded.width(), THUMBNAIL_MAX_SIZE);
+    assert_eq!(decoded.height(), THUMBNAIL_MAX_SIZE / 2);
+}
+
+#[tokio::test]
+async fn only_blocker_can_upload_photo() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blob_dir = std::env::temp_dir().join(format!("rimskiy-test-{}", uuid::Uuid::new_v4()));
+    let blob_store = FsBlobStore::new(&blob_dir);
+
+    let blocker = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(stranger.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let block = blocks
+        .create(
+            blocker.id,
+            &blocker_plate,
+            &common::random_plate(),
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    // Чужая блокировка - 403, а не 401: пользователь аутентифицирован, но прав нет
+    let result = service
+        .upload_block_photo(
+            block.id,
+            stranger.id,
+            &png(4, 4),
+            &blocks,
+            &plates,
+            &blob_store,
+        )
+        .await;
+    let error = result.unwrap_err();
+    assert!(matches!(error, AppError::Forbidden(_)));
+    assert_eq!(error.code(), "FORBIDDEN");
+
+    service
+        .upload_block_photo(
+            block.id,
+            blocker.id,
+            &png(4, 4),
+            &blocks,
+            &plates,
+            &blob_store,
+        )
+        .await
+        .unwrap();
+    let block = blocks.find_by_id(block.id).await.unwrap().unwrap();
+    assert!(block.photo_key.is_some());
+    assert!(block.photo_thumb_key.is_some());
+
+    let _ = std::fs::remove_dir_all(blob_dir);
+}
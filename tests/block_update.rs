@@ -0,0 +1,141 @@
+mod common;
+
+use rimskiy_service::models::block::UpdateBlockRequest;
+use rimskiy_service::repository::user_repository::UpdateUserData;
+use rimskiy_service::repository::{
+    BlockRepository, NotificationRepository, PostgresBlockRepository,
+    PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository, UserRepository,
+};
+use rimskiy_service::utils::time::{local_time, DEFAULT_UTC_OFFSET_MINUTES};
+use rimskiy_service::AppError;
+
+fn update_request(json: serde_json::Value) -> UpdateBlockRequest {
+    serde_json::from_value(json).unwrap()
+}
+
+#[tokio::test]
+async fn blocker_updates_departure_time_and_owners_are_notified() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    users
+        .update(
+            owner.id,
+            &UpdateUserData {
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let block = blocks
+        .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+        .await
+        .unwrap();
+
+    // Посторонний не может менять чужую блокировку
+    let result = service
+        .update_block(
+            block.id,
+            owner.id,
+            update_request(serde_json::json!({ "departure_time": "19:15" })),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    let updated = service
+        .update_block(
+            block.id,
+            blocker.id,
+            update_request(serde_json::json!({
+                "departure_time": "19:15",
+                "note": "  Задерживаюсь  ",
+                "notify_owners": true,
+            })),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await
+        .unwrap();
+    let expires_at = updated.expires_at.expect("expires_at is not set");
+    assert!(expires_at > chrono::Utc::now());
+    assert_eq!(
+        local_time(expires_at, DEFAULT_UTC_OFFSET_MINUTES)
+            .format("%H:%M")
+            .to_string(),
+        "19:15"
+    );
+    assert_eq!(updated.note.as_deref(), Some("Задерживаюсь"));
+
+    let stored = blocks.find_by_id(block.id).await.unwrap().unwrap();
+    assert_eq!(stored.expires_at, updated.expires_at);
+
+    let (owner_notifications, _) = notifications
+        .find_by_user_id(owner.id, false, 50, 0)
+        .await
+        .unwrap();
+    assert_eq!(owner_notifications.len(), 1);
+    assert_eq!(owner_notifications[0].title, "Блокировка обновлена");
+    assert!(owner_notifications[0]
+        .message
+        .contains("уедет примерно в 19:15. Задерживаюсь"));
+
+    // Без notify_owners владельцы не уведомляются
+    service
+        .update_block(
+            block.id,
+            blocker.id,
+            update_request(serde_json::json!({ "duration_minutes": 30 })),
+            &blocks,
+            &notifications,
+            &users,
+            &plates,
+            &outbox,
+        )
+        .await
+        .unwrap();
+    let (_, total) = notifications
+        .find_by_user_id(owner.id, false, 50, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+
+    // Push о новом времени выезда отправляется через очередь уведомлений
+    let queued: Vec<serde_json::Value> = sqlx::query_scalar(
+        "SELECT payload FROM notification_outbox WHERE block_id = $1 AND user_id = $2 AND channel = 'push'",
+    )
+    .bind(block.id)
+    .bind(owner.id)
+    .fetch_all(&*pool)
+    .await
+    .unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0]["title"], "Блокировка обновлена");
+    assert_eq!(queued[0]["data"]["status"], "updated");
+}
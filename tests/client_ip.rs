@@ -0,0 +1,123 @@
+use axum::http::HeaderMap;
+use rimskiy_service::utils::network::{resolve_client_ip, resolve_public_base_url, IpNetwork};
+use std::net::IpAddr;
+
+fn ip(value: &str) -> IpAddr {
+    value.parse().unwrap()
+}
+
+fn proxies() -> Vec<IpNetwork> {
+    ["10.0.0.0/8", "203.0.113.7"]
+        .iter()
+        .map(|p| IpNetwork::parse(p).unwrap())
+        .collect()
+}
+
+#[test]
+fn direct_connection_uses_peer() {
+    assert_eq!(
+        resolve_client_ip(ip("198.51.100.1"), None, &proxies()),
+        ip("198.51.100.1")
+    );
+}
+
+#[test]
+fn untrusted_peer_cannot_spoof_forwarded_for() {
+    assert_eq!(
+        resolve_client_ip(ip("198.51.100.1"), Some("1.2.3.4"), &proxies()),
+        ip("198.51.100.1")
+    );
+    // Без настроенных прокси заголовку не доверяем вовсе
+    assert_eq!(
+        resolve_client_ip(ip("10.0.0.5"), Some("1.2.3.4"), &[]),
+        ip("10.0.0.5")
+    );
+}
+
+#[test]
+fn trusted_chain_takes_first_untrusted_hop_from_the_right() {
+    // Клиент подставил свой адрес в начало цепочки - он не учитывается
+    assert_eq!(
+        resolve_client_ip(
+            ip("10.0.0.5"),
+            Some("6.6.6.6, 198.51.100.9, 203.0.113.7"),
+            &proxies()
+        ),
+        ip("198.51.100.9")
+    );
+    // Все хопы доверенные - берём самый левый
+    assert_eq!(
+        resolve_client_ip(ip("10.0.0.5"), Some("10.1.1.1, 203.0.113.7"), &proxies()),
+        ip("10.1.1.1")
+    );
+    // IPv4-mapped адрес прокси сравнивается как IPv4
+    assert_eq!(
+        resolve_client_ip(ip("::ffff:10.0.0.5"), Some("198.51.100.9"), &proxies()),
+        ip("198.51.100.9")
+    );
+}
+
+fn forwarded(proto: &str, host: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-proto", proto.parse().unwrap());
+    headers.insert("x-forwarded-host", host.parse().unwrap());
+    headers.insert("host", "internal:8080".parse().unwrap());
+    headers
+}
+
+#[test]
+fn configured_public_base_url_wins() {
+    assert_eq!(
+        resolve_public_base_url(
+            Some("https://api.example.com"),
+            Some(ip("10.0.0.5")),
+            &forwarded("http", "other.example.com"),
+            &proxies()
+        )
+        .as_deref(),
+        Some("https://api.example.com")
+    );
+}
+
+#[test]
+fn public_base_url_from_trusted_proxy_headers() {
+    assert_eq!(
+        resolve_public_base_url(
+            None,
+            Some(ip("10.0.0.5")),
+            &forwarded("https, http", "api.example.com, internal"),
+            &proxies()
+        )
+        .as_deref(),
+        Some("https://api.example.com")
+    );
+    // Без X-Forwarded-* берём Host, схема по умолчанию http
+    let mut headers = HeaderMap::new();
+    headers.insert("host", "api.example.com:8080".parse().unwrap());
+    assert_eq!(
+        resolve_public_base_url(None, Some(ip("10.0.0.5")), &headers, &proxies()).as_deref(),
+        Some("http://api.example.com:8080")
+    );
+}
+
+#[test]
+fn untrusted_or_malformed_forwarded_host_is_ignored() {
+    let headers = forwarded("https", "api.example.com");
+    assert_eq!(
+        resolve_public_base_url(None, Some(ip("198.51.100.1")), &headers, &proxies()),
+        None
+    );
+    assert_eq!(
+        resolve_public_base_url(None, None, &headers, &proxies()),
+        None
+    );
+    assert_eq!(
+        resolve_public_base_url(
+            None,
+            Some(ip("10.0.0.5")),
+            &forwarded("https", "evil.example.com/path"),
+            &proxies()
+        ),
+        None
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use rimskiy_service::api::AppState;
+use rimskiy_service::middleware::client_version::CLIENT_VERSION_HEADER;
+use rimskiy_service::middleware::client_version_middleware;
+use tower::ServiceExt;
+
+fn app(state: &AppState) -> Router {
+    Router::new()
+        .route("/api/blocks/check", get(|| async { "ok" }))
+        .route("/api/blocks/check/sync", get(|| async { "ok" }))
+        .route("/api/blocks/checkout", get(|| async { "ok" }))
+        .route("/api/blocks", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            client_version_middleware,
+        ))
+        .with_state(state.clone())
+}
+
+async fn status(app: &Router, path: &str, version: Option<&str>) -> StatusCode {
+    let mut request = Request::get(path);
+    if let Some(version) = version {
+        request = request.header(CLIENT_VERSION_HEADER, version);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn old_client_is_blocked_only_on_gated_routes() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.min_client_version_routes = vec![
+        ("/api/blocks/check".to_string(), "1.4.0".to_string()),
+        ("/api/blocks/check/sync".to_string(), "2.0".to_string()),
+    ];
+    let app = app(&common::test_state(&pool, config));
+
+    let cases = [
+        ("/api/blocks/check", None, StatusCode::UPGRADE_REQUIRED),
+        (
+            "/api/blocks/check",
+            Some("1.3.9"),
+            StatusCode::UPGRADE_REQUIRED,
+        ),
+        ("/api/blocks/check", Some("1.4"), StatusCode::OK),
+        ("/api/blocks/check", Some("1.10.0"), StatusCode::OK),
+        // Действует самый длинный подходящий префикс
+        (
+            "/api/blocks/check/sync",
+            Some("1.10.0"),
+            StatusCode::UPGRADE_REQUIRED,
+        ),
+        ("/api/blocks/check/sync", Some("2.0.0"), StatusCode::OK),
+        // Префикс совпадает только по границе сегмента пути
+        ("/api/blocks/checkout", None, StatusCode::OK),
+        ("/api/blocks", Some("0.1"), StatusCode::OK),
+        ("/api/blocks", None, StatusCode::OK),
+    ];
+    for (path, version, expected) in cases {
+        assert_eq!(
+            status(&app, path, version).await,
+            expected,
+            "{} {:?}",
+            path,
+            version
+        );
+    }
+}
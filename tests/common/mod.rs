@@ -0,0 +1,326 @@
+//! Общие помощники интеграционных тестов. Тесты работают с отдельной БД из
+//! `TEST_DATABASE_URL` (схема создаётся автоматически) и пропускаются, если переменная не задана
+#![allow(dead_code)]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{middleware, Router};
+use rand::Rng;
+use rimskiy_service::api::{AppState, ReadinessState};
+use rimskiy_service::auth::code_store::InMemoryCodeStore;
+use rimskiy_service::auth::middleware::auth_middleware;
+use rimskiy_service::auth::sms::SmsService;
+use rimskiy_service::config::Config;
+use rimskiy_service::db::{init::ensure_database_and_tables, DbPool, DbTransaction};
+use rimskiy_service::error::AppResult;
+use rimskiy_service::models::admin::ChannelDeliveryStats;
+use rimskiy_service::models::outbox::{outbox_status, OutboxChannel, OutboxMessage};
+use rimskiy_service::models::user::User;
+use rimskiy_service::repository::{
+    CreateUserData, FsBlobStore, NewOutboxMessage, NotificationOutboxRepository,
+    PostgresAuditLogRepository, PostgresBlockRepository, PostgresNotificationOutboxRepository,
+    PostgresNotificationRepository, PostgresPlateShareInviteRepository,
+    PostgresPlateTransferRequestRepository, PostgresPushTokenRepository,
+    PostgresRevokedTokenRepository, PostgresTelegramBotRepository, PostgresUserPlateRepository,
+    PostgresUserRepository, PostgresUserSessionRepository, UserRepository,
+};
+use rimskiy_service::service::analytics_service::NoopAnalyticsSink;
+use rimskiy_service::service::{
+    AdminService, AnalyticsService, AuthService, BlockService, PushService, TelegramService,
+    TelephonyService, UserService,
+};
+use rimskiy_service::utils::encryption::{aad, Encryption};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Тестовый ключ шифрования (32 байта в hex)
+pub const TEST_ENCRYPTION_KEY: &str =
+    "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+static SCHEMA: OnceCell<()> = OnceCell::const_new();
+
+/// Пул тестовой БД или None, если `TEST_DATABASE_URL` не задан
+pub async fn test_pool() -> Option<DbPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping database test");
+        return None;
+    };
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(10)
+        .connect(&url)
+        .await
+        .expect("failed to connect to TEST_DATABASE_URL");
+    SCHEMA
+        .get_or_init(|| async {
+            ensure_database_and_tables(&pool)
+                .await
+                .expect("failed to create test schema");
+        })
+        .await;
+    Some(Arc::new(pool))
+}
+
+/// Получает пул тестовой БД или завершает тест, если БД не настроена
+#[macro_export]
+macro_rules! require_db {
+    () => {
+        match common::test_pool().await {
+            Some(pool) => pool,
+            None => return,
+        }
+    };
+}
+
+static ENV: std::sync::Once = std::sync::Once::new();
+
+/// Конфигурация из окружения с тестовыми значениями обязательных переменных.
+/// Отдельные поля тесты меняют в полученной структуре, а не через окружение
+pub fn test_config() -> Config {
+    ENV.call_once(|| {
+        std::env::set_var(
+            "DATABASE_URL",
+            std::env::var("TEST_DATABASE_URL")
+                .unwrap_or_else(|_| "postgres://localhost/unused".to_string()),
+        );
+        std::env::set_var("JWT_SECRET", "test-jwt-secret-at-least-32-characters");
+        std::env::set_var("ENCRYPTION_KEY", TEST_ENCRYPTION_KEY);
+    });
+    Config::from_env().expect("failed to load test config")
+}
+
+pub fn encryption() -> Encryption {
+    Encryption::new(TEST_ENCRYPTION_KEY).expect("invalid test encryption key")
+}
+
+/// Случайный телефон в формате +7XXXXXXXXXX
+pub fn random_phone() -> String {
+    format!(
+        "+79{:09}",
+        rand::thread_rng().gen_range(0..1_000_000_000u64)
+    )
+}
+
+/// Случайный корректный номер автомобиля (буква, три цифры, две буквы, регион)
+pub fn random_plate() -> String {
+    const LETTERS: [char; 12] = ['А', 'В', 'Е', 'К', 'М', 'Н', 'О', 'Р', 'С', 'Т', 'У', 'Х'];
+    let mut rng = rand::thread_rng();
+    let mut letter = || LETTERS[rng.gen_range(0..LETTERS.len())];
+    let (a, b, c) = (letter(), letter(), letter());
+    let mut rng = rand::thread_rng();
+    format!(
+        "{}{:03}{}{}{}",
+        a,
+        rng.gen_range(1..1000),
+        b,
+        c,
+        rng.gen_range(100..1000)
+    )
+}
+
+/// Создаёт пользователя со случайным телефоном
+pub async fn create_user(pool: &DbPool) -> User {
+    let phone = random_phone();
+    let id = Uuid::new_v4();
+    let phone_encrypted = encryption()
+        .encrypt(&phone, Some(&aad::user_phone(id)))
+        .expect("failed to encrypt phone");
+    PostgresUserRepository::new(pool.clone())
+        .create(&CreateUserData {
+            id,
+            phone_encrypted,
+            phone_hash: format!("test-{}", Uuid::new_v4()),
+            plate: String::new(),
+        })
+        .await
+        .expect("failed to create test user")
+}
+
+/// Сервис блокировок без внешних провайдеров и с отключённой аналитикой
+pub fn block_service(config: &Config) -> BlockService {
+    BlockService::new(
+        encryption(),
+        AnalyticsService::new(Arc::new(NoopAnalyticsSink), ""),
+        config.clone(),
+    )
+}
+
+/// SMS сервис с кодами в памяти процесса
+pub fn sms_service(config: Config) -> SmsService {
+    SmsService::new(config, Box::new(InMemoryCodeStore::new()))
+}
+
+/// Состояние приложения поверх тестовой БД, собранное так же, как в `main`
+/// (внешние провайдеры не настроены)
+pub fn test_state(pool: &DbPool, config: Config) -> AppState {
+    let encryption = encryption();
+    let sms_service = sms_service(config.clone());
+    let telegram_service = TelegramService::new(&config);
+    let push_service = PushService::new(None);
+    let blob_dir = std::env::temp_dir().join(format!("rimskiy-test-{}", Uuid::new_v4()));
+    let readiness = ReadinessState::new();
+    readiness.set_ready(true);
+
+    AppState {
+        auth_service: AuthService::new(sms_service.clone(), encryption.clone(), config.clone()),
+        user_service: UserService::new(encryption.clone(), config.mask_public_plates),
+        block_service: block_service(&config),
+        admin_service: AdminService::new(push_service.clone(), telegram_service.clone(), &config),
+        telephony_service: TelephonyService::new(config.clone()),
+        encryption,
+        sms_service,
+        telegram_service,
+        push_service,
+        analytics: AnalyticsService::new(Arc::new(NoopAnalyticsSink), ""),
+        user_repository: PostgresUserRepository::new(pool.clone()),
+        block_repository: PostgresBlockRepository::new(pool.clone()),
+        user_plate_repository: PostgresUserPlateRepository::new(pool.clone()),
+        plate_transfer_request_repository: PostgresPlateTransferRequestRepository::new(
+            pool.clone(),
+        ),
+        plate_share_invite_repository: PostgresPlateShareInviteRepository::new(pool.clone()),
+        notification_repository: PostgresNotificationRepository::new(pool.clone()),
+        push_token_repository: PostgresPushTokenRepository::new(pool.clone()),
+        audit_log_repository: PostgresAuditLogRepository::new(pool.clone()),
+        notification_outbox_repository: PostgresNotificationOutboxRepository::new(pool.clone()),
+        telegram_bot_repository: PostgresTelegramBotRepository::new(pool.clone()),
+        revoked_token_repository: PostgresRevokedTokenRepository::new(pool.clone()),
+        user_session_repository: PostgresUserSessionRepository::new(pool.clone()),
+        blob_store: FsBlobStore::new(&blob_dir),
+        readiness,
+        config,
+    }
+}
+
+/// Роутеры API за `auth_middleware`, как в `main`
+pub fn authed_app(state: &AppState, routes: Router<AppState>) -> Router {
+    routes
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state.clone())
+}
+
+/// Отправляет запрос с токеном без тела; возвращает статус и JSON ответа
+/// (`Null`, если тело пустое или не JSON)
+pub async fn send_authed(
+    app: Router,
+    method: &str,
+    uri: &str,
+    token: &str,
+) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null),
+    )
+}
+
+/// Очередь уведомлений, выдающая диспетчеру только сообщения одного пользователя:
+/// в общей тестовой БД остаются сообщения других тестов, и общая выборка
+/// `claim_due` не доходила бы до сообщений теста
+pub struct UserOutbox {
+    pool: DbPool,
+    inner: PostgresNotificationOutboxRepository,
+    user_id: Uuid,
+}
+
+impl UserOutbox {
+    pub fn new(pool: &DbPool, user_id: Uuid) -> Self {
+        Self {
+            pool: pool.clone(),
+            inner: PostgresNotificationOutboxRepository::new(pool.clone()),
+            user_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationOutboxRepository for UserOutbox {
+    async fn enqueue_in_tx(
+        &self,
+        tx: &mut DbTransaction,
+        messages: &[NewOutboxMessage],
+    ) -> AppResult<()> {
+        self.inner.enqueue_in_tx(tx, messages).await
+    }
+
+    async fn enqueue(&self, messages: &[NewOutboxMessage]) -> AppResult<()> {
+        self.inner.enqueue(messages).await
+    }
+
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease: chrono::Duration,
+    ) -> AppResult<Vec<OutboxMessage>> {
+        let messages = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            UPDATE notification_outbox
+            SET attempts = attempts + 1,
+                next_attempt_at = NOW() + $2,
+                updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM notification_outbox
+                WHERE status = $3 AND next_attempt_at <= NOW() AND user_id = $4
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, block_id, user_id, channel, payload, status, attempts, last_error, next_attempt_at, created_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease)
+        .bind(outbox_status::PENDING)
+        .bind(self.user_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    async fn mark_delivered(&self, id: Uuid) -> AppResult<()> {
+        self.inner.mark_delivered(id).await
+    }
+
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> AppResult<()> {
+        self.inner.mark_failed(id, error, retry_at).await
+    }
+
+    async fn record_receipt(
+        &self,
+        user_id: Uuid,
+        block_id: Uuid,
+        channel: OutboxChannel,
+        opened: bool,
+    ) -> AppResult<()> {
+        self.inner
+            .record_receipt(user_id, block_id, channel, opened)
+            .await
+    }
+
+    async fn delivery_stats(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<Vec<ChannelDeliveryStats>> {
+        self.inner.delivery_stats(since).await
+    }
+}
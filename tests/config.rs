@@ -0,0 +1,139 @@
+mod common;
+
+use rimskiy_service::config::{parse_jwt_private_key, parse_jwt_public_key, Config, JwtAlgorithm};
+
+type Change = Box<dyn FnOnce(&mut Config)>;
+
+fn check(change: impl FnOnce(&mut Config)) -> anyhow::Result<()> {
+    let mut config = common::test_config();
+    change(&mut config);
+    config.validate()
+}
+
+#[test]
+fn valid_config_passes() {
+    check(|_| {}).unwrap();
+    check(|config| {
+        config.encryption_key_version = 2;
+        config.encryption_previous_keys = vec![(1, "ab".repeat(32))];
+        config.min_client_version = Some("1.2".to_string());
+        config.release_client_version = Some("1.2.1".to_string());
+    })
+    .unwrap();
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    let cases: Vec<(&str, Change)> = vec![
+        (
+            "short jwt secret",
+            Box::new(|c| c.jwt_secret = "short".into()),
+        ),
+        ("jwt expiration", Box::new(|c| c.jwt_expiration_minutes = 0)),
+        (
+            "rs256 without keys",
+            Box::new(|c| c.jwt_algorithm = JwtAlgorithm::Rs256),
+        ),
+        (
+            "rs256 without public key",
+            Box::new(|c| {
+                c.jwt_algorithm = JwtAlgorithm::Rs256;
+                c.jwt_private_key = Some(
+                    parse_jwt_private_key(include_str!("fixtures/jwt_rs256_private.pem")).unwrap(),
+                );
+            }),
+        ),
+        (
+            "encryption key",
+            Box::new(|c| c.encryption_key = "zz".repeat(32)),
+        ),
+        (
+            "previous key",
+            Box::new(|c| c.encryption_previous_keys = vec![(0, "abc".into())]),
+        ),
+        ("key version", Box::new(|c| c.encryption_key_version = 128)),
+        (
+            "previous key version",
+            Box::new(|c| c.encryption_previous_keys = vec![(200, "ab".repeat(32))]),
+        ),
+        (
+            "duplicate key version",
+            Box::new(|c| {
+                c.encryption_previous_keys = vec![(c.encryption_key_version, "ab".repeat(32))]
+            }),
+        ),
+        (
+            "server host",
+            Box::new(|c| c.server_host = "localhost:80".into()),
+        ),
+        ("server port", Box::new(|c| c.server_port = 0)),
+        ("sms code length", Box::new(|c| c.sms_code_length = 12)),
+        (
+            "telegram code length",
+            Box::new(|c| c.telegram_code_length = 3),
+        ),
+        (
+            "sms code attempts",
+            Box::new(|c| c.sms_code_max_attempts = 0),
+        ),
+        (
+            "announce batch size",
+            Box::new(|c| c.announce_batch_size = 0),
+        ),
+        (
+            "announce concurrency",
+            Box::new(|c| c.announce_max_concurrency = 0),
+        ),
+        (
+            "redis url",
+            Box::new(|c| c.redis_url = Some("http://localhost:6379".into())),
+        ),
+        (
+            "client version",
+            Box::new(|c| c.min_client_version = Some("v1".into())),
+        ),
+        (
+            "min above release",
+            Box::new(|c| {
+                c.min_client_version = Some("2.0.0".into());
+                c.release_client_version = Some("1.9.9".into());
+            }),
+        ),
+        (
+            "client version route path",
+            Box::new(|c| c.min_client_version_routes = vec![("api/blocks".into(), "1.0".into())]),
+        ),
+        (
+            "client version route version",
+            Box::new(|c| c.min_client_version_routes = vec![("/api/blocks".into(), "x".into())]),
+        ),
+        (
+            "blob storage",
+            Box::new(|c| c.blob_storage_path = " ".into()),
+        ),
+        (
+            "public base url scheme",
+            Box::new(|c| c.public_base_url = Some("ftp://api.example.com".into())),
+        ),
+        (
+            "public base url host",
+            Box::new(|c| c.public_base_url = Some("https://".into())),
+        ),
+        (
+            "public base url query",
+            Box::new(|c| c.public_base_url = Some("https://api.example.com?x=1".into())),
+        ),
+    ];
+
+    for (name, change) in cases {
+        assert!(check(change).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn invalid_rsa_keys_are_rejected() {
+    assert!(parse_jwt_private_key("not a key").is_err());
+    assert!(parse_jwt_public_key("not a key").is_err());
+    assert!(parse_jwt_private_key(include_str!("fixtures/jwt_rs256_private.pem")).is_ok());
+    assert!(parse_jwt_public_key(include_str!("fixtures/jwt_rs256_public.pem")).is_ok());
+}
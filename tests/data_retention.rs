@@ -0,0 +1,84 @@
+mod common;
+
+use rimskiy_service::repository::{PostgresBlockRepository, PostgresNotificationRepository};
+use rimskiy_service::service::data_retention::{purge_expired_data, RetentionPolicy};
+use uuid::Uuid;
+
+async fn insert_notification(
+    pool: &rimskiy_service::db::DbPool,
+    user_id: Uuid,
+    age_days: i32,
+) -> Uuid {
+    let (id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO notifications (user_id, type, title, message, created_at)
+        VALUES ($1, 'system', 'Тест', 'Тест', NOW() - make_interval(days => $2))
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(age_days)
+    .fetch_one(&**pool)
+    .await
+    .unwrap();
+    id
+}
+
+async fn insert_history(pool: &rimskiy_service::db::DbPool, user_id: Uuid, age_days: i32) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO block_history (id, blocker_id, blocked_plate, created_at, deleted_at)
+        VALUES ($1, $2, $3, NOW() - make_interval(days => $4 + 1), NOW() - make_interval(days => $4))
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(common::random_plate())
+    .bind(age_days)
+    .execute(&**pool)
+    .await
+    .unwrap();
+    id
+}
+
+async fn exists(pool: &rimskiy_service::db::DbPool, table: &str, id: Uuid) -> bool {
+    let (exists,): (bool,) = sqlx::query_as(&format!(
+        "SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)",
+        table
+    ))
+    .bind(id)
+    .fetch_one(&**pool)
+    .await
+    .unwrap();
+    exists
+}
+
+#[tokio::test]
+async fn purge_removes_only_expired_rows() {
+    let pool = require_db!();
+    let user = common::create_user(&pool).await;
+
+    let old_notification = insert_notification(&pool, user.id, 40).await;
+    let new_notification = insert_notification(&pool, user.id, 5).await;
+    let old_history = insert_history(&pool, user.id, 40).await;
+    let new_history = insert_history(&pool, user.id, 5).await;
+
+    let report = purge_expired_data(
+        RetentionPolicy {
+            notification_days: 30,
+            block_days: 30,
+        },
+        &PostgresNotificationRepository::new(pool.clone()),
+        &PostgresBlockRepository::new(pool.clone()),
+    )
+    .await
+    .unwrap();
+    assert!(report.notifications_deleted >= 1);
+    assert!(report.block_history_deleted >= 1);
+
+    assert!(!exists(&pool, "notifications", old_notification).await);
+    assert!(exists(&pool, "notifications", new_notification).await);
+    assert!(!exists(&pool, "block_history", old_history).await);
+    assert!(exists(&pool, "block_history", new_history).await);
+}
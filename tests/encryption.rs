@@ -0,0 +1,258 @@
+mod common;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use base64::Engine;
+use rimskiy_service::config::EncryptionBackend;
+use rimskiy_service::repository::{PostgresUserRepository, UserRepository};
+use rimskiy_service::service::UserService;
+use rimskiy_service::utils::encryption::{aad, AesGcmEncryptor, Encryption, Encryptor};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const OTHER_KEY: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+#[test]
+fn undecryptable_phone_is_counted_not_panicked() {
+    let encryption = Encryption::new(KEY).unwrap();
+    let foreign = Encryption::new(OTHER_KEY)
+        .unwrap()
+        .encrypt("+79001234567", None)
+        .unwrap();
+    let user_id = Uuid::new_v4();
+    let context = aad::user_phone(user_id);
+
+    assert_eq!(
+        encryption.decrypt_for_user(&foreign, &context, user_id),
+        None
+    );
+    assert_eq!(
+        encryption.decrypt_for_user("not base64 at all", &context, user_id),
+        None
+    );
+    assert_eq!(encryption.decrypt_failures(), 2);
+
+    // Успешная расшифровка счётчик не меняет; клоны разделяют общий счётчик
+    let own = encryption.encrypt("+79001234567", Some(&context)).unwrap();
+    let clone = encryption.clone();
+    assert_eq!(
+        clone.decrypt_for_user(&own, &context, user_id).as_deref(),
+        Some("+79001234567")
+    );
+    assert_eq!(encryption.decrypt_failures(), 2);
+}
+
+#[test]
+fn default_backend_round_trips_in_compatible_format() {
+    let mut config = common::test_config();
+    config.encryption_backend = EncryptionBackend::parse(" AES-GCM ").unwrap();
+    let encryption = Encryption::from_config(&config).unwrap();
+
+    let ciphertext = encryption.encrypt("+79001234567", None).unwrap();
+    assert_eq!(
+        encryption.decrypt(&ciphertext, None).unwrap(),
+        "+79001234567"
+    );
+    // Формат прежний: данные читаются и напрямую через AES-GCM, и старым конструктором
+    let direct = AesGcmEncryptor::with_keys(1, KEY, &[]).unwrap();
+    assert_eq!(direct.decrypt(&ciphertext, None).unwrap(), "+79001234567");
+    assert_eq!(
+        Encryption::new(KEY)
+            .unwrap()
+            .decrypt(&ciphertext, None)
+            .unwrap(),
+        "+79001234567"
+    );
+    assert_eq!(EncryptionBackend::parse("kms"), None);
+}
+
+/// Алгоритм-заглушка: проверяет, что `Encryption` работает с любым `dyn Encryptor`
+struct Reversed;
+
+impl Encryptor for Reversed {
+    fn encrypt(&self, plaintext: &str, _aad: Option<&[u8]>) -> anyhow::Result<String> {
+        Ok(plaintext.chars().rev().collect())
+    }
+
+    fn decrypt(&self, ciphertext: &str, _aad: Option<&[u8]>) -> anyhow::Result<String> {
+        Ok(ciphertext.chars().rev().collect())
+    }
+
+    fn re_encrypt(&self, _ciphertext: &str, _aad: Option<&[u8]>) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn current_version(&self) -> u8 {
+        7
+    }
+}
+
+#[test]
+fn custom_backend_is_used_through_trait_object() {
+    let backend: Arc<dyn Encryptor> = Arc::new(Reversed);
+    let encryption = Encryption::with_backend(backend);
+    let user_id = Uuid::new_v4();
+    assert_eq!(encryption.encrypt("abc", None).unwrap(), "cba");
+    assert_eq!(
+        encryption
+            .decrypt_for_user("cba", &aad::user_phone(user_id), user_id)
+            .as_deref(),
+        Some("abc")
+    );
+    assert_eq!(encryption.current_version(), 7);
+}
+
+/// Шифротекст в старом формате без байта версии: base64(nonce || ciphertext)
+fn legacy_encrypt(key: &str, plaintext: &str) -> String {
+    let cipher = Aes256Gcm::new_from_slice(&hex::decode(key).unwrap()).unwrap();
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut combined = nonce.to_vec();
+    combined.extend(cipher.encrypt(&nonce, plaintext.as_bytes()).unwrap());
+    base64::engine::general_purpose::STANDARD.encode(combined)
+}
+
+/// Первый байт шифротекста: версия ключа и признак AAD (старший бит)
+fn header(ciphertext: &str) -> u8 {
+    base64::engine::general_purpose::STANDARD
+        .decode(ciphertext)
+        .unwrap()[0]
+}
+
+fn key_version(ciphertext: &str) -> u8 {
+    header(ciphertext) & 0x7f
+}
+
+#[test]
+fn aad_must_match_to_decrypt() {
+    let encryption = Encryption::new(KEY).unwrap();
+    let (owner, other) = (Uuid::new_v4(), Uuid::new_v4());
+    let ciphertext = encryption
+        .encrypt("+79001234567", Some(&aad::user_phone(owner)))
+        .unwrap();
+    assert_eq!(header(&ciphertext), 0x80 | 1);
+
+    assert_eq!(
+        encryption
+            .decrypt(&ciphertext, Some(&aad::user_phone(owner)))
+            .unwrap(),
+        "+79001234567"
+    );
+    // Телефон одного пользователя нельзя подставить другому или прочитать без контекста
+    assert!(encryption
+        .decrypt(&ciphertext, Some(&aad::user_phone(other)))
+        .is_err());
+    assert!(encryption.decrypt(&ciphertext, None).is_err());
+
+    // Сброс признака AAD не позволяет обойти проверку контекста
+    let mut stripped = base64::engine::general_purpose::STANDARD
+        .decode(&ciphertext)
+        .unwrap();
+    stripped[0] &= 0x7f;
+    let stripped = base64::engine::general_purpose::STANDARD.encode(stripped);
+    assert!(encryption.decrypt(&stripped, None).is_err());
+    assert!(encryption
+        .decrypt(&stripped, Some(&aad::user_phone(owner)))
+        .is_err());
+}
+
+#[test]
+fn data_without_aad_still_decrypts_and_is_bound_on_reencrypt() {
+    let encryption = Encryption::new(KEY).unwrap();
+    let user_id = Uuid::new_v4();
+    let context = aad::user_phone(user_id);
+
+    // Шифротексты, созданные до появления AAD, читаются в любом контексте
+    for unbound in [
+        legacy_encrypt(KEY, "+79001234567"),
+        encryption.encrypt("+79001234567", None).unwrap(),
+    ] {
+        assert_eq!(
+            encryption.decrypt(&unbound, Some(&context)).unwrap(),
+            "+79001234567"
+        );
+        // и перешифровываются с привязкой к пользователю
+        let bound = encryption
+            .re_encrypt(&unbound, Some(&context))
+            .unwrap()
+            .expect("must be bound to the context");
+        assert_eq!(
+            encryption.decrypt(&bound, Some(&context)).unwrap(),
+            "+79001234567"
+        );
+        assert!(encryption
+            .decrypt(&bound, Some(&aad::user_phone(Uuid::new_v4())))
+            .is_err());
+        assert_eq!(encryption.re_encrypt(&bound, Some(&context)).unwrap(), None);
+    }
+}
+
+#[test]
+fn key_versions_keep_the_aad_bit_free() {
+    assert!(Encryption::with_keys(128, KEY, &[]).is_err());
+    assert!(Encryption::with_keys(1, KEY, &[(200, OTHER_KEY.to_string())]).is_err());
+    assert!(Encryption::with_keys(127, KEY, &[(0, OTHER_KEY.to_string())]).is_ok());
+}
+
+#[test]
+fn re_encrypt_upgrades_to_current_key_version() {
+    let rotated = Encryption::with_keys(2, OTHER_KEY, &[(1, KEY.to_string())]).unwrap();
+
+    for old in [
+        legacy_encrypt(KEY, "+79001234567"),
+        Encryption::new(KEY)
+            .unwrap()
+            .encrypt("+79001234567", None)
+            .unwrap(),
+    ] {
+        assert_eq!(rotated.decrypt(&old, None).unwrap(), "+79001234567");
+        let upgraded = rotated
+            .re_encrypt(&old, None)
+            .unwrap()
+            .expect("must be rewritten");
+        assert_eq!(key_version(&upgraded), 2);
+        assert_eq!(rotated.decrypt(&upgraded, None).unwrap(), "+79001234567");
+        // Текущий ключ - перешифровывать нечего
+        assert_eq!(rotated.re_encrypt(&upgraded, None).unwrap(), None);
+    }
+}
+
+#[tokio::test]
+async fn reencrypt_endpoint_rewrites_legacy_phone() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let legacy = legacy_encrypt(common::TEST_ENCRYPTION_KEY, "+79001234567");
+    sqlx::query("UPDATE users SET phone_encrypted = $2 WHERE id = $1")
+        .bind(user.id)
+        .bind(&legacy)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let rotated = Encryption::with_keys(
+        2,
+        OTHER_KEY,
+        &[(1, common::TEST_ENCRYPTION_KEY.to_string())],
+    )
+    .unwrap();
+    let service = UserService::new(rotated.clone(), false);
+
+    let response = service.reencrypt_user_data(user.id, &users).await.unwrap();
+    assert!(response.reencrypted);
+    assert_eq!(response.key_version, 2);
+
+    let stored = users.find_by_id(user.id).await.unwrap().unwrap();
+    let phone_encrypted = stored.phone_encrypted.unwrap();
+    assert_eq!(header(&phone_encrypted), 0x80 | 2);
+    assert_eq!(
+        rotated
+            .decrypt(&phone_encrypted, Some(&aad::user_phone(user.id)))
+            .unwrap(),
+        "+79001234567"
+    );
+
+    // Повторный вызов ничего не меняет
+    let response = service.reencrypt_user_data(user.id, &users).await.unwrap();
+    assert!(!response.reencrypted);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use rimskiy_service::error::AppResult;
+use rimskiy_service::repository::{EncryptionCanaryRepository, PostgresEncryptionCanaryRepository};
+use rimskiy_service::service::encryption_canary::verify_encryption_key;
+use rimskiy_service::utils::encryption::{aad, Encryption};
+use rimskiy_service::AppError;
+use std::sync::Mutex;
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const OTHER_KEY: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+/// Контрольное значение в памяти: таблица encryption_canary одна на всю тестовую БД
+#[derive(Default)]
+struct MemoryCanary(Mutex<Option<String>>);
+
+#[async_trait::async_trait]
+impl EncryptionCanaryRepository for MemoryCanary {
+    async fn find(&self) -> AppResult<Option<String>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    async fn save(&self, value_encrypted: &str) -> AppResult<()> {
+        *self.0.lock().unwrap() = Some(value_encrypted.to_string());
+        Ok(())
+    }
+}
+
+impl MemoryCanary {
+    fn stored(&self) -> String {
+        self.0.lock().unwrap().clone().unwrap()
+    }
+}
+
+#[tokio::test]
+async fn mismatched_key_trips_the_guard() {
+    let original = Encryption::new(KEY).unwrap();
+    let replaced = Encryption::new(OTHER_KEY).unwrap();
+    let canary = MemoryCanary::default();
+
+    // Первый запуск сохраняет контрольное значение, повторный с тем же ключом проходит
+    verify_encryption_key(&original, &canary, false)
+        .await
+        .unwrap();
+    let stored = canary.stored();
+    verify_encryption_key(&original, &canary, false)
+        .await
+        .unwrap();
+    assert_eq!(canary.stored(), stored);
+
+    // Ключ сменили без ротации - запуск запрещён, контрольное значение не тронуто
+    let result = verify_encryption_key(&replaced, &canary, false).await;
+    assert!(matches!(result, Err(AppError::Encryption(_))));
+    assert_eq!(canary.stored(), stored);
+
+    // Осознанная смена ключа: контрольное значение перезаписывается новым ключом
+    verify_encryption_key(&replaced, &canary, true)
+        .await
+        .unwrap();
+    assert!(replaced
+        .decrypt(&canary.stored(), Some(aad::ENCRYPTION_CANARY))
+        .is_ok());
+    verify_encryption_key(&replaced, &canary, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn rotated_key_re_encrypts_canary() {
+    let previous = Encryption::with_keys(1, KEY, &[]).unwrap();
+    let rotated = Encryption::with_keys(2, OTHER_KEY, &[(1, KEY.to_string())]).unwrap();
+    let canary = MemoryCanary::default();
+
+    verify_encryption_key(&previous, &canary, false)
+        .await
+        .unwrap();
+    verify_encryption_key(&rotated, &canary, false)
+        .await
+        .unwrap();
+
+    // После ротации контрольное значение читается и без предыдущего ключа
+    let only_new = Encryption::with_keys(2, OTHER_KEY, &[]).unwrap();
+    verify_encryption_key(&only_new, &canary, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn canary_is_stored_in_database() {
+    let pool = require_db!();
+    let repository = PostgresEncryptionCanaryRepository::new(pool.clone());
+    let encryption = common::encryption();
+
+    verify_encryption_key(&encryption, &repository, true)
+        .await
+        .unwrap();
+    let stored = repository.find().await.unwrap().unwrap();
+    assert!(encryption
+        .decrypt(&stored, Some(aad::ENCRYPTION_CANARY))
+        .is_ok());
+    verify_encryption_key(&encryption, &repository, false)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,146 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use rimskiy_service::api::{method_not_allowed, route_not_found};
+use rimskiy_service::AppError;
+use tower::ServiceExt;
+
+async fn body_json(response: Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn every_variant_has_a_stable_code() {
+    let cases = [
+        (
+            AppError::Database(sqlx::Error::RowNotFound),
+            "DATABASE",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+        (
+            AppError::Auth("x".into()),
+            "UNAUTHORIZED",
+            StatusCode::UNAUTHORIZED,
+        ),
+        (
+            AppError::Validation("x".into()),
+            "VALIDATION",
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            AppError::Forbidden("x".into()),
+            "FORBIDDEN",
+            StatusCode::FORBIDDEN,
+        ),
+        (
+            AppError::NotFound("x".into()),
+            "NOT_FOUND",
+            StatusCode::NOT_FOUND,
+        ),
+        (
+            AppError::MethodNotAllowed("x".into()),
+            "METHOD_NOT_ALLOWED",
+            StatusCode::METHOD_NOT_ALLOWED,
+        ),
+        (
+            AppError::RateLimited {
+                message: "x".into(),
+                retry_after_secs: 7,
+            },
+            "RATE_LIMITED",
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        (
+            AppError::ServiceUnavailable("x".into()),
+            "SERVICE_UNAVAILABLE",
+            StatusCode::SERVICE_UNAVAILABLE,
+        ),
+        (
+            AppError::Conflict("x".into()),
+            "CONFLICT",
+            StatusCode::CONFLICT,
+        ),
+        (
+            AppError::RepeatBlockNotAcknowledged("x".into()),
+            "REPEAT_BLOCK_NOT_ACKNOWLEDGED",
+            StatusCode::CONFLICT,
+        ),
+        (
+            AppError::CodeAttemptsExceeded("x".into()),
+            "CODE_ATTEMPTS_EXCEEDED",
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        (
+            AppError::AccountSuspended("x".into()),
+            "ACCOUNT_SUSPENDED",
+            StatusCode::FORBIDDEN,
+        ),
+        (
+            AppError::UpgradeRequired("x".into()),
+            "UPGRADE_REQUIRED",
+            StatusCode::UPGRADE_REQUIRED,
+        ),
+        (
+            AppError::Encryption("x".into()),
+            "ENCRYPTION",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+        (
+            AppError::Internal("x".into()),
+            "INTERNAL",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    ];
+
+    for (error, code, status) in cases {
+        assert_eq!(error.code(), code);
+        let response = error.into_response();
+        assert_eq!(response.status(), status, "{}", code);
+        let body = body_json(response).await;
+        assert_eq!(body["code"], code);
+        assert!(body["error"].is_string());
+        assert!(body["details"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_sets_retry_after() {
+    let response = AppError::RateLimited {
+        message: "x".into(),
+        retry_after_secs: 42,
+    }
+    .into_response();
+    assert_eq!(response.headers()["retry-after"], "42");
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/api/blocks", get(|| async { "[]" }))
+        .fallback(route_not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+}
+
+#[tokio::test]
+async fn unknown_route_is_json_404() {
+    let response = app()
+        .oneshot(Request::get("/api/unknown").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(body_json(response).await["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn wrong_method_is_json_405() {
+    let response = app()
+        .oneshot(Request::delete("/api/blocks").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body_json(response).await["code"], "METHOD_NOT_ALLOWED");
+}
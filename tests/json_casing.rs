@@ -0,0 +1,92 @@
+use chrono::Utc;
+use rimskiy_service::models::user_plate::{plate_transfer_status, PlateTransferRequest};
+use rimskiy_service::openapi::ApiDoc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+fn is_snake_case(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[test]
+fn schema_properties_are_snake_case() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    assert!(!schemas.is_empty());
+
+    for (name, schema) in schemas {
+        let Some(properties) = schema["properties"].as_object() else {
+            continue;
+        };
+        for key in properties.keys() {
+            assert!(is_snake_case(key), "{}.{} is not snake_case", name, key);
+        }
+    }
+}
+
+#[test]
+fn serialized_keys_are_snake_case() {
+    let request = PlateTransferRequest {
+        id: Uuid::new_v4(),
+        plate: "А123БВ777".to_string(),
+        claimant_id: Uuid::new_v4(),
+        status: plate_transfer_status::PENDING.to_string(),
+        created_at: Utc::now(),
+        responded_at: None,
+        responded_by: None,
+    };
+
+    for value in [
+        serde_json::to_value(&request).unwrap(),
+        serde_json::to_value(request.to_response(None)).unwrap(),
+    ] {
+        let keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        assert!(keys.iter().all(|key| is_snake_case(key)), "{:?}", keys);
+        assert!(value.get("claimant_id").is_some());
+        assert!(value.get("created_at").is_some());
+    }
+}
+
+/// Каждая (де)сериализуемая структура в `models` явно задаёт `rename_all = "snake_case"`
+#[test]
+fn every_model_struct_declares_snake_case() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/models");
+    let mut checked = 0;
+
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(name) = line.strip_prefix("pub struct ") else {
+                continue;
+            };
+            let attributes: Vec<&str> = lines[..index]
+                .iter()
+                .rev()
+                .map(|line| line.trim())
+                .take_while(|line| line.starts_with("#[") || line.starts_with("///"))
+                .collect();
+            let derive = attributes
+                .iter()
+                .any(|line| line.contains("Serialize") || line.contains("Deserialize"));
+            if derive {
+                checked += 1;
+                assert!(
+                    attributes
+                        .iter()
+                        .any(|line| line.contains("rename_all = \"snake_case\"")),
+                    "{}: {} has no #[serde(rename_all = \"snake_case\")]",
+                    path.display(),
+                    name
+                );
+            }
+        }
+    }
+
+    assert!(checked > 0);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use jsonwebtoken::{decode_header, Algorithm};
+use rimskiy_service::auth::jwt::{create_token, verify_token};
+use rimskiy_service::config::{parse_jwt_private_key, parse_jwt_public_key, Config, JwtAlgorithm};
+use uuid::Uuid;
+
+const OLD_SECRET: &str = "old-jwt-secret-at-least-32-characters";
+const NEW_SECRET: &str = "new-jwt-secret-at-least-32-characters";
+
+#[test]
+fn rotated_secret_keeps_old_tokens_valid() {
+    let mut before = common::test_config();
+    before.jwt_secret = OLD_SECRET.to_string();
+    before.jwt_secrets_accept = Vec::new();
+
+    let mut after = common::test_config();
+    after.jwt_secret = NEW_SECRET.to_string();
+    after.jwt_secrets_accept = vec![OLD_SECRET.to_string()];
+
+    let user_id = Uuid::new_v4();
+    let old_token = create_token(user_id, &before).unwrap();
+    assert_eq!(verify_token(&old_token, &after).unwrap().sub, user_id);
+
+    // Новые токены подписываются новым секретом: старый секрет их не проверяет
+    let new_token = create_token(user_id, &after).unwrap();
+    assert_eq!(verify_token(&new_token, &after).unwrap().sub, user_id);
+    assert!(verify_token(&new_token, &before).is_err());
+
+    // Секрет, выведенный из JWT_SECRETS_ACCEPT, больше не принимается
+    let mut retired = after.clone();
+    retired.jwt_secrets_accept = Vec::new();
+    assert!(verify_token(&old_token, &retired).is_err());
+}
+
+fn rs256_config() -> Config {
+    let mut config = common::test_config();
+    config.jwt_algorithm = JwtAlgorithm::Rs256;
+    config.jwt_private_key =
+        Some(parse_jwt_private_key(include_str!("fixtures/jwt_rs256_private.pem")).unwrap());
+    config.jwt_public_key =
+        Some(parse_jwt_public_key(include_str!("fixtures/jwt_rs256_public.pem")).unwrap());
+    config
+}
+
+#[test]
+fn rs256_tokens_are_verified_with_public_key_only() {
+    let config = rs256_config();
+    config.validate().unwrap();
+    let user_id = Uuid::new_v4();
+    let token = create_token(user_id, &config).unwrap();
+    assert_eq!(decode_header(&token).unwrap().alg, Algorithm::RS256);
+
+    // Сервису, который только проверяет токены, закрытый ключ не нужен
+    let mut verifier = config.clone();
+    verifier.jwt_private_key = None;
+    assert_eq!(verify_token(&token, &verifier).unwrap().sub, user_id);
+    assert!(create_token(user_id, &verifier).is_err());
+
+    // Токены другого алгоритма не принимаются ни в одну сторону
+    let hs256 = common::test_config();
+    assert!(verify_token(&token, &hs256).is_err());
+    let hs256_token = create_token(user_id, &hs256).unwrap();
+    assert_eq!(decode_header(&hs256_token).unwrap().alg, Algorithm::HS256);
+    assert!(verify_token(&hs256_token, &config).is_err());
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{Duration, Utc};
+use rimskiy_service::api::{auth_logout_router, AppState};
+use rimskiy_service::auth::jwt::{create_token, verify_token};
+use rimskiy_service::repository::RevokedTokenRepository;
+use rimskiy_service::service::revoked_tokens::purge_revoked_tokens;
+use rimskiy_service::AppError;
+use uuid::Uuid;
+
+fn app(state: &AppState) -> Router {
+    common::authed_app(
+        state,
+        Router::new()
+            .route("/me", get(|| async { "ok" }))
+            .nest("/api/auth", auth_logout_router()),
+    )
+}
+
+async fn send(state: &AppState, method: &str, uri: &str, token: &str) -> StatusCode {
+    common::send_authed(app(state), method, uri, token).await.0
+}
+
+#[test]
+fn every_token_gets_its_own_jti() {
+    let config = common::test_config();
+    let user_id = Uuid::new_v4();
+    let first = verify_token(&create_token(user_id, &config).unwrap(), &config).unwrap();
+    let second = verify_token(&create_token(user_id, &config).unwrap(), &config).unwrap();
+    assert!(first.jti.is_some());
+    assert_ne!(first.jti, second.jti);
+}
+
+#[tokio::test]
+async fn logout_revokes_only_current_token() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+    let token = create_token(user.id, &state.config).unwrap();
+    let other_device = create_token(user.id, &state.config).unwrap();
+
+    assert_eq!(send(&state, "GET", "/me", &token).await, StatusCode::OK);
+    assert_eq!(
+        send(&state, "POST", "/api/auth/logout", &token).await,
+        StatusCode::NO_CONTENT
+    );
+
+    assert_eq!(
+        send(&state, "GET", "/me", &token).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send(&state, "POST", "/api/auth/logout", &token).await,
+        StatusCode::UNAUTHORIZED
+    );
+    let refreshed = state
+        .auth_service
+        .refresh_token(
+            &token,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await;
+    assert!(matches!(refreshed, Err(AppError::Auth(msg)) if msg.contains("revoked")));
+
+    // Токен другого устройства продолжает работать
+    assert_eq!(
+        send(&state, "GET", "/me", &other_device).await,
+        StatusCode::OK
+    );
+    state
+        .auth_service
+        .refresh_token(
+            &other_device,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn expired_revocations_are_purged() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let repository = &state.revoked_token_repository;
+    let (expired, active) = (Uuid::new_v4(), Uuid::new_v4());
+    let user_id = Uuid::new_v4();
+    repository
+        .revoke(expired, user_id, Utc::now() - Duration::minutes(1))
+        .await
+        .unwrap();
+    repository
+        .revoke(active, user_id, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+
+    assert!(purge_revoked_tokens(repository).await.unwrap() >= 1);
+    assert!(!repository.is_revoked(expired).await.unwrap());
+    assert!(repository.is_revoked(active).await.unwrap());
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use rimskiy_service::api::block::{check_block_longpoll, LongPollQuery};
+use rimskiy_service::models::block::CreateBlockRequest;
+use rimskiy_service::repository::UserPlateRepository;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn block_created_during_wait_wakes_longpoll() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+
+    let blocker = common::create_user(&pool).await;
+    state
+        .user_plate_repository
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let plate = common::random_plate();
+
+    // Первый запрос без ETag отвечает сразу текущим состоянием
+    let response = check_block_longpoll(
+        State(state.clone()),
+        Path(plate.clone()),
+        Query(LongPollQuery { timeout: Some(1) }),
+        HeaderMap::new(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG].clone();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_NONE_MATCH, etag);
+    let started = Instant::now();
+    let waiting = tokio::spawn(check_block_longpoll(
+        State(state.clone()),
+        Path(plate.clone()),
+        Query(LongPollQuery { timeout: Some(20) }),
+        headers,
+    ));
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let request: CreateBlockRequest =
+        serde_json::from_value(serde_json::json!({ "blocked_plate": plate })).unwrap();
+    state
+        .block_service
+        .create_block(
+            blocker.id,
+            request,
+            &state.block_repository,
+            &state.notification_repository,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.notification_outbox_repository,
+            &state.telephony_service,
+            &state.telegram_service,
+        )
+        .await
+        .unwrap();
+
+    let response = waiting.await.unwrap().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn unchanged_state_times_out_with_304() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let plate = common::random_plate();
+
+    let response = check_block_longpoll(
+        State(state.clone()),
+        Path(plate.clone()),
+        Query(LongPollQuery { timeout: Some(1) }),
+        HeaderMap::new(),
+    )
+    .await
+    .unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::IF_NONE_MATCH,
+        response.headers()[header::ETAG].clone(),
+    );
+
+    let response = check_block_longpoll(
+        State(state),
+        Path(plate),
+        Query(LongPollQuery { timeout: Some(1) }),
+        headers,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
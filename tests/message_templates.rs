@@ -0,0 +1,112 @@
+mod common;
+
+use rimskiy_service::service::message_templates::{
+    MessageContext, MessageTemplate, MessageTemplates,
+};
+
+fn sample(note: Option<&'static str>) -> MessageContext<'static> {
+    MessageContext {
+        plate: "А123БВ777",
+        blocker: "Иван",
+        note,
+        eta: Some("18:30"),
+    }
+}
+
+#[test]
+fn default_templates_render_for_every_channel() {
+    let templates = MessageTemplates::default();
+    templates.validate().unwrap();
+
+    let context = sample(Some("погрузка/разгрузка, до 18:30"));
+    let cases = [
+        (MessageTemplate::InAppTitle, "Ваш автомобиль заблокирован"),
+        (
+            MessageTemplate::InApp,
+            "Автомобиль А123БВ777 заблокирован пользователем Иван (погрузка/разгрузка, до 18:30)",
+        ),
+        (MessageTemplate::PushTitle, "Ваш авто заблокирован"),
+        (
+            MessageTemplate::Push,
+            "Иван перекрыл А123БВ777 (погрузка/разгрузка, до 18:30).",
+        ),
+        (
+            MessageTemplate::Telegram,
+            "🚗 Ваш автомобиль А123БВ777 заблокирован\n\n👤 Блокирующий: Иван\n\n\
+             📝 Причина: погрузка/разгрузка, до 18:30\n\n📱 Проверьте приложение для подробностей",
+        ),
+        (
+            MessageTemplate::Call,
+            "Здравствуйте! Ваш автомобиль А123БВ777 заблокирован пользователем Иван. \
+             Пожалуйста, проверьте приложение.",
+        ),
+    ];
+    for (kind, expected) in cases {
+        assert_eq!(templates.render(kind, &context), expected, "{:?}", kind);
+    }
+}
+
+#[test]
+fn optional_part_is_dropped_without_value() {
+    let templates = MessageTemplates::default();
+
+    assert_eq!(
+        templates.render(MessageTemplate::Push, &sample(None)),
+        "Иван перекрыл А123БВ777."
+    );
+    assert_eq!(
+        templates.render(MessageTemplate::Push, &sample(Some(""))),
+        "Иван перекрыл А123БВ777."
+    );
+    assert!(!templates
+        .render(MessageTemplate::Telegram, &sample(None))
+        .contains("Причина"));
+}
+
+#[test]
+fn custom_template_is_used() {
+    let templates = MessageTemplates::default()
+        .with_template(MessageTemplate::Push, "{plate}: выезд в {eta}[, {note}]");
+    templates.validate().unwrap();
+
+    assert_eq!(
+        templates.render(MessageTemplate::Push, &sample(None)),
+        "А123БВ777: выезд в 18:30"
+    );
+    // Остальные каналы не затронуты
+    assert_eq!(
+        templates.render(MessageTemplate::PushTitle, &sample(None)),
+        "Ваш авто заблокирован"
+    );
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    for template in [
+        "",
+        "   ",
+        "{owner} перекрыл {plate}",
+        "{plate",
+        "{plate}[ ({note})",
+        "{plate}] ",
+        "[{plate}[{note}]]",
+    ] {
+        let templates =
+            MessageTemplates::default().with_template(MessageTemplate::Telegram, template);
+        let error = templates.validate().unwrap_err();
+        assert!(
+            error.to_string().contains("MESSAGE_TEMPLATE_TELEGRAM"),
+            "{:?}: {}",
+            template,
+            error
+        );
+    }
+}
+
+#[test]
+fn config_validates_templates() {
+    let mut config = common::test_config();
+    config.message_templates =
+        MessageTemplates::default().with_template(MessageTemplate::Call, "{unknown}");
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use chrono::Utc;
+use rimskiy_service::db::DbPool;
+use rimskiy_service::models::outbox::{outbox_status, OutboxChannel};
+use rimskiy_service::repository::{
+    NewOutboxMessage, NotificationOutboxRepository, PostgresNotificationOutboxRepository,
+    PostgresTelegramBotRepository, PostgresUserRepository,
+};
+use rimskiy_service::service::analytics_service::NoopAnalyticsSink;
+use rimskiy_service::service::notification_outbox::{
+    outbox_retry_at, OutboxDispatcher, OUTBOX_MAX_ATTEMPTS,
+};
+use rimskiy_service::service::{AnalyticsService, PushService, TelegramService, TelephonyService};
+use std::sync::Arc;
+use uuid::Uuid;
+
+async fn enqueue_push(pool: &DbPool, user_id: Uuid) {
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let mut tx = pool.begin().await.unwrap();
+    outbox
+        .enqueue_in_tx(
+            &mut tx,
+            &[NewOutboxMessage {
+                block_id: None,
+                user_id: Some(user_id),
+                channel: OutboxChannel::Push,
+                payload: serde_json::json!({ "title": "Тест", "body": "Текст" }),
+            }],
+        )
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+}
+
+/// Статус и число попыток сообщения пользователя
+async fn message_state(pool: &DbPool, user_id: Uuid) -> (String, i32) {
+    sqlx::query_as("SELECT status, attempts FROM notification_outbox WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&**pool)
+        .await
+        .unwrap()
+}
+
+fn dispatcher() -> OutboxDispatcher {
+    let config = common::test_config();
+    OutboxDispatcher::new(
+        PushService::new(None),
+        TelegramService::new(&config),
+        TelephonyService::new(config.clone()),
+        common::encryption(),
+        AnalyticsService::new(Arc::new(NoopAnalyticsSink), ""),
+    )
+}
+
+/// Прогоняет очередь пользователя (сообщения других тестов не затрагиваются)
+/// и возвращает итоговое состояние его сообщения
+async fn drain_until_settled(pool: &DbPool, user_id: Uuid) -> (String, i32) {
+    let outbox = common::UserOutbox::new(pool, user_id);
+    let users = PostgresUserRepository::new(pool.clone());
+    let telegram_bot = PostgresTelegramBotRepository::new(pool.clone());
+    let processed = dispatcher()
+        .deliver_due(&outbox, &users, &telegram_bot)
+        .await
+        .unwrap();
+    let state = message_state(pool, user_id).await;
+    assert_ne!(
+        state.0,
+        outbox_status::PENDING,
+        "outbox message was not processed ({} delivered)",
+        processed
+    );
+    state
+}
+
+#[test]
+fn retry_backoff_doubles_until_dead() {
+    let now = Utc::now();
+    let delays: Vec<i64> = (1..OUTBOX_MAX_ATTEMPTS)
+        .map(|attempt| (outbox_retry_at(attempt, now).unwrap() - now).num_seconds())
+        .collect();
+    assert_eq!(delays, vec![30, 60, 120, 240]);
+    assert_eq!(outbox_retry_at(OUTBOX_MAX_ATTEMPTS, now), None);
+}
+
+#[tokio::test]
+async fn failed_send_is_retried_then_dead_lettered() {
+    let pool = require_db!();
+    let user = common::create_user(&pool).await;
+    let outbox = common::UserOutbox::new(&pool, user.id);
+    enqueue_push(&pool, user.id).await;
+
+    // Ошибка с повтором: сообщение остаётся в очереди и выдаётся снова
+    let claimed = outbox
+        .claim_due(10_000, chrono::Duration::minutes(5))
+        .await
+        .unwrap();
+    let message = claimed.iter().find(|m| m.user_id == Some(user.id)).unwrap();
+    assert_eq!(message.attempts, 1);
+    outbox
+        .mark_failed(message.id, "FCM error: status 503", Some(Utc::now()))
+        .await
+        .unwrap();
+    assert_eq!(
+        message_state(&pool, user.id).await,
+        (outbox_status::PENDING.to_string(), 1)
+    );
+
+    let claimed = outbox
+        .claim_due(10_000, chrono::Duration::minutes(5))
+        .await
+        .unwrap();
+    let message = claimed.iter().find(|m| m.user_id == Some(user.id)).unwrap();
+    assert_eq!(message.attempts, 2);
+    assert_eq!(message.last_error.as_deref(), Some("FCM error: status 503"));
+
+    // Пока сообщение забрано, повторно оно не выдаётся
+    let claimed = outbox
+        .claim_due(10_000, chrono::Duration::minutes(5))
+        .await
+        .unwrap();
+    assert!(claimed.iter().all(|m| m.user_id != Some(user.id)));
+
+    // Попытки исчерпаны: сообщение уходит в "dead"
+    outbox
+        .mark_failed(message.id, "FCM error: status 503", None)
+        .await
+        .unwrap();
+    assert_eq!(
+        message_state(&pool, user.id).await,
+        (outbox_status::DEAD.to_string(), 2)
+    );
+}
+
+#[tokio::test]
+async fn dispatcher_marks_delivery_outcome() {
+    let pool = require_db!();
+
+    // Без push token повторять бессмысленно - сразу "dead"
+    let without_token = common::create_user(&pool).await;
+    enqueue_push(&pool, without_token.id).await;
+    assert_eq!(
+        drain_until_settled(&pool, without_token.id).await,
+        (outbox_status::DEAD.to_string(), 1)
+    );
+
+    let with_token = common::create_user(&pool).await;
+    sqlx::query("UPDATE users SET push_token = 'test-token' WHERE id = $1")
+        .bind(with_token.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+    enqueue_push(&pool, with_token.id).await;
+    assert_eq!(
+        drain_until_settled(&pool, with_token.id).await,
+        (outbox_status::DELIVERED.to_string(), 1)
+    );
+}
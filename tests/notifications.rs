@@ -0,0 +1,449 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{Extension, Router};
+use rimskiy_service::api::notification_router;
+use rimskiy_service::auth::middleware::AuthState;
+use rimskiy_service::models::notification::NotificationType;
+use rimskiy_service::models::outbox::OutboxChannel;
+use rimskiy_service::repository::{
+    CreateNotificationData, NewOutboxMessage, NotificationOutboxRepository, NotificationRepository,
+    PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn post(app: &Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::post(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+async fn delete(app: &Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::delete(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+async fn get(app: &Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn single_notification_is_owner_only_and_marked_read() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+
+    let notification = notifications
+        .create(&CreateNotificationData {
+            user_id: owner.id,
+            r#type: NotificationType::System,
+            title: "Заголовок".to_string(),
+            message: "Текст".to_string(),
+            data: None,
+        })
+        .await
+        .unwrap();
+    let path = format!("/{}", notification.id);
+    let app_for = |user_id: Uuid| {
+        notification_router()
+            .layer(Extension(AuthState { user_id }))
+            .with_state(state.clone())
+    };
+
+    // Чужое и несуществующее уведомления неотличимы
+    let (status, _) = get(&app_for(stranger.id), &path).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get(&app_for(owner.id), &format!("/{}", Uuid::new_v4())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let owner_app = app_for(owner.id);
+    let (status, body) = get(&owner_app, &format!("{}?mark_read=false", path)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], notification.id.to_string());
+    assert_eq!(body["read"], false);
+    assert!(
+        !notifications
+            .find_by_id(notification.id, owner.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .read
+    );
+
+    let (status, body) = get(&owner_app, &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["read"], true);
+    assert!(
+        notifications
+            .find_by_id(notification.id, owner.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .read
+    );
+}
+
+#[tokio::test]
+async fn resend_enqueues_original_payload_once_per_interval() {
+    let pool = require_db!();
+    let admin = common::create_user(&pool).await;
+    let mut config = common::test_config();
+    config.admin_user_ids = vec![admin.id];
+    let state = common::test_state(&pool, config);
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    sqlx::query("UPDATE users SET push_token = 'test-token' WHERE id = $1")
+        .bind(owner.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let block_id = Uuid::new_v4();
+    let notification = notifications
+        .create(&CreateNotificationData {
+            user_id: owner.id,
+            r#type: NotificationType::BlockCreated,
+            title: "Ваш автомобиль перекрыт".to_string(),
+            message: "Текст".to_string(),
+            data: Some(serde_json::json!({ "block_id": block_id })),
+        })
+        .await
+        .unwrap();
+    let path = format!("/{}/resend", notification.id);
+    let app_for = |user_id: Uuid| {
+        notification_router()
+            .layer(Extension(AuthState { user_id }))
+            .with_state(state.clone())
+    };
+
+    let (status, _) = post(&app_for(stranger.id), &path).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = post(&app_for(owner.id), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["channels"], serde_json::json!(["push"]));
+
+    let (queued_block, payload): (Option<Uuid>, serde_json::Value) = sqlx::query_as(
+        "SELECT block_id, payload FROM notification_outbox WHERE user_id = $1 AND channel = 'push'",
+    )
+    .bind(owner.id)
+    .fetch_one(&*pool)
+    .await
+    .unwrap();
+    assert_eq!(queued_block, Some(block_id));
+    assert_eq!(payload["title"], "Ваш автомобиль перекрыт");
+    assert_eq!(payload["body"], "Текст");
+    assert_eq!(payload["data"]["block_id"], block_id.to_string());
+    assert_eq!(
+        payload["data"]["notification_id"],
+        notification.id.to_string()
+    );
+
+    // Повтор раньше интервала отклоняется и для получателя, и для администратора
+    let (status, _) = post(&app_for(owner.id), &path).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    let (status, _) = post(&app_for(admin.id), &path).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // Администратор может переотправить чужое уведомление
+    let other = notifications
+        .create(&CreateNotificationData {
+            user_id: owner.id,
+            r#type: NotificationType::System,
+            title: "Заголовок".to_string(),
+            message: "Текст".to_string(),
+            data: None,
+        })
+        .await
+        .unwrap();
+    let (status, _) = post(&app_for(admin.id), &format!("/{}/resend", other.id)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Подтверждения push по каналу за последний час
+async fn push_receipts(pool: &rimskiy_service::db::DbPool) -> (i64, i64) {
+    let stats = PostgresNotificationOutboxRepository::new(pool.clone())
+        .delivery_stats(chrono::Utc::now() - chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    stats
+        .iter()
+        .find(|s| s.channel == "push")
+        .map(|s| (s.confirmed, s.opened))
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn opened_receipt_marks_read_and_is_counted_once() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+
+    // Уведомление о блокировке и доставленный по нему push
+    let block_id = Uuid::new_v4();
+    let notification = notifications
+        .create(&CreateNotificationData {
+            user_id: owner.id,
+            r#type: NotificationType::BlockCreated,
+            title: "Ваш автомобиль перекрыт".to_string(),
+            message: "Текст".to_string(),
+            data: Some(serde_json::json!({ "block_id": block_id })),
+        })
+        .await
+        .unwrap();
+    let mut tx = pool.begin().await.unwrap();
+    outbox
+        .enqueue_in_tx(
+            &mut tx,
+            &[NewOutboxMessage {
+                block_id: Some(block_id),
+                user_id: Some(owner.id),
+                channel: OutboxChannel::Push,
+                payload: serde_json::json!({}),
+            }],
+        )
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    let message_id: Uuid =
+        sqlx::query_scalar("SELECT id FROM notification_outbox WHERE block_id = $1")
+            .bind(block_id)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    outbox.mark_delivered(message_id).await.unwrap();
+
+    let app_for = |user_id: Uuid| {
+        notification_router()
+            .layer(Extension(AuthState { user_id }))
+            .with_state(state.clone())
+    };
+    let before = push_receipts(&pool).await;
+
+    let (status, _) = post(
+        &app_for(stranger.id),
+        &format!("/{}/opened", notification.id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let owner_app = app_for(owner.id);
+    let (status, body) = post(&owner_app, &format!("/{}/delivered", notification.id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["read"], false);
+
+    for _ in 0..2 {
+        let (status, body) = post(&owner_app, &format!("/{}/opened", notification.id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["read"], true);
+    }
+
+    let (confirmed, opened): (bool, bool) = sqlx::query_as(
+        "SELECT confirmed_at IS NOT NULL, opened_at IS NOT NULL FROM notification_outbox WHERE id = $1",
+    )
+    .bind(message_id)
+    .fetch_one(&*pool)
+    .await
+    .unwrap();
+    assert!(confirmed && opened);
+
+    // Повторное подтверждение статистику не меняет
+    let after = push_receipts(&pool).await;
+    assert_eq!((after.0 - before.0, after.1 - before.1), (1, 1));
+}
+
+#[tokio::test]
+async fn duplicate_block_notifications_are_suppressed() {
+    let pool = require_db!();
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let block_id = Uuid::new_v4();
+    let data = |r#type: NotificationType, block_id: Option<Uuid>| CreateNotificationData {
+        user_id: owner.id,
+        r#type,
+        title: "Заголовок".to_string(),
+        message: "Текст".to_string(),
+        data: block_id.map(|id| serde_json::json!({ "block_id": id })),
+    };
+    let count = || async {
+        let (count,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM notifications WHERE user_id = $1")
+                .bind(owner.id)
+                .fetch_one(&*pool)
+                .await
+                .unwrap();
+        count
+    };
+
+    // Параллельные и повторные уведомления об одной блокировке - одна запись
+    let created = data(NotificationType::BlockCreated, Some(block_id));
+    let (first, second) = tokio::join!(
+        notifications.create(&created),
+        notifications.create(&created)
+    );
+    assert_eq!(first.unwrap().id, second.unwrap().id);
+    notifications.create(&created).await.unwrap();
+    assert_eq!(count().await, 1);
+
+    // Другой тип, другая блокировка и уведомления без блокировки не склеиваются
+    notifications
+        .create(&data(NotificationType::BlockDeleted, Some(block_id)))
+        .await
+        .unwrap();
+    notifications
+        .create(&data(NotificationType::BlockCreated, Some(Uuid::new_v4())))
+        .await
+        .unwrap();
+    notifications
+        .create(&data(NotificationType::System, None))
+        .await
+        .unwrap();
+    notifications
+        .create(&data(NotificationType::System, None))
+        .await
+        .unwrap();
+    assert_eq!(count().await, 5);
+}
+
+#[test]
+fn notification_type_keeps_wire_format() {
+    for (kind, value) in [
+        (NotificationType::BlockCreated, "block_created"),
+        (NotificationType::BlockDeleted, "block_deleted"),
+        (NotificationType::WarningCall, "warning_call"),
+        (NotificationType::System, "system"),
+    ] {
+        assert_eq!(kind.as_str(), value);
+        assert_eq!(serde_json::to_value(kind).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<NotificationType>(serde_json::json!(value)).unwrap(),
+            kind
+        );
+    }
+}
+
+#[tokio::test]
+async fn notifications_are_paged_by_twenty() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    for i in 0..25 {
+        notifications
+            .create(&CreateNotificationData {
+                user_id: owner.id,
+                r#type: NotificationType::System,
+                title: "Заголовок".to_string(),
+                message: format!("Текст {}", i),
+                data: None,
+            })
+            .await
+            .unwrap();
+    }
+    let app = notification_router()
+        .layer(Extension(AuthState { user_id: owner.id }))
+        .with_state(state);
+
+    // Без параметров - первая страница из 20
+    let (status, body) = get(&app, "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["limit"], 20);
+    assert_eq!(body["offset"], 0);
+    assert_eq!(body["total_count"], 25);
+    assert_eq!(body["items"].as_array().unwrap().len(), 20);
+    assert_eq!(body["has_more"], true);
+
+    let (_, body) = get(&app, "/?limit=10&offset=10").await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 10);
+    assert_eq!(body["has_more"], true);
+
+    let (_, body) = get(&app, "/?offset=20").await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 5);
+    assert_eq!(body["has_more"], false);
+}
+
+#[tokio::test]
+async fn notifications_are_deleted_by_owner_only() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let mut created = Vec::new();
+    for _ in 0..3 {
+        created.push(
+            notifications
+                .create(&CreateNotificationData {
+                    user_id: owner.id,
+                    r#type: NotificationType::System,
+                    title: "Заголовок".to_string(),
+                    message: "Текст".to_string(),
+                    data: None,
+                })
+                .await
+                .unwrap(),
+        );
+    }
+    let app_for = |user_id: Uuid| {
+        notification_router()
+            .layer(Extension(AuthState { user_id }))
+            .with_state(state.clone())
+    };
+    let owner_app = app_for(owner.id);
+    let path = format!("/{}", created[0].id);
+
+    // Чужое уведомление неотличимо от несуществующего
+    let (status, _) = delete(&app_for(stranger.id), &path).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = delete(&owner_app, &path).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = delete(&owner_app, &path).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Очистка удаляет только прочитанные
+    notifications
+        .mark_as_read(created[1].id, owner.id)
+        .await
+        .unwrap();
+    let (status, body) = delete(&owner_app, "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["deleted"], 1);
+    let (remaining, total) = notifications
+        .find_by_user_id(owner.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(remaining[0].id, created[2].id);
+}
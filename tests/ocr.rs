@@ -0,0 +1,26 @@
+use rimskiy_service::api::plate_recognition_response;
+use rimskiy_service::error::AppError;
+
+#[test]
+fn valid_plate_is_normalized() {
+    let response = plate_recognition_response(Ok("а 123 вс 77".to_string()));
+    assert_eq!(response["success"], true);
+    assert_eq!(response["valid"], true);
+    assert_eq!(response["plate"], "А123ВС77");
+}
+
+#[test]
+fn invalid_plate_is_returned_raw_for_confirmation() {
+    let response = plate_recognition_response(Ok("EXIT 42".to_string()));
+    assert_eq!(response["success"], true);
+    assert_eq!(response["valid"], false);
+    assert_eq!(response["plate"], "EXIT 42");
+}
+
+#[test]
+fn recognition_error_is_reported() {
+    let response =
+        plate_recognition_response(Err(AppError::Internal("OCR unavailable".to_string())));
+    assert_eq!(response["success"], false);
+    assert!(response.get("valid").is_none());
+}
@@ -0,0 +1,24 @@
+use rimskiy_service::openapi::{filter_spec_by_tag, ApiDoc};
+use utoipa::OpenApi;
+
+#[test]
+fn spec_filtered_by_tag() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let filtered = filter_spec_by_tag(&spec, "blocks").unwrap();
+
+    let paths = filtered["paths"].as_object().unwrap();
+    assert!(paths.contains_key("/api/blocks"));
+    assert!(paths.keys().all(|path| !path.starts_with("/api/auth")));
+    assert!(spec["paths"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .any(|path| path.starts_with("/api/auth")));
+
+    // Схемы блокировок остаются, схемы только для авторизации - нет
+    let schemas = filtered["components"]["schemas"].as_object().unwrap();
+    assert!(schemas.contains_key("CreateBlockRequest"));
+    assert!(!schemas.contains_key("AuthVerifyResponse"));
+
+    assert!(filter_spec_by_tag(&spec, "no-such-tag").is_none());
+}
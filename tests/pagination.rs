@@ -0,0 +1,157 @@
+mod common;
+
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::Request;
+use rimskiy_service::api::pagination::{OptionalPagination, Pagination};
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, PostgresUserRepository,
+    UserPlateRepository,
+};
+use rimskiy_service::AppError;
+
+async fn extract(query: &str) -> Result<OptionalPagination, AppError> {
+    let (mut parts, _) = Request::builder()
+        .uri(format!("/api/blocks{}", query))
+        .body(Body::empty())
+        .unwrap()
+        .into_parts();
+    OptionalPagination::from_request_parts(&mut parts, &()).await
+}
+
+async fn body_json(
+    pagination: OptionalPagination,
+    items: Vec<i32>,
+    total: i64,
+) -> serde_json::Value {
+    let body = pagination.into_response(items, total).into_body();
+    serde_json::from_slice(&axum::body::to_bytes(body, usize::MAX).await.unwrap()).unwrap()
+}
+
+#[test]
+fn pagination_defaults_and_clamps() {
+    let pagination = Pagination::new(None, None).unwrap();
+    assert_eq!((pagination.limit, pagination.offset), (50, 0));
+
+    let pagination = Pagination::new(Some(1000), Some(5)).unwrap();
+    assert_eq!((pagination.limit, pagination.offset), (100, 5));
+
+    let pagination = Pagination::new(Some(0), None).unwrap();
+    assert_eq!(pagination.limit, 1);
+
+    assert!(matches!(
+        Pagination::new(None, Some(-1)),
+        Err(AppError::Validation(_))
+    ));
+}
+
+#[tokio::test]
+async fn pagination_is_optional() {
+    // Без параметров - массив целиком, как ждут старые клиенты
+    let pagination = extract("").await.unwrap();
+    assert!(pagination.0.is_none());
+    assert_eq!(pagination.limit(), None);
+    assert_eq!(pagination.offset(), 0);
+    assert_eq!(
+        body_json(pagination, vec![1, 2, 3], 3).await,
+        serde_json::json!([1, 2, 3])
+    );
+
+    let pagination = extract("?limit=2").await.unwrap();
+    assert_eq!(pagination.limit(), Some(2));
+    assert_eq!(
+        body_json(pagination, vec![1, 2], 3).await,
+        serde_json::json!({ "items": [1, 2], "total": 3, "limit": 2, "offset": 0, "has_more": true })
+    );
+    let pagination = extract("?limit=2&offset=1").await.unwrap();
+    assert_eq!(
+        body_json(pagination, vec![2, 3], 3).await["has_more"],
+        false
+    );
+
+    // Только offset - страница с размером по умолчанию
+    let pagination = extract("?offset=2").await.unwrap();
+    assert_eq!(pagination.limit(), Some(50));
+    assert_eq!(pagination.offset(), 2);
+
+    assert!(matches!(
+        extract("?offset=-1").await,
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        extract("?limit=abc").await,
+        Err(AppError::Validation(_))
+    ));
+}
+
+#[tokio::test]
+async fn block_lists_are_paged_in_sql() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let service = common::block_service(&config);
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+
+    let owner = common::create_user(&pool).await;
+    let owner_plates: Vec<String> = (0..3).map(|_| common::random_plate()).collect();
+    for (i, plate) in owner_plates.iter().enumerate() {
+        plates.create(owner.id, plate, i == 0, None).await.unwrap();
+    }
+
+    // Блокировщик и совладелец его автомобиля
+    let blocker = common::create_user(&pool).await;
+    let co_owner = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(co_owner.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+
+    for (user_id, plate) in [
+        (blocker.id, &owner_plates[0]),
+        (blocker.id, &owner_plates[1]),
+        (co_owner.id, &owner_plates[2]),
+    ] {
+        blocks
+            .create(user_id, &blocker_plate, plate, None, None, false)
+            .await
+            .unwrap();
+    }
+
+    let (all, total) = service
+        .get_blocks_for_my_plate(owner.id, None, None, 0, &blocks, &users, &plates)
+        .await
+        .unwrap();
+    assert_eq!((all.len(), total), (3, 3));
+    assert!(all.windows(2).all(|w| w[0].created_at >= w[1].created_at));
+
+    let (page, total) = service
+        .get_blocks_for_my_plate(owner.id, None, Some(2), 0, &blocks, &users, &plates)
+        .await
+        .unwrap();
+    assert_eq!((page.len(), total), (2, 3));
+    let (rest, _) = service
+        .get_blocks_for_my_plate(owner.id, None, Some(2), 2, &blocks, &users, &plates)
+        .await
+        .unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].id, all[2].id);
+
+    // Созданные блокировки включают блокировки совладельца того же автомобиля
+    let (created, total) = service
+        .get_my_blocks(blocker.id, None, 0, &blocks, &plates)
+        .await
+        .unwrap();
+    assert_eq!((created.len(), total), (3, 3));
+    let (page, total) = service
+        .get_my_blocks(blocker.id, Some(1), 1, &blocks, &plates)
+        .await
+        .unwrap();
+    assert_eq!((page.len(), total), (1, 3));
+    assert_eq!(page[0].id, created[1].id);
+}
@@ -0,0 +1,47 @@
+use rimskiy_service::service::ValidationService;
+use rimskiy_service::utils::phone::normalize_phone_heuristic;
+
+/// Разные записи одного российского номера
+const RU_INPUTS: [&str; 5] = [
+    "+7 916 123-45-67",
+    "8 (916) 123-45-67",
+    "89161234567",
+    "79161234567",
+    "+7(916)1234567",
+];
+
+#[test]
+fn heuristic_normalizes_ru_formats() {
+    for input in RU_INPUTS {
+        assert_eq!(
+            normalize_phone_heuristic(input),
+            "+79161234567",
+            "{}",
+            input
+        );
+        assert_eq!(
+            ValidationService::validate_phone(input).unwrap(),
+            "+79161234567",
+            "{}",
+            input
+        );
+    }
+}
+
+#[cfg(feature = "e164")]
+#[test]
+fn e164_matches_heuristic_for_ru_inputs() {
+    use rimskiy_service::utils::phone::parse_phone_e164;
+
+    for input in RU_INPUTS {
+        assert_eq!(
+            parse_phone_e164(input).unwrap(),
+            normalize_phone_heuristic(input),
+            "{}",
+            input
+        );
+    }
+    // Эвристика пропускает несуществующий номер, libphonenumber - нет
+    assert!(parse_phone_e164("+7 000 000-00-00").is_err());
+    assert!(ValidationService::validate_phone("не телефон").is_err());
+}
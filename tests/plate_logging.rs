@@ -0,0 +1,47 @@
+use rimskiy_service::utils::validate_plate;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing::Level;
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Возвращает строки лога, записанные при проверке номера на уровне `level` и выше
+fn logged_lines(level: Level, plate: &str) -> Vec<String> {
+    let capture = Capture::default();
+    let writer = capture.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        validate_plate(plate);
+    });
+    let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    output.lines().map(str::to_string).collect()
+}
+
+#[test]
+fn validation_is_silent_at_info_level() {
+    for plate in ["А123БВ777", "A123BC77", "1234", "А12ЙБВ777", "ААААААААА"] {
+        let lines = logged_lines(Level::INFO, plate);
+        assert!(lines.len() <= 1, "{}: {:?}", plate, lines);
+    }
+}
+
+#[test]
+fn validation_steps_are_traced() {
+    assert!(logged_lines(Level::TRACE, "А123БВ777").len() > 1);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, UserPlateRepository,
+};
+
+/// Тот же номер, набранный латинскими буквами-двойниками
+fn latin(plate: &str) -> String {
+    plate
+        .chars()
+        .map(|c| match c {
+            'А' => 'A',
+            'В' => 'B',
+            'Е' => 'E',
+            'К' => 'K',
+            'М' => 'M',
+            'Н' => 'H',
+            'О' => 'O',
+            'Р' => 'P',
+            'С' => 'C',
+            'Т' => 'T',
+            'У' => 'Y',
+            'Х' => 'X',
+            c => c,
+        })
+        .collect()
+}
+
+/// Варианты ввода одного номера: нижний регистр, смешанный регистр, пробелы и дефисы
+fn spellings(plate: &str) -> Vec<String> {
+    let chars: Vec<char> = plate.chars().collect();
+    let mixed: String = chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i % 2 == 0 {
+                c.to_lowercase().next().unwrap()
+            } else {
+                *c
+            }
+        })
+        .collect();
+    let spaced = format!(
+        " {} {}-{} ",
+        chars[..1].iter().collect::<String>(),
+        chars[1..4].iter().collect::<String>(),
+        chars[4..].iter().collect::<String>()
+    );
+    vec![plate.to_string(), plate.to_lowercase(), mixed, spaced]
+}
+
+#[tokio::test]
+async fn cyrillic_and_latin_plates_match_any_spelling() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let blocker = common::create_user(&pool).await;
+    let blocker_plate = common::random_plate();
+
+    for plate in [common::random_plate(), latin(&common::random_plate())] {
+        let owner = common::create_user(&pool).await;
+        // Номер сохраняется нормализованным, как бы его ни ввели
+        let stored = plates
+            .create(owner.id, &plate.to_lowercase(), true, None)
+            .await
+            .unwrap();
+        assert_eq!(stored.plate, plate);
+        let block = blocks
+            .create(
+                blocker.id,
+                &blocker_plate,
+                &format!(" {} ", plate.to_lowercase()),
+                None,
+                None,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(block.blocked_plate, plate);
+
+        for spelling in spellings(&plate) {
+            let found = plates.find_by_plate(&spelling).await.unwrap();
+            assert_eq!(found.len(), 1, "{:?}", spelling);
+            assert_eq!(found[0].id, stored.id);
+
+            let found = blocks.find_by_blocked_plate(&spelling).await.unwrap();
+            assert_eq!(found.len(), 1, "{:?}", spelling);
+            assert_eq!(found[0].id, block.id);
+
+            assert!(blocks
+                .exists(&blocker_plate.to_lowercase(), &spelling)
+                .await
+                .unwrap());
+        }
+    }
+}
+
+#[tokio::test]
+async fn schema_init_replaces_yo_in_stored_plates() {
+    let pool = require_db!();
+    let user = common::create_user(&pool).await;
+    let plate = format!("Ё{}", &common::random_plate()[2..]);
+    let normalized = plate.replace('Ё', "Е");
+    // Номер с Ё, сохранённый до нормализации, и его дубликат без Ё
+    for stored in [&plate, &normalized] {
+        sqlx::query("INSERT INTO user_plates (user_id, plate, is_primary) VALUES ($1, $2, false)")
+            .bind(user.id)
+            .bind(stored)
+            .execute(&*pool)
+            .await
+            .unwrap();
+    }
+    let other = common::create_user(&pool).await;
+    let other_plate = format!("Ё{}", &common::random_plate()[2..]);
+    sqlx::query("INSERT INTO user_plates (user_id, plate, is_primary) VALUES ($1, $2, true)")
+        .bind(other.id)
+        .bind(&other_plate)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    // Повторный запуск тоже безопасен
+    for _ in 0..2 {
+        rimskiy_service::db::init::ensure_database_and_tables(&pool)
+            .await
+            .unwrap();
+    }
+
+    let plates: Vec<String> =
+        sqlx::query_scalar("SELECT plate FROM user_plates WHERE user_id = ANY($1) ORDER BY plate")
+            .bind(vec![user.id, other.id])
+            .fetch_all(&*pool)
+            .await
+            .unwrap();
+    let mut expected = vec![normalized.clone(), other_plate.replace('Ё', "Е")];
+    expected.sort();
+    assert_eq!(plates, expected);
+    assert_eq!(
+        PostgresUserPlateRepository::new(pool.clone())
+            .find_by_plate(&normalized)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.user_id)
+            .collect::<Vec<_>>(),
+        vec![user.id]
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use rimskiy_service::db::DbPool;
+use rimskiy_service::repository::{PostgresUserPlateRepository, UserPlateRepository};
+use rimskiy_service::service::plate_reconciliation::reconcile_plates;
+use uuid::Uuid;
+
+async fn set_users_plate(pool: &DbPool, user_id: Uuid, plate: Option<&str>) {
+    sqlx::query("UPDATE users SET plate = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(plate)
+        .execute(&**pool)
+        .await
+        .unwrap();
+}
+
+async fn users_plate(pool: &DbPool, user_id: Uuid) -> Option<String> {
+    sqlx::query_scalar("SELECT plate FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&**pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn drifted_rows_are_reconciled() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+
+    // users.plate расходится с основным номером
+    let drifted = common::create_user(&pool).await;
+    let primary = common::random_plate();
+    plates
+        .create(drifted.id, &primary, true, None)
+        .await
+        .unwrap();
+    set_users_plate(&pool, drifted.id, Some(&common::random_plate())).await;
+
+    // Номер есть только в users.plate
+    let legacy = common::create_user(&pool).await;
+    let legacy_plate = common::random_plate();
+    set_users_plate(&pool, legacy.id, Some(&legacy_plate)).await;
+
+    // Номера есть, но ни один не основной
+    let no_primary = common::create_user(&pool).await;
+    let older = common::random_plate();
+    let newer = common::random_plate();
+    plates
+        .create(no_primary.id, &older, false, None)
+        .await
+        .unwrap();
+    plates
+        .create(no_primary.id, &newer, false, None)
+        .await
+        .unwrap();
+
+    let report = reconcile_plates(&plates).await.unwrap();
+    assert!(report.plates_created >= 1);
+    assert!(report.primaries_promoted >= 1);
+    assert!(report.users_synced >= 2);
+
+    assert_eq!(users_plate(&pool, drifted.id).await, Some(primary));
+
+    let legacy_plates = plates.find_by_user_id(legacy.id).await.unwrap();
+    assert_eq!(legacy_plates.len(), 1);
+    assert!(legacy_plates[0].is_primary);
+    assert_eq!(legacy_plates[0].plate, legacy_plate);
+
+    let promoted = plates.find_primary_by_user_id(no_primary.id).await.unwrap();
+    assert_eq!(promoted.map(|p| p.plate), Some(newer.clone()));
+    assert_eq!(users_plate(&pool, no_primary.id).await, Some(newer));
+
+    // Повторная сверка для этих пользователей уже ничего не меняет
+    reconcile_plates(&plates).await.unwrap();
+    assert_eq!(plates.find_by_user_id(legacy.id).await.unwrap().len(), 1);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use rimskiy_service::models::user_plate::{plate_share_status, SharePlateRequest};
+use rimskiy_service::repository::{
+    NotificationRepository, PostgresNotificationRepository, PostgresPlateShareInviteRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository,
+};
+use rimskiy_service::service::{PushService, UserService};
+use rimskiy_service::AppError;
+use uuid::Uuid;
+
+fn service() -> UserService {
+    UserService::new(common::encryption(), false)
+}
+
+fn share(plate_id: Uuid, user_id: Uuid) -> SharePlateRequest {
+    SharePlateRequest {
+        plate_id,
+        user_id: Some(user_id),
+        phone: None,
+    }
+}
+
+#[tokio::test]
+async fn accepted_invite_creates_co_owned_plate() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let invites = PostgresPlateShareInviteRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let push = PushService::new(None);
+
+    let owner = common::create_user(&pool).await;
+    let invitee = common::create_user(&pool).await;
+    let plate = common::random_plate();
+    let owner_plate = plates.create(owner.id, &plate, true, None).await.unwrap();
+
+    let invite = service()
+        .share_plate(
+            owner.id,
+            share(owner_plate.id, invitee.id),
+            &users,
+            &plates,
+            &invites,
+            &notifications,
+            &push,
+        )
+        .await
+        .unwrap();
+    assert_eq!(invite.status, plate_share_status::PENDING);
+    assert_eq!(invite.plate, plate);
+
+    // Повторное приглашение возвращает то же и не уведомляет ещё раз
+    let again = service()
+        .share_plate(
+            owner.id,
+            share(owner_plate.id, invitee.id),
+            &users,
+            &plates,
+            &invites,
+            &notifications,
+            &push,
+        )
+        .await
+        .unwrap();
+    assert_eq!(again.id, invite.id);
+    let (_, notified) = notifications
+        .find_by_user_id(invitee.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(notified, 1);
+
+    // До согласия номер приглашённому не добавлен
+    assert!(plates.find_by_user_id(invitee.id).await.unwrap().is_empty());
+
+    let pending = service()
+        .get_plate_share_invites(invitee.id, &users, &invites)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        pending[0].inviter.as_ref().map(|inviter| inviter.id),
+        Some(owner.id)
+    );
+
+    let invite_id: Uuid = invite.id.parse().unwrap();
+    let accepted = service()
+        .accept_plate_share_invite(invitee.id, invite_id, &plates, &invites)
+        .await
+        .unwrap();
+    assert_eq!(accepted.plate, plate);
+    assert!(accepted.is_primary);
+
+    let owners = plates.find_by_plate(&plate).await.unwrap();
+    assert_eq!(owners.len(), 2);
+    assert!(owners.iter().any(|p| p.user_id == invitee.id));
+    assert!(service()
+        .get_plate_share_invites(invitee.id, &users, &invites)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Ответить повторно нельзя
+    let result = service()
+        .accept_plate_share_invite(invitee.id, invite_id, &plates, &invites)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn declined_or_foreign_invites_do_not_add_plate() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let invites = PostgresPlateShareInviteRepository::new(pool.clone());
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let push = PushService::new(None);
+
+    let owner = common::create_user(&pool).await;
+    let invitee = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let owner_plate = plates
+        .create(owner.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    // Себя пригласить нельзя, как и поделиться чужим номером
+    let result = service()
+        .share_plate(
+            owner.id,
+            share(owner_plate.id, owner.id),
+            &users,
+            &plates,
+            &invites,
+            &notifications,
+            &push,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = service()
+        .share_plate(
+            stranger.id,
+            share(owner_plate.id, invitee.id),
+            &users,
+            &plates,
+            &invites,
+            &notifications,
+            &push,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    let invite = service()
+        .share_plate(
+            owner.id,
+            share(owner_plate.id, invitee.id),
+            &users,
+            &plates,
+            &invites,
+            &notifications,
+            &push,
+        )
+        .await
+        .unwrap();
+    let invite_id: Uuid = invite.id.parse().unwrap();
+
+    // Принять чужое приглашение нельзя
+    let result = service()
+        .accept_plate_share_invite(stranger.id, invite_id, &plates, &invites)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    service()
+        .decline_plate_share_invite(invitee.id, invite_id, &invites)
+        .await
+        .unwrap();
+    let result = service()
+        .accept_plate_share_invite(invitee.id, invite_id, &plates, &invites)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    assert!(plates.find_by_user_id(invitee.id).await.unwrap().is_empty());
+}
@@ -0,0 +1,242 @@
+mod common;
+
+use rimskiy_service::db::DbPool;
+use rimskiy_service::models::user_plate::{claim_status, ClaimPlateRequest};
+use rimskiy_service::repository::{
+    PostgresNotificationRepository, PostgresPlateTransferRequestRepository,
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository,
+};
+use rimskiy_service::service::{PushService, UserService};
+use rimskiy_service::AppError;
+use uuid::Uuid;
+
+struct Repos {
+    users: PostgresUserRepository,
+    plates: PostgresUserPlateRepository,
+    transfers: PostgresPlateTransferRequestRepository,
+    notifications: PostgresNotificationRepository,
+}
+
+fn repos(pool: &DbPool) -> Repos {
+    Repos {
+        users: PostgresUserRepository::new(pool.clone()),
+        plates: PostgresUserPlateRepository::new(pool.clone()),
+        transfers: PostgresPlateTransferRequestRepository::new(pool.clone()),
+        notifications: PostgresNotificationRepository::new(pool.clone()),
+    }
+}
+
+fn service() -> UserService {
+    UserService::new(common::encryption(), false)
+}
+
+async fn claim(
+    repos: &Repos,
+    user_id: Uuid,
+    plate: &str,
+) -> rimskiy_service::AppResult<rimskiy_service::models::user_plate::ClaimPlateResponse> {
+    service()
+        .claim_plate(
+            user_id,
+            ClaimPlateRequest {
+                plate: plate.to_string(),
+            },
+            &repos.users,
+            &repos.plates,
+            &repos.transfers,
+            &repos.notifications,
+            &PushService::new(None),
+        )
+        .await
+}
+
+async fn notification_statuses(pool: &DbPool, user_id: Uuid) -> Vec<String> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT data->>'status' FROM notifications WHERE user_id = $1 ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(&**pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn free_plate_is_claimed_immediately() {
+    let pool = require_db!();
+    let repos = repos(&pool);
+    let user = common::create_user(&pool).await;
+    let plate = common::random_plate();
+
+    let response = claim(&repos, user.id, &plate).await.unwrap();
+
+    assert_eq!(response.status, claim_status::CLAIMED);
+    assert!(response.transfer_request.is_none());
+    let owners = repos.plates.find_by_plate(&plate).await.unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].user_id, user.id);
+    assert!(owners[0].is_primary);
+}
+
+#[tokio::test]
+async fn claim_of_owned_plate_waits_for_owner_approval() {
+    let pool = require_db!();
+    let repos = repos(&pool);
+    let owner = common::create_user(&pool).await;
+    let co_owner = common::create_user(&pool).await;
+    let claimant = common::create_user(&pool).await;
+    let plate = common::random_plate();
+    let other_plate = common::random_plate();
+    repos
+        .plates
+        .create(owner.id, &other_plate, false, None)
+        .await
+        .unwrap();
+    repos
+        .plates
+        .create(owner.id, &plate, true, None)
+        .await
+        .unwrap();
+    repos
+        .plates
+        .create(co_owner.id, &plate, true, None)
+        .await
+        .unwrap();
+
+    let response = claim(&repos, claimant.id, &plate).await.unwrap();
+    assert_eq!(response.status, claim_status::PENDING_APPROVAL);
+    let request = response.transfer_request.unwrap();
+
+    // До согласия номер остаётся у владельцев, владельцы получили запрос
+    assert_eq!(repos.plates.find_by_plate(&plate).await.unwrap().len(), 2);
+    assert_eq!(
+        notification_statuses(&pool, owner.id).await,
+        vec!["transfer_requested"]
+    );
+    assert_eq!(
+        notification_statuses(&pool, co_owner.id).await,
+        vec!["transfer_requested"]
+    );
+
+    // Повторная заявка возвращает ту же и не уведомляет ещё раз
+    let repeated = claim(&repos, claimant.id, &plate).await.unwrap();
+    assert_eq!(repeated.transfer_request.unwrap().id, request.id);
+    assert_eq!(notification_statuses(&pool, owner.id).await.len(), 1);
+
+    let incoming = service()
+        .get_plate_transfer_requests(owner.id, &repos.users, &repos.transfers)
+        .await
+        .unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming[0].id, request.id);
+
+    let request_id: Uuid = request.id.parse().unwrap();
+    // Посторонний пользователь и сам заявитель не могут одобрить заявку
+    for user_id in [common::create_user(&pool).await.id, claimant.id] {
+        let result = service()
+            .approve_plate_transfer(
+                user_id,
+                request_id,
+                &repos.users,
+                &repos.plates,
+                &repos.transfers,
+                &repos.notifications,
+                &PushService::new(None),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    service()
+        .approve_plate_transfer(
+            owner.id,
+            request_id,
+            &repos.users,
+            &repos.plates,
+            &repos.transfers,
+            &repos.notifications,
+            &PushService::new(None),
+        )
+        .await
+        .unwrap();
+
+    // Номер удалён у всех прежних владельцев и добавлен заявителю
+    let owners = repos.plates.find_by_plate(&plate).await.unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].user_id, claimant.id);
+    assert!(owners[0].is_primary);
+    // Владелец лишился основного номера - основным стал оставшийся
+    let owner_primary = repos
+        .plates
+        .find_primary_by_user_id(owner.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(owner_primary.plate, other_plate);
+    assert!(repos
+        .plates
+        .find_primary_by_user_id(co_owner.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        notification_statuses(&pool, co_owner.id).await,
+        vec!["transfer_requested", "transferred"]
+    );
+    assert_eq!(
+        notification_statuses(&pool, claimant.id).await,
+        vec!["transfer_approved"]
+    );
+
+    // На заявку, по которой уже принято решение, ответить нельзя
+    let result = service()
+        .decline_plate_transfer(
+            co_owner.id,
+            request_id,
+            &repos.users,
+            &repos.transfers,
+            &repos.notifications,
+            &PushService::new(None),
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn declined_claim_keeps_plate_with_owner() {
+    let pool = require_db!();
+    let repos = repos(&pool);
+    let owner = common::create_user(&pool).await;
+    let claimant = common::create_user(&pool).await;
+    let plate = common::random_plate();
+    repos
+        .plates
+        .create(owner.id, &plate, true, None)
+        .await
+        .unwrap();
+
+    let request = claim(&repos, claimant.id, &plate)
+        .await
+        .unwrap()
+        .transfer_request
+        .unwrap();
+    service()
+        .decline_plate_transfer(
+            owner.id,
+            request.id.parse().unwrap(),
+            &repos.users,
+            &repos.transfers,
+            &repos.notifications,
+            &PushService::new(None),
+        )
+        .await
+        .unwrap();
+
+    let owners = repos.plates.find_by_plate(&plate).await.unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].user_id, owner.id);
+    assert_eq!(
+        notification_statuses(&pool, claimant.id).await,
+        vec!["transfer_declined"]
+    );
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use axum::http::{HeaderMap, Uri};
+use axum::routing::post;
+use axum::{Json, Router};
+use rimskiy_service::service::{TelegramService, TelephonyService};
+use rimskiy_service::utils::ocr::recognize_plate_from_image;
+use std::sync::{Arc, Mutex};
+
+type Requests = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+/// Заглушка провайдера: запоминает путь и Authorization каждого запроса
+async fn mock_provider() -> (String, Requests) {
+    let requests: Requests = Arc::default();
+    let recorded = requests.clone();
+    let app = Router::new().fallback(post(move |uri: Uri, headers: HeaderMap| {
+        let recorded = recorded.clone();
+        async move {
+            let authorization = headers
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            recorded
+                .lock()
+                .unwrap()
+                .push((uri.path().to_string(), authorization));
+            Json(serde_json::json!({ "ok": true, "plate": "А123БВ777" }))
+        }
+    }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", address), requests)
+}
+
+#[tokio::test]
+async fn services_use_configured_provider_urls() {
+    // Отдельный тестовый бинарник: переменная окружения не влияет на другие тесты
+    std::env::set_var("TELEGRAM_BOT_TOKEN", "test-token");
+    let (base_url, requests) = mock_provider().await;
+    let mut config = common::test_config();
+    config.telephony_api_url = Some(format!("{}/call", base_url));
+    config.telephony_api_key = Some("telephony-key".to_string());
+    config.ocr_api_url = Some(format!("{}/ocr", base_url));
+    config.telegram_api_url = base_url.clone();
+    config.validate().unwrap();
+
+    TelephonyService::new(config.clone())
+        .call_owner("+79991234567", "Проверка")
+        .await
+        .unwrap();
+    TelegramService::new(&config)
+        .send_to_chat(42, "Проверка")
+        .await
+        .unwrap();
+    let plate = recognize_plate_from_image(config.ocr_api_url.as_deref(), b"image")
+        .await
+        .unwrap();
+    assert_eq!(plate, "А123БВ777");
+
+    assert_eq!(
+        *requests.lock().unwrap(),
+        vec![
+            (
+                "/call".to_string(),
+                Some("Bearer telephony-key".to_string())
+            ),
+            ("/bottest-token/sendMessage".to_string(), None),
+            ("/ocr".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn provider_urls_must_be_http() {
+    let mut config = common::test_config();
+    config.telegram_api_url = "api.telegram.org".to_string();
+    assert!(config.validate().is_err());
+
+    let mut config = common::test_config();
+    config.sms_api_url = Some("ftp://sms.example.com".to_string());
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use rimskiy_service::api::user::{register_push_token, PushTokenRequest};
+use rimskiy_service::auth::middleware::AuthState;
+use rimskiy_service::repository::{PostgresPushTokenRepository, PushTokenRepository};
+use rimskiy_service::AppError;
+
+fn request(token: &str, platform: Option<&str>, app_version: Option<&str>) -> PushTokenRequest {
+    PushTokenRequest {
+        token: token.to_string(),
+        platform: platform.map(str::to_string),
+        app_version: app_version.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn repeated_registration_updates_the_same_device() {
+    let pool = require_db!();
+    let repository = PostgresPushTokenRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let other = common::create_user(&pool).await;
+    let token = format!("token-{}", uuid::Uuid::new_v4());
+
+    let first = repository
+        .upsert(user.id, &token, Some("android"), Some("1.0.0"))
+        .await
+        .unwrap();
+    // Клиент без платформы не затирает сохранённые данные
+    let second = repository
+        .upsert(user.id, &token, None, None)
+        .await
+        .unwrap();
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.platform.as_deref(), Some("android"));
+    assert_eq!(second.app_version.as_deref(), Some("1.0.0"));
+    assert!(second.last_seen >= first.last_seen);
+
+    // Устройство сменило владельца
+    let third = repository
+        .upsert(other.id, &token, Some("android"), Some("1.1.0"))
+        .await
+        .unwrap();
+    assert_eq!(third.id, first.id);
+    assert_eq!(third.user_id, other.id);
+    assert_eq!(third.app_version.as_deref(), Some("1.1.0"));
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM push_tokens WHERE token = $1")
+        .bind(&token)
+        .fetch_one(&*pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+}
+
+#[tokio::test]
+async fn registration_validates_platform_and_version() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+    let register = |payload: PushTokenRequest| {
+        register_push_token(
+            State(state.clone()),
+            Extension(AuthState { user_id: user.id }),
+            Json(payload),
+        )
+    };
+
+    for payload in [
+        request("token", Some("windows"), None),
+        request("token", Some("ios"), Some("1.0; DROP")),
+        request("  ", Some("ios"), None),
+    ] {
+        assert!(matches!(
+            register(payload).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    let token = format!("token-{}", uuid::Uuid::new_v4());
+    assert!(
+        register(request(&token, Some(" IOS "), Some("2.3.1-beta+5")))
+            .await
+            .is_ok()
+    );
+    let platform: Option<String> =
+        sqlx::query_scalar("SELECT platform FROM push_tokens WHERE token = $1")
+            .bind(&token)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(platform.as_deref(), Some("ios"));
+}
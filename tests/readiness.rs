@@ -0,0 +1,86 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use rimskiy_service::api::{health_router, ReadinessState};
+use rimskiy_service::middleware::readiness_middleware;
+use tower::ServiceExt;
+
+fn app(readiness: &ReadinessState) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "OK" }))
+        .route("/health/ready", get(|| async { "READY" }))
+        .route("/api/blocks", get(|| async { "[]" }))
+        .layer(middleware::from_fn_with_state(
+            readiness.clone(),
+            readiness_middleware,
+        ))
+}
+
+async fn status(app: Router, path: &str) -> StatusCode {
+    app.oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn only_health_is_served_until_ready() {
+    let readiness = ReadinessState::new();
+
+    let response = app(&readiness)
+        .oneshot(Request::get("/api/blocks").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["code"], "SERVICE_UNAVAILABLE");
+    for path in ["/health", "/health/ready"] {
+        assert_eq!(status(app(&readiness), path).await, StatusCode::OK);
+    }
+
+    readiness.set_ready(true);
+    assert_eq!(status(app(&readiness), "/api/blocks").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn ready_probe_follows_state() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let readiness = state.readiness.clone();
+    let app = health_router().with_state(state);
+
+    readiness.set_ready(false);
+    assert_eq!(
+        status(app.clone(), "/health/ready").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(status(app.clone(), "/health").await, StatusCode::OK);
+
+    readiness.set_ready(true);
+    assert_eq!(status(app, "/health/ready").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn metrics_report_degradation_counters() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let response = health_router()
+        .with_state(state)
+        .oneshot(Request::get("/health/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    // В тестах коды хранятся в памяти, запасного хранилища нет
+    assert!(body["code_store_fallback_operations"].is_null());
+    assert_eq!(body["decrypt_failures"], 0);
+}
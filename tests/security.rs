@@ -0,0 +1,82 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{middleware, Extension, Router};
+use rimskiy_service::api::{security_router, AppState};
+use rimskiy_service::auth::middleware::{security_middleware, AuthState};
+use rimskiy_service::repository::{BlockRepository, PostgresBlockRepository};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn app(state: &AppState, user_id: Uuid) -> Router {
+    security_router()
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            security_middleware,
+        ))
+        .layer(Extension(AuthState { user_id }))
+        .with_state(state.clone())
+}
+
+async fn get(app: Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn active_blocks_require_security_or_admin_role() {
+    let pool = require_db!();
+    let guard = common::create_user(&pool).await;
+    let admin = common::create_user(&pool).await;
+    let resident = common::create_user(&pool).await;
+    let mut config = common::test_config();
+    config.security_user_ids = vec![guard.id];
+    config.admin_user_ids = vec![admin.id];
+    let state = common::test_state(&pool, config);
+
+    let blocker_plate = common::random_plate();
+    let blocked_plate = common::random_plate();
+    let block = PostgresBlockRepository::new(pool.clone())
+        .create(
+            resident.id,
+            &blocker_plate,
+            &blocked_plate,
+            None,
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let (status, body) = get(app(&state, resident.id), "/active-blocks").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+
+    for user_id in [guard.id, admin.id] {
+        let (status, body) = get(app(&state, user_id), "/active-blocks?limit=100").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["limit"], 100);
+        assert!(body["total"].as_i64().unwrap() >= 1);
+    }
+
+    // Новые блокировки - первыми: только что созданная есть на первой странице
+    let (_, body) = get(app(&state, guard.id), "/active-blocks?limit=100").await;
+    let item = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["id"] == block.id.to_string())
+        .expect("new block on the first page")
+        .clone();
+    assert_eq!(item["blocker_plate"], blocker_plate);
+    assert_eq!(item["blocked_plate"], blocked_plate);
+    assert_eq!(item["blocker"]["id"], resident.id.to_string());
+}
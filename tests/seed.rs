@@ -0,0 +1,63 @@
+mod common;
+
+use std::process::{Command, Output};
+
+fn run_seed(dev_mode: Option<&str>) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_seed"));
+    command
+        .env("DATABASE_URL", std::env::var("TEST_DATABASE_URL").unwrap())
+        .env("JWT_SECRET", "test-jwt-secret-at-least-32-characters")
+        .env("ENCRYPTION_KEY", common::TEST_ENCRYPTION_KEY)
+        .env("RUST_LOG", "warn")
+        .env_remove("DEV_MODE");
+    if let Some(value) = dev_mode {
+        command.env("DEV_MODE", value);
+    }
+    command.output().expect("failed to run seed")
+}
+
+#[tokio::test]
+async fn seed_is_idempotent_and_requires_dev_mode() {
+    let pool = require_db!();
+
+    let refused = run_seed(None);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("DEV_MODE"));
+
+    let counts = || async {
+        sqlx::query_as::<_, (i64, i64, i64)>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM users u
+                 JOIN user_plates up ON up.user_id = u.id
+                 WHERE up.plate IN ('А001АА777', 'В002ВВ777', 'Е003ЕЕ777')),
+                (SELECT COUNT(*) FROM blocks
+                 WHERE blocked_plate = 'В002ВВ777' AND blocker_plate IN ('А001АА777', 'Е003ЕЕ777')),
+                (SELECT COUNT(*) FROM notifications n
+                 JOIN user_plates up ON up.user_id = n.user_id
+                 WHERE up.plate = 'В002ВВ777' AND n.type = 'block_created')
+            "#,
+        )
+        .fetch_one(&*pool)
+        .await
+        .unwrap()
+    };
+
+    let first = run_seed(Some("true"));
+    assert!(
+        first.status.success(),
+        "{}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+    assert!(String::from_utf8_lossy(&first.stdout).contains("Authorization: Bearer "));
+    let after_first = counts().await;
+    assert_eq!(after_first, (3, 2, 2));
+
+    let second = run_seed(Some("1"));
+    assert!(
+        second.status.success(),
+        "{}",
+        String::from_utf8_lossy(&second.stderr)
+    );
+    assert_eq!(counts().await, after_first);
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use chrono::{Duration, Utc};
+use rimskiy_service::api::{auth_logout_router, user_router, AppState};
+use rimskiy_service::auth::jwt::{create_token, verify_token};
+use rimskiy_service::repository::UserSessionRepository;
+use rimskiy_service::service::user_sessions::purge_inactive_sessions;
+use rimskiy_service::AppError;
+use uuid::Uuid;
+
+fn app(state: &AppState) -> Router {
+    common::authed_app(
+        state,
+        Router::new()
+            .nest("/api/users", user_router())
+            .nest("/api/auth", auth_logout_router()),
+    )
+}
+
+async fn send(
+    state: &AppState,
+    method: &str,
+    uri: &str,
+    token: &str,
+) -> (StatusCode, serde_json::Value) {
+    common::send_authed(app(state), method, uri, token).await
+}
+
+/// Вход по коду с указанного устройства; возвращает токен
+async fn login(state: &AppState, phone: &str, device_id: Option<&str>) -> String {
+    let code = state
+        .sms_service
+        .store_code(phone, state.config.sms_code_length)
+        .await
+        .unwrap();
+    state
+        .auth_service
+        .verify_auth(
+            phone,
+            &code,
+            device_id,
+            &state.user_repository,
+            &state.user_plate_repository,
+            &state.user_session_repository,
+        )
+        .await
+        .unwrap()
+        .token
+}
+
+#[tokio::test]
+async fn sessions_are_listed_and_revoked_per_device() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let phone = common::random_phone();
+    let phone_token = login(&state, &phone, Some("  iPhone  ")).await;
+    let tablet_token = login(&state, &phone, None).await;
+
+    let (status, sessions) = send(&state, "GET", "/api/users/me/sessions", &phone_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current: Vec<_> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["device_id"], "iPhone");
+    assert!(current[0]["created_at"].is_string());
+    assert!(current[0]["last_seen"].is_string());
+
+    let tablet_session = verify_token(&tablet_token, &state.config)
+        .unwrap()
+        .sid
+        .unwrap();
+    let (status, _) = send(
+        &state,
+        "DELETE",
+        &format!("/api/users/me/sessions/{}", tablet_session),
+        &phone_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // Токены завершённой сессии больше не принимаются и не обновляются
+    let (status, _) = send(&state, "GET", "/api/users/me/sessions", &tablet_token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let refreshed = state
+        .auth_service
+        .refresh_token(
+            &tablet_token,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await;
+    assert!(matches!(refreshed, Err(AppError::Auth(msg)) if msg.contains("terminated")));
+
+    let (status, sessions) = send(&state, "GET", "/api/users/me/sessions", &phone_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sessions.as_array().unwrap().len(), 1);
+
+    // Повторное удаление и чужая сессия - 404
+    let (status, _) = send(
+        &state,
+        "DELETE",
+        &format!("/api/users/me/sessions/{}", tablet_session),
+        &phone_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let stranger = login(&state, &common::random_phone(), None).await;
+    let phone_session = verify_token(&phone_token, &state.config)
+        .unwrap()
+        .sid
+        .unwrap();
+    let (status, _) = send(
+        &state,
+        "DELETE",
+        &format!("/api/users/me/sessions/{}", phone_session),
+        &stranger,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn refresh_keeps_session_and_logout_ends_it() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let token = login(&state, &common::random_phone(), Some("android")).await;
+    let session_id = verify_token(&token, &state.config).unwrap().sid;
+    assert!(session_id.is_some());
+
+    let refreshed = state
+        .auth_service
+        .refresh_token(
+            &token,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await
+        .unwrap()
+        .token;
+    assert_eq!(
+        verify_token(&refreshed, &state.config).unwrap().sid,
+        session_id
+    );
+
+    let (status, _) = send(&state, "POST", "/api/auth/logout", &refreshed).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    // Старый токен той же сессии тоже перестаёт действовать
+    let (status, _) = send(&state, "GET", "/api/users/me/sessions", &token).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn token_without_session_gets_one_on_refresh() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+    let legacy = create_token(user.id, &state.config).unwrap();
+    assert!(verify_token(&legacy, &state.config).unwrap().sid.is_none());
+
+    let (status, _) = send(&state, "GET", "/api/users/me/sessions", &legacy).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let refreshed = state
+        .auth_service
+        .refresh_token(
+            &legacy,
+            &state.revoked_token_repository,
+            &state.user_session_repository,
+        )
+        .await
+        .unwrap()
+        .token;
+    assert!(verify_token(&refreshed, &state.config)
+        .unwrap()
+        .sid
+        .is_some());
+}
+
+#[tokio::test]
+async fn long_inactive_sessions_are_hidden_and_purged() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.jwt_expiration_minutes = 60;
+    let state = common::test_state(&pool, config);
+    let user = common::create_user(&pool).await;
+    let repository = &state.user_session_repository;
+    let active = repository.create(user.id, None).await.unwrap();
+    let stale = repository.create(user.id, None).await.unwrap();
+    sqlx::query("UPDATE user_sessions SET last_seen = $1 WHERE id = $2")
+        .bind(Utc::now() - Duration::days(3))
+        .bind(stale.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let listed = state
+        .auth_service
+        .list_sessions(user.id, None, repository)
+        .await
+        .unwrap();
+    assert_eq!(
+        listed.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![active.id]
+    );
+
+    assert!(
+        purge_inactive_sessions(repository, Duration::days(1))
+            .await
+            .unwrap()
+            >= 1
+    );
+    assert!(!repository.touch(stale.id, user.id).await.unwrap());
+    assert!(repository.touch(active.id, user.id).await.unwrap());
+    assert!(!repository.touch(active.id, Uuid::new_v4()).await.unwrap());
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use axum::body::Body;
+use axum::extract::Query;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use rimskiy_service::api::app_download_router;
+use rimskiy_service::auth::signed_url::{
+    avatar_download_path, sign_path, verify_signed_path, APP_DOWNLOAD_PATH,
+};
+use rimskiy_service::models::download::SignedUrlQuery;
+use rimskiy_service::AppError;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Параметры `expires` и `signature` из подписанной ссылки
+fn query_of(url: &str) -> SignedUrlQuery {
+    let uri = url.parse().unwrap();
+    Query::<SignedUrlQuery>::try_from_uri(&uri).unwrap().0
+}
+
+#[test]
+fn valid_signature_is_accepted() {
+    let config = common::test_config();
+    let signed = sign_path(APP_DOWNLOAD_PATH, &config);
+
+    assert!(signed.url.starts_with("/api/app/download?expires="));
+    assert!(signed.expires_at > chrono::Utc::now());
+    verify_signed_path(APP_DOWNLOAD_PATH, &query_of(&signed.url), &config).unwrap();
+}
+
+#[test]
+fn expired_signature_is_rejected() {
+    let mut config = common::test_config();
+    config.signed_url_expiration_minutes = -1;
+    let signed = sign_path(APP_DOWNLOAD_PATH, &config);
+
+    let result = verify_signed_path(APP_DOWNLOAD_PATH, &query_of(&signed.url), &config);
+    assert!(matches!(result, Err(AppError::Forbidden(msg)) if msg.contains("expired")));
+}
+
+#[test]
+fn tampered_signature_is_rejected() {
+    let config = common::test_config();
+    let user_id = Uuid::new_v4();
+    let path = avatar_download_path(user_id);
+    let query = query_of(&sign_path(&path, &config).url);
+
+    // Ссылка на другой ресурс
+    let other_path = avatar_download_path(Uuid::new_v4());
+    assert!(matches!(
+        verify_signed_path(&other_path, &query, &config),
+        Err(AppError::Forbidden(_))
+    ));
+
+    // Продлённый срок
+    let extended = SignedUrlQuery {
+        expires: query.expires.map(|expires| expires + 3600),
+        signature: query.signature.clone(),
+    };
+    assert!(matches!(
+        verify_signed_path(&path, &extended, &config),
+        Err(AppError::Forbidden(_))
+    ));
+
+    // Изменённая подпись
+    let mut signature = query.signature.clone().unwrap();
+    let last = if signature.ends_with('0') { "1" } else { "0" };
+    signature.replace_range(signature.len() - 1.., last);
+    let forged = SignedUrlQuery {
+        expires: query.expires,
+        signature: Some(signature),
+    };
+    assert!(matches!(
+        verify_signed_path(&path, &forged, &config),
+        Err(AppError::Forbidden(_))
+    ));
+
+    // Без подписи
+    assert!(matches!(
+        verify_signed_path(&path, &SignedUrlQuery::default(), &config),
+        Err(AppError::Auth(_))
+    ));
+}
+
+#[test]
+fn signature_from_previous_secret_is_accepted_during_rotation() {
+    let old_config = common::test_config();
+    let signed = sign_path(APP_DOWNLOAD_PATH, &old_config);
+
+    let mut config = common::test_config();
+    config.jwt_secret = "rotated-jwt-secret-at-least-32-characters".to_string();
+    config.jwt_secrets_accept = vec![old_config.jwt_secret.clone()];
+    verify_signed_path(APP_DOWNLOAD_PATH, &query_of(&signed.url), &config).unwrap();
+}
+
+#[tokio::test]
+async fn apk_download_requires_valid_signature() {
+    let pool = require_db!();
+    let apk_path = std::env::temp_dir().join(format!("rimskiy-test-{}.apk", Uuid::new_v4()));
+    std::fs::write(&apk_path, b"apk").unwrap();
+    let mut config = common::test_config();
+    config.app_apk_path = Some(apk_path.to_string_lossy().into_owned());
+    let state = common::test_state(&pool, config);
+    let app = Router::new()
+        .nest("/api/app", app_download_router())
+        .with_state(state.clone());
+
+    let download = |uri: String| {
+        app.clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+    };
+
+    let signed = sign_path(APP_DOWNLOAD_PATH, &state.config);
+    let response = download(signed.url.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["cache-control"]
+        .to_str()
+        .unwrap()
+        .starts_with("private, max-age="));
+
+    let response = download(APP_DOWNLOAD_PATH.to_string()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let tampered = signed.url.replace("expires=", "expires=1");
+    let response = download(tampered).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    std::fs::remove_file(apk_path).unwrap();
+}
@@ -0,0 +1,306 @@
+mod common;
+
+use rimskiy_service::auth::code_store::{
+    code_store_from_config, CodeEntry, CodeStore, FallbackCodeStore, InMemoryCodeStore,
+};
+use rimskiy_service::auth::sms::SmsService;
+use rimskiy_service::config::SmsCodeFormat;
+use rimskiy_service::error::{AppError, AppResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn service(format: SmsCodeFormat) -> SmsService {
+    let mut config = common::test_config();
+    config.sms_code_format = format;
+    common::sms_service(config)
+}
+
+#[tokio::test]
+async fn alphanumeric_codes_avoid_ambiguous_chars() {
+    let sms = service(SmsCodeFormat::Alphanumeric);
+    for _ in 0..200 {
+        let code = sms.generate_code(&common::random_phone(), 8).await.unwrap();
+        assert_eq!(code.len(), 8);
+        assert!(
+            code.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()),
+            "{}",
+            code
+        );
+        assert!(!code.contains(['0', 'O', '1', 'I']), "{}", code);
+    }
+}
+
+#[tokio::test]
+async fn alphanumeric_codes_verify_case_insensitively() {
+    let sms = service(SmsCodeFormat::Alphanumeric);
+    let phone = common::random_phone();
+    let code = sms.generate_code(&phone, 6).await.unwrap();
+
+    // Такой код не может быть выдан: 0 и O исключены из алфавита
+    assert!(!sms.consume_code(&phone, "0000OO").await.unwrap());
+    assert!(sms
+        .consume_code(&phone, &code.to_lowercase())
+        .await
+        .unwrap());
+    // Код одноразовый
+    assert!(!sms.consume_code(&phone, &code).await.unwrap());
+}
+
+#[tokio::test]
+async fn numeric_codes_stay_digits() {
+    let sms = service(SmsCodeFormat::Numeric);
+    let phone = common::random_phone();
+    let code = sms.generate_code(&phone, 4).await.unwrap();
+    assert_eq!(code.len(), 4);
+    assert!(code.chars().all(|c| c.is_ascii_digit()));
+    assert!(sms.consume_code(&phone, &code).await.unwrap());
+}
+
+#[tokio::test]
+async fn code_from_one_instance_is_consumed_by_another() {
+    // Два экземпляра сервиса с общим хранилищем (как с REDIS_URL)
+    let store = InMemoryCodeStore::new();
+    let first = SmsService::new(common::test_config(), Box::new(store.clone()));
+    let second = SmsService::new(common::test_config(), Box::new(store));
+    let phone = common::random_phone();
+
+    let code = first.store_code(&phone, 6).await.unwrap();
+    assert!(second.consume_code(&phone, &code).await.unwrap());
+    assert!(!first.consume_code(&phone, &code).await.unwrap());
+}
+
+fn expired_entry() -> CodeEntry {
+    CodeEntry {
+        code: "1234".to_string(),
+        expires_at: chrono::Utc::now() - chrono::Duration::minutes(1),
+        user_id: None,
+        failed_attempts: 0,
+    }
+}
+
+#[tokio::test]
+async fn expired_codes_are_removed_by_cleanup() {
+    let store = InMemoryCodeStore::new();
+    let sms = SmsService::new(common::test_config(), Box::new(store.clone()));
+    let expired_phone = common::random_phone();
+    let active_phone = common::random_phone();
+    store.insert(&expired_phone, expired_entry()).await.unwrap();
+    let code = sms.store_code(&active_phone, 4).await.unwrap();
+
+    sms.start_cleanup(Duration::from_millis(10));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(store.get(&expired_phone).await.unwrap().is_none());
+    assert_eq!(store.get(&active_phone).await.unwrap().unwrap().code, code);
+}
+
+#[tokio::test]
+async fn expired_code_is_rejected_and_dropped() {
+    let store = InMemoryCodeStore::new();
+    let sms = SmsService::new(common::test_config(), Box::new(store.clone()));
+    let phone = common::random_phone();
+    store.insert(&phone, expired_entry()).await.unwrap();
+
+    assert!(!sms.consume_code(&phone, "1234").await.unwrap());
+    assert!(store.get(&phone).await.unwrap().is_none());
+}
+
+#[test]
+fn code_format_is_parsed() {
+    assert_eq!(
+        SmsCodeFormat::parse(" Alphanumeric "),
+        Some(SmsCodeFormat::Alphanumeric)
+    );
+    assert_eq!(
+        SmsCodeFormat::parse("numeric"),
+        Some(SmsCodeFormat::Numeric)
+    );
+    assert_eq!(SmsCodeFormat::parse("hex"), None);
+}
+
+#[tokio::test]
+async fn code_is_locked_after_max_failed_attempts() {
+    let mut config = common::test_config();
+    config.sms_code_max_attempts = 3;
+    let sms = common::sms_service(config);
+    let phone = common::random_phone();
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    let wrong = if code == "0000" { "1111" } else { "0000" };
+
+    // До лимита неверный код просто не принимается
+    for _ in 0..2 {
+        assert!(!sms.consume_code(&phone, wrong).await.unwrap());
+    }
+    // Попытка, исчерпавшая лимит, уже сообщает о блокировке
+    assert!(matches!(
+        sms.consume_code(&phone, wrong).await,
+        Err(AppError::CodeAttemptsExceeded(_))
+    ));
+    // Заблокированный код не принимается, даже если введён верно
+    assert!(matches!(
+        sms.consume_code(&phone, &code).await,
+        Err(AppError::CodeAttemptsExceeded(_))
+    ));
+
+    // Новый код сбрасывает счётчик
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    let wrong = if code == "0000" { "1111" } else { "0000" };
+    assert!(!sms.consume_code(&phone, wrong).await.unwrap());
+    assert!(sms.consume_code(&phone, &code).await.unwrap());
+}
+
+#[tokio::test]
+async fn last_allowed_attempt_can_still_succeed() {
+    let mut config = common::test_config();
+    config.sms_code_max_attempts = 3;
+    let sms = common::sms_service(config);
+    let phone = common::random_phone();
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    let wrong = if code == "0000" { "1111" } else { "0000" };
+
+    for _ in 0..2 {
+        assert!(!sms.consume_code(&phone, wrong).await.unwrap());
+    }
+    assert!(sms.consume_code(&phone, &code).await.unwrap());
+}
+
+#[tokio::test]
+async fn concurrent_guesses_do_not_exceed_limit() {
+    let mut config = common::test_config();
+    config.sms_code_max_attempts = 5;
+    let sms = common::sms_service(config);
+    let phone = common::random_phone();
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    let wrong = if code == "0000" { "1111" } else { "0000" };
+
+    let guesses = (0..20).map(|_| {
+        let sms = sms.clone();
+        let phone = phone.clone();
+        tokio::spawn(async move { sms.consume_code(&phone, wrong).await })
+    });
+    let mut rejected = 0;
+    for guess in guesses {
+        if let Ok(false) = guess.await.unwrap() {
+            rejected += 1;
+        }
+    }
+    // Без блокировки проверяется не больше SMS_CODE_MAX_ATTEMPTS - 1 догадок
+    assert!(rejected <= 4, "{}", rejected);
+    assert!(matches!(
+        sms.consume_code(&phone, &code).await,
+        Err(AppError::CodeAttemptsExceeded(_))
+    ));
+}
+
+/// Хранилище, которое по команде теста начинает возвращать ошибки (как недоступный Redis)
+#[derive(Clone, Default)]
+struct FlakyStore {
+    inner: InMemoryCodeStore,
+    failing: Arc<AtomicBool>,
+}
+
+impl FlakyStore {
+    fn check(&self) -> AppResult<()> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(AppError::Internal("Redis error: connection refused".into()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CodeStore for FlakyStore {
+    async fn insert(&self, phone: &str, entry: CodeEntry) -> AppResult<()> {
+        self.check()?;
+        self.inner.insert(phone, entry).await
+    }
+
+    async fn get(&self, phone: &str) -> AppResult<Option<CodeEntry>> {
+        self.check()?;
+        self.inner.get(phone).await
+    }
+
+    async fn remove(&self, phone: &str) -> AppResult<bool> {
+        self.check()?;
+        self.inner.remove(phone).await
+    }
+
+    async fn record_failed_attempt(&self, phone: &str) -> AppResult<Option<u32>> {
+        self.check()?;
+        self.inner.record_failed_attempt(phone).await
+    }
+}
+
+#[tokio::test]
+async fn codes_fall_back_to_memory_while_store_fails() {
+    let primary = FlakyStore::default();
+    primary.failing.store(true, Ordering::SeqCst);
+    let phone = common::random_phone();
+
+    // Без запасного хранилища ошибка доходит до авторизации
+    let strict = SmsService::new(common::test_config(), Box::new(primary.clone()));
+    assert!(matches!(
+        strict.store_code(&phone, 4).await,
+        Err(AppError::Internal(_))
+    ));
+    assert_eq!(strict.code_store_fallback_operations(), None);
+
+    let store = FallbackCodeStore::new(Box::new(primary.clone()));
+    let sms = SmsService::new(common::test_config(), Box::new(store.clone()));
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    assert!(!sms.consume_code(&phone, "xxxx").await.unwrap());
+    assert!(sms.consume_code(&phone, &code).await.unwrap());
+    assert!(sms.code_store_fallback_operations().unwrap() > 0);
+    assert!(primary.inner.get(&phone).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn code_issued_during_outage_is_accepted_after_recovery() {
+    let primary = FlakyStore::default();
+    let store = FallbackCodeStore::with_retry_interval(
+        Box::new(primary.clone()),
+        Duration::from_millis(10),
+    );
+    let sms = SmsService::new(common::test_config(), Box::new(store.clone()));
+    let (during, after) = (common::random_phone(), common::random_phone());
+
+    primary.failing.store(true, Ordering::SeqCst);
+    let code = sms.store_code(&during, 4).await.unwrap();
+    let failures = store.fallback_operations().unwrap();
+    assert!(failures > 0);
+
+    primary.failing.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    // После восстановления новые коды снова хранятся в основном хранилище
+    let fresh = sms.store_code(&after, 4).await.unwrap();
+    assert_eq!(
+        primary.inner.get(&after).await.unwrap().unwrap().code,
+        fresh
+    );
+    assert_eq!(store.fallback_operations(), Some(failures));
+
+    assert!(sms.consume_code(&during, &code).await.unwrap());
+    assert!(sms.consume_code(&after, &fresh).await.unwrap());
+}
+
+#[tokio::test]
+async fn redis_outage_at_startup_falls_back_with_metric() {
+    // На порту 1 Redis нет: подключение при запуске не удаётся
+    let unreachable = "redis://127.0.0.1:1";
+    let (fallback, strict) = tokio::join!(
+        code_store_from_config(Some(unreachable), true),
+        code_store_from_config(Some(unreachable), false),
+    );
+    // Без REDIS_FALLBACK_INMEMORY недоступный Redis не даёт запуститься
+    assert!(strict.is_err());
+
+    let sms = SmsService::new(common::test_config(), fallback.unwrap());
+    assert_eq!(sms.code_store_fallback_operations(), Some(0));
+    let phone = common::random_phone();
+    let code = sms.store_code(&phone, 4).await.unwrap();
+    assert!(sms.consume_code(&phone, &code).await.unwrap());
+    // Операции обслужены памятью и учтены в метрике
+    assert!(sms.code_store_fallback_operations().unwrap() > 0);
+}
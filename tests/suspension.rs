@@ -0,0 +1,212 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use rimskiy_service::api::AppState;
+use rimskiy_service::auth::jwt::create_token;
+use rimskiy_service::auth::middleware::auth_middleware;
+use rimskiy_service::db::DbPool;
+use rimskiy_service::models::admin::SuspendUserRequest;
+use rimskiy_service::models::auth::AuthCodeChannel;
+use rimskiy_service::models::block::CreateBlockRequest;
+use rimskiy_service::repository::{
+    NotificationRepository, PostgresNotificationOutboxRepository, PostgresNotificationRepository,
+    PostgresTelegramBotRepository, PostgresUserPlateRepository, PostgresUserRepository,
+    PostgresUserSessionRepository, UserPlateRepository,
+};
+use rimskiy_service::service::{AuthService, TelegramService, TelephonyService};
+use rimskiy_service::AppError;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn suspend_request(reason: &str) -> SuspendUserRequest {
+    serde_json::from_value(serde_json::json!({ "reason": reason })).unwrap()
+}
+
+async fn suspend(state: &AppState, user_id: Uuid, reason: &str) {
+    let admin = Uuid::new_v4();
+    state
+        .admin_service
+        .suspend_user(
+            admin,
+            user_id,
+            suspend_request(reason),
+            &state.user_repository,
+            &state.audit_log_repository,
+        )
+        .await
+        .unwrap();
+}
+
+/// Переносит срок блокировки в прошлое: блокировка истекла
+async fn expire_suspension(pool: &DbPool, user_id: Uuid) {
+    sqlx::query("UPDATE users SET suspended_until = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(user_id)
+        .execute(&**pool)
+        .await
+        .unwrap();
+}
+
+async fn get_me(state: &AppState, user_id: Uuid) -> (StatusCode, serde_json::Value) {
+    let app = Router::new()
+        .route("/me", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state.clone());
+    let token = create_token(user_id, &state.config).unwrap();
+    let response = app
+        .oneshot(
+            Request::get("/me")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn suspended_user_is_rejected_until_suspension_ends() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let user = common::create_user(&pool).await;
+
+    assert_eq!(get_me(&state, user.id).await.0, StatusCode::OK);
+
+    suspend(&state, user.id, "Ложные блокировки").await;
+    let (status, body) = get_me(&state, user.id).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "ACCOUNT_SUSPENDED");
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("Ложные блокировки"));
+
+    // Истёкшая блокировка больше не действует
+    expire_suspension(&pool, user.id).await;
+    assert_eq!(get_me(&state, user.id).await.0, StatusCode::OK);
+
+    suspend(&state, user.id, "Повторно").await;
+    assert_eq!(get_me(&state, user.id).await.0, StatusCode::FORBIDDEN);
+    state
+        .admin_service
+        .unsuspend_user(
+            Uuid::new_v4(),
+            user.id,
+            &state.user_repository,
+            &state.audit_log_repository,
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_me(&state, user.id).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn suspended_user_cannot_log_in() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let state = common::test_state(&pool, config.clone());
+    let auth = AuthService::new(
+        common::sms_service(config.clone()),
+        common::encryption(),
+        config.clone(),
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let sessions = PostgresUserSessionRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+    let telegram = TelegramService::new(&config);
+    let phone = common::random_phone();
+
+    let login = || async {
+        let code = auth
+            .start_auth(&phone, AuthCodeChannel::Sms, &bots, &telegram)
+            .await
+            .unwrap()
+            .code;
+        auth.verify_auth(&phone, &code, None, &users, &plates, &sessions)
+            .await
+    };
+
+    let user_id = login().await.unwrap().user_id;
+    suspend(&state, user_id, "Спам").await;
+    assert!(matches!(login().await, Err(AppError::AccountSuspended(_))));
+
+    expire_suspension(&pool, user_id).await;
+    assert_eq!(login().await.unwrap().user_id, user_id);
+}
+
+#[tokio::test]
+async fn suspended_owner_is_not_notified() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let state = common::test_state(&pool, config.clone());
+    let service = common::block_service(&config);
+    let notifications = PostgresNotificationRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let telephony = TelephonyService::new(config.clone());
+    let telegram = TelegramService::new(&config);
+
+    let blocker = common::create_user(&pool).await;
+    let suspended = common::create_user(&pool).await;
+    let active = common::create_user(&pool).await;
+    plates
+        .create(blocker.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    let plate = common::random_plate();
+    for owner in [&suspended, &active] {
+        plates.create(owner.id, &plate, true, None).await.unwrap();
+    }
+    suspend(&state, suspended.id, "Спам").await;
+
+    let owner_ids = plates.find_active_owner_ids(&plate).await.unwrap();
+    assert_eq!(owner_ids, vec![active.id]);
+
+    let request: CreateBlockRequest =
+        serde_json::from_value(serde_json::json!({ "blocked_plate": plate })).unwrap();
+    let response = service
+        .create_block(
+            blocker.id,
+            request,
+            &state.block_repository,
+            &notifications,
+            &state.user_repository,
+            &plates,
+            &outbox,
+            &telephony,
+            &telegram,
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.notify_summary.owners_total, 1);
+
+    let (_, suspended_count) = notifications
+        .find_by_user_id(suspended.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(suspended_count, 0);
+    let (_, active_count) = notifications
+        .find_by_user_id(active.id, false, 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(active_count, 1);
+
+    // После окончания блокировки владелец снова получает уведомления
+    expire_suspension(&pool, suspended.id).await;
+    let owner_ids = plates.find_active_owner_ids(&plate).await.unwrap();
+    assert_eq!(owner_ids.len(), 2);
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use rimskiy_service::db::DbPool;
+use rimskiy_service::models::outbox::{outbox_status, OutboxChannel};
+use rimskiy_service::repository::{
+    NewOutboxMessage, NotificationOutboxRepository, PostgresNotificationOutboxRepository,
+    PostgresTelegramBotRepository, PostgresUserRepository, TelegramBotRepository,
+};
+use rimskiy_service::service::analytics_service::NoopAnalyticsSink;
+use rimskiy_service::service::notification_outbox::OutboxDispatcher;
+use rimskiy_service::service::{AnalyticsService, PushService, TelegramService, TelephonyService};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Заглушка Telegram Bot API: запоминает chat_id каждого sendMessage
+async fn mock_telegram() -> (String, Arc<Mutex<Vec<i64>>>) {
+    let chats: Arc<Mutex<Vec<i64>>> = Arc::default();
+    let recorded = chats.clone();
+    let app = Router::new().fallback(post(move |Json(body): Json<serde_json::Value>| {
+        let recorded = recorded.clone();
+        async move {
+            if let Some(chat_id) = body["chat_id"].as_i64() {
+                recorded.lock().unwrap().push(chat_id);
+            }
+            Json(serde_json::json!({ "ok": true }))
+        }
+    }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", address), chats)
+}
+
+/// Случайный chat_id, не пересекающийся с другими тестами
+fn random_chat_id() -> i64 {
+    (Uuid::new_v4().as_u128() % 1_000_000_000_000) as i64 + 1
+}
+
+/// Регистрация с заданным временем последней активности. Вставкой, а не UPDATE:
+/// триггер `update_telegram_bot_users_updated_at` при каждом UPDATE ставит NOW()
+async fn insert_registration(
+    pool: &DbPool,
+    phone_hash: &str,
+    chat_id: i64,
+    updated_at: DateTime<Utc>,
+) {
+    sqlx::query(
+        "INSERT INTO telegram_bot_users (phone_hash, chat_id, created_at, updated_at) \
+         VALUES ($1, $2, $3, $3)",
+    )
+    .bind(phone_hash)
+    .bind(chat_id)
+    .bind(updated_at)
+    .execute(&**pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn chat_ids_are_ordered_by_activity_and_stale_ones_are_removed() {
+    let pool = require_db!();
+    let repository = PostgresTelegramBotRepository::new(pool.clone());
+    let phone_hash = format!("test-{}", Uuid::new_v4());
+    let (recent, older, stale) = (random_chat_id(), random_chat_id(), random_chat_id());
+    // Порядок вставки не совпадает с порядком активности
+    insert_registration(&pool, &phone_hash, stale, Utc::now() - Duration::days(400)).await;
+    insert_registration(&pool, &phone_hash, recent, Utc::now()).await;
+    insert_registration(&pool, &phone_hash, older, Utc::now() - Duration::days(10)).await;
+
+    assert_eq!(
+        repository
+            .find_chat_ids_by_phone_hash(&phone_hash)
+            .await
+            .unwrap(),
+        vec![recent, older, stale]
+    );
+    assert_eq!(
+        repository
+            .find_by_phone_hash(&phone_hash)
+            .await
+            .unwrap()
+            .unwrap()
+            .chat_id,
+        recent
+    );
+
+    let deleted = repository
+        .delete_stale_for_phone_hash(&phone_hash, Utc::now() - Duration::days(180))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(
+        repository
+            .find_chat_ids_by_phone_hash(&phone_hash)
+            .await
+            .unwrap(),
+        vec![recent, older]
+    );
+    // Порог отсекает и среднюю запись, но самая свежая остаётся
+    let deleted = repository
+        .delete_stale_for_phone_hash(&phone_hash, Utc::now() - Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(
+        repository
+            .find_chat_ids_by_phone_hash(&phone_hash)
+            .await
+            .unwrap(),
+        vec![recent]
+    );
+
+    // Единственная (самая свежая) запись номера не удаляется, даже если давно неактивна
+    let lonely_hash = format!("test-{}", Uuid::new_v4());
+    let lonely = random_chat_id();
+    insert_registration(
+        &pool,
+        &lonely_hash,
+        lonely,
+        Utc::now() - Duration::days(400),
+    )
+    .await;
+    let deleted = repository
+        .delete_stale_for_phone_hash(&lonely_hash, Utc::now() - Duration::days(180))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 0);
+    assert_eq!(
+        repository
+            .find_chat_ids_by_phone_hash(&lonely_hash)
+            .await
+            .unwrap(),
+        vec![lonely]
+    );
+}
+
+#[tokio::test]
+async fn temporary_registration_is_replaced_by_phone_binding() {
+    let pool = require_db!();
+    let repository = PostgresTelegramBotRepository::new(pool.clone());
+    let chat_id = random_chat_id();
+    let phone_hash = format!("test-{}", Uuid::new_v4());
+    let registration = repository
+        .upsert(&phone_hash, chat_id, Some("driver"), None)
+        .await
+        .unwrap();
+    // Временная запись обновлена позже, но привязка к номеру важнее
+    insert_registration(
+        &pool,
+        &format!("temp_{}", chat_id),
+        chat_id,
+        Utc::now() + Duration::minutes(1),
+    )
+    .await;
+    assert_eq!(
+        repository
+            .find_by_chat_id(chat_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .phone_hash,
+        phone_hash
+    );
+
+    let deleted = repository
+        .delete_other_for_chat_id(chat_id, registration.id)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert!(repository
+        .find_by_phone_hash(&format!("temp_{}", chat_id))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn telegram_notification_reaches_every_chat_of_user() {
+    let pool = require_db!();
+    // Отдельный тестовый бинарник: переменная окружения не влияет на другие тесты
+    std::env::set_var("TELEGRAM_BOT_TOKEN", "test-token");
+    let (base_url, chats) = mock_telegram().await;
+    let mut config = common::test_config();
+    config.telegram_api_url = base_url;
+
+    let user = common::create_user(&pool).await;
+    let phone_hash = user.phone_hash.clone().unwrap();
+    let telegram_bot = PostgresTelegramBotRepository::new(pool.clone());
+    let (phone_chat, desktop_chat) = (random_chat_id(), random_chat_id());
+    for chat_id in [phone_chat, desktop_chat] {
+        telegram_bot
+            .upsert(&phone_hash, chat_id, None, Some(user.id))
+            .await
+            .unwrap();
+    }
+
+    let outbox = PostgresNotificationOutboxRepository::new(pool.clone());
+    let mut tx = pool.begin().await.unwrap();
+    outbox
+        .enqueue_in_tx(
+            &mut tx,
+            &[NewOutboxMessage {
+                block_id: None,
+                user_id: Some(user.id),
+                channel: OutboxChannel::Telegram,
+                payload: serde_json::json!({ "message": "Ваш автомобиль заблокирован" }),
+            }],
+        )
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let dispatcher = OutboxDispatcher::new(
+        PushService::new(None),
+        TelegramService::new(&config),
+        TelephonyService::new(config.clone()),
+        common::encryption(),
+        AnalyticsService::new(Arc::new(NoopAnalyticsSink), ""),
+    );
+    let users = PostgresUserRepository::new(pool.clone());
+    dispatcher
+        .deliver_due(
+            &common::UserOutbox::new(&pool, user.id),
+            &users,
+            &telegram_bot,
+        )
+        .await
+        .unwrap();
+    let status: String =
+        sqlx::query_scalar("SELECT status FROM notification_outbox WHERE user_id = $1")
+            .bind(user.id)
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert_eq!(status, outbox_status::DELIVERED);
+
+    let mut delivered = chats.lock().unwrap().clone();
+    delivered.sort();
+    let mut expected = vec![phone_chat, desktop_chat];
+    expected.sort();
+    assert_eq!(delivered, expected);
+}
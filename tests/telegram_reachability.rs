@@ -0,0 +1,49 @@
+mod common;
+
+use rand::Rng;
+use rimskiy_service::repository::{
+    PostgresTelegramBotRepository, PostgresUserRepository, TelegramBotRepository,
+};
+use rimskiy_service::service::{TelegramService, UserService};
+
+#[tokio::test]
+async fn reachable_only_after_bot_registration() {
+    let pool = require_db!();
+    // Отдельный тестовый бинарник: переменная окружения не влияет на другие тесты
+    std::env::set_var("TELEGRAM_BOT_USERNAME", "@rimskiy_test_bot");
+    let telegram = TelegramService::new(&common::test_config());
+    let service = UserService::new(common::encryption(), false);
+    let users = PostgresUserRepository::new(pool.clone());
+    let bots = PostgresTelegramBotRepository::new(pool.clone());
+
+    let user = common::create_user(&pool).await;
+
+    let response = service
+        .telegram_reachability(user.id, &users, &bots, &telegram)
+        .await
+        .unwrap();
+    assert!(!response.reachable);
+    assert_eq!(response.bot_username.as_deref(), Some("rimskiy_test_bot"));
+    assert_eq!(
+        response.start_link.as_deref(),
+        Some("https://t.me/rimskiy_test_bot")
+    );
+
+    let chat_id = rand::thread_rng().gen_range(1..i64::MAX);
+    bots.upsert(
+        user.phone_hash.as_deref().unwrap(),
+        chat_id,
+        None,
+        Some(user.id),
+    )
+    .await
+    .unwrap();
+
+    let response = service
+        .telegram_reachability(user.id, &users, &bots, &telegram)
+        .await
+        .unwrap();
+    assert!(response.reachable);
+    assert_eq!(response.bot_username.as_deref(), Some("rimskiy_test_bot"));
+    assert_eq!(response.start_link, None);
+}
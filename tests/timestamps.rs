@@ -0,0 +1,122 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use rimskiy_service::models::admin::{AnnounceJob, AnnounceJobStatus, UserSuspensionResponse};
+use rimskiy_service::models::audit::AuditLogEntry;
+use rimskiy_service::models::download::SignedUrlResponse;
+use rimskiy_service::models::push_token::PushToken;
+use rimskiy_service::models::user_plate::UserPlate;
+use rimskiy_service::repository::{PostgresUserPlateRepository, UserPlateRepository};
+use uuid::Uuid;
+
+/// Проверяет формат `YYYY-MM-DDTHH:MM:SS.mmmZ`
+fn assert_api_timestamp(value: &serde_json::Value) {
+    let value = value.as_str().expect("timestamp must be a string");
+    assert_eq!(value.len(), 24, "unexpected timestamp {}", value);
+    assert!(value.ends_with('Z'), "unexpected timestamp {}", value);
+    assert_eq!(&value[19..20], ".", "unexpected timestamp {}", value);
+    assert!(chrono::DateTime::parse_from_rfc3339(value).is_ok());
+}
+
+#[test]
+fn known_timestamp_has_exact_format() {
+    let time = Utc.with_ymd_and_hms(2024, 11, 17, 12, 30, 0).unwrap();
+    let plate = UserPlate {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        plate: "А123БВ777".to_string(),
+        is_primary: true,
+        departure_time: None,
+        created_at: time,
+        updated_at: time + chrono::Duration::microseconds(123_456),
+    };
+
+    let json = serde_json::to_value(plate.to_response()).unwrap();
+
+    assert_eq!(json["created_at"], "2024-11-17T12:30:00.000Z");
+    assert_eq!(json["updated_at"], "2024-11-17T12:30:00.123Z");
+}
+
+#[test]
+fn response_timestamps_use_api_format() {
+    let time = Utc.with_ymd_and_hms(2024, 11, 17, 12, 30, 0).unwrap();
+    let signed = SignedUrlResponse {
+        url: "/api/app/download?expires=1&signature=00".to_string(),
+        expires_at: time,
+    };
+    assert_eq!(
+        serde_json::to_value(signed).unwrap()["expires_at"],
+        "2024-11-17T12:30:00.000Z"
+    );
+
+    let job = AnnounceJob {
+        job_id: Uuid::new_v4(),
+        status: AnnounceJobStatus::Completed,
+        total_users: 1,
+        processed_users: 1,
+        notifications_created: 1,
+        push_sent: 0,
+        telegram_sent: 0,
+        delivery_failed: 0,
+        error: None,
+        started_at: time,
+        finished_at: Some(time + chrono::Duration::seconds(5)),
+    };
+    let json = serde_json::to_value(job).unwrap();
+    assert_eq!(json["started_at"], "2024-11-17T12:30:00.000Z");
+    assert_eq!(json["finished_at"], "2024-11-17T12:30:05.000Z");
+
+    let suspension = UserSuspensionResponse {
+        user_id: Uuid::new_v4(),
+        suspended: true,
+        suspended_until: Some(time),
+        reason: None,
+    };
+    assert_eq!(
+        serde_json::to_value(suspension).unwrap()["suspended_until"],
+        "2024-11-17T12:30:00.000Z"
+    );
+
+    let entry = AuditLogEntry {
+        id: Uuid::new_v4(),
+        actor_id: None,
+        action: "block_created_on_behalf".to_string(),
+        subject_user_id: None,
+        entity_id: None,
+        details: None,
+        created_at: time,
+    };
+    assert_eq!(
+        serde_json::to_value(entry).unwrap()["created_at"],
+        "2024-11-17T12:30:00.000Z"
+    );
+
+    let token = PushToken {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        token: "token".to_string(),
+        platform: Some("android".to_string()),
+        app_version: None,
+        last_seen: time,
+        created_at: time,
+    };
+    let json = serde_json::to_value(token).unwrap();
+    assert_eq!(json["last_seen"], "2024-11-17T12:30:00.000Z");
+    assert_eq!(json["created_at"], "2024-11-17T12:30:00.000Z");
+}
+
+#[tokio::test]
+async fn stored_timestamps_are_serialized_in_api_format() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let plate = plates
+        .create(user.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    let json = serde_json::to_value(plate.to_response()).unwrap();
+
+    assert_api_timestamp(&json["created_at"]);
+    assert_api_timestamp(&json["updated_at"]);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use rimskiy_service::repository::{PostgresUserPlateRepository, UserPlateRepository};
+use rimskiy_service::AppError;
+
+#[tokio::test]
+async fn concurrent_primary_creation_leaves_one_primary() {
+    let pool = require_db!();
+    let repository = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let repository = repository.clone();
+            tokio::spawn(async move {
+                repository
+                    .create(user.id, &common::random_plate(), true, None)
+                    .await
+            })
+        })
+        .collect();
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    for result in &results {
+        assert!(
+            matches!(result, Ok(_) | Err(AppError::Conflict(_))),
+            "{:?}",
+            result
+        );
+    }
+
+    let plates = repository.find_by_user_id(user.id).await.unwrap();
+    assert_eq!(plates.iter().filter(|p| p.is_primary).count(), 1);
+    assert_eq!(
+        plates.len(),
+        results.iter().filter(|result| result.is_ok()).count()
+    );
+}
@@ -0,0 +1,371 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{Extension, Router};
+use rimskiy_service::api::user_plate_router;
+use rimskiy_service::auth::middleware::AuthState;
+use rimskiy_service::db::DbPool;
+use rimskiy_service::models::user::MAX_PLATES_PER_LOOKUP;
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, PostgresUserRepository,
+    UserPlateRepository, UserRepository,
+};
+use rimskiy_service::service::UserService;
+use rimskiy_service::utils::mask_plate;
+use rimskiy_service::AppError;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn service() -> UserService {
+    UserService::new(common::encryption(), false)
+}
+
+async fn set_show_contacts(pool: &DbPool, user_id: Uuid, show_contacts: bool) {
+    sqlx::query("UPDATE users SET show_contacts = $2 WHERE id = $1")
+        .bind(user_id)
+        .bind(show_contacts)
+        .execute(&**pool)
+        .await
+        .unwrap();
+}
+
+async fn get(app: &Router, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn co_owners_exclude_caller_and_respect_show_contacts() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let visible = common::create_user(&pool).await;
+    let hidden = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    set_show_contacts(&pool, visible.id, true).await;
+    set_show_contacts(&pool, hidden.id, false).await;
+    let plate = common::random_plate();
+    let owner_plate = plates.create(owner.id, &plate, true, None).await.unwrap();
+    for user_id in [visible.id, hidden.id] {
+        plates.create(user_id, &plate, true, None).await.unwrap();
+    }
+    plates
+        .create(stranger.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    let co_owners = service()
+        .get_co_owners(owner.id, owner_plate.id, &plates)
+        .await
+        .unwrap();
+
+    assert_eq!(co_owners.len(), 2);
+    assert!(co_owners.iter().all(|info| info.id != owner.id));
+    let visible_info = co_owners.iter().find(|info| info.id == visible.id).unwrap();
+    assert!(visible_info.phone.is_some());
+    let hidden_info = co_owners.iter().find(|info| info.id == hidden.id).unwrap();
+    assert!(hidden_info.phone.is_none());
+    assert!(hidden_info.telegram.is_none());
+
+    // Чужой номер посмотреть нельзя
+    let result = service()
+        .get_co_owners(stranger.id, owner_plate.id, &plates)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn deleting_primary_promotes_newest_remaining_plate() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let primary = plates
+        .create(user.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+    plates
+        .create(user.id, &common::random_plate(), false, None)
+        .await
+        .unwrap();
+    let newest = plates
+        .create(user.id, &common::random_plate(), false, None)
+        .await
+        .unwrap();
+
+    let new_primary = plates.delete(primary.id, user.id).await.unwrap().unwrap();
+
+    assert_eq!(new_primary.id, newest.id);
+    assert!(new_primary.is_primary);
+    let stored = plates
+        .find_primary_by_user_id(user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.id, newest.id);
+
+    // Удаление не основного номера основной не меняет
+    let remaining: Vec<_> = plates
+        .find_by_user_id(user.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|p| !p.is_primary)
+        .collect();
+    assert!(plates
+        .delete(remaining[0].id, user.id)
+        .await
+        .unwrap()
+        .is_none());
+    // Последний номер удаляется без замены
+    assert!(plates.delete(newest.id, user.id).await.unwrap().is_none());
+    assert!(plates
+        .find_primary_by_user_id(user.id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn plate_check_reports_three_states() {
+    let pool = require_db!();
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let other = common::create_user(&pool).await;
+    let mine = common::random_plate();
+    let theirs = common::random_plate();
+    plates.create(user.id, &mine, true, None).await.unwrap();
+    plates.create(other.id, &theirs, true, None).await.unwrap();
+
+    let check = |plate: String| {
+        let plates = &plates;
+        async move { service().check_plate(user.id, &plate, plates).await }
+    };
+
+    let free = check(common::random_plate()).await.unwrap();
+    assert!(!free.exists_for_me && !free.exists_for_others);
+
+    let own = check(mine.to_lowercase()).await.unwrap();
+    assert!(own.exists_for_me && !own.exists_for_others);
+    assert_eq!(own.normalized, mine);
+
+    let shared = check(theirs.clone()).await.unwrap();
+    assert!(!shared.exists_for_me && shared.exists_for_others);
+
+    assert!(matches!(
+        check("не номер".to_string()).await,
+        Err(AppError::Validation(_))
+    ));
+}
+
+#[test]
+fn mask_plate_hides_last_digit_and_region() {
+    assert_eq!(mask_plate("А123БВ777"), "А12*БВ***");
+    assert_eq!(mask_plate(" а123бв77 "), "А12*БВ**");
+}
+
+#[tokio::test]
+async fn public_plate_is_masked_only_for_unrelated_viewers() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let owner = common::create_user(&pool).await;
+    let blocker = common::create_user(&pool).await;
+    let stranger = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    let blocker_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET plate = $2 WHERE id = $1")
+        .bind(owner.id)
+        .bind(&owner_plate)
+        .execute(&*pool)
+        .await
+        .unwrap();
+    plates
+        .create(blocker.id, &blocker_plate, true, None)
+        .await
+        .unwrap();
+    blocks
+        .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+        .await
+        .unwrap();
+
+    let masking = UserService::new(common::encryption(), true);
+    for (viewer_id, expected) in [
+        (owner.id, owner_plate.clone()),
+        (blocker.id, owner_plate.clone()),
+        (stranger.id, mask_plate(&owner_plate)),
+    ] {
+        let info = masking
+            .get_user_by_plate(viewer_id, &owner_plate, &users, &plates, &blocks)
+            .await
+            .unwrap()
+            .expect("owner not found");
+        assert_eq!(info.plate, expected);
+    }
+
+    // Без MASK_PUBLIC_PLATES номер виден всем
+    let info = service()
+        .get_user_by_plate(stranger.id, &owner_plate, &users, &plates, &blocks)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.plate, owner_plate);
+}
+
+#[tokio::test]
+async fn user_plate_is_read_from_primary_user_plate() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let first = common::random_plate();
+    let second = common::random_plate();
+
+    plates.create(user.id, &first, true, None).await.unwrap();
+    let found = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(found.plate, Some(first.clone()));
+
+    // Смена основного номера видна без синхронизации users.plate
+    let second_plate = plates.create(user.id, &second, false, None).await.unwrap();
+    plates.set_primary(second_plate.id, user.id).await.unwrap();
+    sqlx::query("UPDATE users SET plate = $2 WHERE id = $1")
+        .bind(user.id)
+        .bind(&first)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let found = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(found.plate, Some(second.clone()));
+    assert_eq!(
+        users.get_plate_by_id(user.id).await.unwrap(),
+        Some(second.clone())
+    );
+}
+
+#[tokio::test]
+async fn users_by_plates_returns_null_for_unregistered() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let viewer = common::create_user(&pool).await;
+    let visible = common::create_user(&pool).await;
+    let hidden = common::create_user(&pool).await;
+    set_show_contacts(&pool, visible.id, true).await;
+    set_show_contacts(&pool, hidden.id, false).await;
+    let visible_plate = common::random_plate();
+    let hidden_plate = common::random_plate();
+    let unknown_plate = common::random_plate();
+    plates
+        .create(visible.id, &visible_plate, true, None)
+        .await
+        .unwrap();
+    plates
+        .create(hidden.id, &hidden_plate, true, None)
+        .await
+        .unwrap();
+
+    let result = service()
+        .get_users_by_plates(
+            viewer.id,
+            &[
+                visible_plate.to_lowercase(),
+                hidden_plate.clone(),
+                unknown_plate.clone(),
+            ],
+            &users,
+            &plates,
+            &blocks,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.len(), 3);
+    let visible_info = result[&visible_plate].as_ref().unwrap();
+    assert_eq!(visible_info.id, visible.id);
+    assert!(visible_info.phone.is_some());
+    let hidden_info = result[&hidden_plate].as_ref().unwrap();
+    assert_eq!(hidden_info.id, hidden.id);
+    assert!(hidden_info.phone.is_none());
+    assert!(result[&unknown_plate].is_none());
+
+    let too_many: Vec<String> = (0..=MAX_PLATES_PER_LOOKUP)
+        .map(|_| common::random_plate())
+        .collect();
+    let result = service()
+        .get_users_by_plates(viewer.id, &too_many, &users, &plates, &blocks)
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn user_plates_are_paged_with_block_counts() {
+    let pool = require_db!();
+    let state = common::test_state(&pool, common::test_config());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+
+    let user = common::create_user(&pool).await;
+    let primary = common::random_plate();
+    let second = common::random_plate();
+    plates.create(user.id, &primary, true, None).await.unwrap();
+    plates.create(user.id, &second, false, None).await.unwrap();
+
+    // Основной номер перекрыт дважды, со второго создана одна блокировка
+    for _ in 0..2 {
+        let blocker = common::create_user(&pool).await;
+        let blocker_plate = common::random_plate();
+        plates
+            .create(blocker.id, &blocker_plate, true, None)
+            .await
+            .unwrap();
+        blocks
+            .create(blocker.id, &blocker_plate, &primary, None, None, false)
+            .await
+            .unwrap();
+    }
+    blocks
+        .create(user.id, &second, &common::random_plate(), None, None, false)
+        .await
+        .unwrap();
+
+    let app = user_plate_router()
+        .layer(Extension(AuthState { user_id: user.id }))
+        .with_state(state);
+
+    // По умолчанию - массив без счётчиков
+    let (status, body) = get(&app, "/").await;
+    assert_eq!(status, StatusCode::OK);
+    let list = body.as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["plate"], primary.as_str());
+    assert!(list[0].get("received_block_count").is_none());
+
+    let (_, body) = get(&app, "/?include_counts=true").await;
+    let list = body.as_array().unwrap();
+    assert_eq!(list[0]["received_block_count"], 2);
+    assert_eq!(list[0]["created_block_count"], 0);
+    assert_eq!(list[1]["plate"], second.as_str());
+    assert_eq!(list[1]["received_block_count"], 0);
+    assert_eq!(list[1]["created_block_count"], 1);
+
+    let (_, body) = get(&app, "/?limit=1&offset=1&include_counts=true").await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["has_more"], false);
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["created_block_count"], 1);
+}
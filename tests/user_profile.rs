@@ -0,0 +1,267 @@
+mod common;
+
+use rimskiy_service::models::user::UpdateUserRequest;
+use rimskiy_service::repository::user_repository::UpdateUserData;
+use rimskiy_service::repository::{
+    PostgresUserPlateRepository, PostgresUserRepository, UserPlateRepository, UserRepository,
+};
+use rimskiy_service::service::UserService;
+use rimskiy_service::utils::projection::project_fields;
+use rimskiy_service::AppError;
+use sha2::{Digest, Sha256};
+
+fn update_request(json: serde_json::Value) -> UpdateUserRequest {
+    serde_json::from_value(json).unwrap()
+}
+
+#[tokio::test]
+async fn failed_user_update_rolls_back_plate_sync() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let other = common::create_user(&pool).await;
+    let old_plate = common::random_plate();
+    plates
+        .create(user.id, &old_plate, true, None)
+        .await
+        .unwrap();
+
+    // Телефон уже занят другим пользователем: обновление users упадёт на уникальном индексе
+    let taken_phone = common::random_phone();
+    sqlx::query("UPDATE users SET phone_hash = $2 WHERE id = $1")
+        .bind(other.id)
+        .bind(format!("{:x}", Sha256::digest(taken_phone.as_bytes())))
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let service = UserService::new(common::encryption(), false);
+    let update = |phone: String, plate: String| {
+        service.update_profile(
+            user.id,
+            update_request(serde_json::json!({ "phone": phone, "plate": plate })),
+            &users,
+            &plates,
+        )
+    };
+
+    let result = update(taken_phone, common::random_plate()).await;
+
+    assert!(result.is_err());
+    let user_plates = plates.find_by_user_id(user.id).await.unwrap();
+    assert_eq!(user_plates.len(), 1);
+    assert_eq!(user_plates[0].plate, old_plate);
+    assert!(user_plates[0].is_primary);
+
+    // Со свободным телефоном тот же запрос проходит целиком
+    let new_plate = common::random_plate();
+    update(common::random_phone(), new_plate.clone())
+        .await
+        .unwrap();
+    let primary = plates
+        .find_primary_by_user_id(user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(primary.plate, new_plate);
+}
+
+#[tokio::test]
+async fn concurrent_plate_updates_keep_one_primary() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    plates
+        .create(user.id, &common::random_plate(), true, None)
+        .await
+        .unwrap();
+
+    let service = UserService::new(common::encryption(), false);
+    let update = |request: serde_json::Value| {
+        service.update_profile(user.id, update_request(request), &users, &plates)
+    };
+    let (first, second) = (common::random_plate(), common::random_plate());
+    // Обновление без номера берёт номер из основного автомобиля: он читается в той же
+    // транзакции, поэтому в users.plate не попадает номер, уже заменённый параллельно
+    let (first_result, second_result, rename_result) = tokio::join!(
+        update(serde_json::json!({ "plate": first })),
+        update(serde_json::json!({ "plate": second })),
+        update(serde_json::json!({ "name": "Иван" })),
+    );
+    first_result.unwrap();
+    second_result.unwrap();
+    rename_result.unwrap();
+
+    let user_plates = plates.find_by_user_id(user.id).await.unwrap();
+    let primary: Vec<_> = user_plates.iter().filter(|p| p.is_primary).collect();
+    assert_eq!(primary.len(), 1);
+    assert!(primary[0].plate == first || primary[0].plate == second);
+    let stored = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.plate.as_deref(), Some(primary[0].plate.as_str()));
+}
+
+#[tokio::test]
+async fn profile_never_exposes_push_token() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let service = UserService::new(common::encryption(), false);
+    let stored = users
+        .update(
+            user.id,
+            &UpdateUserData {
+                name: Some("Иван".to_string()),
+                push_token: Some("device-token".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored.push_token.as_deref(), Some("device-token"));
+    assert!(serde_json::to_value(&stored)
+        .unwrap()
+        .get("push_token")
+        .is_none());
+
+    let profile = service.get_profile(user.id, &users, &plates).await.unwrap();
+    let body = serde_json::to_value(&profile).unwrap();
+    assert!(body.get("push_token").is_none());
+
+    let projected = project_fields(&profile, "name, plate").unwrap();
+    let keys: Vec<&str> = projected
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(keys.len(), 3);
+    assert!(keys.contains(&"id") && keys.contains(&"name") && keys.contains(&"plate"));
+    assert!(project_fields(&profile, "push_token").is_err());
+}
+
+#[tokio::test]
+async fn profile_update_rejects_push_token() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+    let service = UserService::new(common::encryption(), false);
+
+    let request = update_request(serde_json::json!({
+        "name": "Иван",
+        "push_token": "device-token",
+    }));
+    let err = service
+        .update_profile(user.id, request, &users, &plates)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, AppError::Validation(ref message) if message.contains("/api/users/push-token"))
+    );
+
+    // Профиль не изменился
+    let stored = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.name, user.name);
+    assert_eq!(stored.push_token, None);
+}
+
+fn offset(json: serde_json::Value) -> Option<Option<i32>> {
+    update_request(json).utc_offset_minutes
+}
+
+#[test]
+fn utc_offset_distinguishes_missing_and_null() {
+    assert_eq!(offset(serde_json::json!({})), None);
+    assert_eq!(
+        offset(serde_json::json!({ "utc_offset_minutes": null })),
+        Some(None)
+    );
+    assert_eq!(
+        offset(serde_json::json!({ "utc_offset_minutes": 180 })),
+        Some(Some(180))
+    );
+}
+
+#[tokio::test]
+async fn utc_offset_can_be_cleared() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let user = common::create_user(&pool).await;
+
+    let update = |utc_offset_minutes| UpdateUserData {
+        utc_offset_minutes,
+        ..Default::default()
+    };
+
+    let updated = users
+        .update(user.id, &update(Some(Some(180))))
+        .await
+        .unwrap();
+    assert_eq!(updated.utc_offset_minutes, Some(180));
+
+    // Поле не передано - смещение не меняется
+    let updated = users.update(user.id, &update(None)).await.unwrap();
+    assert_eq!(updated.utc_offset_minutes, Some(180));
+
+    // Явный null очищает смещение
+    let updated = users.update(user.id, &update(Some(None))).await.unwrap();
+    assert_eq!(updated.utc_offset_minutes, None);
+}
+
+#[tokio::test]
+async fn owner_info_is_typed_and_cleared_for_owners() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let service = UserService::new(common::encryption(), false);
+    let user = common::create_user(&pool).await;
+
+    let response = service
+        .update_profile(
+            user.id,
+            update_request(serde_json::json!({
+                "owner_type": "renter",
+                "show_contacts": false,
+                "owner_info": { "owner_name": "Пётр", "owner_phone": "+7 916 123-45-67" }
+            })),
+            &users,
+            &plates,
+        )
+        .await
+        .unwrap();
+    let info = response.owner_info.unwrap();
+    assert_eq!(info.owner_name.as_deref(), Some("Пётр"));
+    assert_eq!(info.owner_phone.as_deref(), Some("+79161234567"));
+
+    // Другим пользователям телефон собственника виден только с разрешением показывать контакты
+    let stored = users.find_by_id(user.id).await.unwrap().unwrap();
+    let public = stored.public_owner_info().unwrap();
+    assert_eq!(public.owner_name.as_deref(), Some("Пётр"));
+    assert_eq!(public.owner_phone, None);
+
+    let result = service
+        .update_profile(
+            user.id,
+            update_request(serde_json::json!({ "owner_info": { "notes": "x" } })),
+            &users,
+            &plates,
+        )
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let response = service
+        .update_profile(
+            user.id,
+            update_request(serde_json::json!({ "owner_type": "owner" })),
+            &users,
+            &plates,
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.owner_info, None);
+    let stored = users.find_by_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.owner_info, None);
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use rimskiy_service::repository::{
+    BlockRepository, PostgresBlockRepository, PostgresUserPlateRepository, PostgresUserRepository,
+    UserPlateRepository, UserRepository,
+};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn find_by_ids_loads_existing_users() {
+    let pool = require_db!();
+    let users = PostgresUserRepository::new(pool.clone());
+    let first = common::create_user(&pool).await;
+    let second = common::create_user(&pool).await;
+
+    assert!(users.find_by_ids(&[]).await.unwrap().is_empty());
+
+    let mut found: Vec<Uuid> = users
+        .find_by_ids(&[first.id, Uuid::new_v4(), second.id])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|user| user.id)
+        .collect();
+    found.sort();
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+    assert_eq!(found, expected);
+}
+
+#[tokio::test]
+async fn blocks_for_plate_carry_each_blocker_info() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+
+    let owner = common::create_user(&pool).await;
+    let owner_plates: Vec<String> = (0..3).map(|_| common::random_plate()).collect();
+    for (i, plate) in owner_plates.iter().enumerate() {
+        plates.create(owner.id, plate, i == 0, None).await.unwrap();
+    }
+
+    // Один блокирующий перекрыл два авто, другой - одно
+    let first = common::create_user(&pool).await;
+    let second = common::create_user(&pool).await;
+    for (blocker_id, plate) in [
+        (first.id, &owner_plates[0]),
+        (first.id, &owner_plates[1]),
+        (second.id, &owner_plates[2]),
+    ] {
+        let blocker_plate = common::random_plate();
+        plates
+            .create(blocker_id, &blocker_plate, true, None)
+            .await
+            .unwrap();
+        blocks
+            .create(blocker_id, &blocker_plate, plate, None, None, false)
+            .await
+            .unwrap();
+    }
+
+    let (list, _) = service
+        .get_blocks_for_my_plate(owner.id, None, None, 0, &blocks, &users, &plates)
+        .await
+        .unwrap();
+    assert_eq!(list.len(), 3);
+    for block in &list {
+        let expected = if block.blocked_plate == owner_plates[2] {
+            second.id
+        } else {
+            first.id
+        };
+        assert_eq!(block.blocker.id, expected);
+    }
+}
+
+#[tokio::test]
+async fn failed_blocker_enrichment_keeps_other_blocks() {
+    let pool = require_db!();
+    let service = common::block_service(&common::test_config());
+    let blocks = PostgresBlockRepository::new(pool.clone());
+    let plates = PostgresUserPlateRepository::new(pool.clone());
+    let users = PostgresUserRepository::new(pool.clone());
+
+    let owner = common::create_user(&pool).await;
+    let owner_plate = common::random_plate();
+    plates
+        .create(owner.id, &owner_plate, true, None)
+        .await
+        .unwrap();
+
+    let healthy = common::create_user(&pool).await;
+    let broken = common::create_user(&pool).await;
+    for blocker in [&healthy, &broken] {
+        let blocker_plate = common::random_plate();
+        plates
+            .create(blocker.id, &blocker_plate, true, None)
+            .await
+            .unwrap();
+        blocks
+            .create(blocker.id, &blocker_plate, &owner_plate, None, None, false)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE users SET show_contacts = true WHERE id = $1")
+            .bind(blocker.id)
+            .execute(&*pool)
+            .await
+            .unwrap();
+    }
+    // Телефон одного из блокирующих не расшифровывается
+    sqlx::query("UPDATE users SET phone_encrypted = 'not-a-ciphertext' WHERE id = $1")
+        .bind(broken.id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let (list, total) = service
+        .get_blocks_for_my_plate(owner.id, None, None, 0, &blocks, &users, &plates)
+        .await
+        .unwrap();
+    assert_eq!((list.len(), total), (2, 2));
+    for block in &list {
+        if block.blocker.id == broken.id {
+            assert!(block.enrichment_failed);
+            assert_eq!(block.blocker.phone, None);
+        } else {
+            assert_eq!(block.blocker.id, healthy.id);
+            assert!(!block.enrichment_failed);
+            assert!(block.blocker.phone.is_some());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use rimskiy_service::api::validation_router;
+use rimskiy_service::middleware::{ClientIp, IpRateLimiter};
+use rimskiy_service::AppError;
+use std::net::IpAddr;
+use tower::ServiceExt;
+
+fn validate_request(ip: &str, body: serde_json::Value) -> Request<Body> {
+    let mut request = Request::post("/api/validate")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(ClientIp(ip.parse().unwrap()));
+    request
+}
+
+#[tokio::test]
+async fn validate_endpoint_reports_each_field() {
+    let pool = require_db!();
+    let config = common::test_config();
+    let app = validation_router(&config).with_state(common::test_state(&pool, config));
+
+    let response = app
+        .oneshot(validate_request(
+            "203.0.113.1",
+            serde_json::json!({ "plate": "а123вс77", "phone": "12" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(
+        body["plate"],
+        serde_json::json!({ "valid": true, "normalized": "А123ВС77" })
+    );
+    assert_eq!(body["phone"]["valid"], false);
+    assert_eq!(body["phone"]["code"], "INVALID_PHONE");
+    assert!(body["phone"]["message"].is_string());
+    // Поле не передано - его нет и в ответе
+    assert!(body.get("telegram").is_none());
+}
+
+#[tokio::test]
+async fn validate_endpoint_is_rate_limited_per_ip() {
+    let pool = require_db!();
+    let mut config = common::test_config();
+    config.validate_rate_limit_per_minute = 2;
+    let app = validation_router(&config).with_state(common::test_state(&pool, config));
+    let body = serde_json::json!({ "telegram": "driver" });
+
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(validate_request("203.0.113.2", body.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app
+        .clone()
+        .oneshot(validate_request("203.0.113.2", body.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+    // У другого адреса свой лимит
+    let response = app
+        .oneshot(validate_request("203.0.113.3", body))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn rate_limiter_counts_requests_per_ip() {
+    let limiter = IpRateLimiter::per_minute(3);
+    let first: IpAddr = "198.51.100.1".parse().unwrap();
+    let second: IpAddr = "198.51.100.2".parse().unwrap();
+
+    for _ in 0..3 {
+        limiter.check(first).unwrap();
+    }
+    assert!(matches!(
+        limiter.check(first),
+        Err(AppError::RateLimited { retry_after_secs, .. }) if (1..=60).contains(&retry_after_secs)
+    ));
+    limiter.check(second).unwrap();
+
+    // 0 - без ограничения
+    let unlimited = IpRateLimiter::per_minute(0);
+    for _ in 0..100 {
+        unlimited.check(first).unwrap();
+    }
+}
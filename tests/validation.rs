@@ -0,0 +1,171 @@
+use chrono::NaiveTime;
+use rimskiy_service::models::user::OWNER_INFO_FIELD_MAX_LEN;
+use rimskiy_service::models::validation::ValidateRequest;
+use rimskiy_service::service::ValidationService;
+use rimskiy_service::utils::{format_plate, normalize_plate};
+
+#[test]
+fn departure_time_formats() {
+    let cases = [
+        ("8:30", NaiveTime::from_hms_opt(8, 30, 0)),
+        ("08:30", NaiveTime::from_hms_opt(8, 30, 0)),
+        (" 18:30 ", NaiveTime::from_hms_opt(18, 30, 0)),
+        // Секунды отбрасываются
+        ("18:30:45", NaiveTime::from_hms_opt(18, 30, 0)),
+        ("18:30:00", NaiveTime::from_hms_opt(18, 30, 0)),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            ValidationService::parse_departure_time(input).ok(),
+            expected,
+            "{}",
+            input
+        );
+    }
+
+    for input in [
+        "", "18", "18:3", "25:00", "18:60", "18-30", "ab:cd", "018:30", "18:30:0",
+    ] {
+        assert!(
+            ValidationService::parse_departure_time(input).is_err(),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn plate_normalization_matrix() {
+    let cases = [
+        ("а123вс777", "А123ВС777"),
+        ("А 123 ВС 77", "А123ВС77"),
+        ("а-123-вс-77", "А123ВС77"),
+        // Ё на номерах не бывает, её вводят вместо Е
+        ("ё123ёё77", "Е123ЕЕ77"),
+        ("Ё123ЁЕ77", "Е123ЕЕ77"),
+        ("е123ее77", "Е123ЕЕ77"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(normalize_plate(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn plate_with_yo_validates_as_ye() {
+    let normalized = ValidationService::validate_plate("ё123вс777").unwrap();
+    assert_eq!(normalized, "Е123ВС777");
+    assert_eq!(
+        ValidationService::validate_plate("Е123ВС777").unwrap(),
+        normalized
+    );
+}
+
+#[test]
+fn plates_are_formatted_by_chars() {
+    let cases = [
+        ("А123БВ777", "А 123 БВ 777"),
+        ("а123бв77", "А 123 БВ 77"),
+        ("A123BC777", "A 123 BC 777"),
+        // Другие длины возвращаются без разбивки
+        ("1234", "1234"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(format_plate(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn renter_owner_info_is_validated() {
+    let info = ValidationService::validate_owner_info(&serde_json::json!({
+        "owner_name": "  Пётр Петров ",
+        "owner_phone": "8 (916) 123-45-67",
+        "agency": ""
+    }))
+    .unwrap();
+    assert_eq!(info.owner_name.as_deref(), Some("Пётр Петров"));
+    assert_eq!(info.owner_phone.as_deref(), Some("+79161234567"));
+    assert_eq!(info.agency, None);
+
+    assert!(
+        ValidationService::validate_owner_info(&serde_json::json!({}))
+            .unwrap()
+            .is_empty()
+    );
+
+    for value in [
+        serde_json::json!("Пётр"),
+        serde_json::json!({ "comment": "x" }),
+        serde_json::json!({ "owner_name": 5 }),
+        serde_json::json!({ "owner_phone": "123" }),
+        serde_json::json!({ "agency": "я".repeat(OWNER_INFO_FIELD_MAX_LEN + 1) }),
+    ] {
+        assert!(
+            ValidationService::validate_owner_info(&value).is_err(),
+            "{}",
+            value
+        );
+    }
+}
+
+#[test]
+fn form_fields_are_checked_without_saving() {
+    let response = ValidationService::check_fields(&ValidateRequest {
+        // Латиница и нижний регистр: сервер сохранит номер в верхнем регистре без пробелов
+        plate: Some("a 123 bc-77".to_string()),
+        phone: Some("8 (916) 518-09-00".to_string()),
+        telegram: Some(" @Driver ".to_string()),
+    });
+
+    let plate = response.plate.unwrap();
+    assert!(plate.valid);
+    assert_eq!(plate.normalized, "A123BC77");
+    assert_eq!((plate.code, plate.message), (None, None));
+    let plate = ValidationService::check_fields(&ValidateRequest {
+        plate: Some("ё 123 кх 777".to_string()),
+        ..Default::default()
+    })
+    .plate
+    .unwrap();
+    assert!(plate.valid);
+    assert_eq!(plate.normalized, "Е123КХ777");
+
+    let phone = response.phone.unwrap();
+    assert!(phone.valid);
+    assert_eq!(phone.normalized, "+79165180900");
+
+    let telegram = response.telegram.unwrap();
+    assert!(telegram.valid);
+    assert_eq!(telegram.normalized, "@driver");
+}
+
+#[test]
+fn invalid_form_fields_report_code_and_message() {
+    let response = ValidationService::check_fields(&ValidateRequest {
+        plate: Some("12 abc".to_string()),
+        phone: Some("123".to_string()),
+        telegram: Some("x".repeat(33)),
+    });
+
+    let plate = response.plate.unwrap();
+    assert!(!plate.valid);
+    assert_eq!(plate.normalized, "12ABC");
+    assert_eq!(plate.code.as_deref(), Some("INVALID_PLATE"));
+    assert!(plate.message.is_some());
+
+    let phone = response.phone.unwrap();
+    assert!(!phone.valid);
+    assert_eq!(phone.code.as_deref(), Some("INVALID_PHONE"));
+    assert!(phone.message.is_some());
+
+    let telegram = response.telegram.unwrap();
+    assert!(!telegram.valid);
+    assert_eq!(telegram.code.as_deref(), Some("INVALID_TELEGRAM"));
+    assert!(telegram.message.unwrap().contains("32"));
+
+    let blank = ValidationService::check_fields(&ValidateRequest {
+        telegram: Some("   ".to_string()),
+        ..Default::default()
+    });
+    assert!(!blank.telegram.unwrap().valid);
+    assert!(blank.plate.is_none() && blank.phone.is_none());
+}